@@ -3,7 +3,9 @@
 use std::marker::PhantomData;
 use gl;
 use gl::types::*;
-use types::GLObject;
+use types::{GLObject, Labeled};
+use deletion_queue::{self, Deleted, Owner};
+use state_cache;
 
 /// An OpenGL buffer object.
 ///
@@ -27,14 +29,33 @@ use types::GLObject;
 /// Bind a buffer to a target, returning a buffer binding type.
 pub struct Buffer {
     gl_id: GLuint,
+    owner: Owner,
+    size: Option<usize>,
     _phantom: PhantomData<*mut ()>
 }
 
+impl Buffer {
+    /// Get the size (in bytes) of the data that was last sent to the buffer
+    /// with [`gl.buffer_bytes`]
+    /// (../context/buffer_context/trait.ContextBufferExt.html#method.buffer_bytes)
+    /// (or allocated with [`gl.resize_buffer`]
+    /// (../context/buffer_context/trait.ContextBufferExt.html#method.resize_buffer)),
+    /// or `None` if no data has been sent to the buffer with glitter.
+    pub fn size(&self) -> Option<usize> {
+        self.size
+    }
+
+    // Record that data was sent to the buffer
+    #[doc(hidden)]
+    pub fn _set_size(&mut self, size: usize) {
+        self.size = Some(size);
+    }
+}
+
 impl Drop for Buffer {
     fn drop(&mut self) {
-        unsafe {
-            gl::DeleteBuffers(1, &self.gl_id as *const GLuint);
-        }
+        state_cache::forget_buffer(self.gl_id);
+        deletion_queue::delete(self.owner, Deleted::Buffer(self.gl_id));
     }
 }
 
@@ -44,6 +65,8 @@ impl GLObject for Buffer {
     unsafe fn from_raw(id: Self::Id) -> Self {
         Buffer {
             gl_id: id,
+            owner: Owner::current(),
+            size: None,
             _phantom: PhantomData
         }
     }
@@ -53,6 +76,12 @@ impl GLObject for Buffer {
     }
 }
 
+impl Labeled for Buffer {
+    fn label_identifier() -> GLenum {
+        gl::BUFFER
+    }
+}
+
 
 
 gl_enum! {
@@ -67,7 +96,34 @@ gl_enum! {
 
         /// Indicates that a buffer will be set and modified repeatedly
         /// and drawn many times.
-        pub const DynamicDraw as DYNAMIC_DRAW = gl::DYNAMIC_DRAW
+        pub const DynamicDraw as DYNAMIC_DRAW = gl::DYNAMIC_DRAW,
+
+        /// Indicates that a buffer will be set once by OpenGL and read back
+        /// by the application only a few times. Read usages require OpenGL
+        /// ES 3.0.
+        pub const StreamRead as STREAM_READ = gl::STREAM_READ,
+
+        /// Indicates that a buffer will be set once by OpenGL and read back
+        /// by the application many times. Read usages require OpenGL ES 3.0.
+        pub const StaticRead as STATIC_READ = gl::STATIC_READ,
+
+        /// Indicates that a buffer will be set repeatedly by OpenGL and read
+        /// back by the application many times (such as a pixel buffer used
+        /// for readbacks every frame). Read usages require OpenGL ES 3.0.
+        pub const DynamicRead as DYNAMIC_READ = gl::DYNAMIC_READ,
+
+        /// Indicates that a buffer will be set once by OpenGL and drawn
+        /// only a few times. Copy usages require OpenGL ES 3.0.
+        pub const StreamCopy as STREAM_COPY = gl::STREAM_COPY,
+
+        /// Indicates that a buffer will be set once by OpenGL and drawn
+        /// many times. Copy usages require OpenGL ES 3.0.
+        pub const StaticCopy as STATIC_COPY = gl::STATIC_COPY,
+
+        /// Indicates that a buffer will be set repeatedly by OpenGL and
+        /// drawn many times (such as a transform feedback buffer).
+        /// Copy usages require OpenGL ES 3.0.
+        pub const DynamicCopy as DYNAMIC_COPY = gl::DYNAMIC_COPY
     }
 }
 
@@ -83,3 +139,34 @@ gl_enum! {
             gl::ELEMENT_ARRAY_BUFFER
     }
 }
+
+#[cfg(feature = "gles3")]
+gl_enum! {
+    /// The OpenGL buffer targets that have indexed binding points, which
+    /// buffers can be bound to with [`gl.bind_buffer_base`]
+    /// (../context/buffer_context/trait.ContextBufferExt.html#method.bind_buffer_base)
+    /// and [`gl.bind_buffer_range`]
+    /// (../context/buffer_context/trait.ContextBufferExt.html#method.bind_buffer_range).
+    pub gl_enum IndexedBufferTarget {
+        /// The uniform buffer binding points, which back uniform blocks.
+        pub const UniformBuffer as UNIFORM_BUFFER =
+            gl::UNIFORM_BUFFER,
+
+        /// The shader storage buffer binding points, which back `buffer`
+        /// blocks. Shader storage buffers require OpenGL ES 3.1 or
+        /// OpenGL 4.3.
+        pub const ShaderStorageBuffer as SHADER_STORAGE_BUFFER =
+            gl::SHADER_STORAGE_BUFFER,
+
+        /// The transform feedback buffer binding points, which capture the
+        /// outputs of a vertex shader during transform feedback.
+        pub const TransformFeedbackBuffer as TRANSFORM_FEEDBACK_BUFFER =
+            gl::TRANSFORM_FEEDBACK_BUFFER,
+
+        /// The atomic counter buffer binding points, which back
+        /// `atomic_uint` variables. Atomic counter buffers require OpenGL
+        /// ES 3.1 or OpenGL 4.2.
+        pub const AtomicCounterBuffer as ATOMIC_COUNTER_BUFFER =
+            gl::ATOMIC_COUNTER_BUFFER
+    }
+}
@@ -0,0 +1,60 @@
+// Traces each OpenGL call that glitter checks for errors, along with its
+// arguments and the resulting error state, using the `tracing` crate. This
+// is only enabled with the `trace` feature; otherwise, tracing is a no-op
+// that the optimizer can remove entirely.
+
+use std::fmt::{self, Debug};
+use types::GLError;
+
+// Formats a list of arguments as `arg1, arg2, ...`
+struct Args<'a>(&'a [&'a Debug]);
+
+impl<'a> fmt::Display for Args<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, arg) in self.0.iter().enumerate() {
+            if i > 0 {
+                try!(write!(f, ", "));
+            }
+            try!(write!(f, "{:?}", arg));
+        }
+        Ok(())
+    }
+}
+
+// When tracing is enabled, `glGetError` is called after every checked call,
+// regardless of the current `ErrorPolicy`.
+#[cfg(feature = "trace")]
+pub fn enabled() -> bool {
+    ::tracing::enabled!(target: "glitter::gl", ::tracing::Level::WARN)
+}
+
+#[cfg(not(feature = "trace"))]
+#[inline(always)]
+pub fn enabled() -> bool {
+    false
+}
+
+#[cfg(feature = "trace")]
+pub fn trace_call(call: &str, args: &[&Debug], err: Option<&GLError>) {
+    match err {
+        Some(err) => {
+            ::tracing::warn!(target: "glitter::gl",
+                             call = call,
+                             args = %Args(args),
+                             error = ?err,
+                             "{}({}) -> {:?}", call, Args(args), err);
+        },
+        None => {
+            ::tracing::trace!(target: "glitter::gl",
+                              call = call,
+                              args = %Args(args),
+                              "{}({})", call, Args(args));
+        }
+    }
+}
+
+#[cfg(not(feature = "trace"))]
+#[inline(always)]
+pub fn trace_call(_call: &str, _args: &[&Debug], _err: Option<&GLError>) {
+
+}
@@ -1,5 +1,6 @@
 use cgmath;
-use uniform_data::{UniformDatumType, UniformDatum, UniformPrimitive};
+use uniform_data::{UniformDatumType, UniformDatum, UniformPrimitive,
+                   MatrixOrder};
 
 unsafe impl<T: UniformPrimitive> UniformDatum for cgmath::Vector2<T> {
     fn uniform_datum_type() -> UniformDatumType {
@@ -23,16 +24,28 @@ unsafe impl UniformDatum for cgmath::Matrix2<f32> {
     fn uniform_datum_type() -> UniformDatumType {
         UniformDatumType::Matrix2x2
     }
+
+    fn matrix_order() -> Option<MatrixOrder> {
+        Some(MatrixOrder::ColumnMajor)
+    }
 }
 
 unsafe impl UniformDatum for cgmath::Matrix3<f32> {
     fn uniform_datum_type() -> UniformDatumType {
         UniformDatumType::Matrix3x3
     }
+
+    fn matrix_order() -> Option<MatrixOrder> {
+        Some(MatrixOrder::ColumnMajor)
+    }
 }
 
 unsafe impl UniformDatum for cgmath::Matrix4<f32> {
     fn uniform_datum_type() -> UniformDatumType {
         UniformDatumType::Matrix4x4
     }
+
+    fn matrix_order() -> Option<MatrixOrder> {
+        Some(MatrixOrder::ColumnMajor)
+    }
 }
@@ -1,12 +1,15 @@
 use cgmath;
-use vertex_data::{VertexAttributeType, VertexDatum, VertexPrimitive};
+use vertex_data::{VertexAttributeType, VertexDatum, VertexPrimitive,
+                  AttribKind};
 
 unsafe impl<T: VertexPrimitive> VertexDatum for cgmath::Vector2<T> {
     fn attrib_type() -> VertexAttributeType {
         VertexAttributeType {
             data: T::data_type(),
             components: 2,
-            normalize: false
+            columns: 1,
+            normalize: false,
+            kind: AttribKind::Float
         }
     }
 }
@@ -16,7 +19,9 @@ unsafe impl<T: VertexPrimitive> VertexDatum for cgmath::Vector3<T> {
         VertexAttributeType {
             data: T::data_type(),
             components: 3,
-            normalize: false
+            columns: 1,
+            normalize: false,
+            kind: AttribKind::Float
         }
     }
 }
@@ -26,7 +31,45 @@ unsafe impl<T: VertexPrimitive> VertexDatum for cgmath::Vector4<T> {
         VertexAttributeType {
             data: T::data_type(),
             components: 4,
-            normalize: false
+            columns: 1,
+            normalize: false,
+            kind: AttribKind::Float
+        }
+    }
+}
+
+unsafe impl<T: VertexPrimitive> VertexDatum for cgmath::Matrix2<T> {
+    fn attrib_type() -> VertexAttributeType {
+        VertexAttributeType {
+            data: T::data_type(),
+            components: 2,
+            columns: 2,
+            normalize: false,
+            kind: AttribKind::Float
+        }
+    }
+}
+
+unsafe impl<T: VertexPrimitive> VertexDatum for cgmath::Matrix3<T> {
+    fn attrib_type() -> VertexAttributeType {
+        VertexAttributeType {
+            data: T::data_type(),
+            components: 3,
+            columns: 3,
+            normalize: false,
+            kind: AttribKind::Float
+        }
+    }
+}
+
+unsafe impl<T: VertexPrimitive> VertexDatum for cgmath::Matrix4<T> {
+    fn attrib_type() -> VertexAttributeType {
+        VertexAttributeType {
+            data: T::data_type(),
+            components: 4,
+            columns: 4,
+            normalize: false,
+            kind: AttribKind::Float
         }
     }
 }
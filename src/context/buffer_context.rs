@@ -1,17 +1,23 @@
 //! Contains all of the OpenGL state types related to buffer objects.
 
 use std::mem;
+#[cfg(feature = "gles3")] use std::cmp;
 use std::ptr;
+#[cfg(feature = "gles3")] use std::slice;
 use std::marker::PhantomData;
 use std::borrow::BorrowMut;
 use gl;
 use gl::types::*;
 use context::{ContextOf, BaseContext, AContext};
 use buffer::{Buffer, BufferDataUsage, BufferBindingTarget};
+#[cfg(feature = "gles3")] use buffer::IndexedBufferTarget;
 use program::{ProgramAttrib};
 use index_data::{IndexData, IndexDatum, IndexDatumType};
+#[cfg(feature = "gles3")] use index_data::index_range;
 use types::{DataType, DrawingMode, GLObject, GLError};
 use to_ref::{ToRef, ToMut};
+use state_cache;
+use restore_bindings;
 
 unsafe fn _draw_elements(mode: DrawingMode,
                          count: usize,
@@ -20,13 +26,17 @@ unsafe fn _draw_elements(mode: DrawingMode,
 {
     let gl_index_type: GLenum = match index_type {
         IndexDatumType::UnsignedByte => gl::UNSIGNED_BYTE,
-        IndexDatumType::UnsignedShort => gl::UNSIGNED_SHORT
+        IndexDatumType::UnsignedShort => gl::UNSIGNED_SHORT,
+        #[cfg(feature = "gles3")]
+        IndexDatumType::UnsignedInt => gl::UNSIGNED_INT
     };
     gl::DrawElements(mode.gl_enum(),
                      count as GLsizei,
                      gl_index_type,
                      indices);
     dbg_gl_error! {
+        call "glDrawElements";
+        args (mode, count, gl_index_type, indices);
         GLError::InvalidEnum => "`mode` or `type` is not an accepted value",
         GLError::InvalidValue => "`count` is negative",
         GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
@@ -34,16 +44,217 @@ unsafe fn _draw_elements(mode: DrawingMode,
     }
 }
 
-fn _bind_buffer(target: BufferBindingTarget, buffer: &mut Buffer) {
+#[cfg(feature = "gles3")]
+fn _index_size(index_type: &IndexDatumType) -> usize {
+    match *index_type {
+        IndexDatumType::UnsignedByte => mem::size_of::<u8>(),
+        IndexDatumType::UnsignedShort => mem::size_of::<u16>(),
+        #[cfg(feature = "gles3")]
+        IndexDatumType::UnsignedInt => mem::size_of::<u32>()
+    }
+}
+
+// In debug builds, panic if the indices `first..first + count` are beyond
+// the end of the data that was sent to the bound element array buffer
+fn _debug_check_indices(gl_eab: &ElementArrayBufferBinding,
+                        index_type: &IndexDatumType,
+                        first: usize,
+                        count: usize)
+{
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    if let Some(size) = gl_eab.buffer().size() {
+        let index_size = _index_size(index_type);
+        assert!((first + count) * index_size <= size,
+                "Indices {}..{} are out of bounds of the element array buffer, which contains {} indices",
+                first, first + count, size / index_size);
+    }
+}
+
+unsafe fn _draw_range_elements(mode: DrawingMode,
+                               start: u32,
+                               end: u32,
+                               count: usize,
+                               index_type: IndexDatumType,
+                               indices: *const GLvoid)
+{
+    assert!(start <= end, "`start` must not be greater than `end`");
+
+    let gl_index_type: GLenum = match index_type {
+        IndexDatumType::UnsignedByte => gl::UNSIGNED_BYTE,
+        IndexDatumType::UnsignedShort => gl::UNSIGNED_SHORT,
+        IndexDatumType::UnsignedInt => gl::UNSIGNED_INT
+    };
+    gl::DrawRangeElements(mode.gl_enum(),
+                          start as GLuint,
+                          end as GLuint,
+                          count as GLsizei,
+                          gl_index_type,
+                          indices);
+    dbg_gl_error! {
+        call "glDrawRangeElements";
+        args (mode, start, end, count, gl_index_type, indices);
+        GLError::InvalidEnum => "`mode` or `type` is not an accepted value",
+        GLError::InvalidValue => "`count` is negative, or `end` < `start`",
+        GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+        _ => "Unknown error"
+    }
+}
+
+fn _bind_buffer(target: BufferBindingTarget, buffer: &Buffer) {
+    _bind_buffer_id(target, buffer.id());
+}
+
+fn _bind_buffer_id(target: BufferBindingTarget, id: GLuint) {
+    _bind_buffer_target(target.gl_enum(), id);
+}
+
+// Bind a buffer to any target, including targets that aren't a
+// `BufferBindingTarget` (such as `GL_COPY_WRITE_BUFFER`)
+fn _bind_buffer_target(target: GLenum, id: GLuint) {
+    if !state_cache::bind_buffer(target, id) {
+        return;
+    }
     unsafe {
-        gl::BindBuffer(target as GLuint, buffer.id());
+        gl::BindBuffer(target, id);
         dbg_gl_sanity_check! {
+            call "glBindBuffer";
+            args (target, id);
+            GLError::InvalidEnum => "`target` is not an allowed value",
+            _ => "Unknown error"
+        }
+    }
+}
+
+// Read `bytes.len()` bytes from the buffer bound to `target`, starting at
+// `offset`, using `glGetBufferSubData`
+#[cfg(feature = "gl33")]
+fn _get_buffer_sub_bytes(target: GLenum,
+                         offset: usize,
+                         bytes: &mut [u8])
+{
+    let ptr = bytes.as_mut_ptr();
+    unsafe {
+        gl::GetBufferSubData(target,
+                             offset as GLintptr,
+                             bytes.len() as GLsizeiptr,
+                             ptr as *mut GLvoid);
+        dbg_gl_error! {
+            call "glGetBufferSubData";
+            args (target, offset, bytes.len(), ptr);
+            GLError::InvalidEnum => "Invalid `target`",
+            GLError::InvalidValue => "`offset` or `size` is negative, or `offset` + `size` is greater than the size of the buffer",
+            GLError::InvalidOperation => "Object 0 is bound to buffer target, or the buffer is mapped",
+            _ => "Unknown error"
+        }
+    }
+}
+
+// Read `bytes.len()` bytes from the buffer bound to `target`, starting at
+// `offset`, by mapping the range for reading (OpenGL ES doesn't include
+// `glGetBufferSubData`)
+#[cfg(all(feature = "gles3", not(feature = "gl33")))]
+fn _get_buffer_sub_bytes(target: GLenum,
+                         offset: usize,
+                         bytes: &mut [u8])
+{
+    unsafe {
+        let ptr = gl::MapBufferRange(target,
+                                     offset as GLintptr,
+                                     bytes.len() as GLsizeiptr,
+                                     gl::MAP_READ_BIT);
+        dbg_gl_error! {
+            call "glMapBufferRange";
+            args (target, offset, bytes.len(), gl::MAP_READ_BIT);
+            GLError::InvalidValue => "The range is out of bounds of the buffer",
+            GLError::InvalidOperation => "Object 0 is bound to buffer target, or the buffer is already mapped",
+            _ => "Unknown error"
+        }
+        assert!(!ptr.is_null(), "Unable to map the buffer for reading");
+
+        let mapped = slice::from_raw_parts(ptr as *const u8, bytes.len());
+        bytes.copy_from_slice(mapped);
+
+        gl::UnmapBuffer(target);
+        dbg_gl_error! {
+            call "glUnmapBuffer";
+            args (target);
+            GLError::InvalidOperation => "The buffer is not mapped",
+            _ => "Unknown error"
+        }
+    }
+}
+
+// Get the `glGet` parameter for the number of binding points of an
+// indexed target
+#[cfg(feature = "gles3")]
+fn _max_bindings_pname(target: IndexedBufferTarget) -> GLenum {
+    match target {
+        IndexedBufferTarget::UniformBuffer =>
+            gl::MAX_UNIFORM_BUFFER_BINDINGS,
+        IndexedBufferTarget::ShaderStorageBuffer =>
+            gl::MAX_SHADER_STORAGE_BUFFER_BINDINGS,
+        IndexedBufferTarget::TransformFeedbackBuffer =>
+            gl::MAX_TRANSFORM_FEEDBACK_SEPARATE_ATTRIBS,
+        IndexedBufferTarget::AtomicCounterBuffer =>
+            gl::MAX_ATOMIC_COUNTER_BUFFER_BINDINGS
+    }
+}
+
+#[cfg(feature = "gles3")]
+fn _get_positive(pname: GLenum) -> usize {
+    let mut value: GLint = 0;
+    unsafe {
+        gl::GetIntegerv(pname, &mut value);
+    }
+    if value < 0 { 0 } else { value as usize }
+}
+
+// In debug builds, panic if `index` isn't one of the binding points of
+// `target`
+#[cfg(feature = "gles3")]
+fn _debug_check_binding_index(target: IndexedBufferTarget, index: u32) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    let max = _get_positive(_max_bindings_pname(target));
+    assert!((index as usize) < max,
+            "Binding point {} is out of range, {:?} only has {} binding points",
+            index, target, max);
+}
+
+// Bind a buffer to an indexed binding point, which also binds it to the
+// generic binding of `target` (so the state cache is updated to match)
+#[cfg(feature = "gles3")]
+fn _bind_buffer_base_id(target: IndexedBufferTarget, index: u32, id: GLuint) {
+    state_cache::bind_buffer(target.gl_enum(), id);
+    unsafe {
+        gl::BindBufferBase(target.gl_enum(), index as GLuint, id);
+        dbg_gl_error! {
+            call "glBindBufferBase";
+            args (target, index, id);
             GLError::InvalidEnum => "`target` is not an allowed value",
+            GLError::InvalidValue => "`index` is greater than the number of binding points for `target`",
             _ => "Unknown error"
         }
     }
 }
 
+// Panic if `count` atomic counters starting from the counter `first` are
+// out of bounds of the data that was sent to `buffer`
+#[cfg(feature = "gles31")]
+fn _check_atomic_counters(buffer: &Buffer, first: usize, count: usize) {
+    if let Some(size) = buffer.size() {
+        let end = (first + count) * mem::size_of::<u32>();
+        assert!(end <= size,
+                "Atomic counters {}..{} are out of bounds of a buffer of {} bytes",
+                first, first + count, size);
+    }
+}
+
 /// An extension trait that includes buffer-object-related OpenGL methods.
 pub trait ContextBufferExt: BaseContext {
     /// Create a new, empty OpenGL buffer object.
@@ -66,6 +277,8 @@ pub trait ContextBufferExt: BaseContext {
             gl::GenBuffers(1, &mut id as *mut GLuint);
         }
         dbg_gl_sanity_check! {
+            call "glGenBuffers";
+            args (id);
             GLError::InvalidValue => "`n` is negative",
             _ => "Unknown error"
         }
@@ -74,7 +287,9 @@ pub trait ContextBufferExt: BaseContext {
     }
 
     /// Send data to a buffer object. Note that this will replace the buffer's
-    /// current contents, if any.
+    /// current contents, if any. The size of the data is recorded, so that
+    /// draw calls can be checked against it in debug builds (see
+    /// [`Buffer::size`](../../buffer/struct.Buffer.html#method.size)).
     ///
     /// # See also
     /// [`glBufferData`](http://docs.gl/es2/glBufferData) OpenGL docs
@@ -92,6 +307,8 @@ pub trait ContextBufferExt: BaseContext {
                            ptr as *const GLvoid,
                            usage.gl_enum());
             dbg_gl_error! {
+                call "glBufferData";
+                args (gl_buffer.target(), size, ptr, usage);
                 GLError::InvalidEnum => "Invalid `target` or `usage`",
                 GLError::InvalidValue => "`size` is negative",
                 GLError::InvalidOperation => "Object 0 is bound to buffer target",
@@ -99,6 +316,194 @@ pub trait ContextBufferExt: BaseContext {
                 _ => "Unknown error"
             }
         }
+        gl_buffer.buffer_mut()._set_size(size);
+    }
+
+    /// Replace part of a buffer object's data, starting `offset` bytes into
+    /// the buffer. Unlike [`gl.buffer_bytes`]
+    /// (trait.ContextBufferExt.html#method.buffer_bytes), this doesn't
+    /// reallocate the buffer.
+    ///
+    /// # Panics
+    /// This function will panic if the range is out of bounds of the data
+    /// that was sent to the buffer (if its size is known).
+    ///
+    /// # See also
+    /// [`glBufferSubData`](http://docs.gl/es2/glBufferSubData) OpenGL docs
+    fn buffer_sub_bytes<B>(&self,
+                           gl_buffer: &mut B,
+                           offset: usize,
+                           bytes: &[u8])
+        where B: BufferBinding
+    {
+        if let Some(size) = gl_buffer.buffer().size() {
+            assert!(offset + bytes.len() <= size,
+                    "Bytes {}..{} are out of bounds of a buffer of {} bytes",
+                    offset, offset + bytes.len(), size);
+        }
+
+        let ptr = bytes.as_ptr();
+        unsafe {
+            gl::BufferSubData(gl_buffer.target().gl_enum(),
+                              offset as GLintptr,
+                              bytes.len() as GLsizeiptr,
+                              ptr as *const GLvoid);
+            dbg_gl_error! {
+                call "glBufferSubData";
+                args (gl_buffer.target(), offset, bytes.len(), ptr);
+                GLError::InvalidEnum => "Invalid `target`",
+                GLError::InvalidValue => "`offset` or `size` is negative, or `offset` + `size` is greater than the size of the buffer",
+                GLError::InvalidOperation => "Object 0 is bound to buffer target, or the buffer is mapped",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Read data from a buffer into `bytes`, starting `offset` bytes into
+    /// the buffer. This can be used to inspect the results of transform
+    /// feedback or a compute shader on the CPU.
+    ///
+    /// # Panics
+    /// This function will panic if the range is out of bounds of the data
+    /// that was sent to the buffer (if its size is known). Without the `gl33`
+    /// feature, the buffer range is mapped for reading, and this function will
+    /// also panic if the range couldn't be mapped.
+    ///
+    /// # Note
+    /// Reading from buffers requires OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature. Reading from a buffer waits for
+    /// any OpenGL commands that write to it to finish, so this can stall the
+    /// CPU.
+    ///
+    /// # See also
+    /// [`glGetBufferSubData`](http://docs.gl/gl3/glGetBufferSubData) and
+    /// [`glMapBufferRange`](http://docs.gl/es3/glMapBufferRange) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn get_buffer_sub_bytes<B>(&self,
+                               gl_buffer: &B,
+                               offset: usize,
+                               bytes: &mut [u8])
+        where B: BufferBinding
+    {
+        if let Some(size) = gl_buffer.buffer().size() {
+            assert!(offset + bytes.len() <= size,
+                    "Bytes {}..{} are out of bounds of a buffer of {} bytes",
+                    offset, offset + bytes.len(), size);
+        }
+
+        if !bytes.is_empty() {
+            let target = gl_buffer.target().gl_enum();
+            _get_buffer_sub_bytes(target, offset, bytes);
+        }
+    }
+
+    /// Read data from a buffer into `data`, starting `offset` bytes into the
+    /// buffer. See [`gl.get_buffer_sub_bytes`]
+    /// (#method.get_buffer_sub_bytes) for more details.
+    ///
+    /// # Panics
+    /// This function will panic under the same conditions as
+    /// [`gl.get_buffer_sub_bytes`](#method.get_buffer_sub_bytes).
+    ///
+    /// # Safety
+    /// The bytes read from the buffer are reinterpreted as values of type
+    /// `T`, so every value read must be a valid bit pattern for `T` (such as
+    /// for primitive numeric types, or for [`VertexData`]
+    /// (../../vertex_data/trait.VertexData.html) types).
+    ///
+    /// # Note
+    /// Reading from buffers requires OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let mut buffer = gl.gen_buffer();
+    /// let (mut gl_buffer, gl) = (&mut gl).bind_array_buffer(&mut buffer);
+    /// gl.buffer_bytes(&mut gl_buffer, &[0; 64], glitter::STREAM_READ);
+    ///
+    /// // ... Run transform feedback or a compute shader ...
+    ///
+    /// let mut results = [0.0f32; 16];
+    /// unsafe { gl.get_buffer_sub_data(&gl_buffer, 0, &mut results) };
+    /// # }
+    /// ```
+    #[cfg(feature = "gles3")]
+    unsafe fn get_buffer_sub_data<B, T>(&self,
+                                        gl_buffer: &B,
+                                        offset: usize,
+                                        data: &mut [T])
+        where B: BufferBinding, T: Copy
+    {
+        let size = mem::size_of::<T>() * data.len();
+        let bytes = slice::from_raw_parts_mut(data.as_mut_ptr() as *mut u8,
+                                              size);
+        self.get_buffer_sub_bytes(gl_buffer, offset, bytes);
+    }
+
+    /// Reallocate a buffer object with room for `size` bytes, keeping as
+    /// much of its current data as fits (using the size recorded by
+    /// [`Buffer::size`](../../buffer/struct.Buffer.html#method.size)).
+    /// A new OpenGL buffer object replaces the bound buffer's object, which
+    /// is deleted.
+    ///
+    /// If the size of the buffer is unknown (`Buffer::size` returns `None`,
+    /// such as when its data was set by calling OpenGL directly), none of
+    /// its data is kept, and the contents of the new buffer are undefined.
+    ///
+    /// # Note
+    /// Copying between buffers requires OpenGL ES 3.0, so this method is
+    /// only available with the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glCopyBufferSubData`](http://docs.gl/es3/glCopyBufferSubData)
+    /// OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn resize_buffer<B>(&self,
+                        gl_buffer: &mut B,
+                        size: usize,
+                        usage: BufferDataUsage)
+        where B: BufferBinding
+    {
+        let kept = cmp::min(size, gl_buffer.buffer().size().unwrap_or(0));
+        let mut new_buffer = self.gen_buffer();
+        _bind_buffer_target(gl::COPY_WRITE_BUFFER, new_buffer.id());
+        unsafe {
+            gl::BufferData(gl::COPY_WRITE_BUFFER,
+                           size as GLsizeiptr,
+                           ptr::null(),
+                           usage.gl_enum());
+            dbg_gl_error! {
+                call "glBufferData";
+                args (gl::COPY_WRITE_BUFFER, size, usage);
+                GLError::InvalidValue => "`size` is negative",
+                GLError::OutOfMemory => "Unable to create a large enough buffer",
+                _ => "Unknown error"
+            }
+
+            if kept > 0 {
+                gl::CopyBufferSubData(gl_buffer.target().gl_enum(),
+                                      gl::COPY_WRITE_BUFFER,
+                                      0,
+                                      0,
+                                      kept as GLsizeiptr);
+                dbg_gl_error! {
+                    call "glCopyBufferSubData";
+                    args (gl_buffer.target(), gl::COPY_WRITE_BUFFER, kept);
+                    GLError::InvalidValue => "The copied range is out of bounds of either buffer",
+                    GLError::InvalidOperation => "Object 0 is bound to either target, or either buffer is mapped",
+                    _ => "Unknown error"
+                }
+            }
+        }
+        new_buffer._set_size(size);
+
+        mem::swap(gl_buffer.buffer_mut(), &mut new_buffer);
+        _bind_buffer(gl_buffer.target(), gl_buffer.buffer());
     }
 
     /// Specify how an array of vertex data will be treated while rendering.
@@ -134,6 +539,8 @@ pub trait ContextBufferExt: BaseContext {
                                 stride as GLsizei,
                                 offset as *const GLvoid);
         dbg_gl_error! {
+            call "glVertexAttribPointer";
+            args (attrib, components, gl_type, normalized, stride, offset);
             GLError::InvalidEnum => "Illegal vertex attribute type",
             GLError::InvalidValue => "`stride` is negative, `size` is not in range, or `index` is >= GL_MAX_VERTEX_ATTRIBS",
             GLError::InvalidFramebufferOperation => "Currently bound framebuffer is not framebuffer complete",
@@ -141,6 +548,80 @@ pub trait ContextBufferExt: BaseContext {
         }
     }
 
+    /// Specify how an array of integer vertex data will be treated while
+    /// rendering. Unlike [`gl.vertex_attrib_pointer`]
+    /// (trait.ContextBufferExt.html#method.vertex_attrib_pointer), the
+    /// vertex data is read as integer values (such as an `ivec4` or a
+    /// `uvec4`) in a shader, rather than being converted to
+    /// floating-point values.
+    ///
+    /// # Note
+    /// Integer vertex attributes require OpenGL ES 3.0, so this method is
+    /// only available with the `gles3` feature.
+    ///
+    /// # Panics
+    /// This function will panic in debug mode if `components` is less than 1 or
+    /// greater than 4.
+    ///
+    /// # Safety
+    /// Using this function can cause an OpenGL draw call to read uninitialized
+    /// memory from a buffer.
+    ///
+    /// # See also
+    /// [`glVertexAttribIPointer`](http://docs.gl/es3/glVertexAttribIPointer) OpenGL docs
+    #[cfg(feature = "gles3")]
+    unsafe fn vertex_attrib_i_pointer(&self,
+                                      attrib: ProgramAttrib,
+                                      components: i8,
+                                      gl_type: DataType,
+                                      stride: usize,
+                                      offset: usize)
+    {
+        debug_assert!(1 <= components && components <= 4);
+
+        gl::VertexAttribIPointer(attrib.gl_index,
+                                 components as GLint,
+                                 gl_type.gl_enum(),
+                                 stride as GLsizei,
+                                 offset as *const GLvoid);
+        dbg_gl_error! {
+            call "glVertexAttribIPointer";
+            args (attrib, components, gl_type, stride, offset);
+            GLError::InvalidEnum => "Illegal integer vertex attribute type",
+            GLError::InvalidValue => "`stride` is negative, `size` is not in range, or `index` is >= GL_MAX_VERTEX_ATTRIBS",
+            GLError::InvalidOperation => "A non-zero vertex array object is bound, no buffer is bound to GL_ARRAY_BUFFER, and `pointer` is not null",
+            _ => "Unknown error"
+        }
+    }
+
+    /// Set the divisor of a vertex attribute, which is the number of
+    /// instances that are drawn before the attribute advances to the next
+    /// element of its vertex data. A divisor of 0 (the default) means that
+    /// the attribute advances once per vertex.
+    ///
+    /// # Note
+    /// Instanced vertex attributes require OpenGL ES 3.0, so this method is
+    /// only available with the `gles3` feature.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error is generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glVertexAttribDivisor`](http://docs.gl/es3/glVertexAttribDivisor) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn vertex_attrib_divisor(&self, attrib: ProgramAttrib, divisor: u32) {
+        unsafe {
+            gl::VertexAttribDivisor(attrib.gl_index, divisor as GLuint);
+            dbg_gl_error! {
+                call "glVertexAttribDivisor";
+                args (attrib, divisor);
+                GLError::InvalidValue => "`index` is >= GL_MAX_VERTEX_ATTRIBS",
+                _ => "Unknown error"
+            }
+        }
+    }
+
     /// Use the vertex data from the provided array buffer binding to render
     /// primitives.
     ///
@@ -164,6 +645,8 @@ pub trait ContextBufferExt: BaseContext {
     {
         gl::DrawArrays(mode.gl_enum(), first as GLint, count as GLsizei);
         dbg_gl_sanity_check! {
+            call "glDrawArrays";
+            args (mode, first, count);
             GLError::InvalidEnum => "`mode` is not an accepted value",
             GLError::InvalidValue => "`count` is negative",
             _ => "Unknown error"
@@ -191,9 +674,40 @@ pub trait ContextBufferExt: BaseContext {
                                        count: usize,
                                        index_type: IndexDatumType)
     {
+        _debug_check_indices(_eab, &index_type, 0, count);
         _draw_elements(mode, count, index_type, ptr::null());
     }
 
+    /// Draw primitives specified by a range of the provided element array
+    /// buffer, treated as indices of the vertices from the provided
+    /// array buffer.
+    ///
+    /// - `_ab`: The binding for the array buffer that contains the vertex
+    ///          data.
+    /// - `_eab`: The binding for the element array buffer that contains the
+    ///           index data.
+    /// - `mode`: The type of primitives to draw.
+    /// - `first`: The index of the first index to read (as a number of
+    ///            indices, not bytes).
+    /// - `count`: The number of indices to read.
+    /// - `index_type`: Specifies the data type of the index (whether it is
+    ///                 a byte or short, signed unsigned, etc).
+    ///
+    /// # See also
+    /// [`glDrawElements`](http://docs.gl/es2/glDrawElements) OpenGL docs
+    unsafe fn draw_n_elements_buffered_range(&self,
+                                             _ab: &ArrayBufferBinding,
+                                             _eab: &ElementArrayBufferBinding,
+                                             mode: DrawingMode,
+                                             first: usize,
+                                             count: usize,
+                                             index_type: IndexDatumType)
+    {
+        _debug_check_indices(_eab, &index_type, first, count);
+        let offset = first * _index_size(&index_type);
+        _draw_elements(mode, count, index_type, offset as *const GLvoid);
+    }
+
     /// Draw primitives specified by the provided index array, treated as
     /// indices of the vertices from the provided array buffer.
     ///
@@ -237,6 +751,395 @@ pub trait ContextBufferExt: BaseContext {
     {
         self.draw_n_elements(_ab, mode, indices.len(), indices);
     }
+
+    /// Draw primitives specified by a range of the provided element array
+    /// buffer, where every index is between `start` and `end` (inclusive).
+    /// The range is a hint that lets the OpenGL implementation only process
+    /// the vertices that are used.
+    ///
+    /// - `_ab`: The binding for the array buffer that contains the vertex
+    ///          data.
+    /// - `_eab`: The binding for the element array buffer that contains the
+    ///           index data.
+    /// - `mode`: The type of primitives to draw.
+    /// - `start`: The smallest index within the range of indices.
+    /// - `end`: The largest index within the range of indices.
+    /// - `first`: The index of the first index to read (as a number of
+    ///            indices, not bytes).
+    /// - `count`: The number of indices to read.
+    /// - `index_type`: Specifies the data type of the index.
+    ///
+    /// # Note
+    /// `glDrawRangeElements` requires OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    ///
+    /// # Panics
+    /// This function will panic if `start` is greater than `end`.
+    ///
+    /// # Safety
+    /// Every index that is read must be between `start` and `end`.
+    ///
+    /// # See also
+    /// [`glDrawRangeElements`](http://docs.gl/es3/glDrawRangeElements)
+    /// OpenGL docs
+    #[cfg(feature = "gles3")]
+    unsafe fn draw_range_elements_buffered(&self,
+                                           _ab: &ArrayBufferBinding,
+                                           _eab: &ElementArrayBufferBinding,
+                                           mode: DrawingMode,
+                                           start: u32,
+                                           end: u32,
+                                           first: usize,
+                                           count: usize,
+                                           index_type: IndexDatumType)
+    {
+        _debug_check_indices(_eab, &index_type, first, count);
+        let offset = first * _index_size(&index_type);
+        _draw_range_elements(mode,
+                             start,
+                             end,
+                             count,
+                             index_type,
+                             offset as *const GLvoid);
+    }
+
+    /// Draw primitives specified by the provided index array, where every
+    /// index is between `start` and `end` (inclusive), treated as indices
+    /// of the vertices from the provided array buffer. The range can be
+    /// computed with [`index_range`](../../index_data/fn.index_range.html).
+    ///
+    /// # Note
+    /// `glDrawRangeElements` requires OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    ///
+    /// # Panics
+    /// This function will panic if `start` is greater than `end`. If debug
+    /// assertions are enabled, it will also panic if any index is outside
+    /// of the range.
+    ///
+    /// # See also
+    /// [`glDrawRangeElements`](http://docs.gl/es3/glDrawRangeElements)
+    /// OpenGL docs
+    #[cfg(feature = "gles3")]
+    unsafe fn draw_range_elements<I>(&self,
+                                     _ab: &ArrayBufferBinding,
+                                     mode: DrawingMode,
+                                     start: u32,
+                                     end: u32,
+                                     indices: &[I])
+        where I: IndexDatum, [I]: IndexData
+    {
+        if let Some((min, max)) = index_range(indices) {
+            debug_assert!(start <= min && max <= end,
+                          "Indices range from {} to {}, outside of {} to {}",
+                          min, max, start, end);
+        }
+        else {
+            return;
+        }
+
+        let ptr = indices.index_bytes().as_ptr();
+        _draw_range_elements(mode,
+                             start,
+                             end,
+                             indices.len(),
+                             I::index_datum_type(),
+                             ptr as *const GLvoid);
+    }
+
+    /// Get the number of indexed binding points of `target` (such as
+    /// `GL_MAX_UNIFORM_BUFFER_BINDINGS` for uniform buffers).
+    ///
+    /// # Note
+    /// Indexed binding points require OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    #[cfg(feature = "gles3")]
+    fn get_max_indexed_bindings(&self, target: IndexedBufferTarget) -> u32 {
+        _get_positive(_max_bindings_pname(target)) as u32
+    }
+
+    /// Get the alignment (in bytes) that the offset passed to
+    /// [`gl.bind_buffer_range`](#method.bind_buffer_range) must be a
+    /// multiple of for `target` (such as `GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT`
+    /// for uniform buffers).
+    ///
+    /// # Note
+    /// Indexed binding points require OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    #[cfg(feature = "gles3")]
+    fn get_indexed_offset_alignment(&self, target: IndexedBufferTarget)
+        -> usize
+    {
+        let pname = match target {
+            IndexedBufferTarget::UniformBuffer =>
+                gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT,
+            IndexedBufferTarget::ShaderStorageBuffer =>
+                gl::SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT,
+
+            // Transform feedback and atomic counter buffer offsets must be
+            // a multiple of 4
+            IndexedBufferTarget::TransformFeedbackBuffer |
+            IndexedBufferTarget::AtomicCounterBuffer => { return 4; }
+        };
+        cmp::max(_get_positive(pname), 1)
+    }
+
+    /// Bind a whole buffer to the indexed binding point `index` of `target`,
+    /// such as to back a shader storage block with `layout(binding = index)`.
+    ///
+    /// # Note
+    /// Indexed binding points require OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature. Binding to an indexed binding
+    /// point also binds the buffer to `target` itself, which isn't tracked
+    /// by the context's bindings.
+    ///
+    /// # Panics
+    /// This function will panic in debug mode if `index` is greater than or
+    /// equal to the number of binding points of `target` (see
+    /// [`gl.get_max_indexed_bindings`](#method.get_max_indexed_bindings)),
+    /// or if an OpenGL error was generated.
+    ///
+    /// # See also
+    /// [`glBindBufferBase`](http://docs.gl/es3/glBindBufferBase) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn bind_buffer_base(&self,
+                        target: IndexedBufferTarget,
+                        index: u32,
+                        buffer: &Buffer)
+    {
+        _debug_check_binding_index(target, index);
+        _bind_buffer_base_id(target, index, buffer.id());
+    }
+
+    /// Unbind the buffer that is bound to the indexed binding point `index`
+    /// of `target`.
+    ///
+    /// # Note
+    /// Indexed binding points require OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    ///
+    /// # Panics
+    /// This function will panic under the same conditions as
+    /// [`gl.bind_buffer_base`](#method.bind_buffer_base).
+    ///
+    /// # See also
+    /// [`glBindBufferBase`](http://docs.gl/es3/glBindBufferBase) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn unbind_buffer_base(&self, target: IndexedBufferTarget, index: u32) {
+        _debug_check_binding_index(target, index);
+        _bind_buffer_base_id(target, index, 0);
+    }
+
+    /// Bind the range of `size` bytes starting `offset` bytes into a buffer
+    /// to the indexed binding point `index` of `target`.
+    ///
+    /// # Note
+    /// Indexed binding points require OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature. Binding to an indexed binding
+    /// point also binds the buffer to `target` itself, which isn't tracked
+    /// by the context's bindings.
+    ///
+    /// # Panics
+    /// This function will panic in debug mode if `index` is greater than or
+    /// equal to the number of binding points of `target`, if `size` is 0,
+    /// if `offset` isn't a multiple of [`gl.get_indexed_offset_alignment`]
+    /// (#method.get_indexed_offset_alignment), if the range is out of bounds
+    /// of the data that was sent to the buffer (if its size is known), or if
+    /// an OpenGL error was generated.
+    ///
+    /// # See also
+    /// [`glBindBufferRange`](http://docs.gl/es3/glBindBufferRange) OpenGL
+    /// docs
+    #[cfg(feature = "gles3")]
+    fn bind_buffer_range(&self,
+                         target: IndexedBufferTarget,
+                         index: u32,
+                         buffer: &Buffer,
+                         offset: usize,
+                         size: usize)
+    {
+        if cfg!(debug_assertions) {
+            _debug_check_binding_index(target, index);
+
+            let alignment = self.get_indexed_offset_alignment(target);
+            assert!(size > 0, "Can't bind an empty range of a buffer");
+            assert!(offset % alignment == 0,
+                    "Offset {} isn't a multiple of the {}-byte alignment of {:?}",
+                    offset, alignment, target);
+            if let Some(buffer_size) = buffer.size() {
+                assert!(offset + size <= buffer_size,
+                        "Bytes {}..{} are out of bounds of a buffer of {} bytes",
+                        offset, offset + size, buffer_size);
+            }
+        }
+
+        state_cache::bind_buffer(target.gl_enum(), buffer.id());
+        unsafe {
+            gl::BindBufferRange(target.gl_enum(),
+                                index as GLuint,
+                                buffer.id(),
+                                offset as GLintptr,
+                                size as GLsizeiptr);
+            dbg_gl_error! {
+                call "glBindBufferRange";
+                args (target, index, buffer.id(), offset, size);
+                GLError::InvalidEnum => "`target` is not an allowed value",
+                GLError::InvalidValue => "`index` is too large, `size` is 0, or `offset` is not aligned",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Create a new buffer that holds `count` atomic counters (each of which
+    /// is a `u32`), which are all set to 0. The buffer can be bound to an
+    /// atomic counter binding point with [`gl.bind_buffer_base`]
+    /// (#method.bind_buffer_base).
+    ///
+    /// # Note
+    /// Atomic counters require OpenGL ES 3.1 or OpenGL 4.2, so this method
+    /// is only available with the `gles31` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::ContextComputeExt;
+    ///
+    /// # fn main() {
+    /// # let mut program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let counters = gl.gen_atomic_counter_buffer(1);
+    /// gl.bind_buffer_base(glitter::ATOMIC_COUNTER_BUFFER, 0, &counters);
+    ///
+    /// // Each frame:
+    /// gl.clear_atomic_counters(&counters, 0, 1);
+    /// let (gl_program, gl) = gl.use_program(&mut program);
+    /// gl.dispatch_compute(&gl_program, 16, 1, 1);
+    /// gl.memory_barrier(glitter::BUFFER_UPDATE_BARRIER_BIT);
+    ///
+    /// let mut visible = [0u32; 1];
+    /// gl.get_atomic_counters(&counters, 0, &mut visible);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glBufferData`](http://docs.gl/es3/glBufferData) OpenGL docs
+    #[cfg(feature = "gles31")]
+    fn gen_atomic_counter_buffer(&self, count: usize) -> Buffer {
+        let mut buffer = self.gen_buffer();
+        let size = count * mem::size_of::<u32>();
+        let zeros = vec![0u32; count];
+        _bind_buffer_target(gl::ATOMIC_COUNTER_BUFFER, buffer.id());
+        unsafe {
+            gl::BufferData(gl::ATOMIC_COUNTER_BUFFER,
+                           size as GLsizeiptr,
+                           zeros.as_ptr() as *const GLvoid,
+                           gl::DYNAMIC_COPY);
+            dbg_gl_error! {
+                call "glBufferData";
+                args (gl::ATOMIC_COUNTER_BUFFER, size, gl::DYNAMIC_COPY);
+                GLError::InvalidValue => "`size` is negative",
+                GLError::OutOfMemory => "Unable to create a large enough buffer",
+                _ => "Unknown error"
+            }
+        }
+        buffer._set_size(size);
+        buffer
+    }
+
+    /// Set the atomic counters in a buffer, starting from the counter with
+    /// the index `first` (which is at the byte offset `4 * first`).
+    ///
+    /// # Panics
+    /// This function will panic if the counters are out of bounds of the data
+    /// that was sent to the buffer (if its size is known).
+    ///
+    /// # Note
+    /// Atomic counters require OpenGL ES 3.1 or OpenGL 4.2, so this method
+    /// is only available with the `gles31` feature.
+    ///
+    /// # See also
+    /// [`glBufferSubData`](http://docs.gl/es3/glBufferSubData) OpenGL docs
+    #[cfg(feature = "gles31")]
+    fn set_atomic_counters(&self,
+                           buffer: &Buffer,
+                           first: usize,
+                           values: &[u32])
+    {
+        _check_atomic_counters(buffer, first, values.len());
+        if values.is_empty() {
+            return;
+        }
+
+        let offset = first * mem::size_of::<u32>();
+        let size = values.len() * mem::size_of::<u32>();
+        _bind_buffer_target(gl::ATOMIC_COUNTER_BUFFER, buffer.id());
+        unsafe {
+            gl::BufferSubData(gl::ATOMIC_COUNTER_BUFFER,
+                              offset as GLintptr,
+                              size as GLsizeiptr,
+                              values.as_ptr() as *const GLvoid);
+            dbg_gl_error! {
+                call "glBufferSubData";
+                args (gl::ATOMIC_COUNTER_BUFFER, offset, size);
+                GLError::InvalidValue => "`offset` or `size` is out of range",
+                GLError::InvalidOperation => "The buffer is mapped",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Set `count` atomic counters in a buffer to 0, starting from the
+    /// counter with the index `first`. See [`gl.set_atomic_counters`]
+    /// (#method.set_atomic_counters) for more details.
+    ///
+    /// # Note
+    /// Atomic counters require OpenGL ES 3.1 or OpenGL 4.2, so this method
+    /// is only available with the `gles31` feature.
+    #[cfg(feature = "gles31")]
+    fn clear_atomic_counters(&self,
+                             buffer: &Buffer,
+                             first: usize,
+                             count: usize)
+    {
+        self.set_atomic_counters(buffer, first, &vec![0; count]);
+    }
+
+    /// Read the atomic counters in a buffer into `values`, starting from the
+    /// counter with the index `first`. Shaders that increment the counters
+    /// should be followed by [`gl.memory_barrier`]
+    /// (../compute_context/trait.ContextComputeExt.html#method.memory_barrier)
+    /// with `BUFFER_UPDATE_BARRIER_BIT` before reading them.
+    ///
+    /// # Panics
+    /// This function will panic if the counters are out of bounds of the data
+    /// that was sent to the buffer (if its size is known), or under the same
+    /// conditions as [`gl.get_buffer_sub_bytes`]
+    /// (#method.get_buffer_sub_bytes).
+    ///
+    /// # Note
+    /// Atomic counters require OpenGL ES 3.1 or OpenGL 4.2, so this method
+    /// is only available with the `gles31` feature. Reading the counters
+    /// waits for the shaders that write to them to finish, so this can stall
+    /// the CPU.
+    #[cfg(feature = "gles31")]
+    fn get_atomic_counters(&self,
+                           buffer: &Buffer,
+                           first: usize,
+                           values: &mut [u32])
+    {
+        _check_atomic_counters(buffer, first, values.len());
+        if values.is_empty() {
+            return;
+        }
+
+        let offset = first * mem::size_of::<u32>();
+        let size = values.len() * mem::size_of::<u32>();
+        _bind_buffer_target(gl::ATOMIC_COUNTER_BUFFER, buffer.id());
+        let ptr = values.as_mut_ptr() as *mut u8;
+        let bytes = unsafe { slice::from_raw_parts_mut(ptr, size) };
+        _get_buffer_sub_bytes(gl::ATOMIC_COUNTER_BUFFER, offset, bytes);
+    }
 }
 
 impl<C: BaseContext> ContextBufferExt for C {
@@ -268,6 +1171,41 @@ pub trait ArrayBufferContext: AContext {
         let (mut binder, rest) = self.split_array_buffer();
         (binder.borrow_mut().bind(buffer), rest)
     }
+
+    /// Bind a buffer to this context's array buffer for the duration of `f`,
+    /// which is called with the binding and the remaining context. This is a
+    /// scoped alternative to [`bind_array_buffer`](#method.bind_array_buffer),
+    /// which can be easier to use when nesting short-lived bindings.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let mut vertices = gl.gen_buffer();
+    /// let mut indices = gl.gen_buffer();
+    ///
+    /// gl.with_array_buffer(&mut vertices, |mut gl_vertices, gl| {
+    ///     gl.buffer_bytes(&mut gl_vertices, &[1, 2, 3], glitter::STATIC_DRAW);
+    ///     gl.with_element_array_buffer(&mut indices, |mut gl_indices, gl| {
+    ///         gl.buffer_bytes(&mut gl_indices, &[0, 1, 2], glitter::STATIC_DRAW);
+    ///     });
+    /// });
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glBindBuffer`](http://docs.gl/es2/glBindBuffer) OpenGL docs
+    fn with_array_buffer<'a, U, C>(self, buffer: &'a mut Buffer, f: C)
+        -> U
+        where Self: Sized,
+              C: FnOnce(ArrayBufferBinding<'a>, Self::Rest) -> U
+    {
+        let (gl_buffer, gl) = self.bind_array_buffer(buffer);
+        f(gl_buffer, gl)
+    }
 }
 
 /// An OpenGL context that has a free `GL_ELEMENT_ARRAY_BUFFER` binding.
@@ -294,6 +1232,23 @@ pub trait ElementArrayBufferContext: AContext {
         let (mut binder, rest) = self.split_element_array_buffer();
         (binder.borrow_mut().bind(buffer), rest)
     }
+
+    /// Bind a buffer to this context's element array buffer for the duration of
+    /// `f`, which is called with the binding and the remaining context. This is
+    /// a scoped alternative to
+    /// [`bind_element_array_buffer`](#method.bind_element_array_buffer), which
+    /// can be easier to use when nesting short-lived bindings.
+    ///
+    /// # See also
+    /// [`glBindBuffer`](http://docs.gl/es2/glBindBuffer) OpenGL docs
+    fn with_element_array_buffer<'a, U, C>(self, buffer: &'a mut Buffer, f: C)
+        -> U
+        where Self: Sized,
+              C: FnOnce(ElementArrayBufferBinding<'a>, Self::Rest) -> U
+    {
+        let (gl_buffer, gl) = self.bind_element_array_buffer(buffer);
+        f(gl_buffer, gl)
+    }
 }
 
 impl<BA, BE, F, P, R, T> ArrayBufferContext
@@ -361,6 +1316,23 @@ impl<'a, BA, BE, F, P, R, T> ArrayBufferContext
     }
 }
 
+impl<'a, BA, BE, F, P, R, T> ArrayBufferContext
+    for ContextOf<&'a mut BufferBinderOf<BA, BE>, F, P, R, T>
+    where BA: BorrowMut<ArrayBufferBinder>
+{
+    type Binder = &'a mut ArrayBufferBinder;
+    type Rest = ContextOf<BufferBinderOf<(), &'a mut BE>, F, P, R, T>;
+
+    fn split_array_buffer(self) -> (Self::Binder, Self::Rest) {
+        let (buffers, gl) = self.swap_buffers(());
+        let buffers = buffers.borrowed_mut();
+        let (binder, rest_buffers) = buffers.swap_array(());
+        let ((), gl) = gl.swap_buffers(rest_buffers);
+
+        (binder, gl)
+    }
+}
+
 impl<BA, BE, F, P, R, T> ElementArrayBufferContext
     for ContextOf<BufferBinderOf<BA, BE>, F, P, R, T>
     where BE: BorrowMut<ElementArrayBufferBinder>
@@ -427,6 +1399,23 @@ impl<'a, BA, BE, F, P, R, T> ElementArrayBufferContext
 }
 
 
+impl<'a, BA, BE, F, P, R, T> ElementArrayBufferContext
+    for ContextOf<&'a mut BufferBinderOf<BA, BE>, F, P, R, T>
+    where BE: BorrowMut<ElementArrayBufferBinder>
+{
+    type Binder = &'a mut ElementArrayBufferBinder;
+    type Rest = ContextOf<BufferBinderOf<&'a mut BA, ()>, F, P, R, T>;
+
+    fn split_element_array_buffer(self) -> (Self::Binder, Self::Rest) {
+        let (buffers, gl) = self.swap_buffers(());
+        let buffers = buffers.borrowed_mut();
+        let (binder, rest_buffers) = buffers.swap_element_array(());
+        let ((), gl) = gl.swap_buffers(rest_buffers);
+
+        (binder, gl)
+    }
+}
+
 
 /// An OpenGL context that has all free buffer bindings. This trait implies
 /// both [`ArrayBufferContext`](trait.ArrayBufferContext.html) and
@@ -443,6 +1432,14 @@ impl<BA, BE, F, P, R, T> BufferContext
 
 }
 
+impl<'a, BA, BE, F, P, R, T> BufferContext
+    for ContextOf<&'a mut BufferBinderOf<BA, BE>, F, P, R, T>
+    where BA: BorrowMut<ArrayBufferBinder>,
+          BE: BorrowMut<ElementArrayBufferBinder>
+{
+
+}
+
 impl<'a, BA, BE, F, P, R, T> BufferContext
     for &'a mut ContextOf<BufferBinderOf<BA, BE>, F, P, R, T>
     where BA: BorrowMut<ArrayBufferBinder>,
@@ -470,12 +1467,19 @@ pub trait BufferBinding {
     /// Returns the OpenGL binding target that this buffer binding
     /// references.
     fn target(&self) -> BufferBindingTarget;
+
+    /// Get the buffer that is bound.
+    fn buffer(&self) -> &Buffer;
+
+    /// Get the buffer that is bound, as a mutable reference.
+    fn buffer_mut(&mut self) -> &mut Buffer;
 }
 
 /// Represents a buffer that has been bound to the `GL_ARRAY_BUFFER`
 /// binding target.
 pub struct ArrayBufferBinding<'a> {
-    _phantom_ref: PhantomData<&'a mut Buffer>,
+    previous: Option<GLuint>,
+    buffer: &'a mut Buffer,
     _phantom_ptr: PhantomData<*mut ()>
 }
 
@@ -483,12 +1487,29 @@ impl<'a> BufferBinding for ArrayBufferBinding<'a> {
     fn target(&self) -> BufferBindingTarget {
         BufferBindingTarget::ArrayBuffer
     }
+
+    fn buffer(&self) -> &Buffer {
+        &*self.buffer
+    }
+
+    fn buffer_mut(&mut self) -> &mut Buffer {
+        &mut *self.buffer
+    }
+}
+
+impl<'a> Drop for ArrayBufferBinding<'a> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            _bind_buffer_id(self.target(), previous);
+        }
+    }
 }
 
 /// Represents a buffer that has been bound to the `GL_ELEMENT_ARRAY_BUFFER`
 /// binding target.
 pub struct ElementArrayBufferBinding<'a> {
-    _phantom_ref: PhantomData<&'a mut Buffer>,
+    previous: Option<GLuint>,
+    buffer: &'a mut Buffer,
     _phantom_ptr: PhantomData<*mut ()>
 }
 
@@ -496,6 +1517,22 @@ impl<'a> BufferBinding for ElementArrayBufferBinding<'a> {
     fn target(&self) -> BufferBindingTarget {
         BufferBindingTarget::ElementArrayBuffer
     }
+
+    fn buffer(&self) -> &Buffer {
+        &*self.buffer
+    }
+
+    fn buffer_mut(&mut self) -> &mut Buffer {
+        &mut *self.buffer
+    }
+}
+
+impl<'a> Drop for ElementArrayBufferBinding<'a> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            _bind_buffer_id(self.target(), previous);
+        }
+    }
 }
 
 
@@ -622,10 +1659,11 @@ impl ArrayBufferBinder {
     pub fn bind<'a>(&mut self, buffer: &'a mut Buffer) -> ArrayBufferBinding<'a>
     {
         let binding = ArrayBufferBinding {
-            _phantom_ref: PhantomData,
+            previous: restore_bindings::previous(gl::ARRAY_BUFFER_BINDING),
+            buffer: buffer,
             _phantom_ptr: PhantomData
         };
-        _bind_buffer(binding.target(), buffer);
+        _bind_buffer(binding.target(), binding.buffer());
         binding
     }
 }
@@ -654,10 +1692,11 @@ impl ElementArrayBufferBinder {
         -> ElementArrayBufferBinding<'a>
     {
         let binding = ElementArrayBufferBinding {
-            _phantom_ref: PhantomData,
+            previous: restore_bindings::previous(gl::ELEMENT_ARRAY_BUFFER_BINDING),
+            buffer: buffer,
             _phantom_ptr: PhantomData
         };
-        _bind_buffer(binding.target(), buffer);
+        _bind_buffer(binding.target(), binding.buffer());
         binding
     }
 }
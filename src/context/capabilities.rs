@@ -0,0 +1,170 @@
+//! Contains the [`Capabilities`](struct.Capabilities.html) type, which
+//! describes the implementation-defined limits of an OpenGL context.
+
+use gl;
+use gl::types::*;
+use error_policy;
+use image_data::CompressedTextureFormat;
+
+unsafe fn _get_u32(pname: GLenum) -> u32 {
+    let mut value: GLint = 0;
+    gl::GetIntegerv(pname, &mut value as *mut GLint);
+    if value < 0 { 0 } else { value as u32 }
+}
+
+unsafe fn _get_range(pname: GLenum) -> [f32; 2] {
+    let mut range: [GLfloat; 2] = [0.0; 2];
+    gl::GetFloatv(pname, range.as_mut_ptr());
+    range
+}
+
+// Query a limit that may be unsupported (such as the ES-only limits on
+// a desktop core profile), returning `None` if the query fails
+unsafe fn _get_optional_u32(pname: GLenum) -> Option<u32> {
+    // Discard any errors that were already pending, so that an error after
+    // the query can be attributed to it
+    error_policy::discard_errors();
+    let value = _get_u32(pname);
+    if gl::GetError() == gl::NO_ERROR { Some(value) } else { None }
+}
+
+unsafe fn _get_optional_range(pname: GLenum) -> Option<[f32; 2]> {
+    error_policy::discard_errors();
+    let range = _get_range(pname);
+    if gl::GetError() == gl::NO_ERROR { Some(range) } else { None }
+}
+
+unsafe fn _get_compressed_texture_formats() -> Vec<CompressedTextureFormat> {
+    let count = _get_u32(gl::NUM_COMPRESSED_TEXTURE_FORMATS);
+    let mut formats: Vec<GLint> = vec![0; count as usize];
+    if count > 0 {
+        gl::GetIntegerv(gl::COMPRESSED_TEXTURE_FORMATS, formats.as_mut_ptr());
+    }
+
+    // Formats that glitter doesn't know about are skipped
+    formats.into_iter().filter_map(|format| {
+        CompressedTextureFormat::from_gl(format as GLenum).ok()
+    }).collect()
+}
+
+/// The implementation-defined limits of an OpenGL context. These values
+/// are queried once when the context is created with
+/// [`ContextOf::current_context`]
+/// (../struct.ContextOf.html#method.current_context), and can be accessed
+/// with [`gl.capabilities`](../struct.ContextOf.html#method.capabilities).
+///
+/// # Example
+/// ```no_run
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let caps = gl.capabilities();
+/// println!("Max texture size: {}", caps.max_texture_size);
+/// ```
+///
+/// # See also
+/// [`glGet`](http://docs.gl/es2/glGet) OpenGL docs
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct Capabilities {
+    /// `GL_MAX_VERTEX_ATTRIBS`: The maximum number of vertex attributes
+    /// that can be used by a program.
+    pub max_vertex_attribs: u32,
+
+    /// `GL_MAX_VERTEX_UNIFORM_VECTORS`: The maximum number of `vec4` uniform
+    /// values that can be used by a vertex shader, or `None` if the limit
+    /// isn't supported (such as on a desktop core profile before 4.1).
+    pub max_vertex_uniform_vectors: Option<u32>,
+
+    /// `GL_MAX_FRAGMENT_UNIFORM_VECTORS`: The maximum number of `vec4`
+    /// uniform values that can be used by a fragment shader, or `None` if
+    /// the limit isn't supported.
+    pub max_fragment_uniform_vectors: Option<u32>,
+
+    /// `GL_MAX_VARYING_VECTORS`: The maximum number of `vec4` varying values
+    /// that can be passed from a vertex shader to a fragment shader, or
+    /// `None` if the limit isn't supported.
+    pub max_varying_vectors: Option<u32>,
+
+    /// `GL_MAX_TEXTURE_SIZE`: The largest width or height of a 2D texture.
+    pub max_texture_size: u32,
+
+    /// `GL_MAX_CUBE_MAP_TEXTURE_SIZE`: The largest width or height of
+    /// a cubemap texture.
+    pub max_cube_map_texture_size: u32,
+
+    /// `GL_MAX_TEXTURE_IMAGE_UNITS`: The maximum number of texture units
+    /// that can be used by a fragment shader.
+    pub max_texture_image_units: u32,
+
+    /// `GL_MAX_VERTEX_TEXTURE_IMAGE_UNITS`: The maximum number of texture
+    /// units that can be used by a vertex shader (possibly 0).
+    pub max_vertex_texture_image_units: u32,
+
+    /// `GL_MAX_COMBINED_TEXTURE_IMAGE_UNITS`: The maximum number of texture
+    /// units that can be used by all of the shaders in a program combined.
+    pub max_combined_texture_image_units: u32,
+
+    /// `GL_MAX_RENDERBUFFER_SIZE`: The largest width or height of
+    /// a renderbuffer.
+    pub max_renderbuffer_size: u32,
+
+    /// `GL_MAX_VIEWPORT_DIMS`: The largest viewport, as `[width, height]`.
+    pub max_viewport_dims: [u32; 2],
+
+    /// `GL_ALIASED_POINT_SIZE_RANGE`: The smallest and largest supported
+    /// point sizes, as `[min, max]`, or `None` if the limit isn't supported
+    /// (such as on a desktop core profile).
+    pub aliased_point_size_range: Option<[f32; 2]>,
+
+    /// `GL_ALIASED_LINE_WIDTH_RANGE`: The smallest and largest supported
+    /// line widths, as `[min, max]`.
+    pub aliased_line_width_range: [f32; 2],
+
+    /// `GL_COMPRESSED_TEXTURE_FORMATS`: The compressed texture formats
+    /// that the implementation lists as supported. Some implementations
+    /// don't list every format they support, so prefer checking a format
+    /// with [`gl.supports_compressed_format`]
+    /// (../struct.ContextOf.html#method.supports_compressed_format).
+    pub compressed_texture_formats: Vec<CompressedTextureFormat>
+}
+
+impl Capabilities {
+    /// Query the capabilities of the current OpenGL context. Prefer using
+    /// [`gl.capabilities`](../struct.ContextOf.html#method.capabilities),
+    /// which returns the capabilities that were queried when the context
+    /// was created.
+    ///
+    /// # Safety
+    /// The same rules apply to this method as the
+    /// [`ContextOf::current_context()` method]
+    /// (../struct.ContextOf.html#method.current_context).
+    pub unsafe fn current() -> Self {
+        let mut viewport_dims: [GLint; 2] = [0; 2];
+        gl::GetIntegerv(gl::MAX_VIEWPORT_DIMS, viewport_dims.as_mut_ptr());
+
+        Capabilities {
+            max_vertex_attribs: _get_u32(gl::MAX_VERTEX_ATTRIBS),
+            max_vertex_uniform_vectors:
+                _get_optional_u32(gl::MAX_VERTEX_UNIFORM_VECTORS),
+            max_fragment_uniform_vectors:
+                _get_optional_u32(gl::MAX_FRAGMENT_UNIFORM_VECTORS),
+            max_varying_vectors:
+                _get_optional_u32(gl::MAX_VARYING_VECTORS),
+            max_texture_size: _get_u32(gl::MAX_TEXTURE_SIZE),
+            max_cube_map_texture_size:
+                _get_u32(gl::MAX_CUBE_MAP_TEXTURE_SIZE),
+            max_texture_image_units: _get_u32(gl::MAX_TEXTURE_IMAGE_UNITS),
+            max_vertex_texture_image_units:
+                _get_u32(gl::MAX_VERTEX_TEXTURE_IMAGE_UNITS),
+            max_combined_texture_image_units:
+                _get_u32(gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS),
+            max_renderbuffer_size: _get_u32(gl::MAX_RENDERBUFFER_SIZE),
+            max_viewport_dims: [viewport_dims[0].max(0) as u32,
+                                viewport_dims[1].max(0) as u32],
+            aliased_point_size_range:
+                _get_optional_range(gl::ALIASED_POINT_SIZE_RANGE),
+            aliased_line_width_range:
+                _get_range(gl::ALIASED_LINE_WIDTH_RANGE),
+            compressed_texture_formats: _get_compressed_texture_formats()
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Contains the OpenGL methods for dispatching compute shaders.
+
+use gl;
+use gl::types::*;
+use context::{BaseContext, ProgramBinding};
+use buffer::Buffer;
+use program::Program;
+use types::{GLObject, GLError};
+use state_cache;
+
+bitflags! {
+    /// The kinds of memory accesses that a [`gl.memory_barrier`]
+    /// (trait.ContextComputeExt.html#method.memory_barrier) orders after
+    /// the writes made by previous shaders (such as compute shaders).
+    pub flags MemoryBarriers: ::gl::types::GLbitfield {
+        /// Vertex attributes sourced from buffers.
+        const VERTEX_ATTRIB_ARRAY_BARRIER_BIT = ::gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT,
+
+        /// Indices sourced from element array buffers.
+        const ELEMENT_ARRAY_BARRIER_BIT = ::gl::ELEMENT_ARRAY_BARRIER_BIT,
+
+        /// Uniforms sourced from uniform buffers.
+        const UNIFORM_BARRIER_BIT = ::gl::UNIFORM_BARRIER_BIT,
+
+        /// Texture fetches from shaders.
+        const TEXTURE_FETCH_BARRIER_BIT = ::gl::TEXTURE_FETCH_BARRIER_BIT,
+
+        /// Image loads and stores from shaders.
+        const SHADER_IMAGE_ACCESS_BARRIER_BIT = ::gl::SHADER_IMAGE_ACCESS_BARRIER_BIT,
+
+        /// Commands sourced from indirect buffers, such as
+        /// [`gl.dispatch_compute_indirect`]
+        /// (trait.ContextComputeExt.html#method.dispatch_compute_indirect).
+        const COMMAND_BARRIER_BIT = ::gl::COMMAND_BARRIER_BIT,
+
+        /// Reads and writes of buffer objects (such as with
+        /// `glBufferSubData` or `glMapBufferRange`).
+        const BUFFER_UPDATE_BARRIER_BIT = ::gl::BUFFER_UPDATE_BARRIER_BIT,
+
+        /// Reads and writes of framebuffer attachments.
+        const FRAMEBUFFER_BARRIER_BIT = ::gl::FRAMEBUFFER_BARRIER_BIT,
+
+        /// Accesses of shader storage buffers from shaders.
+        const SHADER_STORAGE_BARRIER_BIT = ::gl::SHADER_STORAGE_BARRIER_BIT,
+
+        /// Accesses of atomic counters from shaders.
+        const ATOMIC_COUNTER_BARRIER_BIT = ::gl::ATOMIC_COUNTER_BARRIER_BIT,
+
+        /// Every kind of memory access.
+        const ALL_BARRIER_BITS = ::gl::ALL_BARRIER_BITS
+    }
+}
+
+/// An extension trait that includes compute-shader-related OpenGL methods.
+///
+/// # Note
+/// Compute shaders require OpenGL ES 3.1 or OpenGL 4.3, so this trait is
+/// only available with the `gles31` feature.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::ContextComputeExt;
+///
+/// # fn main() {
+/// let compute_source = r##"#version 310 es
+///     layout(local_size_x = 64) in;
+///
+///     void main() {
+///         // ...
+///     }
+/// "##;
+///
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let shader = gl.build_compute_shader(compute_source).unwrap();
+/// let mut program = gl.build_program(&[shader]).unwrap();
+///
+/// let size = gl.get_compute_work_group_size(&program);
+/// let (gl_program, gl) = gl.use_program(&mut program);
+/// gl.dispatch_compute(&gl_program, 1024 / size[0], 1, 1);
+/// gl.memory_barrier(glitter::SHADER_STORAGE_BARRIER_BIT);
+/// # }
+/// ```
+pub trait ContextComputeExt: BaseContext {
+    /// Run the compute shader of the bound program, with `x * y * z`
+    /// work groups.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glDispatchCompute`](http://docs.gl/es3/glDispatchCompute)
+    /// OpenGL docs
+    fn dispatch_compute(&self,
+                        _gl_program: &ProgramBinding,
+                        x: u32,
+                        y: u32,
+                        z: u32)
+    {
+        unsafe {
+            gl::DispatchCompute(x as GLuint, y as GLuint, z as GLuint);
+            dbg_gl_error! {
+                call "glDispatchCompute";
+                args (x, y, z);
+                GLError::InvalidOperation => "The bound program has no compute shader",
+                GLError::InvalidValue => "A work group count is greater than GL_MAX_COMPUTE_WORK_GROUP_COUNT",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Run the compute shader of the bound program, reading the number of
+    /// work groups from `buffer`, as three `u32` values starting `offset`
+    /// bytes into the buffer.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # Note
+    /// This method binds `buffer` to the `GL_DISPATCH_INDIRECT_BUFFER`
+    /// target, which isn't tracked by the context's bindings.
+    ///
+    /// # See also
+    /// [`glDispatchComputeIndirect`](http://docs.gl/es3/glDispatchComputeIndirect)
+    /// OpenGL docs
+    fn dispatch_compute_indirect(&self,
+                                 _gl_program: &ProgramBinding,
+                                 buffer: &Buffer,
+                                 offset: usize)
+    {
+        _bind_dispatch_indirect_buffer(buffer);
+        unsafe {
+            gl::DispatchComputeIndirect(offset as GLintptr);
+            dbg_gl_error! {
+                call "glDispatchComputeIndirect";
+                args (offset);
+                GLError::InvalidOperation => "The bound program has no compute shader, or `offset` is out of range",
+                GLError::InvalidValue => "`offset` is not a multiple of 4",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Get the local work group size of a linked program's compute shader,
+    /// as declared with `layout(local_size_x = X, ...) in;`.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glGetProgramiv`](http://docs.gl/es3/glGetProgramiv) OpenGL docs
+    fn get_compute_work_group_size(&self, program: &Program) -> [u32; 3] {
+        let mut size: [GLint; 3] = [0; 3];
+        unsafe {
+            gl::GetProgramiv(program.id(),
+                             gl::COMPUTE_WORK_GROUP_SIZE,
+                             size.as_mut_ptr());
+            dbg_gl_error! {
+                call "glGetProgramiv";
+                args (program.id(), gl::COMPUTE_WORK_GROUP_SIZE);
+                GLError::InvalidOperation => "`program` is not linked, or has no compute shader",
+                _ => "Unknown error"
+            }
+        }
+        [size[0] as u32, size[1] as u32, size[2] as u32]
+    }
+
+    /// Order memory accesses made by later commands after the writes made
+    /// by shaders in earlier commands (such as a compute shader dispatched
+    /// with [`gl.dispatch_compute`]
+    /// (trait.ContextComputeExt.html#method.dispatch_compute)).
+    ///
+    /// # See also
+    /// [`glMemoryBarrier`](http://docs.gl/es3/glMemoryBarrier) OpenGL docs
+    fn memory_barrier(&self, barriers: MemoryBarriers) {
+        unsafe {
+            gl::MemoryBarrier(barriers.bits());
+            dbg_gl_sanity_check! {
+                call "glMemoryBarrier";
+                args (barriers);
+                GLError::InvalidValue => "`barriers` contains unsupported bits",
+                _ => "Unknown error"
+            }
+        }
+    }
+}
+
+impl<C: BaseContext> ContextComputeExt for C {
+
+}
+
+fn _bind_dispatch_indirect_buffer(buffer: &Buffer) {
+    if !state_cache::bind_buffer(gl::DISPATCH_INDIRECT_BUFFER, buffer.id()) {
+        return;
+    }
+    unsafe {
+        gl::BindBuffer(gl::DISPATCH_INDIRECT_BUFFER, buffer.id());
+        dbg_gl_sanity_check! {
+            call "glBindBuffer";
+            args (gl::DISPATCH_INDIRECT_BUFFER, buffer.id());
+            GLError::InvalidEnum => "`target` is not an allowed value",
+            _ => "Unknown error"
+        }
+    }
+}
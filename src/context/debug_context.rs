@@ -0,0 +1,520 @@
+//! Contains all of the OpenGL state types related to debug output
+//! (`KHR_debug`).
+
+use std::ptr;
+use std::slice;
+use std::process;
+use std::panic::{self, AssertUnwindSafe};
+use std::cell::{Cell, RefCell};
+use std::ffi::CStr;
+use std::ops::{Deref, DerefMut};
+use gl;
+use gl::types::*;
+use context::BaseContext;
+use types::{GLError, GLObject, Labeled};
+
+thread_local! {
+    static DEBUG_CALLBACK: RefCell<Option<Box<FnMut(DebugMessage)>>> =
+        RefCell::new(None);
+    static DEBUG_CALLBACK_GENERATION: Cell<usize> = Cell::new(0);
+}
+
+fn _replace_debug_callback(callback: Option<Box<FnMut(DebugMessage)>>) {
+    DEBUG_CALLBACK_GENERATION.with(|gen| gen.set(gen.get().wrapping_add(1)));
+    DEBUG_CALLBACK.with(|cb| *cb.borrow_mut() = callback);
+}
+
+// Debug output is set to be synchronous, so this function is always called
+// from the thread that made the OpenGL call (and that owns the callback).
+extern "system" fn _debug_callback(source: GLenum,
+                                   gl_type: GLenum,
+                                   id: GLuint,
+                                   severity: GLenum,
+                                   length: GLsizei,
+                                   message: *const GLchar,
+                                   _user_param: *mut GLvoid)
+{
+    let message = unsafe {
+        if length >= 0 {
+            let bytes = slice::from_raw_parts(message as *const u8,
+                                              length as usize);
+            String::from_utf8_lossy(bytes).into_owned()
+        }
+        else {
+            CStr::from_ptr(message).to_string_lossy().into_owned()
+        }
+    };
+    let message = DebugMessage {
+        source: DebugSource::from_gl(source).unwrap_or(DebugSource::Other),
+        ty: DebugType::from_gl(gl_type).unwrap_or(DebugType::Other),
+        severity: DebugSeverity::from_gl(severity)
+            .unwrap_or(DebugSeverity::Notification),
+        id: id,
+        message: message
+    };
+
+    // The callback is taken out while it runs, so that any debug messages
+    // generated from within the callback itself are ignored.
+    let generation = DEBUG_CALLBACK_GENERATION.with(|gen| gen.get());
+    let callback = DEBUG_CALLBACK.with(|cb| cb.borrow_mut().take());
+    if let Some(mut callback) = callback {
+        // Unwinding across an FFI boundary is undefined behavior
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            callback(message);
+        }));
+        if result.is_err() {
+            process::abort();
+        }
+
+        // Put the callback back, unless it was replaced or cleared
+        if generation == DEBUG_CALLBACK_GENERATION.with(|gen| gen.get()) {
+            DEBUG_CALLBACK.with(|cb| *cb.borrow_mut() = Some(callback));
+        }
+    }
+}
+
+/// An extension trait that includes debug-output-related OpenGL methods.
+///
+/// # Note
+/// These methods require OpenGL 4.3 or the `KHR_debug` extension. When
+/// debug output is not supported, these methods will do nothing.
+pub trait ContextDebugExt: BaseContext {
+    /// Install a callback that will be called with each debug message
+    /// generated by the OpenGL driver. Any previously-installed callback
+    /// will be replaced. Returns `false` if debug output is not supported.
+    ///
+    /// Debug output is made synchronous (`GL_DEBUG_OUTPUT_SYNCHRONOUS`), so
+    /// the callback will always be called on the current thread, during
+    /// the OpenGL call that generated the message. Debug messages generated
+    /// by OpenGL calls within the callback will be ignored.
+    ///
+    /// # Panics
+    /// If the callback panics, the process will be aborted (since a panic
+    /// can't unwind through the OpenGL driver).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use glitter::prelude::*;
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// gl.set_debug_callback(|msg| {
+    ///     println!("{:?} {:?}: {}", msg.severity, msg.ty, msg.message);
+    /// });
+    /// ```
+    ///
+    /// # See also
+    /// [`glDebugMessageCallback`](http://docs.gl/gl4/glDebugMessageCallback)
+    /// OpenGL docs
+    fn set_debug_callback<F>(&mut self, callback: F) -> bool
+        where F: FnMut(DebugMessage) + 'static
+    {
+        if !gl::DebugMessageCallback::is_loaded() {
+            return false;
+        }
+
+        _replace_debug_callback(Some(Box::new(callback)));
+        unsafe {
+            gl::Enable(gl::DEBUG_OUTPUT);
+            gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+            gl::DebugMessageCallback(_debug_callback, ptr::null());
+            dbg_gl_error! {
+                call "glDebugMessageCallback";
+                _ => "Unknown error"
+            }
+        }
+        true
+    }
+
+    /// Remove the current debug callback, if any, and disable
+    /// debug output.
+    ///
+    /// # See also
+    /// [`glDebugMessageCallback`](http://docs.gl/gl4/glDebugMessageCallback)
+    /// OpenGL docs
+    fn clear_debug_callback(&mut self) {
+        _replace_debug_callback(None);
+        if gl::DebugMessageCallback::is_loaded() {
+            unsafe {
+                gl::Disable(gl::DEBUG_OUTPUT);
+                dbg_gl_sanity_check! {
+                    call "glDisable";
+                    _ => "Unknown error"
+                }
+            }
+        }
+    }
+
+    /// Enable or disable a category of debug messages. A value of `None`
+    /// for `source`, `ty`, or `severity` matches any value (`GL_DONT_CARE`).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use glitter::prelude::*;
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    ///
+    /// // Ignore notifications (such as buffer usage hints)
+    /// gl.debug_message_control(None,
+    ///                          None,
+    ///                          Some(glitter::DEBUG_SEVERITY_NOTIFICATION),
+    ///                          false);
+    /// ```
+    ///
+    /// # See also
+    /// [`glDebugMessageControl`](http://docs.gl/gl4/glDebugMessageControl)
+    /// OpenGL docs
+    fn debug_message_control(&mut self,
+                             source: Option<DebugSource>,
+                             ty: Option<DebugType>,
+                             severity: Option<DebugSeverity>,
+                             enabled: bool)
+    {
+        if !gl::DebugMessageControl::is_loaded() {
+            return;
+        }
+
+        let gl_source = source.map_or(gl::DONT_CARE, |s| s.gl_enum());
+        let gl_type = ty.map_or(gl::DONT_CARE, |t| t.gl_enum());
+        let gl_severity = severity.map_or(gl::DONT_CARE, |s| s.gl_enum());
+        let gl_enabled = if enabled { gl::TRUE } else { gl::FALSE };
+        unsafe {
+            gl::DebugMessageControl(gl_source,
+                                    gl_type,
+                                    gl_severity,
+                                    0,
+                                    ptr::null(),
+                                    gl_enabled);
+            dbg_gl_error! {
+                call "glDebugMessageControl";
+                args (source, ty, severity, enabled);
+                GLError::InvalidOperation => "`severity` was specified along with a specific `source` or `type`",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Give an OpenGL object a human-readable label, which will show up
+    /// in debugging tools (such as RenderDoc or Nsight) and in debug
+    /// messages. Labels longer than `GL_MAX_LABEL_LENGTH` are truncated.
+    /// Does nothing if object labels are not supported.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use glitter::prelude::*;
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let mut buffer = gl.gen_buffer();
+    /// gl.label(&mut buffer, "terrain_vertices");
+    /// ```
+    ///
+    /// # See also
+    /// [`glObjectLabel`](http://docs.gl/gl4/glObjectLabel) OpenGL docs
+    fn label<O: Labeled>(&mut self, object: &mut O, label: &str) {
+        if !gl::ObjectLabel::is_loaded() {
+            return;
+        }
+
+        unsafe {
+            let mut max_length : GLint = 0;
+            gl::GetIntegerv(gl::MAX_LABEL_LENGTH, &mut max_length);
+
+            let mut length = label.len();
+            if length >= max_length as usize {
+                // Truncate to a character boundary, leaving room for
+                // the null terminator
+                length = max_length.saturating_sub(1) as usize;
+                while !label.is_char_boundary(length) {
+                    length -= 1;
+                }
+            }
+
+            gl::ObjectLabel(O::label_identifier(),
+                            object.id(),
+                            length as GLsizei,
+                            label.as_ptr() as *const GLchar);
+            dbg_gl_error! {
+                call "glObjectLabel";
+                args (O::label_identifier(), object.id(), label);
+                GLError::InvalidEnum => "`identifier` is not an accepted value",
+                GLError::InvalidValue => "`name` is not the name of an existing object, or `length` is too long",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Get the label of an OpenGL object, or `None` if the object has no
+    /// label or if object labels are not supported.
+    ///
+    /// # Note
+    /// If the label contained an invalid UTF-8 sequence, `None` will
+    /// be returned.
+    ///
+    /// # See also
+    /// [`glGetObjectLabel`](http://docs.gl/gl4/glGetObjectLabel) OpenGL docs
+    fn get_label<O: Labeled>(&self, object: &O) -> Option<String> {
+        if !gl::GetObjectLabel::is_loaded() {
+            return None;
+        }
+
+        unsafe {
+            let mut length : GLsizei = 0;
+            gl::GetObjectLabel(O::label_identifier(),
+                               object.id(),
+                               0,
+                               &mut length,
+                               ptr::null_mut());
+            if length <= 0 {
+                return None;
+            }
+
+            let mut bytes = Vec::<u8>::with_capacity(length as usize + 1);
+            gl::GetObjectLabel(O::label_identifier(),
+                               object.id(),
+                               length + 1,
+                               &mut length,
+                               bytes.as_mut_ptr() as *mut GLchar);
+            dbg_gl_error! {
+                call "glGetObjectLabel";
+                args (O::label_identifier(), object.id());
+                GLError::InvalidEnum => "`identifier` is not an accepted value",
+                GLError::InvalidValue => "`name` is not the name of an existing object",
+                _ => "Unknown error"
+            }
+            bytes.set_len(length as usize);
+
+            String::from_utf8(bytes).ok()
+        }
+    }
+    /// Push a named debug group onto the debug group stack, which is popped
+    /// again when the returned guard is dropped. Debug groups are used by
+    /// debugging tools (such as RenderDoc or Nsight) to organize the calls
+    /// in a frame capture into named passes. The guard dereferences to the
+    /// context, so it can be used to make OpenGL calls within the group.
+    ///
+    /// Messages longer than `GL_MAX_DEBUG_MESSAGE_LENGTH` are truncated.
+    /// If debug groups are not supported, or the group couldn't be pushed
+    /// (such as when the debug group stack is full), the guard will
+    /// do nothing.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use glitter::prelude::*;
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// {
+    ///     let mut gl = gl.debug_group("shadow pass");
+    ///     // Draw shadow casters...
+    /// } // The debug group is popped here
+    /// ```
+    ///
+    /// # See also
+    /// [`glPushDebugGroup`](http://docs.gl/gl4/glPushDebugGroup) OpenGL docs
+    ///
+    /// [`glPopDebugGroup`](http://docs.gl/gl4/glPopDebugGroup) OpenGL docs
+    fn debug_group<'a>(&'a mut self, message: &str) -> DebugGroupGuard<'a, Self>
+        where Self: Sized
+    {
+        if !gl::PushDebugGroup::is_loaded() {
+            return DebugGroupGuard { gl: self, pushed: false };
+        }
+
+        unsafe {
+            let mut max_length : GLint = 0;
+            gl::GetIntegerv(gl::MAX_DEBUG_MESSAGE_LENGTH, &mut max_length);
+
+            // The push fails (leaving the stack as it was) if the stack is
+            // full, so compare the depth before and after to find out
+            // whether the group needs to be popped
+            let mut depth_before : GLint = 0;
+            gl::GetIntegerv(gl::DEBUG_GROUP_STACK_DEPTH, &mut depth_before);
+
+            let mut length = message.len();
+            if length >= max_length as usize {
+                // Truncate to a character boundary, leaving room for
+                // the null terminator
+                length = max_length.saturating_sub(1) as usize;
+                while !message.is_char_boundary(length) {
+                    length -= 1;
+                }
+            }
+
+            gl::PushDebugGroup(gl::DEBUG_SOURCE_APPLICATION,
+                               0,
+                               length as GLsizei,
+                               message.as_ptr() as *const GLchar);
+            dbg_gl_error! {
+                call "glPushDebugGroup";
+                args (message);
+                GLError::InvalidValue => "`length` is too long",
+                GLError::StackOverflow => "The debug group stack is full",
+                _ => "Unknown error"
+            }
+
+            let mut depth_after : GLint = 0;
+            gl::GetIntegerv(gl::DEBUG_GROUP_STACK_DEPTH, &mut depth_after);
+
+            DebugGroupGuard { gl: self, pushed: depth_after > depth_before }
+        }
+    }
+}
+
+impl<C: BaseContext> ContextDebugExt for C {
+
+}
+
+
+
+/// A guard for a debug group, which is created with [`gl.debug_group`]
+/// (trait.ContextDebugExt.html#method.debug_group). The debug group is
+/// popped when the guard is dropped. The guard dereferences to the
+/// context, so it can be used to make OpenGL calls.
+pub struct DebugGroupGuard<'a, C: 'a> {
+    gl: &'a mut C,
+    pushed: bool
+}
+
+impl<'a, C: 'a> Deref for DebugGroupGuard<'a, C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        self.gl
+    }
+}
+
+impl<'a, C: 'a> DerefMut for DebugGroupGuard<'a, C> {
+    fn deref_mut(&mut self) -> &mut C {
+        self.gl
+    }
+}
+
+impl<'a, C: 'a> Drop for DebugGroupGuard<'a, C> {
+    fn drop(&mut self) {
+        if self.pushed {
+            unsafe {
+                gl::PopDebugGroup();
+                dbg_gl_error! {
+                    call "glPopDebugGroup";
+                    GLError::StackUnderflow => "The debug group stack is empty",
+                    _ => "Unknown error"
+                }
+            }
+        }
+    }
+}
+
+
+
+/// A debug message generated by the OpenGL driver.
+///
+/// # See also
+/// [`gl.set_debug_callback`]
+/// (trait.ContextDebugExt.html#method.set_debug_callback): Install a
+/// callback that receives debug messages.
+#[derive(Debug, Clone)]
+pub struct DebugMessage {
+    /// The source that generated the message.
+    pub source: DebugSource,
+
+    /// The type of the message.
+    pub ty: DebugType,
+
+    /// The severity of the message.
+    pub severity: DebugSeverity,
+
+    /// The implementation-dependent ID of the message.
+    pub id: GLuint,
+
+    /// The human-readable message text.
+    pub message: String
+}
+
+gl_enum! {
+    /// The possible sources of a debug message.
+    pub gl_enum DebugSource {
+        /// The message was generated by calls to the OpenGL API.
+        pub const Api as DEBUG_SOURCE_API =
+            gl::DEBUG_SOURCE_API,
+
+        /// The message was generated by the window system API.
+        pub const WindowSystem as DEBUG_SOURCE_WINDOW_SYSTEM =
+            gl::DEBUG_SOURCE_WINDOW_SYSTEM,
+
+        /// The message was generated by the shader compiler.
+        pub const ShaderCompiler as DEBUG_SOURCE_SHADER_COMPILER =
+            gl::DEBUG_SOURCE_SHADER_COMPILER,
+
+        /// The message was generated by a third-party tool
+        /// associated with OpenGL.
+        pub const ThirdParty as DEBUG_SOURCE_THIRD_PARTY =
+            gl::DEBUG_SOURCE_THIRD_PARTY,
+
+        /// The message was generated by the application itself.
+        pub const Application as DEBUG_SOURCE_APPLICATION =
+            gl::DEBUG_SOURCE_APPLICATION,
+
+        /// The message was generated by some other source.
+        pub const Other as DEBUG_SOURCE_OTHER =
+            gl::DEBUG_SOURCE_OTHER
+    }
+}
+
+gl_enum! {
+    /// The possible types of a debug message.
+    pub gl_enum DebugType {
+        /// An OpenGL error was generated.
+        pub const Error as DEBUG_TYPE_ERROR =
+            gl::DEBUG_TYPE_ERROR,
+
+        /// Deprecated behavior was used.
+        pub const DeprecatedBehavior as DEBUG_TYPE_DEPRECATED_BEHAVIOR =
+            gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR,
+
+        /// Behavior that is undefined according to the
+        /// specification was used.
+        pub const UndefinedBehavior as DEBUG_TYPE_UNDEFINED_BEHAVIOR =
+            gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR,
+
+        /// Functionality that is not portable was used.
+        pub const Portability as DEBUG_TYPE_PORTABILITY =
+            gl::DEBUG_TYPE_PORTABILITY,
+
+        /// Functionality with possible performance problems was used.
+        pub const Performance as DEBUG_TYPE_PERFORMANCE =
+            gl::DEBUG_TYPE_PERFORMANCE,
+
+        /// An annotation (such as a command stream marker).
+        pub const Marker as DEBUG_TYPE_MARKER =
+            gl::DEBUG_TYPE_MARKER,
+
+        /// A debug group was pushed.
+        pub const PushGroup as DEBUG_TYPE_PUSH_GROUP =
+            gl::DEBUG_TYPE_PUSH_GROUP,
+
+        /// A debug group was popped.
+        pub const PopGroup as DEBUG_TYPE_POP_GROUP =
+            gl::DEBUG_TYPE_POP_GROUP,
+
+        /// Some other type of message.
+        pub const Other as DEBUG_TYPE_OTHER =
+            gl::DEBUG_TYPE_OTHER
+    }
+}
+
+gl_enum! {
+    /// The possible severities of a debug message.
+    pub gl_enum DebugSeverity {
+        /// An error or highly-dangerous undefined behavior.
+        pub const High as DEBUG_SEVERITY_HIGH =
+            gl::DEBUG_SEVERITY_HIGH,
+
+        /// A major performance warning, or use of
+        /// deprecated functionality.
+        pub const Medium as DEBUG_SEVERITY_MEDIUM =
+            gl::DEBUG_SEVERITY_MEDIUM,
+
+        /// A redundant state change, a performance warning, or
+        /// unimportant undefined behavior.
+        pub const Low as DEBUG_SEVERITY_LOW =
+            gl::DEBUG_SEVERITY_LOW,
+
+        /// Anything that isn't an error or a performance issue.
+        pub const Notification as DEBUG_SEVERITY_NOTIFICATION =
+            gl::DEBUG_SEVERITY_NOTIFICATION
+    }
+}
@@ -0,0 +1,290 @@
+//! Contains the OpenGL methods for drawing with a base vertex or a base
+//! instance, which are used to draw meshes that are packed together into
+//! shared vertex and index buffers.
+
+use std::mem;
+use gl;
+use gl::types::*;
+use context::{BaseContext, ArrayBufferBinding, ElementArrayBufferBinding};
+use index_data::IndexDatumType;
+use types::{DrawingMode, GLError, UnsupportedFunction};
+
+const BASE_VERTEX_VERSIONS: &'static str = "OpenGL 3.2 or OpenGL ES 3.2";
+const BASE_INSTANCE_VERSIONS: &'static str = "OpenGL 4.2";
+
+fn _gl_index_type(index_type: IndexDatumType) -> GLenum {
+    match index_type {
+        IndexDatumType::UnsignedByte => gl::UNSIGNED_BYTE,
+        IndexDatumType::UnsignedShort => gl::UNSIGNED_SHORT,
+        IndexDatumType::UnsignedInt => gl::UNSIGNED_INT
+    }
+}
+
+// Get the byte offset of the index `first` within an element array buffer
+fn _index_offset(index_type: IndexDatumType, first: usize) -> *const GLvoid {
+    let index_size = match index_type {
+        IndexDatumType::UnsignedByte => mem::size_of::<u8>(),
+        IndexDatumType::UnsignedShort => mem::size_of::<u16>(),
+        IndexDatumType::UnsignedInt => mem::size_of::<u32>()
+    };
+    (first * index_size) as *const GLvoid
+}
+
+fn _check_loaded(call: &'static str,
+                 is_loaded: bool,
+                 requires: &'static str)
+    -> Result<(), UnsupportedFunction>
+{
+    if is_loaded {
+        Ok(())
+    }
+    else {
+        Err(UnsupportedFunction { call: call, requires: requires })
+    }
+}
+
+/// An extension trait that includes methods for drawing with a base vertex
+/// (which is added to every index before reading a vertex) or a base
+/// instance (which offsets instanced vertex attributes). These allow
+/// several meshes to share one vertex buffer and one index buffer, without
+/// rebasing each mesh's indices on the CPU.
+///
+/// Each method checks that the OpenGL function is supported by the current
+/// context, and returns an [`UnsupportedFunction`]
+/// (../../types/struct.UnsupportedFunction.html) error instead of drawing
+/// if it isn't.
+///
+/// # Note
+/// Drawing with a base vertex requires OpenGL 3.2 or OpenGL ES 3.2, and
+/// drawing with a base instance requires OpenGL 4.2. Instanced drawing
+/// requires OpenGL ES 3.0, so this trait is only available with the `gles3`
+/// feature.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::ContextDrawBaseExt;
+///
+/// # fn main() {
+/// # let mut vertex_buffer = unsafe { ::std::mem::uninitialized() };
+/// # let mut index_buffer = unsafe { ::std::mem::uninitialized() };
+/// // Two meshes, packed one after the other into the same buffers
+/// # let (first_vertex, first_index, index_count) = (0, 0, 0);
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let (gl_vertices, gl) = gl.bind_array_buffer(&mut vertex_buffer);
+/// let (gl_indices, gl) = gl.bind_element_array_buffer(&mut index_buffer);
+/// // Set up vertex attributes...
+///
+/// let result = unsafe {
+///     gl.draw_elements_base_vertex(&gl_vertices,
+///                                  &gl_indices,
+///                                  glitter::TRIANGLES,
+///                                  first_index,
+///                                  index_count,
+///                                  glitter::IndexDatumType::UnsignedShort,
+///                                  first_vertex)
+/// };
+/// if let Err(err) = result {
+///     println!("{}", err);
+/// }
+/// # }
+/// ```
+pub trait ContextDrawBaseExt: BaseContext {
+    /// Draw primitives specified by a range of the provided element array
+    /// buffer, adding `base_vertex` to each index before reading the vertex
+    /// from the provided array buffer.
+    ///
+    /// # Failures
+    /// An error will be returned if the current context doesn't support
+    /// OpenGL 3.2 or OpenGL ES 3.2.
+    ///
+    /// # Safety
+    /// The vertex attributes need to be set up before calling this method,
+    /// and every index (after adding `base_vertex`) must refer to a vertex
+    /// within the array buffer.
+    ///
+    /// # See also
+    /// [`glDrawElementsBaseVertex`](http://docs.gl/gl4/glDrawElementsBaseVertex)
+    /// OpenGL docs
+    unsafe fn draw_elements_base_vertex(&self,
+                                        _ab: &ArrayBufferBinding,
+                                        _eab: &ElementArrayBufferBinding,
+                                        mode: DrawingMode,
+                                        first: usize,
+                                        count: usize,
+                                        index_type: IndexDatumType,
+                                        base_vertex: i32)
+        -> Result<(), UnsupportedFunction>
+    {
+        try!(_check_loaded("glDrawElementsBaseVertex",
+                           gl::DrawElementsBaseVertex::is_loaded(),
+                           BASE_VERTEX_VERSIONS));
+
+        let gl_index_type = _gl_index_type(index_type);
+        gl::DrawElementsBaseVertex(mode.gl_enum(),
+                                   count as GLsizei,
+                                   gl_index_type,
+                                   _index_offset(index_type, first),
+                                   base_vertex as GLint);
+        dbg_gl_error! {
+            call "glDrawElementsBaseVertex";
+            args (mode, count, gl_index_type, first, base_vertex);
+            GLError::InvalidEnum => "`mode` or `type` is not an accepted value",
+            GLError::InvalidValue => "`count` is negative",
+            GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+            _ => "Unknown error"
+        }
+        Ok(())
+    }
+
+    /// Draw `instance_count` instances of the primitives specified by a
+    /// range of the provided element array buffer, adding `base_vertex` to
+    /// each index before reading the vertex from the provided array buffer.
+    ///
+    /// # Failures
+    /// An error will be returned if the current context doesn't support
+    /// OpenGL 3.2 or OpenGL ES 3.2.
+    ///
+    /// # Safety
+    /// The vertex attributes need to be set up before calling this method,
+    /// and every index (after adding `base_vertex`) must refer to a vertex
+    /// within the array buffer.
+    ///
+    /// # See also
+    /// [`glDrawElementsInstancedBaseVertex`](http://docs.gl/gl4/glDrawElementsInstancedBaseVertex)
+    /// OpenGL docs
+    unsafe fn draw_elements_instanced_base_vertex(&self,
+                                                  _ab: &ArrayBufferBinding,
+                                                  _eab: &ElementArrayBufferBinding,
+                                                  mode: DrawingMode,
+                                                  first: usize,
+                                                  count: usize,
+                                                  index_type: IndexDatumType,
+                                                  instance_count: usize,
+                                                  base_vertex: i32)
+        -> Result<(), UnsupportedFunction>
+    {
+        try!(_check_loaded("glDrawElementsInstancedBaseVertex",
+                           gl::DrawElementsInstancedBaseVertex::is_loaded(),
+                           BASE_VERTEX_VERSIONS));
+
+        let gl_index_type = _gl_index_type(index_type);
+        gl::DrawElementsInstancedBaseVertex(mode.gl_enum(),
+                                            count as GLsizei,
+                                            gl_index_type,
+                                            _index_offset(index_type, first),
+                                            instance_count as GLsizei,
+                                            base_vertex as GLint);
+        dbg_gl_error! {
+            call "glDrawElementsInstancedBaseVertex";
+            args (mode, count, gl_index_type, first, instance_count, base_vertex);
+            GLError::InvalidEnum => "`mode` or `type` is not an accepted value",
+            GLError::InvalidValue => "`count` or `instancecount` is negative",
+            GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+            _ => "Unknown error"
+        }
+        Ok(())
+    }
+
+    /// Draw `instance_count` instances of the primitives from the provided
+    /// array buffer binding, starting from the instance `base_instance`
+    /// (which offsets the instanced vertex attributes).
+    ///
+    /// # Failures
+    /// An error will be returned if the current context doesn't support
+    /// OpenGL 4.2.
+    ///
+    /// # Safety
+    /// The vertex attributes need to be set up before calling this method.
+    ///
+    /// # See also
+    /// [`glDrawArraysInstancedBaseInstance`](http://docs.gl/gl4/glDrawArraysInstancedBaseInstance)
+    /// OpenGL docs
+    unsafe fn draw_arrays_instanced_base_instance(&self,
+                                                  _ab: &ArrayBufferBinding,
+                                                  mode: DrawingMode,
+                                                  first: u32,
+                                                  count: usize,
+                                                  instance_count: usize,
+                                                  base_instance: u32)
+        -> Result<(), UnsupportedFunction>
+    {
+        try!(_check_loaded("glDrawArraysInstancedBaseInstance",
+                           gl::DrawArraysInstancedBaseInstance::is_loaded(),
+                           BASE_INSTANCE_VERSIONS));
+
+        gl::DrawArraysInstancedBaseInstance(mode.gl_enum(),
+                                            first as GLint,
+                                            count as GLsizei,
+                                            instance_count as GLsizei,
+                                            base_instance as GLuint);
+        dbg_gl_error! {
+            call "glDrawArraysInstancedBaseInstance";
+            args (mode, first, count, instance_count, base_instance);
+            GLError::InvalidEnum => "`mode` is not an accepted value",
+            GLError::InvalidValue => "`count` or `instancecount` is negative",
+            GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+            _ => "Unknown error"
+        }
+        Ok(())
+    }
+
+    /// Draw `instance_count` instances of the primitives specified by a
+    /// range of the provided element array buffer, adding `base_vertex` to
+    /// each index before reading the vertex from the provided array buffer,
+    /// and starting from the instance `base_instance`.
+    ///
+    /// # Failures
+    /// An error will be returned if the current context doesn't support
+    /// OpenGL 4.2.
+    ///
+    /// # Safety
+    /// The vertex attributes need to be set up before calling this method,
+    /// and every index (after adding `base_vertex`) must refer to a vertex
+    /// within the array buffer.
+    ///
+    /// # See also
+    /// [`glDrawElementsInstancedBaseVertexBaseInstance`](http://docs.gl/gl4/glDrawElementsInstancedBaseVertexBaseInstance)
+    /// OpenGL docs
+    unsafe fn draw_elements_instanced_base_instance(&self,
+                                                    _ab: &ArrayBufferBinding,
+                                                    _eab: &ElementArrayBufferBinding,
+                                                    mode: DrawingMode,
+                                                    first: usize,
+                                                    count: usize,
+                                                    index_type: IndexDatumType,
+                                                    instance_count: usize,
+                                                    base_vertex: i32,
+                                                    base_instance: u32)
+        -> Result<(), UnsupportedFunction>
+    {
+        try!(_check_loaded("glDrawElementsInstancedBaseVertexBaseInstance",
+                           gl::DrawElementsInstancedBaseVertexBaseInstance::is_loaded(),
+                           BASE_INSTANCE_VERSIONS));
+
+        let gl_index_type = _gl_index_type(index_type);
+        gl::DrawElementsInstancedBaseVertexBaseInstance(
+            mode.gl_enum(),
+            count as GLsizei,
+            gl_index_type,
+            _index_offset(index_type, first),
+            instance_count as GLsizei,
+            base_vertex as GLint,
+            base_instance as GLuint
+        );
+        dbg_gl_error! {
+            call "glDrawElementsInstancedBaseVertexBaseInstance";
+            args (mode, count, gl_index_type, first, instance_count, base_vertex, base_instance);
+            GLError::InvalidEnum => "`mode` or `type` is not an accepted value",
+            GLError::InvalidValue => "`count` or `instancecount` is negative",
+            GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+            _ => "Unknown error"
+        }
+        Ok(())
+    }
+}
+
+impl<C: BaseContext> ContextDrawBaseExt for C {
+
+}
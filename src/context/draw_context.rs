@@ -0,0 +1,255 @@
+//! Contains checked drawing methods, which require a program and a source
+//! of vertex data to be bound.
+
+use context::{BaseContext, ContextBufferExt, ArrayBufferBinding,
+              ElementArrayBufferBinding, ProgramBinding};
+use index_data::IndexDatumType;
+use types::DrawingMode;
+
+/// A binding that can provide vertex data for a draw call, such as a
+/// [`VertexBufferBinding`]
+/// (../../vertex_buffer/struct.VertexBufferBinding.html). Drawing from a
+/// raw vertex source is unsafe, since other vertex attribute arrays may
+/// still be enabled (see [`VertexSource`](trait.VertexSource.html)).
+///
+/// # Safety
+/// The vertex attributes used by the program must be set up to read from
+/// `array_buffer` while the binding is alive, and `vertex_count` must not
+/// return more vertices than `array_buffer` contains.
+pub unsafe trait RawVertexSource {
+    /// Get the array buffer binding that vertices are read from.
+    fn array_buffer(&self) -> &ArrayBufferBinding;
+
+    /// Get the number of vertices that can be drawn.
+    fn vertex_count(&self) -> usize;
+}
+
+/// A vertex source where every enabled vertex attribute array reads from
+/// the source's vertices, such as a [`GpuMeshBinding`]
+/// (../../mesh/gpu/struct.GpuMeshBinding.html) with the `gles3` feature
+/// (since its vertex array object only has the mesh's own attributes
+/// enabled). Drawing from a `VertexSource` is safe, since checking the
+/// vertices drawn against the source checks every attribute array that
+/// OpenGL reads from.
+///
+/// # Safety
+/// While the binding is alive, every enabled vertex attribute array must
+/// read from `array_buffer`, in addition to the requirements of
+/// [`RawVertexSource`](trait.RawVertexSource.html).
+pub unsafe trait VertexSource: RawVertexSource {
+
+}
+
+/// A binding that can provide index data for a draw call, such as an
+/// [`IndexBufferBinding`]
+/// (../../vertex_buffer/struct.IndexBufferBinding.html).
+///
+/// # Safety
+/// `index_count` must not return more indices than `element_array_buffer`
+/// contains, and `index_range` must return the range of every index that
+/// it contains (if any).
+///
+/// # Note
+/// The range of an `IndexBufferBinding` is tracked by [`gl.buffer_indices`]
+/// (../../vertex_buffer/trait.ContextVertexBufferExt.html#method.buffer_indices),
+/// so it will be incorrect if the underlying buffer is written to
+/// directly.
+pub unsafe trait IndexSource {
+    /// Get the element array buffer binding that indices are read from.
+    fn element_array_buffer(&self) -> &ElementArrayBufferBinding;
+
+    /// Get the data type of each index.
+    fn index_type(&self) -> IndexDatumType;
+
+    /// Get the number of indices that can be drawn.
+    fn index_count(&self) -> usize;
+
+    /// Get the smallest and largest index, or `None` if there are
+    /// no indices.
+    fn index_range(&self) -> Option<(u32, u32)>;
+}
+
+/// An extension trait that adds checked drawing methods to OpenGL
+/// contexts. Unlike [`gl.draw_arrays_range`]
+/// (../buffer_context/trait.ContextBufferExt.html#method.draw_arrays_range)
+/// and the other drawing methods of [`ContextBufferExt`]
+/// (../buffer_context/trait.ContextBufferExt.html), these methods take a
+/// [`ProgramBinding`](../program_context/struct.ProgramBinding.html) and a
+/// source of vertex data, so they can't be called without a program in use
+/// and vertex data bound, and the vertices that are drawn are checked
+/// against the size of the source.
+///
+/// `draw`, `draw_range`, and `draw_indexed` take a [`VertexSource`]
+/// (trait.VertexSource.html), such as a [`GpuMeshBinding`]
+/// (../../mesh/gpu/struct.GpuMeshBinding.html), and are safe. The `_raw`
+/// variants take any [`RawVertexSource`](trait.RawVertexSource.html),
+/// such as a [`VertexBufferBinding`]
+/// (../../vertex_buffer/struct.VertexBufferBinding.html), and are unsafe:
+/// OpenGL reads from every vertex attribute array that is enabled, which
+/// may include arrays that were enabled for other buffers, so every
+/// enabled vertex attribute array must have enough data for the vertices
+/// that are drawn.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+///
+/// # fn main() {
+/// # let mut program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+/// # let mut vbo: glitter::VertexBuffer<[f32; 2]> = unsafe { ::std::mem::uninitialized() };
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let (gl_program, gl) = gl.use_program(&mut program);
+/// let (gl_vbo, gl) = gl.bind_vertex_buffer(&mut vbo);
+/// unsafe {
+///     gl.draw_raw(&gl_program, &gl_vbo, glitter::TRIANGLES);
+/// }
+/// # }
+/// ```
+pub trait ContextDrawExt: BaseContext {
+    /// Draw every vertex from `source` with the program in use.
+    ///
+    /// # See also
+    /// [`glDrawArrays`](http://docs.gl/es2/glDrawArrays) OpenGL docs
+    fn draw<S>(&self,
+               gl_program: &ProgramBinding,
+               source: &S,
+               mode: DrawingMode)
+        where S: VertexSource
+    {
+        unsafe {
+            self.draw_raw(gl_program, source, mode);
+        }
+    }
+
+    /// Draw `count` vertices from `source`, starting at the vertex `first`,
+    /// with the program in use.
+    ///
+    /// # Panics
+    /// This function will panic if the range of vertices is out of bounds
+    /// of `source`.
+    ///
+    /// # See also
+    /// [`glDrawArrays`](http://docs.gl/es2/glDrawArrays) OpenGL docs
+    fn draw_range<S>(&self,
+                     gl_program: &ProgramBinding,
+                     source: &S,
+                     mode: DrawingMode,
+                     first: u32,
+                     count: usize)
+        where S: VertexSource
+    {
+        unsafe {
+            self.draw_range_raw(gl_program, source, mode, first, count);
+        }
+    }
+
+    /// Draw every index from `indices`, treated as indices of the vertices
+    /// from `source`, with the program in use.
+    ///
+    /// # Panics
+    /// This function will panic if any index refers to a vertex beyond the
+    /// end of `source`.
+    ///
+    /// # See also
+    /// [`glDrawElements`](http://docs.gl/es2/glDrawElements) OpenGL docs
+    fn draw_indexed<S, I>(&self,
+                          gl_program: &ProgramBinding,
+                          source: &S,
+                          indices: &I,
+                          mode: DrawingMode)
+        where S: VertexSource, I: IndexSource
+    {
+        unsafe {
+            self.draw_indexed_raw(gl_program, source, indices, mode);
+        }
+    }
+
+    /// Draw every vertex from a raw vertex source with the program in use.
+    ///
+    /// # Safety
+    /// Every enabled vertex attribute array must have at least as many
+    /// vertices as `source` (see the [trait docs]
+    /// (trait.ContextDrawExt.html)).
+    ///
+    /// # See also
+    /// [`glDrawArrays`](http://docs.gl/es2/glDrawArrays) OpenGL docs
+    unsafe fn draw_raw<S>(&self,
+                          _gl_program: &ProgramBinding,
+                          source: &S,
+                          mode: DrawingMode)
+        where S: RawVertexSource
+    {
+        self.draw_arrays_range(source.array_buffer(),
+                               mode,
+                               0,
+                               source.vertex_count());
+    }
+
+    /// Draw `count` vertices from a raw vertex source, starting at the
+    /// vertex `first`, with the program in use.
+    ///
+    /// # Panics
+    /// This function will panic if the range of vertices is out of bounds
+    /// of `source`.
+    ///
+    /// # Safety
+    /// Every enabled vertex attribute array must have at least
+    /// `first + count` vertices (see the [trait docs]
+    /// (trait.ContextDrawExt.html)).
+    ///
+    /// # See also
+    /// [`glDrawArrays`](http://docs.gl/es2/glDrawArrays) OpenGL docs
+    unsafe fn draw_range_raw<S>(&self,
+                                _gl_program: &ProgramBinding,
+                                source: &S,
+                                mode: DrawingMode,
+                                first: u32,
+                                count: usize)
+        where S: RawVertexSource
+    {
+        assert!((first as usize) + count <= source.vertex_count(),
+                "Vertices {}..{} are out of bounds of {} vertices",
+                first, (first as usize) + count, source.vertex_count());
+
+        self.draw_arrays_range(source.array_buffer(), mode, first, count);
+    }
+
+    /// Draw every index from `indices`, treated as indices of the vertices
+    /// from a raw vertex source, with the program in use.
+    ///
+    /// # Panics
+    /// This function will panic if any index refers to a vertex beyond the
+    /// end of `source`.
+    ///
+    /// # Safety
+    /// Every enabled vertex attribute array must have at least as many
+    /// vertices as `source` (see the [trait docs]
+    /// (trait.ContextDrawExt.html)).
+    ///
+    /// # See also
+    /// [`glDrawElements`](http://docs.gl/es2/glDrawElements) OpenGL docs
+    unsafe fn draw_indexed_raw<S, I>(&self,
+                                     _gl_program: &ProgramBinding,
+                                     source: &S,
+                                     indices: &I,
+                                     mode: DrawingMode)
+        where S: RawVertexSource, I: IndexSource
+    {
+        if let Some((_, max)) = indices.index_range() {
+            assert!((max as usize) < source.vertex_count(),
+                    "Index {} is out of bounds of {} vertices",
+                    max, source.vertex_count());
+        }
+
+        self.draw_n_elements_buffered(source.array_buffer(),
+                                      indices.element_array_buffer(),
+                                      mode,
+                                      indices.index_count(),
+                                      indices.index_type());
+    }
+}
+
+impl<C: BaseContext> ContextDrawExt for C {
+
+}
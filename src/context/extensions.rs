@@ -0,0 +1,243 @@
+//! Contains the [`Extensions`](struct.Extensions.html) type, which is the
+//! set of OpenGL extensions supported by a context.
+
+use std::collections::HashSet;
+use std::collections::hash_set;
+use std::ffi::CStr;
+use gl;
+use gl::types::*;
+
+unsafe fn _c_string(ptr: *const GLubyte) -> Option<String> {
+    if ptr.is_null() {
+        None
+    }
+    else {
+        let c_str = CStr::from_ptr(ptr as *const _);
+        Some(c_str.to_string_lossy().into_owned())
+    }
+}
+
+/// The set of OpenGL extensions supported by a context. The supported
+/// extensions are queried once when the context is created with
+/// [`ContextOf::current_context`]
+/// (../struct.ContextOf.html#method.current_context), and can be accessed
+/// with [`gl.extensions`](../struct.ContextOf.html#method.extensions).
+///
+/// # Note
+/// The typed helper methods (such as [`vertex_array_object`]
+/// (struct.Extensions.html#method.vertex_array_object)) only check for
+/// the presence of an extension. On newer versions of OpenGL, a feature may
+/// be part of the core API, and so the extension may not be listed.
+///
+/// # Example
+/// ```no_run
+/// let gl = unsafe { glitter::Context::current_context() };
+/// if gl.extensions().has("GL_OES_vertex_array_object") {
+///     // Use vertex array objects
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Extensions {
+    names: HashSet<String>
+}
+
+impl Extensions {
+    /// Query the extensions supported by the current OpenGL context. Prefer
+    /// using [`gl.extensions`](../struct.ContextOf.html#method.extensions),
+    /// which returns the extensions that were queried when the context
+    /// was created.
+    ///
+    /// # Safety
+    /// The same rules apply to this method as the
+    /// [`ContextOf::current_context()` method]
+    /// (../struct.ContextOf.html#method.current_context).
+    ///
+    /// # See also
+    /// [`glGetString`](http://docs.gl/es2/glGetString) and
+    /// [`glGetStringi`](http://docs.gl/gl4/glGetString) OpenGL docs
+    pub unsafe fn current() -> Self {
+        let names = match _c_string(gl::GetString(gl::EXTENSIONS)) {
+            Some(extensions) => {
+                extensions.split_whitespace().map(String::from).collect()
+            },
+            None => {
+                // Core profiles only support querying extensions one-by-one
+                // (and generate an error for `GL_EXTENSIONS`)
+                gl::GetError();
+                if !gl::GetStringi::is_loaded() {
+                    HashSet::new()
+                }
+                else {
+                    let mut count: GLint = 0;
+                    gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+                    (0..count).filter_map(|i| {
+                        _c_string(gl::GetStringi(gl::EXTENSIONS, i as GLuint))
+                    }).collect()
+                }
+            }
+        };
+
+        Extensions { names: names }
+    }
+
+    /// Returns `true` if the extension with the given name is supported,
+    /// such as `"GL_OES_vertex_array_object"`.
+    pub fn has(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Returns `true` if any of the extensions with the given names
+    /// are supported.
+    pub fn has_any(&self, names: &[&str]) -> bool {
+        names.iter().any(|name| self.has(name))
+    }
+
+    /// Return the number of supported extensions.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns `true` if no extensions are supported.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Iterate over the names of the supported extensions, in
+    /// an arbitrary order.
+    pub fn iter(&self) -> ExtensionsIter {
+        ExtensionsIter { iter: self.names.iter() }
+    }
+
+    /// Vertex array objects (`GL_OES_vertex_array_object`,
+    /// `GL_ARB_vertex_array_object`, or `GL_APPLE_vertex_array_object`).
+    pub fn vertex_array_object(&self) -> bool {
+        self.has_any(&["GL_OES_vertex_array_object",
+                       "GL_ARB_vertex_array_object",
+                       "GL_APPLE_vertex_array_object"])
+    }
+
+    /// Instanced rendering with per-instance vertex attributes
+    /// (`GL_ANGLE_instanced_arrays`, `GL_EXT_instanced_arrays`,
+    /// `GL_NV_instanced_arrays`, or `GL_ARB_instanced_arrays`).
+    pub fn instanced_arrays(&self) -> bool {
+        self.has_any(&["GL_ANGLE_instanced_arrays",
+                       "GL_EXT_instanced_arrays",
+                       "GL_NV_instanced_arrays",
+                       "GL_ARB_instanced_arrays"])
+    }
+
+    /// 32-bit (`GL_UNSIGNED_INT`) index data
+    /// (`GL_OES_element_index_uint`).
+    pub fn element_index_uint(&self) -> bool {
+        self.has("GL_OES_element_index_uint")
+    }
+
+    /// Anisotropic texture filtering (`GL_EXT_texture_filter_anisotropic`
+    /// or `GL_ARB_texture_filter_anisotropic`).
+    pub fn anisotropic_filtering(&self) -> bool {
+        self.has_any(&["GL_EXT_texture_filter_anisotropic",
+                       "GL_ARB_texture_filter_anisotropic"])
+    }
+
+    /// Depth textures (`GL_OES_depth_texture`, `GL_ANGLE_depth_texture`,
+    /// or `GL_ARB_depth_texture`).
+    pub fn depth_texture(&self) -> bool {
+        self.has_any(&["GL_OES_depth_texture",
+                       "GL_ANGLE_depth_texture",
+                       "GL_ARB_depth_texture"])
+    }
+
+    /// Packed depth/stencil formats (`GL_OES_packed_depth_stencil` or
+    /// `GL_EXT_packed_depth_stencil`).
+    pub fn packed_depth_stencil(&self) -> bool {
+        self.has_any(&["GL_OES_packed_depth_stencil",
+                       "GL_EXT_packed_depth_stencil"])
+    }
+
+    /// Floating-point textures (`GL_OES_texture_float` or
+    /// `GL_ARB_texture_float`).
+    pub fn texture_float(&self) -> bool {
+        self.has_any(&["GL_OES_texture_float", "GL_ARB_texture_float"])
+    }
+
+    /// Half-float textures (`GL_OES_texture_half_float` or
+    /// `GL_ARB_half_float_pixel`).
+    pub fn texture_half_float(&self) -> bool {
+        self.has_any(&["GL_OES_texture_half_float",
+                       "GL_ARB_half_float_pixel"])
+    }
+
+    /// The `dFdx`, `dFdy`, and `fwidth` GLSL functions in fragment shaders
+    /// (`GL_OES_standard_derivatives`).
+    pub fn standard_derivatives(&self) -> bool {
+        self.has("GL_OES_standard_derivatives")
+    }
+
+    /// sRGB textures and renderbuffers on OpenGL ES 2 (`GL_EXT_sRGB`),
+    /// which allow the `SRGB` and `SRGB_ALPHA` texel formats and the
+    /// `SRGB8_ALPHA8` renderbuffer format to be used. OpenGL ES 3 and
+    /// desktop OpenGL support sRGB formats without an extension.
+    pub fn srgb(&self) -> bool {
+        self.has("GL_EXT_sRGB")
+    }
+
+    /// Context reset notification (`GL_KHR_robustness`,
+    /// `GL_EXT_robustness`, or `GL_ARB_robustness`), which is needed to
+    /// detect context loss with [`gl.is_lost`]
+    /// (../struct.ContextOf.html#method.is_lost) before OpenGL 4.5 or
+    /// OpenGL ES 3.2.
+    pub fn robustness(&self) -> bool {
+        self.has_any(&["GL_KHR_robustness",
+                       "GL_EXT_robustness",
+                       "GL_ARB_robustness"])
+    }
+
+    /// S3TC/DXT compressed textures (`GL_EXT_texture_compression_s3tc`).
+    pub fn texture_compression_s3tc(&self) -> bool {
+        self.has("GL_EXT_texture_compression_s3tc")
+    }
+
+    /// ETC2/EAC compressed textures on desktop OpenGL
+    /// (`GL_ARB_ES3_compatibility`). OpenGL ES 3 supports ETC2/EAC formats
+    /// without an extension.
+    pub fn texture_compression_etc2(&self) -> bool {
+        self.has("GL_ARB_ES3_compatibility")
+    }
+
+    /// ASTC compressed textures (`GL_KHR_texture_compression_astc_ldr`
+    /// or `GL_OES_texture_compression_astc`).
+    pub fn texture_compression_astc(&self) -> bool {
+        self.has_any(&["GL_KHR_texture_compression_astc_ldr",
+                       "GL_OES_texture_compression_astc"])
+    }
+
+    /// Debug output, debug groups, and object labels (`GL_KHR_debug`).
+    /// See the [`ContextDebugExt`]
+    /// (../debug_context/trait.ContextDebugExt.html) trait.
+    pub fn debug(&self) -> bool {
+        self.has("GL_KHR_debug")
+    }
+}
+
+impl<'a> IntoIterator for &'a Extensions {
+    type Item = &'a str;
+    type IntoIter = ExtensionsIter<'a>;
+
+    fn into_iter(self) -> ExtensionsIter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the names of the extensions in an [`Extensions`]
+/// (struct.Extensions.html) set.
+pub struct ExtensionsIter<'a> {
+    iter: hash_set::Iter<'a, String>
+}
+
+impl<'a> Iterator for ExtensionsIter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.iter.next().map(|name| name.as_str())
+    }
+}
@@ -0,0 +1,255 @@
+//! Contains the [`Frame`](struct.Frame.html) type, which scopes the
+//! per-frame work done with a context.
+
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use context::ContextOf;
+use deletion_queue::ContextAlive;
+
+/// Statistics about a frame, returned when the frame ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameStats {
+    /// The number of the frame, starting from 0.
+    pub index: u64,
+
+    /// The (wall-clock) time spent on the CPU between beginning and
+    /// ending the frame.
+    pub cpu_time: Duration,
+
+    /// The number of deferred functions that were run at the end
+    /// of the frame.
+    pub deferred: usize,
+
+    /// The number of queued object deletions that were made at the end
+    /// of the frame (see [`gl.cleanup`]
+    /// (../struct.ContextOf.html#method.cleanup)).
+    pub deleted: usize
+}
+
+/// A subsystem that does housekeeping at the beginning and end of each
+/// frame, such as a [`GpuProfiler`](../../struct.GpuProfiler.html).
+/// Hooks are attached to a frame with [`Frame::attach`]
+/// (struct.Frame.html#method.attach).
+///
+/// Both methods do nothing by default.
+pub trait FrameHook<C> {
+    /// Called when the hook is attached to the frame with the number
+    /// `index`.
+    fn begin_frame(&mut self, _gl: &mut C, _index: u64) {
+
+    }
+
+    /// Called at the end of the frame with the number `index`, after the
+    /// frame's deferred functions have run.
+    fn end_frame(&mut self, _gl: &mut C, _index: u64) {
+
+    }
+}
+
+/// A single frame, created with [`gl.begin_frame`]
+/// (../struct.ContextOf.html#method.begin_frame). A `Frame` dereferences
+/// to the context it was created from, so it can be used for drawing.
+///
+/// The frame ends when it is dropped, or when [`Frame::end`]
+/// (struct.Frame.html#method.end) is called. At the end of the frame, the
+/// functions added with [`Frame::defer`](struct.Frame.html#method.defer)
+/// are run, in the order they were added. Then, the hooks attached with
+/// [`Frame::attach`](struct.Frame.html#method.attach) are ended (in the
+/// reverse order they were attached), and any objects that were queued for
+/// deletion are deleted.
+///
+/// # Note
+/// Frame-oriented resources, such as a [`PerFrameUbo`]
+/// (../../per_frame_ubo/struct.PerFrameUbo.html), should be updated once
+/// per `Frame`. For example, a `PerFrameUbo` should be written to and bound
+/// after the frame begins, and fenced after the last draw call of the frame.
+pub struct Frame<'a, C: 'a> {
+    gl: &'a mut C,
+    index: u64,
+    started: Instant,
+    deferred: Vec<Box<FnMut(&mut C) + 'a>>,
+    hooks: Vec<&'a mut FrameHook<C>>,
+    alive: Rc<ContextAlive>,
+    ended: bool
+}
+
+impl<'a, C: 'a> Frame<'a, C> {
+    /// Get the number of the frame, starting from 0.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Get the (wall-clock) time since the frame began.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Run a function at the end of the frame, such as deleting an object
+    /// that may still be used by a draw call in this frame, or fencing a
+    /// resource after its last use.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let old_buffer = gl.gen_buffer();
+    ///
+    /// let mut frame = gl.begin_frame();
+    /// // Draw with `old_buffer`...
+    /// frame.defer(move |_| drop(old_buffer));
+    /// # }
+    /// ```
+    pub fn defer<F>(&mut self, f: F)
+        where F: FnOnce(&mut C) + 'a
+    {
+        let mut f = Some(f);
+        self.deferred.push(Box::new(move |gl: &mut C| {
+            if let Some(f) = f.take() {
+                f(gl);
+            }
+        }));
+    }
+
+    /// Attach a hook to the frame, which is begun immediately and ended at
+    /// the end of the frame.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::FrameHook;
+    ///
+    /// struct FrameCounter {
+    ///     frames: u64
+    /// }
+    ///
+    /// impl<C> FrameHook<C> for FrameCounter {
+    ///     fn end_frame(&mut self, _gl: &mut C, _index: u64) {
+    ///         self.frames += 1;
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let mut counter = FrameCounter { frames: 0 };
+    /// loop {
+    ///     let mut frame = gl.begin_frame();
+    ///     frame.attach(&mut counter);
+    ///     // Draw the frame...
+    ///     frame.end();
+    /// }
+    /// # }
+    /// ```
+    pub fn attach<H>(&mut self, hook: &'a mut H)
+        where H: FrameHook<C> + 'a
+    {
+        hook.begin_frame(&mut *self.gl, self.index);
+        self.hooks.push(hook);
+    }
+
+    /// End the frame, running all of the deferred functions and ending the
+    /// attached hooks, and return the frame's statistics.
+    pub fn end(mut self) -> FrameStats {
+        self._end()
+    }
+
+    fn _end(&mut self) -> FrameStats {
+        self.ended = true;
+
+        let deferred = mem::replace(&mut self.deferred, vec![]);
+        let deferred_count = deferred.len();
+        for mut f in deferred {
+            (*f)(&mut *self.gl);
+        }
+
+        let hooks = mem::replace(&mut self.hooks, vec![]);
+        for hook in hooks.into_iter().rev() {
+            hook.end_frame(&mut *self.gl, self.index);
+        }
+
+        let deleted = unsafe { self.alive.flush() };
+
+        FrameStats {
+            index: self.index,
+            cpu_time: self.started.elapsed(),
+            deferred: deferred_count,
+            deleted: deleted
+        }
+    }
+}
+
+impl<'a, C: 'a> Drop for Frame<'a, C> {
+    fn drop(&mut self) {
+        if !self.ended {
+            self._end();
+        }
+    }
+}
+
+impl<'a, C: 'a> Deref for Frame<'a, C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        self.gl
+    }
+}
+
+impl<'a, C: 'a> DerefMut for Frame<'a, C> {
+    fn deref_mut(&mut self) -> &mut C {
+        self.gl
+    }
+}
+
+impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
+    /// Begin a new frame. The returned [`Frame`](frame/struct.Frame.html)
+    /// dereferences to the context, so it can be used for drawing, and
+    /// the frame ends when it is dropped (or when [`Frame::end`]
+    /// (frame/struct.Frame.html#method.end) is called).
+    ///
+    /// Frames are numbered starting from 0, and the frame number is shared
+    /// between all of the contexts derived from the same
+    /// [`ContextOf::current_context`]
+    /// (struct.ContextOf.html#method.current_context) call.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// loop {
+    ///     let mut frame = gl.begin_frame();
+    ///     frame.clear(glitter::COLOR_BUFFER_BIT);
+    ///     // Draw the rest of the frame...
+    ///     let stats = frame.end();
+    ///     println!("Frame {} took {:?}", stats.index, stats.cpu_time);
+    /// }
+    /// # }
+    /// ```
+    pub fn begin_frame(&mut self) -> Frame<Self> {
+        let index = self.shared.frame_index.get();
+        self.shared.frame_index.set(index + 1);
+        let alive = self.shared.alive.clone();
+        Frame {
+            gl: self,
+            index: index,
+            started: Instant::now(),
+            deferred: vec![],
+            hooks: vec![],
+            alive: alive,
+            ended: false
+        }
+    }
+
+    /// Get the number of frames that have been started with
+    /// [`gl.begin_frame`](struct.ContextOf.html#method.begin_frame).
+    pub fn frame_count(&self) -> u64 {
+        self.shared.frame_index.get()
+    }
+}
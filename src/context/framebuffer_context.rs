@@ -11,6 +11,9 @@ use renderbuffer::{Renderbuffer, RenderbufferTarget};
 use texture::{Texture, TextureType, ImageTargetType,
               Texture2d, Tx2dImageTarget};
 use types::{BufferBits, GLError, GLObject, GLFramebufferError};
+use restore_bindings;
+#[cfg(feature = "gles3")] use types::{Color, Viewport, BlitRect};
+#[cfg(feature = "gles3")] use texture::TextureFilter;
 
 /// An extension trait that includes framebuffer-related OpenGL methods.
 pub trait ContextFramebufferExt: BaseContext {
@@ -37,6 +40,8 @@ pub trait ContextFramebufferExt: BaseContext {
 
         gl::GenFramebuffers(1, &mut id as *mut GLuint);
         dbg_gl_sanity_check! {
+            call "glGenFramebuffers";
+            args (id);
             GLError::InvalidValue => "`n` is negative",
             _ => "Unknown error"
         }
@@ -95,6 +100,8 @@ pub trait ContextFramebufferExt: BaseContext {
                                         renderbuffer_target.gl_enum(),
                                         renderbuffer.id());
             dbg_gl_sanity_check! {
+                call "glFramebufferRenderbuffer";
+                args (gl_fbo.target(), attachment, renderbuffer.id());
                 GLError::InvalidEnum => "`target` is not `GL_FRAMEBUFFER`, `attachment` is not a valid attachment point, or `renderbuffer` is not `GL_RENDERBUFFER` and `renderbuffer` is not 0",
                 GLError::InvalidOperation => "Framebuffer 0 is bound, or `renderbuffer` is neither 0 nor the name of an existing renderbuffer object",
                 _ => "Unknown error"
@@ -134,6 +141,8 @@ pub trait ContextFramebufferExt: BaseContext {
                                      texture.id(),
                                      level as GLint);
             dbg_gl_sanity_check! {
+                call "glFramebufferTexture2D";
+                args (gl_fbo.target(), attachment, texture.id(), level);
                 GLError::InvalidEnum => "`target` is not `GL_FRAMEBUFFER`, `attachment` is not an accepted attachment point, or `textarget` is not an accepted texture target and texture is not 0",
                 GLError::InvalidValue => "`level` is not 0 and `texture` is not 0",
                 GLError::InvalidOperation => "Framebuffer object 0 is bound, `texture` is neither 0 nor the name of an existing texture object, or `textarget` is not a valid target for `texture`",
@@ -155,17 +164,452 @@ pub trait ContextFramebufferExt: BaseContext {
         unsafe {
             gl::Clear(buffers.bits());
             dbg_gl_sanity_check! {
+                call "glClear";
+                args (buffers);
                 GLError::InvalidValue => "`mask` includes a bit other than an allowed value",
                 _ => "Unkown error"
             }
         }
     }
+
+    /// Clear a single draw buffer (set with [`gl.draw_buffers`]
+    /// (trait.ContextFramebufferExt.html#method.draw_buffers)) to `color`,
+    /// without changing the clear color. This allows each render target
+    /// to be cleared to a different value.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::Color;
+    ///
+    /// # fn main() {
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// // Clear the albedo target to black, and the normal target to +Z
+    /// gl.clear_color_buffer(0, Color::rgba(0.0, 0.0, 0.0, 1.0));
+    /// gl.clear_color_buffer(1, Color::rgba(0.5, 0.5, 1.0, 0.0));
+    /// gl.clear_depth_buffer(1.0);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glClearBuffer`](http://docs.gl/es3/glClearBuffer) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn clear_color_buffer(&self, draw_buffer: u32, color: Color) {
+        let value = [color.r, color.g, color.b, color.a];
+        unsafe {
+            gl::ClearBufferfv(gl::COLOR, draw_buffer as GLint, value.as_ptr());
+            dbg_gl_error! {
+                call "glClearBufferfv";
+                args (gl::COLOR, draw_buffer, value);
+                GLError::InvalidValue => "`drawbuffer` is greater than or equal to `GL_MAX_DRAW_BUFFERS`",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Clear the depth buffer to `depth`, without changing the depth
+    /// clear value.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glClearBuffer`](http://docs.gl/es3/glClearBuffer) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn clear_depth_buffer(&self, depth: f32) {
+        unsafe {
+            gl::ClearBufferfv(gl::DEPTH, 0, &depth);
+            dbg_gl_sanity_check! {
+                call "glClearBufferfv";
+                args (gl::DEPTH, 0, depth);
+                GLError::InvalidValue => "`drawbuffer` is not 0",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Clear the stencil buffer to `stencil`, without changing the stencil
+    /// clear value.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glClearBuffer`](http://docs.gl/es3/glClearBuffer) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn clear_stencil_buffer(&self, stencil: i32) {
+        unsafe {
+            gl::ClearBufferiv(gl::STENCIL, 0, &stencil);
+            dbg_gl_sanity_check! {
+                call "glClearBufferiv";
+                args (gl::STENCIL, 0, stencil);
+                GLError::InvalidValue => "`drawbuffer` is not 0",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Clear the depth buffer to `depth` and the stencil buffer to
+    /// `stencil` at once, without changing the depth or stencil clear
+    /// values.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glClearBuffer`](http://docs.gl/es3/glClearBuffer) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn clear_depth_stencil_buffer(&self, depth: f32, stencil: i32) {
+        unsafe {
+            gl::ClearBufferfi(gl::DEPTH_STENCIL, 0, depth, stencil);
+            dbg_gl_sanity_check! {
+                call "glClearBufferfi";
+                args (gl::DEPTH_STENCIL, 0, depth, stencil);
+                GLError::InvalidValue => "`drawbuffer` is not 0",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Set the color attachments that fragment shader outputs are written
+    /// to, for drawing to multiple render targets (such as the G-buffer
+    /// of a deferred renderer). The output at location `i` is written to
+    /// `buffers[i]`, which must be `COLOR_ATTACHMENT<i>`.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # Failures
+    /// An error will be returned (and the draw buffers will not be
+    /// changed) if there are more buffers than [`gl.get_max_draw_buffers`]
+    /// (trait.ContextFramebufferExt.html#method.get_max_draw_buffers), if a
+    /// buffer's index is not less than [`gl.get_max_color_attachments`]
+    /// (trait.ContextFramebufferExt.html#method.get_max_color_attachments),
+    /// or if `buffers[i]` is not `COLOR_ATTACHMENT<i>`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// # let mut g_buffer = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let (mut gl_fbo, gl) = gl.bind_framebuffer(&mut g_buffer);
+    /// // Write albedo, normals, and material properties at once
+    /// gl.draw_buffers(&mut gl_fbo, &[glitter::COLOR_ATTACHMENT0,
+    ///                                glitter::COLOR_ATTACHMENT1,
+    ///                                glitter::COLOR_ATTACHMENT2])
+    ///   .unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glDrawBuffers`](http://docs.gl/es3/glDrawBuffers) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn draw_buffers(&self,
+                    _gl_fbo: &mut FramebufferBinding,
+                    buffers: &[FramebufferAttachment])
+        -> Result<(), GLError>
+    {
+        let max_draw_buffers = self.get_max_draw_buffers();
+        if buffers.len() > max_draw_buffers as usize {
+            let msg = format!("Error setting draw buffers: {} buffers were provided, but only {} are supported",
+                              buffers.len(),
+                              max_draw_buffers);
+            return Err(GLError::Message(msg));
+        }
+
+        let max_color_attachments = self.get_max_color_attachments();
+        for (i, buffer) in buffers.iter().enumerate() {
+            match buffer.color_index() {
+                Some(index) if index as usize == i => {
+                    if index >= max_color_attachments {
+                        let msg = format!("Error setting draw buffers: {:?} is not supported (only {} color attachments are supported)",
+                                          buffer,
+                                          max_color_attachments);
+                        return Err(GLError::Message(msg));
+                    }
+                },
+                _ => {
+                    let msg = format!("Error setting draw buffers: expected `COLOR_ATTACHMENT{}` for output {}, found {:?}",
+                                      i, i, buffer);
+                    return Err(GLError::Message(msg));
+                }
+            }
+        }
+
+        let gl_buffers: Vec<GLenum> = buffers.iter()
+            .map(|buffer| buffer.gl_enum())
+            .collect();
+        unsafe {
+            gl::DrawBuffers(gl_buffers.len() as GLsizei, gl_buffers.as_ptr());
+            dbg_gl_error! {
+                call "glDrawBuffers";
+                args (gl_buffers);
+                GLError::InvalidEnum => "A buffer is not an accepted value",
+                GLError::InvalidOperation => "A buffer is not `GL_COLOR_ATTACHMENT<i>` for its index",
+                GLError::InvalidValue => "`n` is greater than `GL_MAX_DRAW_BUFFERS`",
+                _ => "Unknown error"
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the maximum number of draw buffers that can be written to at
+    /// once with [`gl.draw_buffers`]
+    /// (trait.ContextFramebufferExt.html#method.draw_buffers)
+    /// (`GL_MAX_DRAW_BUFFERS`).
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    #[cfg(feature = "gles3")]
+    fn get_max_draw_buffers(&self) -> u32 {
+        let mut max: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_DRAW_BUFFERS, &mut max);
+        }
+        if max < 0 { 0 } else { max as u32 }
+    }
+
+    /// Get the maximum number of color attachment points of a framebuffer
+    /// (`GL_MAX_COLOR_ATTACHMENTS`).
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    #[cfg(feature = "gles3")]
+    fn get_max_color_attachments(&self) -> u32 {
+        let mut max: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_COLOR_ATTACHMENTS, &mut max);
+        }
+        if max < 0 { 0 } else { max as u32 }
+    }
+
+    /// Hint that the contents of some of a framebuffer's attachments are no
+    /// longer needed (such as the depth buffer, after a pass has finished
+    /// drawing). On tile-based GPUs (which most mobile GPUs are), this
+    /// avoids writing the attachments' contents back to memory.
+    ///
+    /// The contents of the attachments are undefined after invalidating
+    /// them, until they are cleared or drawn to again. Since invalidation is
+    /// only a hint, this method does nothing if `glInvalidateFramebuffer`
+    /// isn't supported (such as with OpenGL 4.2 or earlier).
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// # let mut fbo = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let (mut gl_fbo, gl) = gl.bind_framebuffer(&mut fbo);
+    /// // Draw the scene...
+    ///
+    /// // Only the color buffer is used after this pass
+    /// gl.invalidate_framebuffer(&mut gl_fbo, &[glitter::DEPTH_ATTACHMENT,
+    ///                                         glitter::STENCIL_ATTACHMENT]);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glInvalidateFramebuffer`](http://docs.gl/es3/glInvalidateFramebuffer)
+    /// OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn invalidate_framebuffer(&self,
+                              gl_fbo: &mut FramebufferBinding,
+                              attachments: &[FramebufferAttachment])
+    {
+        if !gl::InvalidateFramebuffer::is_loaded() {
+            return;
+        }
+
+        let gl_attachments: Vec<GLenum> = attachments.iter()
+            .map(|attachment| attachment.gl_enum())
+            .collect();
+        unsafe {
+            gl::InvalidateFramebuffer(gl_fbo.target().gl_enum(),
+                                      gl_attachments.len() as GLsizei,
+                                      gl_attachments.as_ptr());
+            dbg_gl_error! {
+                call "glInvalidateFramebuffer";
+                args (gl_fbo.target(), gl_attachments);
+                GLError::InvalidEnum => "`target` or an attachment is not an accepted value",
+                GLError::InvalidOperation => "An attachment's index is greater than or equal to `GL_MAX_COLOR_ATTACHMENTS`",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Copy a rectangle of pixels from the framebuffer bound to
+    /// `GL_READ_FRAMEBUFFER` to the framebuffer bound to
+    /// `GL_DRAW_FRAMEBUFFER`. The buffers specified by `mask` are copied
+    /// from `src_rect` to `dst_rect`, scaling with `filter` if the
+    /// rectangles have different sizes. This is used for resolving
+    /// multisampled framebuffers, mirroring, and post-processing copies.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::{BlitRect, Viewport};
+    ///
+    /// # fn main() {
+    /// # let mut scene_fbo = unsafe { ::std::mem::uninitialized() };
+    /// # let mut mirror_fbo = unsafe { ::std::mem::uninitialized() };
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let (gl_read, mut gl_draw, gl) =
+    ///     gl.bind_read_draw_framebuffers(&mut scene_fbo, Some(&mut mirror_fbo));
+    /// // Copy the scene, mirrored horizontally
+    /// let rect = BlitRect::from(Viewport::new(0, 0, 640, 480));
+    /// gl.blit_framebuffer(&gl_read,
+    ///                     &mut gl_draw,
+    ///                     rect,
+    ///                     rect.flip_x(),
+    ///                     glitter::COLOR_BUFFER_BIT,
+    ///                     glitter::NEAREST);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glBlitFramebuffer`](http://docs.gl/es3/glBlitFramebuffer) OpenGL
+    /// docs
+    #[cfg(feature = "gles3")]
+    fn blit_framebuffer(&self,
+                        _gl_read: &ReadFramebufferBinding,
+                        _gl_draw: &mut DrawFramebufferBinding,
+                        src_rect: BlitRect,
+                        dst_rect: BlitRect,
+                        mask: BufferBits,
+                        filter: TextureFilter)
+    {
+        unsafe {
+            _blit_framebuffer(src_rect, dst_rect, mask, filter);
+        }
+    }
+
+    /// Resolve the color buffer of a multisampled framebuffer (such as one
+    /// with a renderbuffer created with [`gl.storage_multisample`]
+    /// (../renderbuffer_context/trait.ContextRenderbufferExt.html#method.storage_multisample))
+    /// into a single-sampled framebuffer (or the default framebuffer, if
+    /// `draw` is `None`). Both framebuffers must be `width` by `height`
+    /// pixels.
+    ///
+    /// Unlike [`gl.blit_framebuffer`]
+    /// (trait.ContextFramebufferExt.html#method.blit_framebuffer), the
+    /// framebuffers don't need to be bound: they are bound to the
+    /// `GL_READ_FRAMEBUFFER` and `GL_DRAW_FRAMEBUFFER` targets during the
+    /// copy, and the previous bindings are restored afterwards.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let mut msaa_rbo = unsafe { gl.gen_renderbuffer() };
+    /// {
+    ///     let (mut gl_rbo, gl) = (&mut gl).bind_renderbuffer(&mut msaa_rbo);
+    ///     let samples = gl.get_max_samples().min(4);
+    ///     gl.storage_multisample(&mut gl_rbo, samples, glitter::RGBA4, 1280, 720);
+    /// }
+    /// let msaa_fbo = (&mut gl).build_framebuffer()
+    ///     .renderbuffer(glitter::COLOR_ATTACHMENT0, &mut msaa_rbo)
+    ///     .unwrap();
+    ///
+    /// // Draw the scene into `msaa_fbo`...
+    ///
+    /// // Resolve to the screen
+    /// gl.resolve_framebuffer(&msaa_fbo, None, 1280, 720);
+    /// # }
+    /// ```
+    #[cfg(feature = "gles3")]
+    fn resolve_framebuffer(&self,
+                           read: &Framebuffer,
+                           draw: Option<&Framebuffer>,
+                           width: u32,
+                           height: u32)
+    {
+        let draw_id = match draw {
+            Some(draw) => draw.id(),
+            None => 0
+        };
+        let rect = BlitRect::from(Viewport::new(0, 0, width, height));
+
+        unsafe {
+            let mut prev_read: GLint = 0;
+            let mut prev_draw: GLint = 0;
+            gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut prev_read);
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut prev_draw);
+
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, read.id());
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, draw_id);
+            _blit_framebuffer(rect,
+                              rect,
+                              ::types::COLOR_BUFFER_BIT,
+                              TextureFilter::Nearest);
+
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, prev_read as GLuint);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, prev_draw as GLuint);
+        }
+    }
 }
 
 impl<C: BaseContext> ContextFramebufferExt for C {
 
 }
 
+#[cfg(feature = "gles3")]
+unsafe fn _blit_framebuffer(src_rect: BlitRect,
+                            dst_rect: BlitRect,
+                            mask: BufferBits,
+                            filter: TextureFilter)
+{
+    gl::BlitFramebuffer(src_rect.x0 as GLint,
+                        src_rect.y0 as GLint,
+                        src_rect.x1 as GLint,
+                        src_rect.y1 as GLint,
+                        dst_rect.x0 as GLint,
+                        dst_rect.y0 as GLint,
+                        dst_rect.x1 as GLint,
+                        dst_rect.y1 as GLint,
+                        mask.bits(),
+                        filter.gl_enum());
+    dbg_gl_error! {
+        call "glBlitFramebuffer";
+        args (src_rect, dst_rect, mask, filter);
+        GLError::InvalidValue => "`mask` includes a bit other than an allowed value",
+        GLError::InvalidOperation => "The framebuffers have incompatible formats or sample counts, or `filter` is `LINEAR` while copying depth or stencil values",
+        GLError::InvalidFramebufferOperation => "The read or draw framebuffer is not framebuffer-complete",
+        _ => "Unknown error"
+    }
+}
+
 
 
 enum BuilderAttachment<'a> {
@@ -332,13 +776,107 @@ gl_enum! {
         pub const ColorAttachment0 as COLOR_ATTACHMENT0 =
             gl::COLOR_ATTACHMENT0,
 
+        /// The color buffer attachment point with index 1, which is used
+        /// when drawing to multiple render targets (see [`gl.draw_buffers`]
+        /// (trait.ContextFramebufferExt.html#method.draw_buffers)).
+        pub const ColorAttachment1 as COLOR_ATTACHMENT1 =
+            gl::COLOR_ATTACHMENT1,
+
+        /// The color buffer attachment point with index 2, which is used
+        /// when drawing to multiple render targets (see [`gl.draw_buffers`]
+        /// (trait.ContextFramebufferExt.html#method.draw_buffers)).
+        pub const ColorAttachment2 as COLOR_ATTACHMENT2 =
+            gl::COLOR_ATTACHMENT2,
+
+        /// The color buffer attachment point with index 3, which is used
+        /// when drawing to multiple render targets (see [`gl.draw_buffers`]
+        /// (trait.ContextFramebufferExt.html#method.draw_buffers)).
+        pub const ColorAttachment3 as COLOR_ATTACHMENT3 =
+            gl::COLOR_ATTACHMENT3,
+
+        /// The color buffer attachment point with index 4, which is used
+        /// when drawing to multiple render targets (see [`gl.draw_buffers`]
+        /// (trait.ContextFramebufferExt.html#method.draw_buffers)).
+        pub const ColorAttachment4 as COLOR_ATTACHMENT4 =
+            gl::COLOR_ATTACHMENT4,
+
+        /// The color buffer attachment point with index 5, which is used
+        /// when drawing to multiple render targets (see [`gl.draw_buffers`]
+        /// (trait.ContextFramebufferExt.html#method.draw_buffers)).
+        pub const ColorAttachment5 as COLOR_ATTACHMENT5 =
+            gl::COLOR_ATTACHMENT5,
+
+        /// The color buffer attachment point with index 6, which is used
+        /// when drawing to multiple render targets (see [`gl.draw_buffers`]
+        /// (trait.ContextFramebufferExt.html#method.draw_buffers)).
+        pub const ColorAttachment6 as COLOR_ATTACHMENT6 =
+            gl::COLOR_ATTACHMENT6,
+
+        /// The color buffer attachment point with index 7, which is used
+        /// when drawing to multiple render targets (see [`gl.draw_buffers`]
+        /// (trait.ContextFramebufferExt.html#method.draw_buffers)).
+        pub const ColorAttachment7 as COLOR_ATTACHMENT7 =
+            gl::COLOR_ATTACHMENT7,
+
         /// The depth buffer attachment point.
         pub const DepthAttachment as DEPTH_ATTACHMENT =
             gl::DEPTH_ATTACHMENT,
 
         /// The stencil buffer attachment point.
         pub const StencilAttachment as STENCIL_ATTACHMENT =
-            gl::STENCIL_ATTACHMENT
+            gl::STENCIL_ATTACHMENT,
+
+        /// The depth and stencil buffer attachment points at once, which
+        /// is used to attach a packed depth/stencil texture or
+        /// renderbuffer.
+        ///
+        /// # Note
+        /// This attachment point requires OpenGL ES 3.0, so it can only
+        /// be used with the `gles3` feature.
+        pub const DepthStencilAttachment as DEPTH_STENCIL_ATTACHMENT =
+            gl::DEPTH_STENCIL_ATTACHMENT
+    }
+}
+
+// Every color attachment, in index order
+const COLOR_ATTACHMENTS: [FramebufferAttachment; 8] = [
+    FramebufferAttachment::ColorAttachment0,
+    FramebufferAttachment::ColorAttachment1,
+    FramebufferAttachment::ColorAttachment2,
+    FramebufferAttachment::ColorAttachment3,
+    FramebufferAttachment::ColorAttachment4,
+    FramebufferAttachment::ColorAttachment5,
+    FramebufferAttachment::ColorAttachment6,
+    FramebufferAttachment::ColorAttachment7
+];
+
+impl FramebufferAttachment {
+    /// Get the color attachment point with the given index, or `None` if
+    /// `index` is 8 or greater.
+    ///
+    /// # Example
+    /// ```
+    /// use glitter::FramebufferAttachment;
+    ///
+    /// assert_eq!(FramebufferAttachment::color(2), Some(glitter::COLOR_ATTACHMENT2));
+    /// assert_eq!(FramebufferAttachment::color(8), None);
+    /// ```
+    pub fn color(index: u32) -> Option<Self> {
+        COLOR_ATTACHMENTS.get(index as usize).cloned()
+    }
+
+    /// Get the index of a color attachment point, or `None` if the
+    /// attachment point is not a color attachment point.
+    ///
+    /// # Example
+    /// ```
+    /// assert_eq!(glitter::COLOR_ATTACHMENT3.color_index(), Some(3));
+    /// assert_eq!(glitter::DEPTH_ATTACHMENT.color_index(), None);
+    /// ```
+    pub fn color_index(&self) -> Option<u32> {
+        COLOR_ATTACHMENTS.iter()
+            .position(|attachment| attachment == self)
+            .map(|index| index as u32)
     }
 }
 
@@ -365,6 +903,46 @@ pub trait FramebufferContext: AContext {
         let (mut binder, rest) = self.split_framebuffer();
         (binder.borrow_mut().bind(fbo), rest)
     }
+
+    /// Bind a framebuffer to this context for the duration of `f`, which is
+    /// called with the binding and the remaining context. This is a scoped
+    /// alternative to [`bind_framebuffer`](#method.bind_framebuffer), which can
+    /// be easier to use when nesting short-lived bindings.
+    ///
+    /// # See also
+    /// [`glBindFramebuffer`](http://docs.gl/es2/glBindFramebuffer) OpenGL docs
+    fn with_framebuffer<'a, U, C>(self, fbo: &'a mut Framebuffer, f: C)
+        -> U
+        where Self: Sized,
+              C: FnOnce(FramebufferBinding<'a>, Self::Rest) -> U
+    {
+        let (gl_fbo, gl) = self.bind_framebuffer(fbo);
+        f(gl_fbo, gl)
+    }
+
+    /// Bind a framebuffer to read from and a framebuffer to draw to (or the
+    /// default framebuffer, if `draw` is `None`), returning a new context
+    /// and a binding for each. Together, the `GL_READ_FRAMEBUFFER` and
+    /// `GL_DRAW_FRAMEBUFFER` targets make up the context's framebuffer
+    /// binding, so both are bound at once.
+    ///
+    /// # Note
+    /// Separate read and draw framebuffers require OpenGL ES 3.0, so this
+    /// method is only available with the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glBindFramebuffer`](http://docs.gl/es3/glBindFramebuffer) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn bind_read_draw_framebuffers<'a, 'b>(self,
+                                           read: &'a mut Framebuffer,
+                                           draw: Option<&'b mut Framebuffer>)
+        -> (ReadFramebufferBinding<'a>, DrawFramebufferBinding<'b>, Self::Rest)
+        where Self: Sized
+    {
+        let (mut binder, rest) = self.split_framebuffer();
+        let (gl_read, gl_draw) = binder.borrow_mut().bind_read_draw(read, draw);
+        (gl_read, gl_draw, rest)
+    }
 }
 
 impl<B, F, P, R, T> FramebufferContext for ContextOf<B, F, P, R, T>
@@ -395,6 +973,7 @@ impl<'a, B, F, P, R, T> FramebufferContext for &'a mut ContextOf<B, F, P, R, T>
 /// Represents a framebuffer that has been bound to the `GL_FRAMEBUFFER`
 /// binding target.
 pub struct FramebufferBinding<'a> {
+    previous: Option<GLuint>,
     _phantom_ref: PhantomData<&'a mut Framebuffer>,
     _phantom_ptr: PhantomData<*mut ()>
 }
@@ -405,6 +984,70 @@ impl<'a> FramebufferBinding<'a> {
     }
 }
 
+impl<'a> Drop for FramebufferBinding<'a> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            _bind_framebuffer(self.target().gl_enum(), previous);
+        }
+    }
+}
+
+fn _bind_framebuffer(target: GLenum, id: GLuint) {
+    unsafe {
+        gl::BindFramebuffer(target, id);
+        dbg_gl_sanity_check! {
+            call "glBindFramebuffer";
+            args (target, id);
+            GLError::InvalidEnum => "`target` is not an allowed value",
+            _ => "Unknown error"
+        }
+    }
+}
+
+/// Represents a framebuffer that has been bound to the
+/// `GL_READ_FRAMEBUFFER` binding target.
+///
+/// # Note
+/// This type requires OpenGL ES 3.0, so it is only available with the
+/// `gles3` feature.
+#[cfg(feature = "gles3")]
+pub struct ReadFramebufferBinding<'a> {
+    previous: Option<GLuint>,
+    _phantom_ref: PhantomData<&'a mut Framebuffer>,
+    _phantom_ptr: PhantomData<*mut ()>
+}
+
+/// Represents a framebuffer (or the default framebuffer) that has been
+/// bound to the `GL_DRAW_FRAMEBUFFER` binding target.
+///
+/// # Note
+/// This type requires OpenGL ES 3.0, so it is only available with the
+/// `gles3` feature.
+#[cfg(feature = "gles3")]
+pub struct DrawFramebufferBinding<'a> {
+    previous: Option<GLuint>,
+    _phantom_ref: PhantomData<&'a mut Framebuffer>,
+    _phantom_ptr: PhantomData<*mut ()>
+}
+
+#[cfg(feature = "gles3")]
+impl<'a> Drop for ReadFramebufferBinding<'a> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            _bind_framebuffer(gl::READ_FRAMEBUFFER, previous);
+        }
+    }
+}
+
+#[cfg(feature = "gles3")]
+impl<'a> Drop for DrawFramebufferBinding<'a> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            _bind_framebuffer(gl::DRAW_FRAMEBUFFER, previous);
+        }
+    }
+}
+
 /// The OpenGL state representing the `GL_FRAMEBUFFER` target.
 pub struct FramebufferBinder {
     _phantom: PhantomData<*mut ()>
@@ -430,6 +1073,7 @@ impl FramebufferBinder {
     /// binding.
     pub unsafe fn current_binding(&mut self) -> FramebufferBinding {
         FramebufferBinding {
+            previous: None,
             _phantom_ref: PhantomData,
             _phantom_ptr: PhantomData
         }
@@ -440,16 +1084,51 @@ impl FramebufferBinder {
         -> FramebufferBinding<'a>
     {
         let binding = FramebufferBinding {
+            previous: restore_bindings::previous(gl::FRAMEBUFFER_BINDING),
+            _phantom_ref: PhantomData,
+            _phantom_ptr: PhantomData
+        };
+        _bind_framebuffer(binding.target().gl_enum(), fbo.id());
+        binding
+    }
+
+    /// Bind a framebuffer to the `GL_READ_FRAMEBUFFER` target and a
+    /// framebuffer to the `GL_DRAW_FRAMEBUFFER` target (or the default
+    /// framebuffer, if `draw` is `None`), returning a binding for each.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    #[cfg(feature = "gles3")]
+    pub fn bind_read_draw<'a, 'b>(&mut self,
+                                  read: &'a mut Framebuffer,
+                                  draw: Option<&'b mut Framebuffer>)
+        -> (ReadFramebufferBinding<'a>, DrawFramebufferBinding<'b>)
+    {
+        let draw_id = match draw {
+            Some(draw) => draw.id(),
+            None => 0
+        };
+        let gl_read = ReadFramebufferBinding {
+            previous: restore_bindings::previous(gl::READ_FRAMEBUFFER_BINDING),
+            _phantom_ref: PhantomData,
+            _phantom_ptr: PhantomData
+        };
+        let gl_draw = DrawFramebufferBinding {
+            previous: restore_bindings::previous(gl::DRAW_FRAMEBUFFER_BINDING),
             _phantom_ref: PhantomData,
             _phantom_ptr: PhantomData
         };
         unsafe {
-            gl::BindFramebuffer(binding.target().gl_enum(), fbo.id());
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, read.id());
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, draw_id);
             dbg_gl_sanity_check! {
-                GLError::InvalidEnum => "`target` is not `GL_FRAMEBUFFER`",
+                call "glBindFramebuffer";
+                args (read.id(), draw_id);
+                GLError::InvalidEnum => "`target` is not an allowed value",
                 _ => "Unknown error"
             }
         }
-        binding
+        (gl_read, gl_draw)
     }
 }
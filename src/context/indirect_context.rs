@@ -0,0 +1,315 @@
+//! Contains the OpenGL methods for indirect drawing, where the parameters
+//! of each draw call are read from a buffer.
+
+use std::mem;
+use std::slice;
+use gl;
+use gl::types::*;
+use context::{BaseContext, ArrayBufferBinding, ElementArrayBufferBinding};
+use buffer::Buffer;
+use index_data::IndexDatumType;
+use types::{DrawingMode, GLObject, GLError};
+use state_cache;
+
+/// The parameters of a single [`gl.draw_arrays_indirect`]
+/// (trait.ContextIndirectExt.html#method.draw_arrays_indirect) call, as
+/// stored in an indirect buffer.
+///
+/// # Note
+/// OpenGL ES 3.1 doesn't support `base_instance`, which must be 0.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawArraysIndirectCommand {
+    /// The number of vertices to draw.
+    pub count: u32,
+
+    /// The number of instances to draw.
+    pub instance_count: u32,
+
+    /// The index of the first vertex to draw.
+    pub first: u32,
+
+    /// The first instance to draw.
+    pub base_instance: u32
+}
+
+/// The parameters of a single [`gl.draw_elements_indirect`]
+/// (trait.ContextIndirectExt.html#method.draw_elements_indirect) call, as
+/// stored in an indirect buffer.
+///
+/// # Note
+/// OpenGL ES 3.1 doesn't support `base_instance`, which must be 0.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawElementsIndirectCommand {
+    /// The number of indices to draw.
+    pub count: u32,
+
+    /// The number of instances to draw.
+    pub instance_count: u32,
+
+    /// The index of the first index to draw (as a number of indices,
+    /// not bytes).
+    pub first_index: u32,
+
+    /// A value that is added to each index before reading the vertex.
+    pub base_vertex: i32,
+
+    /// The first instance to draw.
+    pub base_instance: u32
+}
+
+/// A command that can be stored in an indirect buffer.
+///
+/// # Safety
+/// This type must be `#[repr(C)]`, and match the layout that OpenGL
+/// expects for its commands.
+pub unsafe trait IndirectCommand: Copy {
+
+}
+
+unsafe impl IndirectCommand for DrawArraysIndirectCommand {
+
+}
+
+unsafe impl IndirectCommand for DrawElementsIndirectCommand {
+
+}
+
+/// Get the bytes of a list of indirect commands, so that they can be sent
+/// to a buffer with [`gl.buffer_bytes`]
+/// (../buffer_context/trait.ContextBufferExt.html#method.buffer_bytes).
+pub fn indirect_command_bytes<C: IndirectCommand>(commands: &[C]) -> &[u8] {
+    let size = mem::size_of::<C>() * commands.len();
+    unsafe {
+        slice::from_raw_parts(commands.as_ptr() as *const u8, size)
+    }
+}
+
+fn _bind_draw_indirect_buffer(buffer: &Buffer) {
+    if !state_cache::bind_buffer(gl::DRAW_INDIRECT_BUFFER, buffer.id()) {
+        return;
+    }
+    unsafe {
+        gl::BindBuffer(gl::DRAW_INDIRECT_BUFFER, buffer.id());
+        dbg_gl_sanity_check! {
+            call "glBindBuffer";
+            args (gl::DRAW_INDIRECT_BUFFER, buffer.id());
+            GLError::InvalidEnum => "`target` is not an allowed value",
+            _ => "Unknown error"
+        }
+    }
+}
+
+fn _gl_index_type(index_type: IndexDatumType) -> GLenum {
+    match index_type {
+        IndexDatumType::UnsignedByte => gl::UNSIGNED_BYTE,
+        IndexDatumType::UnsignedShort => gl::UNSIGNED_SHORT,
+        IndexDatumType::UnsignedInt => gl::UNSIGNED_INT
+    }
+}
+
+/// An extension trait that includes indirect drawing OpenGL methods. With
+/// indirect drawing, the parameters of draw calls live in a buffer, so
+/// they can be written by the GPU (such as by a compute shader).
+///
+/// Each method binds the indirect buffer to the `GL_DRAW_INDIRECT_BUFFER`
+/// target, which isn't tracked by the context's bindings.
+///
+/// # Note
+/// Indirect drawing requires OpenGL ES 3.1 or OpenGL 4.0, so this trait is
+/// only available with the `gles31` feature. Drawing several commands with
+/// one call requires OpenGL 4.3, so the `multi_draw_*_indirect` methods are
+/// only available with the `gl45` feature.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::{ContextIndirectExt, DrawArraysIndirectCommand};
+///
+/// # fn main() {
+/// let mut gl = unsafe { glitter::Context::current_context() };
+/// let mut vertex_buffer = gl.gen_buffer();
+/// let mut indirect_buffer = gl.gen_buffer();
+///
+/// let commands = [
+///     DrawArraysIndirectCommand { count: 3, instance_count: 1, first: 0, base_instance: 0 }
+/// ];
+/// {
+///     let (mut gl_indirect, gl) = (&mut gl).bind_array_buffer(&mut indirect_buffer);
+///     gl.buffer_bytes(&mut gl_indirect,
+///                     glitter::indirect_command_bytes(&commands),
+///                     glitter::STATIC_DRAW);
+/// }
+///
+/// let (gl_vertices, gl) = gl.bind_array_buffer(&mut vertex_buffer);
+/// // Fill the vertex buffer and set up vertex attributes...
+/// unsafe {
+///     gl.draw_arrays_indirect(&gl_vertices,
+///                             glitter::TRIANGLES,
+///                             &indirect_buffer,
+///                             0);
+/// }
+/// # }
+/// ```
+pub trait ContextIndirectExt: BaseContext {
+    /// Draw primitives from the provided array buffer binding, reading the
+    /// parameters from a [`DrawArraysIndirectCommand`]
+    /// (struct.DrawArraysIndirectCommand.html) that starts `offset` bytes
+    /// into `buffer`.
+    ///
+    /// # Safety
+    /// The vertex attributes need to be set up before calling this method,
+    /// and the command must only refer to vertices within the array buffer.
+    ///
+    /// # See also
+    /// [`glDrawArraysIndirect`](http://docs.gl/es3/glDrawArraysIndirect)
+    /// OpenGL docs
+    unsafe fn draw_arrays_indirect(&self,
+                                   _ab: &ArrayBufferBinding,
+                                   mode: DrawingMode,
+                                   buffer: &Buffer,
+                                   offset: usize)
+    {
+        _bind_draw_indirect_buffer(buffer);
+        gl::DrawArraysIndirect(mode.gl_enum(), offset as *const GLvoid);
+        dbg_gl_error! {
+            call "glDrawArraysIndirect";
+            args (mode, offset);
+            GLError::InvalidEnum => "`mode` is not an accepted value",
+            GLError::InvalidOperation => "The command is out of range of the indirect buffer, or `offset` is not a multiple of 4",
+            GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+            _ => "Unknown error"
+        }
+    }
+
+    /// Draw primitives specified by the provided element array buffer,
+    /// reading the parameters from a [`DrawElementsIndirectCommand`]
+    /// (struct.DrawElementsIndirectCommand.html) that starts `offset` bytes
+    /// into `buffer`.
+    ///
+    /// # Safety
+    /// The vertex attributes need to be set up before calling this method,
+    /// and the command must only refer to indices within the element array
+    /// buffer (and vertices within the array buffer).
+    ///
+    /// # See also
+    /// [`glDrawElementsIndirect`](http://docs.gl/es3/glDrawElementsIndirect)
+    /// OpenGL docs
+    unsafe fn draw_elements_indirect(&self,
+                                     _ab: &ArrayBufferBinding,
+                                     _eab: &ElementArrayBufferBinding,
+                                     mode: DrawingMode,
+                                     index_type: IndexDatumType,
+                                     buffer: &Buffer,
+                                     offset: usize)
+    {
+        let gl_index_type = _gl_index_type(index_type);
+        _bind_draw_indirect_buffer(buffer);
+        gl::DrawElementsIndirect(mode.gl_enum(),
+                                 gl_index_type,
+                                 offset as *const GLvoid);
+        dbg_gl_error! {
+            call "glDrawElementsIndirect";
+            args (mode, gl_index_type, offset);
+            GLError::InvalidEnum => "`mode` or `type` is not an accepted value",
+            GLError::InvalidOperation => "The command is out of range of the indirect buffer, or `offset` is not a multiple of 4",
+            GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+            _ => "Unknown error"
+        }
+    }
+
+    /// Draw primitives from the provided array buffer binding once for each
+    /// of `draw_count` [`DrawArraysIndirectCommand`]
+    /// (struct.DrawArraysIndirectCommand.html)s, which start `offset` bytes
+    /// into `buffer` and are `stride` bytes apart (or tightly packed, if
+    /// `stride` is 0).
+    ///
+    /// # Note
+    /// This method requires OpenGL 4.3, so it is only available with the
+    /// `gl45` feature.
+    ///
+    /// # Safety
+    /// The vertex attributes need to be set up before calling this method,
+    /// and the commands must only refer to vertices within the array buffer.
+    ///
+    /// # See also
+    /// [`glMultiDrawArraysIndirect`](http://docs.gl/gl4/glMultiDrawArraysIndirect)
+    /// OpenGL docs
+    #[cfg(feature = "gl45")]
+    unsafe fn multi_draw_arrays_indirect(&self,
+                                         _ab: &ArrayBufferBinding,
+                                         mode: DrawingMode,
+                                         buffer: &Buffer,
+                                         offset: usize,
+                                         draw_count: usize,
+                                         stride: usize)
+    {
+        _bind_draw_indirect_buffer(buffer);
+        gl::MultiDrawArraysIndirect(mode.gl_enum(),
+                                    offset as *const GLvoid,
+                                    draw_count as GLsizei,
+                                    stride as GLsizei);
+        dbg_gl_error! {
+            call "glMultiDrawArraysIndirect";
+            args (mode, offset, draw_count, stride);
+            GLError::InvalidEnum => "`mode` is not an accepted value",
+            GLError::InvalidValue => "`stride` is not a multiple of 4",
+            GLError::InvalidOperation => "The commands are out of range of the indirect buffer, or `offset` is not a multiple of 4",
+            GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+            _ => "Unknown error"
+        }
+    }
+
+    /// Draw primitives specified by the provided element array buffer once
+    /// for each of `draw_count` [`DrawElementsIndirectCommand`]
+    /// (struct.DrawElementsIndirectCommand.html)s, which start `offset`
+    /// bytes into `buffer` and are `stride` bytes apart (or tightly packed,
+    /// if `stride` is 0).
+    ///
+    /// # Note
+    /// This method requires OpenGL 4.3, so it is only available with the
+    /// `gl45` feature.
+    ///
+    /// # Safety
+    /// The vertex attributes need to be set up before calling this method,
+    /// and the commands must only refer to indices within the element array
+    /// buffer (and vertices within the array buffer).
+    ///
+    /// # See also
+    /// [`glMultiDrawElementsIndirect`](http://docs.gl/gl4/glMultiDrawElementsIndirect)
+    /// OpenGL docs
+    #[cfg(feature = "gl45")]
+    unsafe fn multi_draw_elements_indirect(&self,
+                                           _ab: &ArrayBufferBinding,
+                                           _eab: &ElementArrayBufferBinding,
+                                           mode: DrawingMode,
+                                           index_type: IndexDatumType,
+                                           buffer: &Buffer,
+                                           offset: usize,
+                                           draw_count: usize,
+                                           stride: usize)
+    {
+        let gl_index_type = _gl_index_type(index_type);
+        _bind_draw_indirect_buffer(buffer);
+        gl::MultiDrawElementsIndirect(mode.gl_enum(),
+                                      gl_index_type,
+                                      offset as *const GLvoid,
+                                      draw_count as GLsizei,
+                                      stride as GLsizei);
+        dbg_gl_error! {
+            call "glMultiDrawElementsIndirect";
+            args (mode, gl_index_type, offset, draw_count, stride);
+            GLError::InvalidEnum => "`mode` or `type` is not an accepted value",
+            GLError::InvalidValue => "`stride` is not a multiple of 4",
+            GLError::InvalidOperation => "The commands are out of range of the indirect buffer, or `offset` is not a multiple of 4",
+            GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+            _ => "Unknown error"
+        }
+    }
+}
+
+impl<C: BaseContext> ContextIndirectExt for C {
+
+}
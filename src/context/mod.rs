@@ -3,25 +3,62 @@
 //! OpenGL calls.
 
 use std::borrow::{Borrow, BorrowMut};
+use std::cell::Cell;
+use std::error;
+use std::fmt;
 use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
 use gl;
 use gl::types::*;
-use types::{Color, Viewport, Capability, GLError};
+use types::{Color, Viewport, Capability, DepthFunction, CullFace, Winding,
+            RasterizerState, PolygonOffset, SampleCoverage, MultisampleState,
+            LineWidthOutOfRange, GLError, ErrorPolicy, ResetStatus};
 use program::ProgramAttrib;
 use shader::ContextShaderExt;
 use to_ref::{ToRef, ToMut};
+use error_policy;
+use matrix_order;
+use state_cache;
+use restore_bindings;
+use deletion_queue::{self, ContextAlive, ThreadContext};
+use context_loss;
+use fullscreen;
+use index_validation;
+use uniform_data::MatrixOrder;
 
 pub mod buffer_context;
+pub mod capabilities;
+#[cfg(feature = "gles31")] pub mod compute_context;
+pub mod debug_context;
+#[cfg(feature = "gles3")] pub mod draw_base_context;
+pub mod draw_context;
+pub mod extensions;
+pub mod frame;
 pub mod framebuffer_context;
+#[cfg(feature = "gles31")] pub mod indirect_context;
 pub mod program_context;
+#[cfg(feature = "gl33")] pub mod query_context;
 pub mod renderbuffer_context;
+pub mod sampler_context;
+pub mod snapshot;
 pub mod texture_context;
 pub mod texture_units;
 
 pub use self::buffer_context::*;
+pub use self::capabilities::*;
+#[cfg(feature = "gles31")] pub use self::compute_context::*;
+pub use self::debug_context::*;
+#[cfg(feature = "gles3")] pub use self::draw_base_context::*;
+pub use self::draw_context::*;
+pub use self::extensions::*;
+pub use self::frame::*;
 pub use self::framebuffer_context::*;
+#[cfg(feature = "gles31")] pub use self::indirect_context::*;
 pub use self::program_context::*;
+#[cfg(feature = "gl33")] pub use self::query_context::*;
 pub use self::renderbuffer_context::*;
+pub use self::sampler_context::*;
+pub use self::snapshot::*;
 pub use self::texture_context::*;
 pub use self::texture_units::*;
 
@@ -111,34 +148,116 @@ pub type Context = ContextOf<BufferBinder,
 /// [`ElementArrayBufferContext`](trait.ElementArrayBufferContext.html), and
 /// [`BufferContext`](buffer_context/trait.BufferContext.html)
 /// - [`FramebufferContext`](framebuffer_context/trait.FramebufferContext.html)
+/// - [`ProgramContext`](program_context/trait.ProgramContext.html)
 /// - [`RenderbufferContext`](renderbuffer_context/trait.RenderbufferContext.html)
 /// - [`TextureUnit0Context`](texture_units/trait.TextureUnit0Context.html)
 /// through [`TextureUnit7Context`](texture_units/trait.TextureUnit7Context.html)
+///
+/// Each of these traits is implemented both for `ContextOf` (which is
+/// consumed) and for `&mut ContextOf` (which is borrowed), and has a
+/// `split_*` method (such as [`split_array_buffer`]
+/// (buffer_context/trait.ArrayBufferContext.html#tymethod.split_array_buffer)
+/// or [`split_tex_unit_0`]
+/// (texture_units/trait.TextureUnit0Context.html#tymethod.split_tex_unit_0))
+/// that separates the binder from the rest of the context. When splitting a
+/// `&mut ContextOf`, the binder and the rest of the context are mutable
+/// borrows of the original context, so a [`Context`](type.Context.html) can
+/// be kept around and passed to functions as `&mut Context`, and will have
+/// all of its bindings available again once the borrows end.
 pub struct ContextOf<B, F, P, R, T> {
     buffers: B,
     framebuffer: F,
     program: P,
     renderbuffer: R,
     tex_units: T,
+    shared: Rc<ContextShared>,
     _phantom: PhantomData<*mut ()>
 }
 
+// The state shared by a context and every context derived from it.
+struct ContextShared {
+    capabilities: Capabilities,
+    extensions: Extensions,
+    frame_index: Cell<u64>,
+    _claim: Option<ContextClaim>,
+    alive: Rc<ContextAlive>
+}
+
 impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
     /// Use a function to load OpenGL function pointers. This function must
     /// be called before calling [`ContextOf::current_context`]
     /// (struct.ContextOf.html#method.current_context).
     ///
+    /// `load_fn` is usually provided by a windowing library, such as
+    /// glutin's `get_proc_address` or SDL's `gl_get_proc_address`. A
+    /// function that can't be found should be returned as a null pointer.
+    ///
     /// # Safety
     /// `load_fn` takes an OpenGL function name, and must return a function
     /// pointer that can be used as this OpenGL function.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # fn get_proc_address(_: &str) -> *const std::os::raw::c_void {
+    /// #     std::ptr::null()
+    /// # }
+    /// unsafe {
+    ///     glitter::Context::load_with(|name| get_proc_address(name));
+    /// }
+    /// if !glitter::Context::is_loaded() {
+    ///     println!("Missing: {:?}", glitter::Context::missing_functions());
+    /// }
+    /// ```
     pub unsafe fn load_with<L>(load_fn: L)
         where L: FnMut(&str) -> *const GLvoid
     {
         gl::load_with(load_fn);
     }
 
+    /// Returns `true` if all of the OpenGL functions that are required by
+    /// glitter have been loaded with [`ContextOf::load_with`]
+    /// (struct.ContextOf.html#method.load_with).
+    ///
+    /// # Note
+    /// Optional functions (such as those used by the [`ContextDebugExt`]
+    /// (debug_context/trait.ContextDebugExt.html) trait) aren't required,
+    /// so this function may return `true` even if they weren't loaded.
+    pub fn is_loaded() -> bool {
+        Self::missing_functions().is_empty()
+    }
+
+    /// Get the names of the OpenGL functions that are required by glitter,
+    /// but that haven't been loaded with [`ContextOf::load_with`]
+    /// (struct.ContextOf.html#method.load_with).
+    pub fn missing_functions() -> Vec<&'static str> {
+        REQUIRED_FUNCTIONS.iter()
+            .filter(|&&(_, is_loaded)| !is_loaded())
+            .map(|&(name, _)| name)
+            .collect()
+    }
+
     /// Get the current OpenGL context.
     ///
+    /// # Note
+    /// OpenGL objects are owned by the OpenGL context that is current on
+    /// this thread, rather than by the returned `ContextOf` value, so
+    /// objects may outlive the `ContextOf` that created them, and are
+    /// still deleted once the context is acquired again. Objects dropped
+    /// while no context is acquired are queued, and their deletion happens
+    /// when this function is next called, when [`gl.cleanup`]
+    /// (struct.ContextOf.html#method.cleanup) is called, at the end of
+    /// each [`Frame`](frame/struct.Frame.html), when the last context on
+    /// this thread is dropped, or once the queue becomes too long while a
+    /// context is acquired. Objects are only leaked if the context has
+    /// been lost, or forgotten with [`ContextOf::forget_current_context`]
+    /// (struct.ContextOf.html#method.forget_current_context).
+    ///
+    /// # Panics
+    /// This function will panic if any of the OpenGL functions that are
+    /// required by glitter haven't been loaded (see
+    /// [`ContextOf::missing_functions`]
+    /// (struct.ContextOf.html#method.missing_functions)).
+    ///
     /// # Safety
     /// Before calling this function, **a context must be created and
     /// set** within the current thread, and **an OpenGL library needs
@@ -183,16 +302,192 @@ impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
     /// // buffer_2: [4, 5, 6]
     /// ```
     pub unsafe fn current_context() -> Context {
+        Self::_acquire(None)
+    }
+
+    /// Get the current OpenGL context, after checking that the OpenGL
+    /// functions have been loaded and that a context is current on this
+    /// thread. Whether a context is current is decided by `is_current`,
+    /// which should ask the windowing library that created the context
+    /// (since OpenGL itself can't be called safely without a current
+    /// context). Unlike [`ContextOf::current_context`]
+    /// (struct.ContextOf.html#method.current_context), only one context
+    /// can be acquired this way per thread at a time: the context is
+    /// released once it (and every context derived from it, such as by
+    /// binding objects) has been dropped.
+    ///
+    /// # Failures
+    /// An error will be returned if:
+    ///
+    /// - Any of the OpenGL functions required by glitter haven't been
+    ///   loaded with [`ContextOf::load_with`]
+    ///   (struct.ContextOf.html#method.load_with).
+    /// - `is_current` returns `false`.
+    /// - A context acquired with `try_current_context` on this thread
+    ///   hasn't been released yet.
+    ///
+    /// # Safety
+    /// glitter trusts `is_current`: if it returns `true`, an OpenGL context
+    /// must be current on this thread, and the functions loaded with
+    /// [`ContextOf::load_with`](struct.ContextOf.html#method.load_with)
+    /// must belong to it. Otherwise, the same rules apply to this method as
+    /// the [`ContextOf::current_context()` method]
+    /// (struct.ContextOf.html#method.current_context), except that only one
+    /// context can be acquired at a time.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # fn window_context_is_current() -> bool { true }
+    /// let is_current = || window_context_is_current();
+    /// match unsafe { glitter::Context::try_current_context(is_current) } {
+    ///     Ok(gl) => {
+    ///         // Use the context
+    ///     },
+    ///     Err(err) => {
+    ///         println!("Couldn't get an OpenGL context: {}", err);
+    ///     }
+    /// }
+    /// ```
+    pub unsafe fn try_current_context<F>(is_current: F)
+        -> Result<Context, NoCurrentContext>
+        where F: FnOnce() -> bool
+    {
+        let missing = Self::missing_functions();
+        if !missing.is_empty() {
+            return Err(NoCurrentContext::NotLoaded(missing));
+        }
+
+        if !is_current() {
+            return Err(NoCurrentContext::NotCurrent);
+        }
+
+        let claim = match ContextClaim::acquire() {
+            Some(claim) => claim,
+            None => { return Err(NoCurrentContext::AlreadyAcquired); }
+        };
+
+        Ok(Self::_acquire(Some(claim)))
+    }
+
+    // Get the current context, which has been acquired with `claim` if it
+    // was acquired with `try_current_context`
+    unsafe fn _acquire(claim: Option<ContextClaim>) -> Context {
+        let missing = Self::missing_functions();
+        if !missing.is_empty() {
+            panic!("OpenGL functions have not been loaded (missing: {}). \
+                    Call `Context::load_with` before `current_context`.",
+                   missing.join(", "));
+        }
+
+        // Nothing is known about the bindings of the newly-current context
+        state_cache::invalidate();
+        fullscreen::_forget_fullscreen_vertex_array();
+
+        // Delete the objects that were dropped since the context was last
+        // acquired
+        let alive = ContextAlive::new();
+        alive.flush();
+
         ContextOf {
             buffers: BufferBinder::current(),
             framebuffer: FramebufferBinder::current(),
             program: ProgramBinder::current(),
             renderbuffer: RenderbufferBinder::current(),
             tex_units: TextureUnits::current(),
+            shared: Rc::new(ContextShared {
+                capabilities: Capabilities::current(),
+                extensions: Extensions::current(),
+                frame_index: Cell::new(0),
+                _claim: claim,
+                alive: alive
+            }),
             _phantom: PhantomData
         }
     }
 
+    /// Get the implementation-defined limits of the OpenGL context, such as
+    /// the maximum texture size. These values are queried once, when the
+    /// context is created with [`ContextOf::current_context`]
+    /// (struct.ContextOf.html#method.current_context), and are shared
+    /// between all of the contexts derived from it.
+    ///
+    /// # Example
+    /// ```no_run
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let max_attribs = gl.capabilities().max_vertex_attribs;
+    /// ```
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.shared.capabilities
+    }
+
+    /// Get a handle to the OpenGL context, which can be used to check
+    /// whether the context is still alive. See [`ContextBound`]
+    /// (../context_bound/struct.ContextBound.html) for tying an object to
+    /// the context that created it.
+    pub fn handle(&self) -> ContextHandle {
+        ContextHandle {
+            context: Rc::downgrade(self.shared.alive.context()),
+            generation: context_loss::generation()
+        }
+    }
+
+    /// Set the width of rasterized lines, in pixels. The width is checked
+    /// against the `aliased_line_width_range` of the context's
+    /// [`capabilities`](struct.ContextOf.html#method.capabilities), since
+    /// many drivers (especially with core profiles) only support a width
+    /// of `1.0`.
+    ///
+    /// # Failures
+    /// An error will be returned (and the line width will not be changed)
+    /// if `width` is outside of the supported range.
+    ///
+    /// # Example
+    /// ```no_run
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// if let Err(err) = gl.set_line_width(3.0) {
+    ///     println!("{}, drawing thin lines instead", err);
+    /// }
+    /// ```
+    ///
+    /// # See also
+    /// [`glLineWidth`](http://docs.gl/es2/glLineWidth) OpenGL docs
+    pub fn set_line_width(&mut self, width: f32)
+        -> Result<(), LineWidthOutOfRange>
+    {
+        let range = self.shared.capabilities.aliased_line_width_range;
+        if !(width >= range[0] && width <= range[1]) {
+            return Err(LineWidthOutOfRange { width: width, range: range });
+        }
+
+        unsafe {
+            gl::LineWidth(width);
+            dbg_gl_sanity_check! {
+                call "glLineWidth";
+                args (width);
+                GLError::InvalidValue => "`width` is less than or equal to 0",
+                _ => "Unknown error"
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the set of OpenGL extensions supported by the context. The
+    /// extensions are queried once, when the context is created with
+    /// [`ContextOf::current_context`]
+    /// (struct.ContextOf.html#method.current_context), and are shared
+    /// between all of the contexts derived from it.
+    ///
+    /// # Example
+    /// ```no_run
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// if gl.extensions().anisotropic_filtering() {
+    ///     // Enable anisotropic filtering
+    /// }
+    /// ```
+    pub fn extensions(&self) -> &Extensions {
+        &self.shared.extensions
+    }
+
     /// Get an OpenGL error that was generated since the last call to
     /// `ContextOf::get_error()`, or `None` is none occurred.
     ///
@@ -200,23 +495,398 @@ impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
     /// When the `debug_assertions` configuration option is set,
     /// `ContextOf::get_error` is automatically called after most OpenGL
     /// function calls (and the program will often panic if an error
-    /// was generated).
+    /// was generated). This behavior can be changed with
+    /// [`ContextOf::set_error_policy`]
+    /// (struct.ContextOf.html#method.set_error_policy).
     pub fn get_error() -> Option<GLError> {
         unsafe {
-            match gl::GetError() {
-                gl::INVALID_ENUM =>
-                    Some(GLError::InvalidEnum),
-                gl::INVALID_VALUE =>
-                    Some(GLError::InvalidValue),
-                gl::INVALID_OPERATION =>
-                    Some(GLError::InvalidOperation),
-                gl::INVALID_FRAMEBUFFER_OPERATION =>
-                    Some(GLError::InvalidFramebufferOperation),
-                gl::OUT_OF_MEMORY =>
-                    Some(GLError::OutOfMemory),
-                _ =>
-                    None
-            }
+            GLError::from_gl_enum(gl::GetError())
+        }
+    }
+
+    /// Set the policy used to handle OpenGL errors generated by glitter's
+    /// OpenGL calls on the current thread. Returns the previous policy.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use glitter::ErrorPolicy;
+    ///
+    /// // Never check for errors, even in debug builds
+    /// glitter::Context::set_error_policy(ErrorPolicy::Ignore);
+    /// ```
+    pub fn set_error_policy(policy: ErrorPolicy) -> ErrorPolicy {
+        error_policy::replace_policy(policy)
+    }
+
+    /// Get the policy used to handle OpenGL errors generated by glitter's
+    /// OpenGL calls on the current thread.
+    pub fn error_policy() -> ErrorPolicy {
+        error_policy::policy()
+    }
+
+    /// Set the order that matrices are stored in when they are uploaded
+    /// as uniforms on the current thread, such as with [`gl.set_uniform`]
+    /// (program_context/trait.ContextProgramExt.html#method.set_uniform).
+    /// Returns the previous order. The default order is
+    /// `MatrixOrder::ColumnMajor`.
+    ///
+    /// # Note
+    /// This order only applies to types that don't specify their own order
+    /// (see [`UniformDatum::matrix_order`]
+    /// (../uniform_data/trait.UniformDatum.html#method.matrix_order)), such
+    /// as plain arrays. Math library types, such as `cgmath::Matrix4`, are
+    /// always uploaded using their own order.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use glitter::MatrixOrder;
+    ///
+    /// // Treat `[[f32; 4]; 4]` uniforms as arrays of rows
+    /// glitter::Context::set_matrix_order(MatrixOrder::RowMajor);
+    /// ```
+    pub fn set_matrix_order(order: MatrixOrder) -> MatrixOrder {
+        matrix_order::replace_order(order)
+    }
+
+    /// Get the order that matrices are stored in when they are uploaded
+    /// as uniforms on the current thread.
+    pub fn matrix_order() -> MatrixOrder {
+        matrix_order::order()
+    }
+
+    /// Enable or disable the state cache on the current thread. Returns
+    /// whether the state cache was previously enabled. The state cache is
+    /// enabled by default.
+    ///
+    /// While enabled, glitter remembers which program, buffers, and textures
+    /// it has bound (and which vertex attribute arrays it has enabled), and
+    /// skips OpenGL calls that would bind an object that is already bound.
+    /// This reduces driver overhead when the same objects are bound
+    /// repeatedly, such as when drawing many objects with one program.
+    ///
+    /// # Note
+    /// The state cache only knows about OpenGL calls made by glitter. If
+    /// OpenGL is also called directly (or by another library), either
+    /// disable the state cache, or call [`ContextOf::invalidate_state_cache`]
+    /// (struct.ContextOf.html#method.invalidate_state_cache) afterwards.
+    /// The state cache is reset by [`ContextOf::current_context`]
+    /// (struct.ContextOf.html#method.current_context), so it should be
+    /// called again after making a different context current on the
+    /// same thread. The state cache is also bypassed while restoring
+    /// bindings is enabled (see [`ContextOf::set_restore_bindings`]
+    /// (struct.ContextOf.html#method.set_restore_bindings)).
+    ///
+    /// # Example
+    /// ```no_run
+    /// // Always make binding calls, since other code binds objects too
+    /// glitter::Context::set_state_cache_enabled(false);
+    /// ```
+    pub fn set_state_cache_enabled(enabled: bool) -> bool {
+        state_cache::replace_enabled(enabled)
+    }
+
+    /// Returns `true` if the state cache is enabled on the current thread.
+    pub fn state_cache_enabled() -> bool {
+        state_cache::enabled()
+    }
+
+    /// Forget every binding in the state cache on the current thread, so
+    /// that the next binding of each kind is always made. This should be
+    /// called after OpenGL is called directly while the state cache
+    /// is enabled.
+    pub fn invalidate_state_cache() {
+        state_cache::invalidate();
+    }
+
+    /// Delete every OpenGL object created with this context (or a context
+    /// derived from it) that has been dropped, returning the number of
+    /// objects that were deleted. This should be called regularly, such as
+    /// once per frame.
+    ///
+    /// When an object (such as a [`Buffer`](../buffer/struct.Buffer.html)
+    /// or a [`Program`](../program/struct.Program.html)) is dropped, there
+    /// may not be a current OpenGL context to delete it with, so its
+    /// deletion is queued on the context that created it instead. Queued
+    /// deletions are also made at the end of each [`Frame`]
+    /// (frame/struct.Frame.html), when the context is acquired again, when
+    /// every context on this thread has been dropped, and once the queue
+    /// becomes too long while a context is acquired. An object that is
+    /// dropped after its context has been lost or forgotten is leaked
+    /// instead, so that an object with the same name in a different
+    /// context isn't deleted by mistake.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// loop {
+    ///     let frame = gl.begin_frame();
+    ///     // Draw the frame...
+    ///     frame.end();
+    ///     gl.cleanup();
+    /// }
+    /// # }
+    /// ```
+    pub fn cleanup(&mut self) -> usize {
+        unsafe {
+            self.shared.alive.flush()
+        }
+    }
+
+    /// Get the number of OpenGL objects that are waiting to be deleted by
+    /// [`gl.cleanup`](struct.ContextOf.html#method.cleanup).
+    pub fn pending_deletions(&self) -> usize {
+        self.shared.alive.pending()
+    }
+
+    /// Forget the OpenGL context that is current on this thread, so that
+    /// the objects that were created with it are leaked when they're
+    /// dropped, rather than being deleted with the next context that is
+    /// acquired. This should be called when the context is destroyed (or
+    /// replaced with an unrelated context) without being lost. Objects
+    /// that are still waiting to be deleted are leaked too, so
+    /// [`gl.cleanup`](struct.ContextOf.html#method.cleanup) should be
+    /// called first if the context is still current.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let buffer = gl.gen_buffer();
+    /// gl.cleanup();
+    /// drop(gl);
+    ///
+    /// // Destroy the window and its context...
+    /// glitter::Context::forget_current_context();
+    ///
+    /// // The buffer's context is gone, so it's leaked
+    /// drop(buffer);
+    /// # }
+    /// ```
+    pub fn forget_current_context() {
+        deletion_queue::forget_current();
+    }
+
+    /// Enable or disable restoring bindings on the current thread. Returns
+    /// whether restoring bindings was previously enabled. Restoring
+    /// bindings is disabled by default.
+    ///
+    /// While enabled, each binding (such as an [`ArrayBufferBinding`]
+    /// (buffer_context/struct.ArrayBufferBinding.html) or a
+    /// [`ProgramBinding`](program_context/struct.ProgramBinding.html))
+    /// remembers the object that was bound before it was made, and binds
+    /// that object again when it is dropped. This makes it possible to use
+    /// glitter within code that must leave the OpenGL state as it found it,
+    /// such as a plugin or overlay that draws as part of another
+    /// application. Each binding queries the previous binding from OpenGL,
+    /// so this adds some overhead to every binding.
+    ///
+    /// While restoring bindings is enabled, the state cache (see
+    /// [`ContextOf::set_state_cache_enabled`]
+    /// (struct.ContextOf.html#method.set_state_cache_enabled)) is bypassed,
+    /// so every binding is made, since the cache can't know which object a
+    /// restored binding left bound. The state cache is also invalidated
+    /// whenever this function is called.
+    ///
+    /// # Note
+    /// Only the bindings themselves are restored: the active texture unit
+    /// and any other state (such as enabled vertex attribute arrays or
+    /// uniform values) are not.
+    ///
+    /// # Example
+    /// ```no_run
+    /// // Leave the host application's bindings untouched
+    /// glitter::Context::set_restore_bindings(true);
+    /// ```
+    pub fn set_restore_bindings(enabled: bool) -> bool {
+        let old_enabled = restore_bindings::replace_enabled(enabled);
+        state_cache::invalidate();
+        old_enabled
+    }
+
+    /// Returns `true` if bindings restore the previously-bound object when
+    /// they are dropped on the current thread.
+    pub fn restore_bindings() -> bool {
+        restore_bindings::enabled()
+    }
+
+    /// Enable or disable index validation on the current thread. Returns
+    /// whether index validation was previously enabled. Index validation is
+    /// disabled by default.
+    ///
+    /// While enabled, indexed draw calls that know which vertex buffer they
+    /// read from (such as [`gl.draw_elements_buffered_vbo`]
+    /// (../vertex_buffer/trait.ContextVertexBufferExt.html#method.draw_elements_buffered_vbo))
+    /// check that the largest index refers to a vertex within the vertex
+    /// buffer, and report an error using the current [`ErrorPolicy`]
+    /// (../enum.ErrorPolicy.html) if it doesn't. Reading vertices out of
+    /// bounds is a common cause of GPU crashes and garbage rendering.
+    ///
+    /// The range of the indices in an [`IndexBuffer`]
+    /// (../vertex_buffer/struct.IndexBuffer.html) is recorded when the
+    /// indices are buffered, so checking them is cheap. Index arrays that
+    /// are passed directly to a draw call are scanned on every draw call.
+    ///
+    /// # Example
+    /// ```no_run
+    /// // Only validate indices in debug builds
+    /// glitter::Context::set_index_validation(cfg!(debug_assertions));
+    /// ```
+    pub fn set_index_validation(enabled: bool) -> bool {
+        index_validation::replace_enabled(enabled)
+    }
+
+    /// Returns `true` if index validation is enabled on the current thread.
+    pub fn index_validation() -> bool {
+        index_validation::enabled()
+    }
+
+    /// Get the reset status of the context. Contexts can be reset (or
+    /// "lost") at any time on some platforms, such as after a graphics
+    /// driver update or a GPU hang, and on mobile devices when the
+    /// application is sent to the background.
+    ///
+    /// # Note
+    /// Context reset notification requires OpenGL 4.5, OpenGL ES 3.2, or a
+    /// robustness extension (see [`Extensions::robustness`]
+    /// (extensions/struct.Extensions.html#method.robustness)). If
+    /// `glGetGraphicsResetStatus` wasn't loaded, [`NO_RESET`]
+    /// (../constant.NO_RESET.html) is always returned.
+    ///
+    /// # See also
+    /// [`glGetGraphicsResetStatus`]
+    /// (http://docs.gl/gl4/glGetGraphicsResetStatus) OpenGL docs
+    pub fn reset_status(&self) -> ResetStatus {
+        let status = unsafe { context_loss::reset_status() };
+        ResetStatus::from_gl(status)
+            .unwrap_or(ResetStatus::UnknownContextReset)
+    }
+
+    /// Returns `true` if the context has been lost on the current thread,
+    /// either because [`gl.reset_status`]
+    /// (struct.ContextOf.html#method.reset_status) reported a reset, or
+    /// because an OpenGL call generated a [`GLError::ContextLost`]
+    /// (../enum.GLError.html#variant.ContextLost) error.
+    ///
+    /// Once the context has been lost, OpenGL calls have no effect, and
+    /// dropped objects are no longer deleted (since they were destroyed
+    /// along with the context). The application should drop the context
+    /// and its objects, create a new context, and then call
+    /// [`gl.restore_resources`]
+    /// (struct.ContextOf.html#method.restore_resources) with the new
+    /// context.
+    ///
+    /// # Note
+    /// A `GLError::ContextLost` error never causes a panic, even with the
+    /// [`ErrorPolicy::Panic`](../enum.ErrorPolicy.html#variant.Panic)
+    /// policy in effect. Within [`gl.checked`]
+    /// (struct.ContextOf.html#method.checked), it is returned as an error.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// loop {
+    ///     if gl.is_lost() {
+    ///         // Recreate the context, then restore its resources...
+    ///         break;
+    ///     }
+    ///     gl.clear(glitter::COLOR_BUFFER_BIT);
+    /// }
+    /// # }
+    /// ```
+    pub fn is_lost(&self) -> bool {
+        context_loss::is_lost() || self.reset_status() != ResetStatus::NoReset
+    }
+
+    /// Add a hook to recreate resources (such as textures and buffers)
+    /// after the context on the current thread has been lost. Every hook
+    /// is called with the new context by [`gl.restore_resources`]
+    /// (struct.ContextOf.html#method.restore_resources), in the order the
+    /// hooks were added. Returns a [`RestoreHook`](struct.RestoreHook.html)
+    /// that can be used to remove the hook.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use std::rc::Rc;
+    /// use std::cell::RefCell;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let buffer = Rc::new(RefCell::new(gl.gen_buffer()));
+    ///
+    /// let restored_buffer = buffer.clone();
+    /// glitter::Context::add_restore_hook(move |gl| {
+    ///     *restored_buffer.borrow_mut() = gl.gen_buffer();
+    /// });
+    /// # }
+    /// ```
+    pub fn add_restore_hook<H>(hook: H) -> RestoreHook
+        where H: FnMut(&mut Context) + 'static
+    {
+        RestoreHook { id: context_loss::add_hook(Box::new(hook)) }
+    }
+
+    /// Remove a hook that was added with [`ContextOf::add_restore_hook`]
+    /// (struct.ContextOf.html#method.add_restore_hook). Returns `false` if
+    /// the hook was already removed.
+    pub fn remove_restore_hook(hook: RestoreHook) -> bool {
+        context_loss::remove_hook(hook.id)
+    }
+
+    /// Call a function with a "checked" view of the context, returning the
+    /// first OpenGL error that was generated by any glitter method called
+    /// within the function, or the function's result if no error
+    /// was generated.
+    ///
+    /// While `f` is running, the [`ErrorPolicy::Result`]
+    /// (../enum.ErrorPolicy.html#variant.Result) policy is in effect,
+    /// meaning that errors are checked even in release builds, and do not
+    /// cause a panic. Any errors generated before this function was called
+    /// are discarded.
+    ///
+    /// # Failures
+    /// An error will be returned if any OpenGL call made within `f`
+    /// generated an OpenGL error.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use glitter::prelude::*;
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let result = gl.checked(|gl| {
+    ///     gl.clear_color(glitter::Color::rgba(0.0, 0.0, 0.0, 1.0));
+    ///     gl.clear(glitter::COLOR_BUFFER_BIT);
+    /// });
+    ///
+    /// if let Err(e) = result {
+    ///     println!("Failed to clear the screen: {}", e);
+    /// }
+    /// ```
+    pub fn checked<U, C>(&mut self, f: C) -> Result<U, GLError>
+        where C: FnOnce(&mut Self) -> U
+    {
+        error_policy::discard_errors();
+        let _ = error_policy::take();
+
+        let result = {
+            let _guard = error_policy::PolicyGuard::new(ErrorPolicy::Result);
+            f(self)
+        };
+
+        match error_policy::take().or_else(Context::get_error) {
+            Some(err) => Err(err),
+            None => Ok(result)
         }
     }
 
@@ -238,6 +908,7 @@ impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
             program: self.program.borrow(),
             renderbuffer: self.renderbuffer.borrow(),
             tex_units: self.tex_units.borrow(),
+            shared: self.shared.clone(),
             _phantom: PhantomData
         }
     }
@@ -264,6 +935,7 @@ impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
             program: self.program.borrow_mut(),
             renderbuffer: self.renderbuffer.borrow_mut(),
             tex_units: self.tex_units.borrow_mut(),
+            shared: self.shared.clone(),
             _phantom: PhantomData
         }
     }
@@ -282,14 +954,24 @@ impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
     pub fn swap_buffers<NB>(self, new_buffer: NB)
         -> (B, ContextOf<NB, F, P, R, T>)
     {
+        let ContextOf {
+            buffers,
+            framebuffer,
+            program,
+            renderbuffer,
+            tex_units,
+            shared,
+            _phantom
+        } = self;
         (
-            self.buffers,
+            buffers,
             ContextOf {
                 buffers: new_buffer,
-                framebuffer: self.framebuffer,
-                program: self.program,
-                renderbuffer: self.renderbuffer,
-                tex_units: self.tex_units,
+                framebuffer: framebuffer,
+                program: program,
+                renderbuffer: renderbuffer,
+                tex_units: tex_units,
+                shared: shared,
                 _phantom: PhantomData
             }
         )
@@ -309,14 +991,24 @@ impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
     pub fn swap_framebuffer<NF>(self, new_framebuffer: NF)
         -> (F, ContextOf<B, NF, P, R, T>)
     {
+        let ContextOf {
+            buffers,
+            framebuffer,
+            program,
+            renderbuffer,
+            tex_units,
+            shared,
+            _phantom
+        } = self;
         (
-            self.framebuffer,
+            framebuffer,
             ContextOf {
-                buffers: self.buffers,
+                buffers: buffers,
                 framebuffer: new_framebuffer,
-                program: self.program,
-                renderbuffer: self.renderbuffer,
-                tex_units: self.tex_units,
+                program: program,
+                renderbuffer: renderbuffer,
+                tex_units: tex_units,
+                shared: shared,
                 _phantom: PhantomData
             }
         )
@@ -336,14 +1028,24 @@ impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
     pub fn swap_program<NP>(self, new_program: NP)
         -> (P, ContextOf<B, F, NP, R, T>)
     {
+        let ContextOf {
+            buffers,
+            framebuffer,
+            program,
+            renderbuffer,
+            tex_units,
+            shared,
+            _phantom
+        } = self;
         (
-            self.program,
+            program,
             ContextOf {
-                buffers: self.buffers,
-                framebuffer: self.framebuffer,
+                buffers: buffers,
+                framebuffer: framebuffer,
                 program: new_program,
-                renderbuffer: self.renderbuffer,
-                tex_units: self.tex_units,
+                renderbuffer: renderbuffer,
+                tex_units: tex_units,
+                shared: shared,
                 _phantom: PhantomData
             }
         )
@@ -363,14 +1065,24 @@ impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
     pub fn swap_renderbuffer<NR>(self, new_renderbuffer: NR)
         -> (R, ContextOf<B, F, P, NR, T>)
     {
+        let ContextOf {
+            buffers,
+            framebuffer,
+            program,
+            renderbuffer,
+            tex_units,
+            shared,
+            _phantom
+        } = self;
         (
-            self.renderbuffer,
+            renderbuffer,
             ContextOf {
-                buffers: self.buffers,
-                framebuffer: self.framebuffer,
-                program: self.program,
+                buffers: buffers,
+                framebuffer: framebuffer,
+                program: program,
                 renderbuffer: new_renderbuffer,
-                tex_units: self.tex_units,
+                tex_units: tex_units,
+                shared: shared,
                 _phantom: PhantomData
             }
         )
@@ -390,20 +1102,238 @@ impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
     pub fn swap_tex_units<NT>(self, new_tex_units: NT)
         -> (T, ContextOf<B, F, P, R, NT>)
     {
+        let ContextOf {
+            buffers,
+            framebuffer,
+            program,
+            renderbuffer,
+            tex_units,
+            shared,
+            _phantom
+        } = self;
         (
-            self.tex_units,
+            tex_units,
             ContextOf {
-                buffers: self.buffers,
-                framebuffer: self.framebuffer,
-                program: self.program,
-                renderbuffer: self.renderbuffer,
+                buffers: buffers,
+                framebuffer: framebuffer,
+                program: program,
+                renderbuffer: renderbuffer,
                 tex_units: new_tex_units,
+                shared: shared,
                 _phantom: PhantomData
             }
         )
     }
 }
 
+impl Context {
+    /// Recreate resources after the context on the current thread was lost,
+    /// by calling every hook that was added with
+    /// [`ContextOf::add_restore_hook`]
+    /// (struct.ContextOf.html#method.add_restore_hook) with this context.
+    /// Returns the number of hooks that were called.
+    ///
+    /// This should be called with the new context, once it has been made
+    /// current. Objects from the lost context (including objects that the
+    /// hooks replace) are never deleted once they're dropped, so they can't
+    /// delete objects from the new context by mistake.
+    pub fn restore_resources(&mut self) -> usize {
+        fullscreen::_forget_fullscreen_vertex_array();
+        context_loss::restore(self)
+    }
+}
+
+thread_local! {
+    static CLAIMED: Cell<bool> = Cell::new(false);
+}
+
+// Marks that a context was acquired with `ContextOf::try_current_context`
+// on the current thread, until the claim is dropped.
+struct ContextClaim {
+    _phantom: PhantomData<*mut ()>
+}
+
+impl ContextClaim {
+    fn acquire() -> Option<Self> {
+        CLAIMED.with(|claimed| {
+            if claimed.get() {
+                None
+            }
+            else {
+                claimed.set(true);
+                Some(ContextClaim { _phantom: PhantomData })
+            }
+        })
+    }
+}
+
+impl Drop for ContextClaim {
+    fn drop(&mut self) {
+        CLAIMED.with(|claimed| claimed.set(false));
+    }
+}
+
+/// A handle to an OpenGL context, created with [`gl.handle`]
+/// (struct.ContextOf.html#method.handle). A handle refers to the OpenGL
+/// context itself rather than to a `ContextOf` value, so the context is
+/// still alive after every `ContextOf` on the thread has been dropped. The
+/// context is considered dead once it has been lost, or forgotten with
+/// [`ContextOf::forget_current_context`]
+/// (struct.ContextOf.html#method.forget_current_context).
+#[derive(Clone)]
+pub struct ContextHandle {
+    context: Weak<ThreadContext>,
+    generation: u64
+}
+
+impl ContextHandle {
+    /// Returns `true` if the context that this handle was created from is
+    /// still alive.
+    pub fn is_alive(&self) -> bool {
+        !context_loss::is_lost() &&
+        self.generation == context_loss::generation() &&
+        self.context.upgrade().map_or(false, |context| context.is_current())
+    }
+
+    /// Returns `true` if this handle was created from the same OpenGL
+    /// context as `gl`, and that context is still alive.
+    pub fn is_handle_of<B, F, P, R, T>(&self, gl: &ContextOf<B, F, P, R, T>)
+        -> bool
+    {
+        self.is_alive() && match self.context.upgrade() {
+            Some(context) => {
+                &*context as *const ThreadContext ==
+                &**gl.shared.alive.context() as *const _
+            },
+            None => false
+        }
+    }
+}
+
+/// A hook that was added with [`ContextOf::add_restore_hook`]
+/// (struct.ContextOf.html#method.add_restore_hook).
+#[derive(Debug, PartialEq, Eq)]
+pub struct RestoreHook {
+    id: usize
+}
+
+/// An error returned by [`ContextOf::try_current_context`]
+/// (struct.ContextOf.html#method.try_current_context) when a context
+/// could not be acquired.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoCurrentContext {
+    /// The OpenGL functions with the given names have not been loaded.
+    NotLoaded(Vec<&'static str>),
+
+    /// No OpenGL context is current on this thread.
+    NotCurrent,
+
+    /// A context has already been acquired on this thread, and hasn't
+    /// been released yet.
+    AlreadyAcquired
+}
+
+impl fmt::Display for NoCurrentContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NoCurrentContext::NotLoaded(ref missing) => {
+                write!(f, "OpenGL functions have not been loaded (missing: {})", missing.join(", "))
+            },
+            NoCurrentContext::NotCurrent => {
+                write!(f, "No OpenGL context is current on this thread")
+            },
+            NoCurrentContext::AlreadyAcquired => {
+                write!(f, "An OpenGL context has already been acquired on this thread")
+            }
+        }
+    }
+}
+
+impl error::Error for NoCurrentContext {
+    fn description(&self) -> &str {
+        match *self {
+            NoCurrentContext::NotLoaded(_) => "OpenGL functions have not been loaded",
+            NoCurrentContext::NotCurrent => "No OpenGL context is current",
+            NoCurrentContext::AlreadyAcquired => "An OpenGL context has already been acquired"
+        }
+    }
+}
+
+// The OpenGL ES 2 functions that glitter calls unconditionally. Functions
+// that are only used when available (such as `glDebugMessageCallback`)
+// aren't included.
+static REQUIRED_FUNCTIONS: &'static [(&'static str, fn() -> bool)] = &[
+    ("glActiveTexture", gl::ActiveTexture::is_loaded as fn() -> bool),
+    ("glAttachShader", gl::AttachShader::is_loaded as fn() -> bool),
+    ("glBindAttribLocation", gl::BindAttribLocation::is_loaded as fn() -> bool),
+    ("glBindBuffer", gl::BindBuffer::is_loaded as fn() -> bool),
+    ("glBindFramebuffer", gl::BindFramebuffer::is_loaded as fn() -> bool),
+    ("glBindRenderbuffer", gl::BindRenderbuffer::is_loaded as fn() -> bool),
+    ("glBindTexture", gl::BindTexture::is_loaded as fn() -> bool),
+    ("glBufferData", gl::BufferData::is_loaded as fn() -> bool),
+    ("glCheckFramebufferStatus", gl::CheckFramebufferStatus::is_loaded as fn() -> bool),
+    ("glClear", gl::Clear::is_loaded as fn() -> bool),
+    ("glClearColor", gl::ClearColor::is_loaded as fn() -> bool),
+    ("glColorMask", gl::ColorMask::is_loaded as fn() -> bool),
+    ("glCompileShader", gl::CompileShader::is_loaded as fn() -> bool),
+    ("glCreateProgram", gl::CreateProgram::is_loaded as fn() -> bool),
+    ("glCreateShader", gl::CreateShader::is_loaded as fn() -> bool),
+    ("glDeleteBuffers", gl::DeleteBuffers::is_loaded as fn() -> bool),
+    ("glDeleteFramebuffers", gl::DeleteFramebuffers::is_loaded as fn() -> bool),
+    ("glDeleteProgram", gl::DeleteProgram::is_loaded as fn() -> bool),
+    ("glDeleteRenderbuffers", gl::DeleteRenderbuffers::is_loaded as fn() -> bool),
+    ("glDeleteShader", gl::DeleteShader::is_loaded as fn() -> bool),
+    ("glDeleteTextures", gl::DeleteTextures::is_loaded as fn() -> bool),
+    ("glDepthFunc", gl::DepthFunc::is_loaded as fn() -> bool),
+    ("glDepthMask", gl::DepthMask::is_loaded as fn() -> bool),
+    ("glDetachShader", gl::DetachShader::is_loaded as fn() -> bool),
+    ("glDisable", gl::Disable::is_loaded as fn() -> bool),
+    ("glDrawArrays", gl::DrawArrays::is_loaded as fn() -> bool),
+    ("glDrawElements", gl::DrawElements::is_loaded as fn() -> bool),
+    ("glEnable", gl::Enable::is_loaded as fn() -> bool),
+    ("glEnableVertexAttribArray", gl::EnableVertexAttribArray::is_loaded as fn() -> bool),
+    ("glFramebufferRenderbuffer", gl::FramebufferRenderbuffer::is_loaded as fn() -> bool),
+    ("glFramebufferTexture2D", gl::FramebufferTexture2D::is_loaded as fn() -> bool),
+    ("glGenBuffers", gl::GenBuffers::is_loaded as fn() -> bool),
+    ("glGenFramebuffers", gl::GenFramebuffers::is_loaded as fn() -> bool),
+    ("glGenRenderbuffers", gl::GenRenderbuffers::is_loaded as fn() -> bool),
+    ("glGenTextures", gl::GenTextures::is_loaded as fn() -> bool),
+    ("glGenerateMipmap", gl::GenerateMipmap::is_loaded as fn() -> bool),
+    ("glGetAttribLocation", gl::GetAttribLocation::is_loaded as fn() -> bool),
+    ("glGetBooleanv", gl::GetBooleanv::is_loaded as fn() -> bool),
+    ("glGetError", gl::GetError::is_loaded as fn() -> bool),
+    ("glGetFloatv", gl::GetFloatv::is_loaded as fn() -> bool),
+    ("glGetIntegerv", gl::GetIntegerv::is_loaded as fn() -> bool),
+    ("glGetProgramInfoLog", gl::GetProgramInfoLog::is_loaded as fn() -> bool),
+    ("glGetProgramiv", gl::GetProgramiv::is_loaded as fn() -> bool),
+    ("glGetShaderInfoLog", gl::GetShaderInfoLog::is_loaded as fn() -> bool),
+    ("glGetShaderSource", gl::GetShaderSource::is_loaded as fn() -> bool),
+    ("glGetShaderiv", gl::GetShaderiv::is_loaded as fn() -> bool),
+    ("glGetString", gl::GetString::is_loaded as fn() -> bool),
+    ("glGetUniformLocation", gl::GetUniformLocation::is_loaded as fn() -> bool),
+    ("glIsEnabled", gl::IsEnabled::is_loaded as fn() -> bool),
+    ("glLinkProgram", gl::LinkProgram::is_loaded as fn() -> bool),
+    ("glRenderbufferStorage", gl::RenderbufferStorage::is_loaded as fn() -> bool),
+    ("glScissor", gl::Scissor::is_loaded as fn() -> bool),
+    ("glShaderSource", gl::ShaderSource::is_loaded as fn() -> bool),
+    ("glTexImage2D", gl::TexImage2D::is_loaded as fn() -> bool),
+    ("glTexParameteriv", gl::TexParameteriv::is_loaded as fn() -> bool),
+    ("glUniform1fv", gl::Uniform1fv::is_loaded as fn() -> bool),
+    ("glUniform1iv", gl::Uniform1iv::is_loaded as fn() -> bool),
+    ("glUniform2fv", gl::Uniform2fv::is_loaded as fn() -> bool),
+    ("glUniform2iv", gl::Uniform2iv::is_loaded as fn() -> bool),
+    ("glUniform3fv", gl::Uniform3fv::is_loaded as fn() -> bool),
+    ("glUniform3iv", gl::Uniform3iv::is_loaded as fn() -> bool),
+    ("glUniform4fv", gl::Uniform4fv::is_loaded as fn() -> bool),
+    ("glUniform4iv", gl::Uniform4iv::is_loaded as fn() -> bool),
+    ("glUniformMatrix2fv", gl::UniformMatrix2fv::is_loaded as fn() -> bool),
+    ("glUniformMatrix3fv", gl::UniformMatrix3fv::is_loaded as fn() -> bool),
+    ("glUniformMatrix4fv", gl::UniformMatrix4fv::is_loaded as fn() -> bool),
+    ("glUseProgram", gl::UseProgram::is_loaded as fn() -> bool),
+    ("glVertexAttribPointer", gl::VertexAttribPointer::is_loaded as fn() -> bool),
+    ("glViewport", gl::Viewport::is_loaded as fn() -> bool)
+];
+
 impl<'a, B, F, P, R, T> ToRef<'a> for ContextOf<B, F, P, R, T>
     where B: 'a + ToRef<'a>,
           F: 'a + ToRef<'a>,
@@ -420,6 +1350,7 @@ impl<'a, B, F, P, R, T> ToRef<'a> for ContextOf<B, F, P, R, T>
             program: self.program.to_ref(),
             renderbuffer: self.renderbuffer.to_ref(),
             tex_units: self.tex_units.to_ref(),
+            shared: self.shared.clone(),
             _phantom: PhantomData
         }
     }
@@ -441,6 +1372,7 @@ impl<'a, B, F, P, R, T> ToMut<'a> for ContextOf<B, F, P, R, T>
             program: self.program.to_mut(),
             renderbuffer: self.renderbuffer.to_mut(),
             tex_units: self.tex_units.to_mut(),
+            shared: self.shared.clone(),
             _phantom: PhantomData
         }
     }
@@ -470,6 +1402,16 @@ unsafe impl<'a, B, F, P, R, T> BaseContext
 
 
 
+#[cfg(feature = "gl33")]
+fn _is_program_point_size_enabled() -> bool {
+    unsafe { gl::IsEnabled(gl::PROGRAM_POINT_SIZE) == gl::TRUE }
+}
+
+#[cfg(not(feature = "gl33"))]
+fn _is_program_point_size_enabled() -> bool {
+    true
+}
+
 /// An extension trait that contains some of the core OpenGL methods that
 /// maintain state, such as the current clear color or whether depth testing
 /// is enabled.
@@ -513,6 +1455,8 @@ pub trait ContextExt: BaseContext {
         unsafe {
             gl::Enable(cap.gl_enum());
             dbg_gl_sanity_check! {
+                call "glEnable";
+                args (cap);
                 GLError::InvalidEnum => "`cap` is not a valid OpenGL capability",
                 _ => "Unknown error"
             }
@@ -536,6 +1480,8 @@ pub trait ContextExt: BaseContext {
         unsafe {
             gl::Disable(cap.gl_enum());
             dbg_gl_sanity_check! {
+                call "glDisable";
+                args (cap);
                 GLError::InvalidEnum => "`cap` is not a valid OpenGL capability",
                 _ => "Unknown error"
             }
@@ -551,9 +1497,14 @@ pub trait ContextExt: BaseContext {
     /// # Panics
     /// This function will panics on an OpenGL error in debug mode.
     fn enable_vertex_attrib_array(&self, attrib: ProgramAttrib) {
+        if !state_cache::enable_attrib_array(attrib.gl_index) {
+            return;
+        }
         unsafe {
             gl::EnableVertexAttribArray(attrib.gl_index);
             dbg_gl_error! {
+                call "glEnableVertexAttribArray";
+                args (attrib);
                 GLError::InvalidValue => "`index` is >= GL_MAX_VERTEX_ATTRIBS",
                 _ => "Unknown error"
             }
@@ -569,11 +1520,468 @@ pub trait ContextExt: BaseContext {
                          viewport.width as GLsizei,
                          viewport.height as GLsizei);
             dbg_gl_sanity_check! {
+                call "glViewport";
+                args (viewport.x, viewport.y, viewport.width, viewport.height);
                 GLError::InvalidValue => "`width` or `height` is negative",
                 _ => "Unknown error"
             }
         }
     }
+
+    /// Set the scissor rectangle. While the scissor test is enabled (with
+    /// `gl.enable(glitter::SCISSCOR_TEST)`), only fragments within the
+    /// rectangle are drawn. Rectangles in fractions of the window can be
+    /// created with [`Viewport::from_normalized`]
+    /// (../types/struct.Viewport.html#method.from_normalized).
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::Viewport;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let window = Viewport::new(0, 0, 1280, 720);
+    /// gl.enable(glitter::SCISSCOR_TEST);
+    /// // Only draw to the bottom half of the window
+    /// gl.scissor(Viewport::from_normalized(window, 0.0, 0.0, 1.0, 0.5));
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glScissor`](http://docs.gl/es2/glScissor) OpenGL docs
+    fn scissor(&self, rect: Viewport) {
+        unsafe {
+            gl::Scissor(rect.x as GLint,
+                        rect.y as GLint,
+                        rect.width as GLsizei,
+                        rect.height as GLsizei);
+            dbg_gl_sanity_check! {
+                call "glScissor";
+                args (rect.x, rect.y, rect.width, rect.height);
+                GLError::InvalidValue => "`width` or `height` is negative",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Set the comparison function used for the depth test. The depth test
+    /// must be enabled with `gl.enable(glitter::DEPTH_TEST)`.
+    ///
+    /// # See also
+    /// [`glDepthFunc`](http://docs.gl/es2/glDepthFunc) OpenGL docs
+    fn depth_func(&mut self, func: DepthFunction) {
+        unsafe {
+            gl::DepthFunc(func.gl_enum());
+            dbg_gl_sanity_check! {
+                call "glDepthFunc";
+                args (func);
+                GLError::InvalidEnum => "`func` is not an accepted value",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Enable or disable writing to the depth buffer.
+    ///
+    /// # See also
+    /// [`glDepthMask`](http://docs.gl/es2/glDepthMask) OpenGL docs
+    fn depth_mask(&mut self, enabled: bool) {
+        unsafe {
+            gl::DepthMask(if enabled { gl::TRUE } else { gl::FALSE });
+        }
+    }
+
+    /// Enable or disable writing to each component of the color buffer.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// // Disable color writes (such as for a depth-only pass)
+    /// gl.color_mask(false, false, false, false);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glColorMask`](http://docs.gl/es2/glColorMask) OpenGL docs
+    fn color_mask(&mut self, red: bool, green: bool, blue: bool, alpha: bool) {
+        let gl_bool = |b| if b { gl::TRUE } else { gl::FALSE };
+        unsafe {
+            gl::ColorMask(gl_bool(red),
+                          gl_bool(green),
+                          gl_bool(blue),
+                          gl_bool(alpha));
+        }
+    }
+
+    /// Enable or disable writing to each component of a single draw buffer,
+    /// leaving the masks of the other draw buffers unchanged. This is
+    /// useful when rendering to multiple render targets, where only some
+    /// of the targets should be written to.
+    ///
+    /// # Note
+    /// This method requires OpenGL 3.0 (or OpenGL ES 3.2), so it is only
+    /// available with the `gl33` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// // Keep the alpha channel of the second render target intact
+    /// gl.color_mask_indexed(1, true, true, true, false);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glColorMaski`](http://docs.gl/gl4/glColorMask) OpenGL docs
+    #[cfg(feature = "gl33")]
+    fn color_mask_indexed(&mut self,
+                          draw_buffer: u32,
+                          red: bool,
+                          green: bool,
+                          blue: bool,
+                          alpha: bool)
+    {
+        let gl_bool = |b| if b { gl::TRUE } else { gl::FALSE };
+        unsafe {
+            gl::ColorMaski(draw_buffer as GLuint,
+                           gl_bool(red),
+                           gl_bool(green),
+                           gl_bool(blue),
+                           gl_bool(alpha));
+            dbg_gl_error! {
+                call "glColorMaski";
+                args (draw_buffer, red, green, blue, alpha);
+                GLError::InvalidValue => "`buf` is greater than or equal to GL_MAX_DRAW_BUFFERS",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Set which polygon faces are culled, or disable face culling with
+    /// `None`. This enables or disables `glitter::CULL_FACE` as needed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// // Don't draw polygons facing away from the camera
+    /// gl.set_cull_face(Some(glitter::BACK));
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glCullFace`](http://docs.gl/es2/glCullFace) OpenGL docs
+    fn set_cull_face(&mut self, cull_face: Option<CullFace>) {
+        match cull_face {
+            Some(mode) => {
+                unsafe {
+                    gl::CullFace(mode.gl_enum());
+                    dbg_gl_sanity_check! {
+                        call "glCullFace";
+                        args (mode);
+                        GLError::InvalidEnum => "`mode` is not an accepted value",
+                        _ => "Unknown error"
+                    }
+                }
+                self.enable(Capability::CullFace);
+            },
+            None => {
+                self.disable(Capability::CullFace);
+            }
+        }
+    }
+
+    /// Set the winding of front-facing polygons.
+    ///
+    /// # See also
+    /// [`glFrontFace`](http://docs.gl/es2/glFrontFace) OpenGL docs
+    fn set_front_face(&mut self, winding: Winding) {
+        unsafe {
+            gl::FrontFace(winding.gl_enum());
+            dbg_gl_sanity_check! {
+                call "glFrontFace";
+                args (winding);
+                GLError::InvalidEnum => "`mode` is not an accepted value",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Set the face culling and front-face winding from a
+    /// [`RasterizerState`](../types/struct.RasterizerState.html) at once.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::RasterizerState;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// gl.set_rasterizer_state(&RasterizerState {
+    ///     cull_face: Some(glitter::BACK),
+    ///     front_face: glitter::CW
+    /// });
+    /// # }
+    /// ```
+    fn set_rasterizer_state(&mut self, state: &RasterizerState) {
+        self.set_front_face(state.front_face);
+        self.set_cull_face(state.cull_face);
+    }
+
+    /// Set the depth offset that is added to filled polygons, or disable
+    /// polygon offset with `None`. This enables or disables
+    /// `glitter::POLYGON_OFFSET_FILL` as needed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::PolygonOffset;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// // Bias depth values while drawing a shadow map
+    /// gl.set_polygon_offset(Some(PolygonOffset { factor: 2.0, units: 4.0 }));
+    /// // Draw the shadow casters...
+    /// gl.set_polygon_offset(None);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glPolygonOffset`](http://docs.gl/es2/glPolygonOffset) OpenGL docs
+    fn set_polygon_offset(&mut self, offset: Option<PolygonOffset>) {
+        match offset {
+            Some(offset) => {
+                unsafe {
+                    gl::PolygonOffset(offset.factor, offset.units);
+                }
+                self.enable(Capability::PolygonOffsetFill);
+            },
+            None => {
+                self.disable(Capability::PolygonOffsetFill);
+            }
+        }
+    }
+
+    /// Enable or disable applying the polygon offset (set with
+    /// [`gl.set_polygon_offset`]
+    /// (trait.ContextExt.html#method.set_polygon_offset)) to polygons
+    /// that are drawn as lines.
+    ///
+    /// # Note
+    /// OpenGL ES doesn't support drawing polygons as lines, so this method
+    /// is only available with the `gl33` feature.
+    ///
+    /// # See also
+    /// [`glPolygonOffset`](http://docs.gl/gl4/glPolygonOffset) OpenGL docs
+    #[cfg(feature = "gl33")]
+    fn set_polygon_offset_line(&mut self, enabled: bool) {
+        unsafe {
+            if enabled {
+                gl::Enable(gl::POLYGON_OFFSET_LINE);
+            }
+            else {
+                gl::Disable(gl::POLYGON_OFFSET_LINE);
+            }
+        }
+    }
+
+    /// Set the coverage value that is combined with the coverage of each
+    /// fragment while multisampling, or disable sample coverage with `None`.
+    /// This enables or disables `glitter::SAMPLE_COVERAGE` as needed.
+    ///
+    /// # See also
+    /// [`glSampleCoverage`](http://docs.gl/es2/glSampleCoverage) OpenGL docs
+    fn set_sample_coverage(&mut self, coverage: Option<SampleCoverage>) {
+        match coverage {
+            Some(coverage) => {
+                let invert = if coverage.invert { gl::TRUE } else { gl::FALSE };
+                unsafe {
+                    gl::SampleCoverage(coverage.value, invert);
+                }
+                self.enable(Capability::SampleCoverage);
+            },
+            None => {
+                self.disable(Capability::SampleCoverage);
+            }
+        }
+    }
+
+    /// Enable or disable computing each fragment's coverage from its alpha
+    /// value while multisampling (`glitter::SAMPLE_ALPHA_TO_COVERAGE`).
+    fn set_alpha_to_coverage(&mut self, enabled: bool) {
+        if enabled {
+            self.enable(Capability::SampleAlphaToCoverage);
+        }
+        else {
+            self.disable(Capability::SampleAlphaToCoverage);
+        }
+    }
+
+    /// Enable or disable dithering color values before they are written to
+    /// the color buffer (`glitter::DITHER`).
+    fn set_dither(&mut self, enabled: bool) {
+        if enabled {
+            self.enable(Capability::Dither);
+        }
+        else {
+            self.disable(Capability::Dither);
+        }
+    }
+
+    /// Enable or disable seamless cubemap filtering, which samples across
+    /// the edges between a cubemap's faces instead of clamping to the edge
+    /// of each face (`GL_TEXTURE_CUBE_MAP_SEAMLESS`).
+    ///
+    /// # Note
+    /// Cubemap filtering is always seamless in OpenGL ES 3.0, but must be
+    /// enabled in desktop OpenGL, so this method is only available with the
+    /// `gl33` feature.
+    #[cfg(feature = "gl33")]
+    fn set_seamless_cube_map(&mut self, enabled: bool) {
+        unsafe {
+            if enabled {
+                gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+            }
+            else {
+                gl::Disable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+            }
+            dbg_gl_sanity_check! {
+                call "glEnable";
+                args (gl::TEXTURE_CUBE_MAP_SEAMLESS, enabled);
+                GLError::InvalidEnum => "`cap` is not a valid OpenGL capability",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Set the sample coverage, alpha-to-coverage, and dithering state from
+    /// a [`MultisampleState`](../types/struct.MultisampleState.html) at
+    /// once.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::MultisampleState;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// // Draw foliage with smooth, alpha-tested edges
+    /// gl.set_multisample_state(&MultisampleState {
+    ///     alpha_to_coverage: true,
+    ///     ..Default::default()
+    /// });
+    /// # }
+    /// ```
+    fn set_multisample_state(&mut self, state: &MultisampleState) {
+        self.set_sample_coverage(state.sample_coverage);
+        self.set_alpha_to_coverage(state.alpha_to_coverage);
+        self.set_dither(state.dither);
+    }
+
+    /// Enable or disable converting fragment colors from linear values to
+    /// the sRGB color space when they are written to (and blended with) an
+    /// sRGB color buffer.
+    ///
+    /// # Note
+    /// OpenGL ES always converts colors written to sRGB color buffers, so
+    /// this method is only available with the `gl33` feature.
+    ///
+    /// # See also
+    /// [`glEnable`](http://docs.gl/gl4/glEnable) OpenGL docs
+    #[cfg(feature = "gl33")]
+    fn set_framebuffer_srgb(&mut self, enabled: bool) {
+        unsafe {
+            if enabled {
+                gl::Enable(gl::FRAMEBUFFER_SRGB);
+            }
+            else {
+                gl::Disable(gl::FRAMEBUFFER_SRGB);
+            }
+        }
+    }
+
+    /// Enable or disable using the point sizes written to `gl_PointSize` by
+    /// vertex shaders. While disabled, points are drawn with a size of 1
+    /// pixel.
+    ///
+    /// # Note
+    /// OpenGL ES always uses `gl_PointSize`, so this method is only
+    /// available with the `gl33` feature.
+    ///
+    /// # See also
+    /// [`glEnable`](http://docs.gl/gl4/glEnable) OpenGL docs
+    #[cfg(feature = "gl33")]
+    fn set_program_point_size(&mut self, enabled: bool) {
+        unsafe {
+            if enabled {
+                gl::Enable(gl::PROGRAM_POINT_SIZE);
+            }
+            else {
+                gl::Disable(gl::PROGRAM_POINT_SIZE);
+            }
+        }
+    }
+
+    /// Returns `true` if the point sizes written to `gl_PointSize` by
+    /// vertex shaders are used when drawing points. This is always the case
+    /// with OpenGL ES; with the `gl33` feature, it depends on whether
+    /// `GL_PROGRAM_POINT_SIZE` is enabled (see [`gl.set_program_point_size`]
+    /// (trait.ContextExt.html#method.set_program_point_size)).
+    ///
+    /// The sizes are still clamped to the implementation's point size range
+    /// (see `aliased_point_size_range` in the context's [`capabilities`]
+    /// (struct.ContextOf.html#method.capabilities)).
+    fn is_shader_point_size_enabled(&self) -> bool {
+        _is_program_point_size_enabled()
+    }
+
+    /// Make sure that all of the OpenGL commands that have been issued so
+    /// far will be executed in a finite amount of time, without waiting for
+    /// them to complete.
+    ///
+    /// # See also
+    /// [`glFlush`](http://docs.gl/es2/glFlush) OpenGL docs
+    fn flush(&self) {
+        unsafe {
+            gl::Flush();
+            dbg_gl_sanity_check! {
+                call "glFlush";
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Block until all of the OpenGL commands that have been issued so far
+    /// have completed. This stalls the CPU until the GPU is idle, so it
+    /// should generally only be used for debugging or benchmarking; prefer
+    /// using a fence to wait for specific commands.
+    ///
+    /// # See also
+    /// [`glFinish`](http://docs.gl/es2/glFinish) OpenGL docs
+    fn finish(&self) {
+        unsafe {
+            gl::Finish();
+            dbg_gl_sanity_check! {
+                call "glFinish";
+                _ => "Unknown error"
+            }
+        }
+    }
 }
 
 impl<C: BaseContext> ContextExt for C {
@@ -586,11 +1994,19 @@ pub mod ext {
     pub use BaseContext;
     pub use ContextExt;
     pub use ContextBufferExt;
+    #[cfg(feature = "gles31")] pub use ContextComputeExt;
+    pub use ContextDebugExt;
+    #[cfg(feature = "gles3")] pub use ContextDrawBaseExt;
+    pub use ContextDrawExt;
     pub use ContextFramebufferExt;
+    #[cfg(feature = "gles31")] pub use ContextIndirectExt;
     pub use ContextProgramExt;
+    #[cfg(feature = "gl33")] pub use ContextQueryExt;
     pub use ContextRenderbufferExt;
+    pub use ContextSamplerExt;
     pub use ContextShaderExt;
     pub use ContextTextureExt;
+    pub use ContextTextureUnitExt;
 }
 
 /// The 'core' OpenGL context trait. This trait provides access to any OpenGL
@@ -598,6 +2014,7 @@ pub mod ext {
 /// `ContextOf<_, _, _, _, _>`, as well as for `&mut ContextOf<_, _, _, _, _>`.
 pub trait AContext: ContextExt +
                     ContextBufferExt +
+                    ContextDebugExt +
                     ContextFramebufferExt +
                     ContextProgramExt +
                     ContextRenderbufferExt +
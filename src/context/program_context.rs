@@ -1,6 +1,8 @@
 //! Contains all of the OpenGL state types related to shader programs.
 
+use std::mem;
 use std::ptr;
+use std::slice;
 use std::error;
 use std::fmt;
 use std::borrow::BorrowMut;
@@ -10,9 +12,20 @@ use gl;
 use gl::types::*;
 use types::{GLObject, GLError};
 use context::{AContext, BaseContext, ContextOf};
-use program::{Program, ProgramAttrib, ProgramUniform};
-use shader::Shader;
-use uniform_data::{UniformData, UniformDatumType, UniformPrimitiveType};
+use program::{Program, ProgramAttrib, ProgramUniform, ActiveVariable,
+              UniformLocation, UniformLocations, UniformDeclaration};
+#[cfg(feature = "gles3")] use program::ProgramUniformBlock;
+#[cfg(feature = "gles3")]
+use uniform_block::{UniformBlockLayout, UniformBlockMember};
+#[cfg(feature = "gles31")] use program::{StorageBlock, AtomicCounterBinding};
+use shader::{Shader, ShaderType, ContextShaderBuilderExt};
+use uniform_data::{UniformData, UniformDatum, UniformDatumType,
+                   UniformPrimitiveType, MatrixOrder, Uniforms};
+use matrix_order;
+use state_cache;
+use restore_bindings;
+use vertex_data::VertexData;
+use source_dump::SourceDump;
 
 unsafe fn _get_program_iv(program: &Program,
                           pname: GLenum,
@@ -20,6 +33,8 @@ unsafe fn _get_program_iv(program: &Program,
 {
     gl::GetProgramiv(program.id(), pname, params);
     dbg_gl_sanity_check! {
+        call "glGetProgramiv";
+        args (program.id(), pname, params);
         GLError::InvalidEnum => "`pname` is not an accepted value",
         GLError::InvalidValue => "`program` is not a value generated by OpenGL",
         GLError::InvalidOperation => "`program` does not refer to a program object",
@@ -27,37 +42,644 @@ unsafe fn _get_program_iv(program: &Program,
     }
 }
 
+#[cfg(feature = "gles3")]
+fn _set_binary_retrievable<C: BaseContext>(gl: &C, program: &mut Program) {
+    use program_binary::ContextProgramBinaryExt;
+    gl.set_program_binary_retrievable(program);
+}
+
+// The hint can only be set with the `gles3` feature
+#[cfg(not(feature = "gles3"))]
+fn _set_binary_retrievable<C: BaseContext>(_gl: &C, _program: &mut Program) {
+
+}
+
+fn _bind_attrib_location(program: &mut Program, index: u32, name: &str)
+    -> Result<(), GLError>
+{
+    let c_str = try! {
+        CString::new(name).map_err(|_| {
+            let msg = format!("Attribute name {:?} contains a nul byte", name);
+            GLError::Message(msg)
+        })
+    };
+
+    unsafe {
+        gl::BindAttribLocation(program.id(),
+                               index as GLuint,
+                               c_str.as_ptr() as *const GLchar);
+        dbg_gl_error! {
+            call "glBindAttribLocation";
+            args (program.id(), index, name);
+            GLError::InvalidValue => "`index` is greater than or equal to GL_MAX_VERTEX_ATTRIBS, or `program` is not a value generated by OpenGL",
+            GLError::InvalidOperation => "`name` starts with the reserved prefix \"gl_\", or `program` is not a program object",
+            _ => "Unknown error"
+        }
+    }
+    Ok(())
+}
+
+fn _detach_shader(program: &mut Program, shader: &Shader) {
+    unsafe {
+        gl::DetachShader(program.id(), shader.id());
+        dbg_gl_error! {
+            call "glDetachShader";
+            args (program.id(), shader.id());
+            GLError::InvalidValue => "One of either `program` or `shader` is not an OpenGL object",
+            GLError::InvalidOperation => "`shader` is not attached to `program`, `shader` is not a shader object, or `program` is not a program object",
+            _ => "Unknown error"
+        }
+    }
+}
+
+#[cfg(feature = "gles3")]
+unsafe fn _get_active_uniform_block_iv(program: &Program,
+                                       block: ProgramUniformBlock,
+                                       pname: GLenum,
+                                       params: *mut GLint)
+{
+    gl::GetActiveUniformBlockiv(program.id(), block.gl_index, pname, params);
+    dbg_gl_sanity_check! {
+        call "glGetActiveUniformBlockiv";
+        args (program.id(), block.gl_index, pname);
+        GLError::InvalidValue => "`block` is not an active uniform block of `program`",
+        GLError::InvalidOperation => "`program` is not a program object",
+        _ => "Unknown error"
+    }
+}
+
+#[cfg(feature = "gles3")]
+unsafe fn _get_active_uniforms_iv(program: &Program,
+                                  indices: &[GLuint],
+                                  pname: GLenum)
+    -> Vec<GLint>
+{
+    let mut params = vec![0; indices.len()];
+    gl::GetActiveUniformsiv(program.id(),
+                            indices.len() as GLsizei,
+                            indices.as_ptr(),
+                            pname,
+                            params.as_mut_ptr());
+    dbg_gl_sanity_check! {
+        call "glGetActiveUniformsiv";
+        args (program.id(), indices.len(), pname);
+        GLError::InvalidValue => "An index is out of range",
+        GLError::InvalidOperation => "`program` is not a program object",
+        _ => "Unknown error"
+    }
+    params
+}
+
+#[cfg(feature = "gles3")]
+unsafe fn _get_uniform_block_layout(program: &Program,
+                                    block: ProgramUniformBlock)
+    -> UniformBlockLayout
+{
+    let mut data_size: GLint = 0;
+    _get_active_uniform_block_iv(program,
+                                 block,
+                                 gl::UNIFORM_BLOCK_DATA_SIZE,
+                                 &mut data_size);
+
+    let mut count: GLint = 0;
+    _get_active_uniform_block_iv(program,
+                                 block,
+                                 gl::UNIFORM_BLOCK_ACTIVE_UNIFORMS,
+                                 &mut count);
+
+    let mut indices: Vec<GLint> = vec![0; count as usize];
+    if count > 0 {
+        _get_active_uniform_block_iv(program,
+                                     block,
+                                     gl::UNIFORM_BLOCK_ACTIVE_UNIFORM_INDICES,
+                                     indices.as_mut_ptr());
+    }
+    let indices: Vec<GLuint> = indices.iter().map(|&i| i as GLuint).collect();
+
+    let offsets = _get_active_uniforms_iv(program, &indices, gl::UNIFORM_OFFSET);
+    let array_strides = _get_active_uniforms_iv(program,
+                                                &indices,
+                                                gl::UNIFORM_ARRAY_STRIDE);
+    let matrix_strides = _get_active_uniforms_iv(program,
+                                                 &indices,
+                                                 gl::UNIFORM_MATRIX_STRIDE);
+    let row_majors = _get_active_uniforms_iv(program,
+                                             &indices,
+                                             gl::UNIFORM_IS_ROW_MAJOR);
+
+    let mut max_length: GLint = 0;
+    _get_program_iv(program,
+                    gl::ACTIVE_UNIFORM_MAX_LENGTH,
+                    &mut max_length as *mut GLint);
+
+    let mut members = Vec::with_capacity(indices.len());
+    for (i, &index) in indices.iter().enumerate() {
+        let mut bytes = Vec::<u8>::with_capacity(max_length as usize);
+        let mut length: GLsizei = 0;
+        let mut size: GLint = 0;
+        let mut gl_type: GLenum = 0;
+        gl::GetActiveUniform(program.id(),
+                             index,
+                             max_length,
+                             &mut length as *mut GLsizei,
+                             &mut size as *mut GLint,
+                             &mut gl_type as *mut GLenum,
+                             bytes.as_mut_ptr() as *mut GLchar);
+        dbg_gl_sanity_check! {
+            call "glGetActiveUniform";
+            args (program.id(), index);
+            GLError::InvalidValue => "`program` is not a value generated by OpenGL, `index` is out of range, or `bufSize` < 0",
+            GLError::InvalidOperation => "`program` is not a program object",
+            _ => "Unknown error"
+        }
+        bytes.set_len(length as usize);
+
+        if let Ok(name) = String::from_utf8(bytes) {
+            members.push(UniformBlockMember {
+                name: name,
+                gl_type: gl_type,
+                size: size as usize,
+                offset: offsets[i] as usize,
+                array_stride: array_strides[i] as usize,
+                matrix_stride: matrix_strides[i] as usize,
+                row_major: row_majors[i] != 0
+            });
+        }
+    }
+
+    UniformBlockLayout {
+        data_size: data_size as usize,
+        members: members
+    }
+}
+
+#[cfg(feature = "gles31")]
+unsafe fn _get_program_interface_iv(program: &Program,
+                                    interface: GLenum,
+                                    pname: GLenum)
+    -> GLint
+{
+    let mut value: GLint = 0;
+    gl::GetProgramInterfaceiv(program.id(), interface, pname, &mut value);
+    dbg_gl_sanity_check! {
+        call "glGetProgramInterfaceiv";
+        args (program.id(), interface, pname);
+        GLError::InvalidEnum => "`programInterface` or `pname` is not an accepted value",
+        GLError::InvalidOperation => "`program` is not a program object",
+        _ => "Unknown error"
+    }
+    value
+}
+
+#[cfg(feature = "gles31")]
+unsafe fn _get_shader_storage_blocks(program: &Program) -> Vec<StorageBlock> {
+    let count = _get_program_interface_iv(program,
+                                          gl::SHADER_STORAGE_BLOCK,
+                                          gl::ACTIVE_RESOURCES);
+    let max_length = _get_program_interface_iv(program,
+                                               gl::SHADER_STORAGE_BLOCK,
+                                               gl::MAX_NAME_LENGTH);
+
+    let mut blocks = Vec::with_capacity(count as usize);
+    for index in 0..count as GLuint {
+        let mut bytes = vec![0u8; max_length as usize];
+        let mut length: GLsizei = 0;
+        gl::GetProgramResourceName(program.id(),
+                                   gl::SHADER_STORAGE_BLOCK,
+                                   index,
+                                   max_length,
+                                   &mut length,
+                                   bytes.as_mut_ptr() as *mut GLchar);
+        dbg_gl_sanity_check! {
+            call "glGetProgramResourceName";
+            args (program.id(), gl::SHADER_STORAGE_BLOCK, index);
+            GLError::InvalidValue => "`index` is out of range",
+            GLError::InvalidOperation => "`program` is not a program object",
+            _ => "Unknown error"
+        }
+        bytes.truncate(length as usize);
+
+        let props = [gl::BUFFER_BINDING, gl::BUFFER_DATA_SIZE];
+        let mut values: [GLint; 2] = [0; 2];
+        gl::GetProgramResourceiv(program.id(),
+                                 gl::SHADER_STORAGE_BLOCK,
+                                 index,
+                                 props.len() as GLsizei,
+                                 props.as_ptr(),
+                                 values.len() as GLsizei,
+                                 ptr::null_mut(),
+                                 values.as_mut_ptr());
+        dbg_gl_sanity_check! {
+            call "glGetProgramResourceiv";
+            args (program.id(), gl::SHADER_STORAGE_BLOCK, index);
+            GLError::InvalidValue => "`index` is out of range",
+            GLError::InvalidOperation => "`program` is not a program object",
+            _ => "Unknown error"
+        }
+
+        if let Ok(name) = String::from_utf8(bytes) {
+            blocks.push(StorageBlock {
+                name: name,
+                index: index,
+                binding: values[0] as GLuint,
+                data_size: values[1] as usize
+            });
+        }
+    }
+    blocks
+}
+
+#[cfg(feature = "gles31")]
+unsafe fn _get_atomic_counter_bindings(program: &Program)
+    -> Vec<AtomicCounterBinding>
+{
+    let count = _get_program_interface_iv(program,
+                                          gl::ATOMIC_COUNTER_BUFFER,
+                                          gl::ACTIVE_RESOURCES);
+
+    let mut bindings = Vec::with_capacity(count as usize);
+    for index in 0..count as GLuint {
+        let props = [gl::BUFFER_BINDING,
+                     gl::BUFFER_DATA_SIZE,
+                     gl::NUM_ACTIVE_VARIABLES];
+        let mut values: [GLint; 3] = [0; 3];
+        gl::GetProgramResourceiv(program.id(),
+                                 gl::ATOMIC_COUNTER_BUFFER,
+                                 index,
+                                 props.len() as GLsizei,
+                                 props.as_ptr(),
+                                 values.len() as GLsizei,
+                                 ptr::null_mut(),
+                                 values.as_mut_ptr());
+        dbg_gl_sanity_check! {
+            call "glGetProgramResourceiv";
+            args (program.id(), gl::ATOMIC_COUNTER_BUFFER, index);
+            GLError::InvalidValue => "`index` is out of range",
+            GLError::InvalidOperation => "`program` is not a program object",
+            _ => "Unknown error"
+        }
+
+        bindings.push(AtomicCounterBinding {
+            index: index,
+            binding: values[0] as GLuint,
+            data_size: values[1] as usize,
+            active_counters: values[2] as usize
+        });
+    }
+    bindings
+}
+
+type GetActiveFn = unsafe fn(GLuint,
+                             GLuint,
+                             GLsizei,
+                             *mut GLsizei,
+                             *mut GLint,
+                             *mut GLenum,
+                             *mut GLchar);
+
+unsafe fn _get_active_variables(program: &Program,
+                                count_pname: GLenum,
+                                max_length_pname: GLenum,
+                                get_active: GetActiveFn,
+                                call_name: &'static str)
+    -> Vec<ActiveVariable>
+{
+    let mut count : GLint = 0;
+    _get_program_iv(program, count_pname, &mut count as *mut GLint);
+
+    let mut max_length : GLint = 0;
+    _get_program_iv(program,
+                    max_length_pname,
+                    &mut max_length as *mut GLint);
+
+    let mut variables = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        let mut bytes = Vec::<u8>::with_capacity(max_length as usize);
+        let mut length : GLsizei = 0;
+        let mut size : GLint = 0;
+        let mut gl_type : GLenum = 0;
+
+        get_active(program.id(),
+                   index as GLuint,
+                   max_length,
+                   &mut length as *mut GLsizei,
+                   &mut size as *mut GLint,
+                   &mut gl_type as *mut GLenum,
+                   bytes.as_mut_ptr() as *mut GLchar);
+        dbg_gl_sanity_check! {
+            call call_name;
+            args (program.id(), index);
+            GLError::InvalidValue => "`program` is not a value generated by OpenGL, `index` is out of range, or `bufSize` < 0",
+            GLError::InvalidOperation => "`program` is not a program object",
+            _ => "Unknown error"
+        }
+        bytes.set_len(length as usize);
+
+        if let Ok(name) = String::from_utf8(bytes) {
+            variables.push(ActiveVariable {
+                name: name,
+                size: size,
+                gl_type: gl_type
+            });
+        }
+    }
+    variables
+}
+
+// Compare the names of a program's active variables against a list of
+// expected names, emitting a warning for each mismatch. Built-in variables
+// are ignored, and the `[0]` suffix OpenGL adds to arrays is stripped.
+fn _check_active_variables(program: &Program,
+                           kind: &str,
+                           expected: &[&str],
+                           active: &[ActiveVariable])
+    -> bool
+{
+    let active_names: Vec<&str> = active.iter()
+        .map(|var| var.name.trim_right_matches("[0]"))
+        .filter(|name| !name.starts_with("gl_"))
+        .collect();
+
+    let mut matches = true;
+    for name in expected {
+        if !active_names.contains(name) {
+            warn!("Program {}: {} `{}` is not active (it may be unused \
+                   or may have been optimized out)",
+                  program.id(), kind, name);
+            matches = false;
+        }
+    }
+    for name in &active_names {
+        if !expected.contains(name) {
+            warn!("Program {}: active {} `{}` is never provided",
+                  program.id(), kind, name);
+            matches = false;
+        }
+    }
+    matches
+}
+
+// Returns true if a uniform of the given OpenGL type can be set with
+// uniform data of the given datum type.
+fn _uniform_type_matches(datum_type: UniformDatumType, gl_type: GLenum)
+    -> bool
+{
+    use uniform_data::UniformDatumType as D;
+    use uniform_data::UniformPrimitiveType as P;
+
+    match datum_type {
+        D::Vec1(P::Float) => gl_type == gl::FLOAT,
+        D::Vec2(P::Float) => gl_type == gl::FLOAT_VEC2,
+        D::Vec3(P::Float) => gl_type == gl::FLOAT_VEC3,
+        D::Vec4(P::Float) => gl_type == gl::FLOAT_VEC4,
+        D::Vec1(P::Int) => {
+            // Samplers are set with integer values
+            match gl_type {
+                gl::INT | gl::BOOL |
+                gl::SAMPLER_1D | gl::SAMPLER_2D | gl::SAMPLER_3D |
+                gl::SAMPLER_CUBE | gl::SAMPLER_2D_SHADOW |
+                gl::SAMPLER_2D_ARRAY => true,
+                _ => false
+            }
+        },
+        D::Vec2(P::Int) => gl_type == gl::INT_VEC2 || gl_type == gl::BOOL_VEC2,
+        D::Vec3(P::Int) => gl_type == gl::INT_VEC3 || gl_type == gl::BOOL_VEC3,
+        D::Vec4(P::Int) => gl_type == gl::INT_VEC4 || gl_type == gl::BOOL_VEC4,
+        D::Matrix2x2 => gl_type == gl::FLOAT_MAT2,
+        D::Matrix3x3 => gl_type == gl::FLOAT_MAT3,
+        D::Matrix4x4 => gl_type == gl::FLOAT_MAT4
+    }
+}
+
 /// Provides a safe interface for creating program objects. A
 /// `ProgramBuilder` can be created using the [`gl.build_program`]
-/// (trait.ContextProgramBuilderExt.html#method.build_program) method.
+/// (trait.ContextProgramBuilderExt.html#method.build_program) method, or
+/// with [`ProgramBuilder::new`](struct.ProgramBuilder.html#method.new) to
+/// compile the program's shaders from source as part of building it.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::ProgramBuilder;
+///
+/// # fn main() {
+/// # let vertex_source = "";
+/// # let fragment_source = "";
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let program = ProgramBuilder::new(&gl)
+///     .vertex(vertex_source)
+///     .fragment(fragment_source)
+///     .bind_attrib(0, "a_position")
+///     .link();
+///
+/// match program {
+///     Ok(program) => { /* Draw with the program... */ },
+///     Err(e) => println!("Failed to build program:\n{}", e)
+/// }
+/// # }
+/// ```
 pub struct ProgramBuilder<'a, C>
     where C: AContext + 'a
 {
     gl: &'a C,
-    shaders: &'a [Shader]
+    shaders: &'a [Shader],
+    sources: Vec<(ShaderType, &'a str)>,
+    attrib_locations: Vec<(u32, &'a str)>,
+    attribs: Option<Vec<String>>,
+    uniforms: Option<&'a [&'a str]>,
+    uniform_locations: Option<&'a [UniformDeclaration]>,
+    dump_source: bool,
+    binary_retrievable: bool
 }
 
 impl<'a, C> ProgramBuilder<'a, C>
     where C: AContext
 {
-    /// Create a new program builder.
-    pub fn new(gl: &'a C, shaders: &'a [Shader])
+    /// Create a new program builder with no shaders.
+    pub fn new(gl: &'a C) -> Self {
+        ProgramBuilder {
+            gl: gl,
+            shaders: &[],
+            sources: vec![],
+            attrib_locations: vec![],
+            attribs: None,
+            uniforms: None,
+            uniform_locations: None,
+            dump_source: false,
+            binary_retrievable: false
+        }
+    }
+
+    /// Attach already-compiled shaders to the program. The shaders are left
+    /// attached to the program after linking.
+    pub fn shaders(mut self, shaders: &'a [Shader]) -> Self {
+        self.shaders = shaders;
+        self
+    }
+
+    /// Compile a shader of the given type from `source`, and attach it to
+    /// the program. The shader is detached and deleted after linking.
+    pub fn shader(mut self, ty: ShaderType, source: &'a str) -> Self {
+        self.sources.push((ty, source));
+        self
+    }
+
+    /// Compile a vertex shader from `source`, and attach it to the program.
+    /// The shader is detached and deleted after linking.
+    pub fn vertex(self, source: &'a str) -> Self {
+        self.shader(ShaderType::VertexShader, source)
+    }
+
+    /// Compile a fragment shader from `source`, and attach it to the
+    /// program. The shader is detached and deleted after linking.
+    pub fn fragment(self, source: &'a str) -> Self {
+        self.shader(ShaderType::FragmentShader, source)
+    }
+
+    /// Bind the vertex attribute `name` to the attribute index `index`
+    /// before linking, so that the same index can be used for the attribute
+    /// across programs.
+    ///
+    /// # See also
+    /// [`glBindAttribLocation`](http://docs.gl/es2/glBindAttribLocation)
+    /// OpenGL docs
+    pub fn bind_attrib(mut self, index: u32, name: &'a str) -> Self {
+        self.attrib_locations.push((index, name));
+        self
+    }
+
+    /// After linking, verify that each declared uniform has the expected
+    /// explicit location and type. Linking will fail with an error if any
+    /// of the declarations do not match the program.
+    ///
+    /// # See also
+    /// [`gl.check_uniform_locations`]
+    /// (trait.ContextProgramExt.html#method.check_uniform_locations)
+    pub fn uniform_locations(mut self, declarations: &'a [UniformDeclaration])
         -> Self
     {
-        ProgramBuilder { gl: gl, shaders: shaders }
+        self.uniform_locations = Some(declarations);
+        self
+    }
+
+    /// Write the source of each shader (with line numbers) to a file in the
+    /// temporary directory before linking. If linking fails, the linker
+    /// errors are appended to the file, and the file's path is included in
+    /// the returned error. If linking succeeds, the file is removed. If the
+    /// driver crashes while linking, the file will be left behind.
+    pub fn dump_source_on_failure(mut self) -> Self {
+        self.dump_source = true;
+        self
+    }
+
+    /// Hint that the binary of the program will be retrieved after linking,
+    /// so that it can be cached.
+    ///
+    /// # See also
+    /// [`gl.set_program_binary_retrievable`]
+    /// (../../program_binary/trait.ContextProgramBinaryExt.html#method.set_program_binary_retrievable)
+    #[cfg(feature = "gles3")]
+    pub fn binary_retrievable(mut self) -> Self {
+        self.binary_retrievable = true;
+        self
+    }
+
+    /// After linking, compare the program's active attributes against
+    /// the attributes of the vertex data type `V`, emitting a warning
+    /// (using the `log` crate) for each attribute that is inactive in the
+    /// program, and for each active attribute that `V` does not provide.
+    ///
+    /// # See also
+    /// [`gl.check_active_attribs`]
+    /// (trait.ContextProgramExt.html#method.check_active_attribs)
+    pub fn vertex_data<V: VertexData>(mut self) -> Self {
+        let mut names = vec![];
+        V::visit_attributes(|attrib| names.push(attrib.name));
+        self.attribs = Some(names);
+        self
+    }
+
+    /// After linking, compare the program's active uniforms against the
+    /// provided list of uniform names, emitting a warning (using the `log`
+    /// crate) for each uniform that is inactive in the program, and for each
+    /// active uniform that is not in the list.
+    ///
+    /// # See also
+    /// [`gl.check_active_uniforms`]
+    /// (trait.ContextProgramExt.html#method.check_active_uniforms)
+    pub fn uniforms(mut self, names: &'a [&'a str]) -> Self {
+        self.uniforms = Some(names);
+        self
+    }
+
+    /// Compile the shaders that were provided as source, then create and
+    /// link the program object with all of the provided shaders, or return
+    /// an error. This is the same as [`try_unwrap`]
+    /// (struct.ProgramBuilder.html#method.try_unwrap).
+    ///
+    /// # Failures
+    /// An error will be returned if any shader failed to compile (with the
+    /// log of each shader that failed), or if there was an error linking
+    /// the program object (with the link log, followed by the log of each
+    /// shader).
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL
+    /// error was generated with debug assertions enabled.
+    pub fn link(self) -> Result<Program, GLError> {
+        self.try_unwrap()
+    }
+
+    fn _compile_sources(&self) -> Result<Vec<Shader>, GLError> {
+        let mut compiled = vec![];
+        let mut errors = vec![];
+        for &(ty, source) in &self.sources {
+            let mut builder = self.gl.build_shader(ty, source);
+            if self.dump_source {
+                builder = builder.dump_source_on_failure();
+            }
+            match builder.try_unwrap() {
+                Ok(shader) => compiled.push(shader),
+                Err(err) => errors.push(format!("{:?} log:\n{}", ty, err))
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(compiled)
+        }
+        else {
+            Err(GLError::Message(errors.join("\n")))
+        }
+    }
+
+    // Append the info log of each compiled shader to a link error, since
+    // link errors often refer to warnings from compiling the shaders
+    fn _link_error(&self, compiled: &[Shader], err: GLError) -> GLError {
+        let mut msg = format!("Link log:\n{}", err);
+        for (&(ty, _), shader) in self.sources.iter().zip(compiled) {
+            if let Some(log) = self.gl.get_shader_info_log(shader) {
+                msg.push_str(&format!("\n{:?} log:\n{}", ty, log));
+            }
+        }
+        GLError::Message(msg)
     }
 
-    /// Create and link the program object with the provided shaders, or
+    /// Compile the shaders that were provided as source, then create and
+    /// link the program object with all of the provided shaders, or
     /// return an error.
     ///
     /// # Failures
-    /// An error will be returned if there was an error linking the program
-    /// object.
+    /// An error will be returned if any shader failed to compile, or if
+    /// there was an error linking the program object. See [`link`]
+    /// (struct.ProgramBuilder.html#method.link) for details.
     ///
     /// # Panics
     /// This function will panic if an OpenGL
     /// error was generated with debug assertions enabled.
     pub fn try_unwrap(self) -> Result<Program, GLError> {
+        let compiled = try!(self._compile_sources());
+
         unsafe {
             let mut program = try! {
                 self.gl.create_program().or_else(|_| {
@@ -66,11 +688,72 @@ impl<'a, C> ProgramBuilder<'a, C>
                 })
             };
 
-            for shader in self.shaders {
+            for shader in self.shaders.iter().chain(&compiled) {
                 self.gl.attach_shader(&mut program, shader);
             }
+            for &(index, name) in &self.attrib_locations {
+                try!(_bind_attrib_location(&mut program, index, name));
+            }
+            if self.binary_retrievable {
+                _set_binary_retrievable(self.gl, &mut program);
+            }
+
+            let dump = if self.dump_source {
+                let sources: Vec<_> = self.shaders.iter()
+                    .chain(&compiled)
+                    .enumerate()
+                    .map(|(i, shader)| {
+                        let label = format!("Shader {}", i);
+                        let source = self.gl.get_shader_source(shader)
+                            .unwrap_or_else(String::new);
+                        (label, source)
+                    })
+                    .collect();
+                SourceDump::create("program", &sources).ok()
+            }
+            else {
+                None
+            };
+
+            let linked = self.gl.link_program(&mut program);
+            for shader in &compiled {
+                _detach_shader(&mut program, shader);
+            }
+
+            match linked {
+                Ok(()) => {
+                    if let Some(dump) = dump {
+                        dump.remove();
+                    }
+                },
+                Err(err) => {
+                    let err = if compiled.is_empty() {
+                        err
+                    }
+                    else {
+                        self._link_error(&compiled, err)
+                    };
+                    return match dump {
+                        Some(dump) => Err(dump.into_error(err)),
+                        None => Err(err)
+                    };
+                }
+            }
+
+            if let Some(ref attribs) = self.attribs {
+                let names: Vec<&str> = attribs.iter().map(|s| &s[..]).collect();
+                self.gl.check_active_attribs(&program, &names);
+            }
+            if let Some(uniforms) = self.uniforms {
+                self.gl.check_active_uniforms(&program, uniforms);
+            }
+            if let Some(declarations) = self.uniform_locations {
+                try! {
+                    self.gl.check_uniform_locations(&program, declarations)
+                        .map_err(|e| GLError::Message(format!("{}", e)))
+                };
+            }
 
-            try!(self.gl.link_program(&mut program));
             Ok(program)
         }
     }
@@ -95,7 +778,7 @@ pub trait ContextProgramBuilderExt: AContext + Sized {
     fn build_program<'a>(&'a self, shaders: &'a [Shader])
         -> ProgramBuilder<'a, Self>
     {
-        ProgramBuilder::new(self, shaders)
+        ProgramBuilder::new(self).shaders(shaders)
     }
 }
 
@@ -143,6 +826,8 @@ pub trait ContextProgramExt: BaseContext {
         unsafe {
             gl::AttachShader(program.id(), shader.id());
             dbg_gl_error! {
+                call "glAttachShader";
+                args (program.id(), shader.id());
                 GLError::InvalidValue => "One of either `program` or `shader` is not an OpenGL object",
                 GLError::InvalidOperation => "`shader` is already attached to `program`, `shader` is not a shader object, or `program` is not a program object",
                 _ => "Unknown error"
@@ -170,6 +855,8 @@ pub trait ContextProgramExt: BaseContext {
         let success = unsafe {
             gl::LinkProgram(program.id());
             dbg_gl_error! {
+                call "glLinkProgram";
+                args (program.id());
                 GLError::InvalidValue => "`program` is not a value from OpenGL",
                 GLError::InvalidOperation => "`program` is not a program object",
                 _ => "Unknown error"
@@ -195,6 +882,70 @@ pub trait ContextProgramExt: BaseContext {
         }
     }
 
+    /// Check whether the program object can be executed with the current
+    /// OpenGL state (such as the textures bound to the texture units used
+    /// by its samplers). Validation can be slow, so it's mostly useful as a
+    /// debugging check right before a draw call.
+    ///
+    /// # Failures
+    /// If the `GL_VALIDATE_STATUS` after validating the program was not
+    /// `GL_TRUE`, then an error object containing the program's info log
+    /// will be returned.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error is generated and debug
+    /// assertions are enabled.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// # let program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// if cfg!(debug_assertions) {
+    ///     if let Err(e) = gl.validate_program(&program) {
+    ///         println!("Program can't be used for drawing: {}", e);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glValidateProgram`](http://docs.gl/es2/glValidateProgram) OpenGL
+    /// docs
+    fn validate_program(&self, program: &Program) -> Result<(), GLError> {
+        let success = unsafe {
+            gl::ValidateProgram(program.id());
+            dbg_gl_error! {
+                call "glValidateProgram";
+                args (program.id());
+                GLError::InvalidValue => "`program` is not a value from OpenGL",
+                GLError::InvalidOperation => "`program` is not a program object",
+                _ => "Unknown error"
+            }
+
+            let mut validate_status : GLint = 0;
+            _get_program_iv(program,
+                            gl::VALIDATE_STATUS,
+                            &mut validate_status as *mut GLint);
+
+            validate_status == gl::TRUE as GLint
+        };
+
+        if success {
+            Ok(())
+        }
+        else {
+            let msg = match self.get_program_info_log(program) {
+                Some(s) => { s },
+                None => { String::from("[Unknown program validation error]") }
+            };
+            Err(GLError::Message(msg))
+        }
+    }
+
     /// Return the information log for the program object, if any is
     /// available.
     ///
@@ -219,18 +970,271 @@ pub trait ContextProgramExt: BaseContext {
                                       ptr::null_mut(),
                                       bytes.as_mut_ptr() as *mut GLchar);
                 dbg_gl_sanity_check! {
+                    call "glGetProgramInfoLog";
+                    args (program.id(), info_length);
                     GLError::InvalidValue => "`program` is not a value generated by OpenGL, or `maxLength` < 0",
                     GLError::InvalidOperation => "`program` is not a program object",
                     _ => "Unknown error"
                 }
                 bytes.set_len((info_length - 1) as usize);
 
-                String::from_utf8(bytes).ok()
-            }
-            else {
-                None
+                String::from_utf8(bytes).ok()
+            }
+            else {
+                None
+            }
+        }
+    }
+
+    /// Return a list of all of the active attributes within a linked
+    /// program object.
+    ///
+    /// # Note
+    /// Attributes whose names contain an invalid UTF-8 sequence will
+    /// be omitted.
+    ///
+    /// # See also
+    /// [`glGetActiveAttrib`](http://docs.gl/es2/glGetActiveAttrib) OpenGL docs
+    fn get_active_attribs(&self, program: &Program) -> Vec<ActiveVariable> {
+        unsafe {
+            _get_active_variables(program,
+                                  gl::ACTIVE_ATTRIBUTES,
+                                  gl::ACTIVE_ATTRIBUTE_MAX_LENGTH,
+                                  gl::GetActiveAttrib,
+                                  "glGetActiveAttrib")
+        }
+    }
+
+    /// Return a list of all of the active uniforms within a linked
+    /// program object.
+    ///
+    /// # Note
+    /// Uniforms whose names contain an invalid UTF-8 sequence will
+    /// be omitted.
+    ///
+    /// # See also
+    /// [`glGetActiveUniform`](http://docs.gl/es2/glGetActiveUniform)
+    /// OpenGL docs
+    fn get_active_uniforms(&self, program: &Program) -> Vec<ActiveVariable> {
+        unsafe {
+            _get_active_variables(program,
+                                  gl::ACTIVE_UNIFORMS,
+                                  gl::ACTIVE_UNIFORM_MAX_LENGTH,
+                                  gl::GetActiveUniform,
+                                  "glGetActiveUniform")
+        }
+    }
+
+    /// Compare a linked program's active attributes against a list of
+    /// attribute names (such as the attributes of a vertex layout). A warning
+    /// is emitted (using the `log` crate) for each attribute that is not
+    /// active in the program (usually because it was unused and optimized
+    /// out), and for each active attribute that is not in the list.
+    /// Returns `true` if the attributes matched exactly.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use glitter::prelude::*;
+    /// # let gl = unsafe { glitter::Context::current_context() };
+    /// # let shaders = vec![];
+    /// let program = gl.build_program(&shaders).unwrap();
+    /// if !gl.check_active_attribs(&program, &["position", "color"]) {
+    ///     println!("Attribute mismatch! Check the log for details.");
+    /// }
+    /// ```
+    fn check_active_attribs(&self, program: &Program, names: &[&str])
+        -> bool
+    {
+        let active = self.get_active_attribs(program);
+        _check_active_variables(program, "attribute", names, &active)
+    }
+
+    /// Compare a linked program's active uniforms against a list of
+    /// uniform names (such as the uniforms set by a material). A warning
+    /// is emitted (using the `log` crate) for each uniform that is not
+    /// active in the program (usually because it was unused and optimized
+    /// out), and for each active uniform that is not in the list.
+    /// Returns `true` if the uniforms matched exactly.
+    fn check_active_uniforms(&self, program: &Program, names: &[&str])
+        -> bool
+    {
+        let active = self.get_active_uniforms(program);
+        _check_active_variables(program, "uniform", names, &active)
+    }
+
+    /// Verify a list of uniforms with explicit locations (declared in GLSL
+    /// with `layout(location = N)`) against a linked program. Checks that
+    /// no two declarations share a location, and that each uniform exists
+    /// in the program with the declared location and a compatible type.
+    ///
+    /// # Failures
+    /// An error is returned for the first declaration that does not
+    /// match the program.
+    ///
+    /// # See also
+    /// [`UniformLocation`](../../program/struct.UniformLocation.html):
+    /// A typed, constant uniform location.
+    fn check_uniform_locations(&self,
+                               program: &Program,
+                               declarations: &[UniformDeclaration])
+        -> Result<(), UniformLocationError>
+    {
+        for (i, declaration) in declarations.iter().enumerate() {
+            for other in &declarations[..i] {
+                if other.location == declaration.location &&
+                   other.name != declaration.name
+                {
+                    return Err(UniformLocationError::Conflict {
+                        location: declaration.location,
+                        names: (other.name, declaration.name)
+                    });
+                }
+            }
+        }
+
+        let active = self.get_active_uniforms(program);
+        for declaration in declarations {
+            let name = declaration.name;
+            let uniform = match self.get_uniform_location(program, name) {
+                Ok(uniform) => uniform,
+                Err(_) => return Err(UniformLocationError::Missing(name))
+            };
+
+            if uniform.gl_index != declaration.location {
+                return Err(UniformLocationError::WrongLocation {
+                    name: name,
+                    expected: declaration.location,
+                    actual: uniform.gl_index
+                });
+            }
+
+            let active_var = active.iter().find(|var| {
+                var.name.trim_right_matches("[0]") == name
+            });
+            if let Some(var) = active_var {
+                if !_uniform_type_matches(declaration.datum_type, var.gl_type) {
+                    return Err(UniformLocationError::WrongType {
+                        name: name,
+                        gl_type: var.gl_type
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Bind the vertex attribute `name` to the attribute `attrib`. The
+    /// binding takes effect the next time the program is linked, so this
+    /// should be called before [`gl.link_program`]
+    /// (trait.ContextProgramExt.html#method.link_program). Binding the same
+    /// attributes to the same indices in several programs lets them share
+    /// the same vertex attribute setup, instead of relying on the indices
+    /// chosen by the driver (see [`gl.get_attrib_location`]
+    /// (trait.ContextProgramExt.html#method.get_attrib_location)).
+    ///
+    /// # Failures
+    /// An error will be returned if `name` contains a nul byte.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::ProgramAttrib;
+    ///
+    /// # fn main() {
+    /// # let mut program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let position = ProgramAttrib { gl_index: 0 };
+    /// gl.bind_attrib_location(&mut program, position, "a_position").unwrap();
+    /// gl.link_program(&mut program).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glBindAttribLocation`](http://docs.gl/es2/glBindAttribLocation)
+    /// OpenGL docs
+    ///
+    /// [`ProgramBuilder::bind_attrib`]
+    /// (struct.ProgramBuilder.html#method.bind_attrib): Bind attribute
+    /// locations while building a program.
+    fn bind_attrib_location(&self,
+                            program: &mut Program,
+                            attrib: ProgramAttrib,
+                            name: &str)
+        -> Result<(), GLError>
+    {
+        _bind_attrib_location(program, attrib.gl_index, name)
+    }
+
+    /// Bind the fragment shader output variable `name` to the draw buffer
+    /// `color_number`. Like [`gl.bind_attrib_location`]
+    /// (trait.ContextProgramExt.html#method.bind_attrib_location), the
+    /// binding takes effect the next time the program is linked. This lets
+    /// programs that render to multiple render targets (such as a G-buffer)
+    /// map their outputs to draw buffers without `layout(location = ...)`
+    /// qualifiers.
+    ///
+    /// # Note
+    /// This method requires OpenGL 3.0, so it is only available with the
+    /// `gl33` feature. OpenGL ES doesn't support this function, so outputs
+    /// must use `layout(location = ...)` qualifiers instead.
+    ///
+    /// # Failures
+    /// An error will be returned if `name` contains a nul byte.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// # let mut gbuffer_program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// gl.bind_frag_data_location(&mut gbuffer_program, 0, "out_albedo").unwrap();
+    /// gl.bind_frag_data_location(&mut gbuffer_program, 1, "out_normal").unwrap();
+    /// gl.link_program(&mut gbuffer_program).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glBindFragDataLocation`](http://docs.gl/gl4/glBindFragDataLocation)
+    /// OpenGL docs
+    #[cfg(feature = "gl33")]
+    fn bind_frag_data_location(&self,
+                               program: &mut Program,
+                               color_number: u32,
+                               name: &str)
+        -> Result<(), GLError>
+    {
+        let c_str = try! {
+            CString::new(name).map_err(|_| {
+                let msg = format!("Output name {:?} contains a nul byte", name);
+                GLError::Message(msg)
+            })
+        };
+
+        unsafe {
+            gl::BindFragDataLocation(program.id(),
+                                     color_number as GLuint,
+                                     c_str.as_ptr() as *const GLchar);
+            dbg_gl_error! {
+                call "glBindFragDataLocation";
+                args (program.id(), color_number, name);
+                GLError::InvalidValue => "`color_number` is greater than or equal to GL_MAX_DRAW_BUFFERS, or `program` is not a value generated by OpenGL",
+                GLError::InvalidOperation => "`name` starts with the reserved prefix \"gl_\", or `program` is not a program object",
+                _ => "Unknown error"
             }
         }
+        Ok(())
     }
 
     /// Retrieve a program attribute's index by name, or return an error
@@ -257,6 +1261,8 @@ pub trait ContextProgramExt: BaseContext {
         unsafe {
             let index = gl::GetAttribLocation(program.id(), str_ptr);
             dbg_gl_error! {
+                call "glGetAttribLocation";
+                args (program.id(), name);
                 GLError::InvalidOperation => "`program` has not been linked, `program` is not a program object, or `program` is not a value generated by OpenGL",
                 _ => "Unknown error"
             }
@@ -294,6 +1300,8 @@ pub trait ContextProgramExt: BaseContext {
         unsafe {
             let index = gl::GetUniformLocation(program.id(), str_ptr);
             dbg_gl_error! {
+                call "glGetUniformLocation";
+                args (program.id(), name);
                 GLError::InvalidValue => "`program` is not a value generated by OpenGL",
                 GLError::InvalidOperation => "`program` is not a program object, or has not been successfully linked",
                 _ => "Unknown error"
@@ -332,84 +1340,478 @@ pub trait ContextProgramExt: BaseContext {
                       val: T)
         where T: UniformData
     {
-        let idx = uniform.gl_index as GLint;
-        let count = val.uniform_elements() as GLsizei;
-        let ptr = val.uniform_bytes().as_ptr();
+        _set_uniform(uniform, &val);
+    }
+
+    /// Set the value of a uniform variable with an explicit location
+    /// within the provided program object binding. Unlike [`gl.set_uniform`]
+    /// (trait.ContextProgramExt.html#method.set_uniform), the type of `val`
+    /// is checked against the type of the [`UniformLocation`]
+    /// (../../program/struct.UniformLocation.html).
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error is generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glUniform`](http://docs.gl/es2/glUniform) OpenGL docs
+    fn set_uniform_at<T>(&self,
+                         gl_program: &ProgramBinding,
+                         location: &UniformLocation<T>,
+                         val: T)
+        where T: UniformData
+    {
+        self.set_uniform(gl_program, location.uniform(), val);
+    }
+
+    /// Set the values of a uniform array within the provided program object
+    /// binding, starting at the element `uniform` (which can be an element
+    /// other than the first, using [`ProgramUniform::element`]
+    /// (../../program/struct.ProgramUniform.html#method.element)). Each
+    /// value of `vals` is uploaded to the next element of the array.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error is generated and
+    /// debug assertions are enabled.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// # let mut program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    ///
+    /// // uniform vec3 u_light_positions[4];
+    /// let positions = gl.get_uniform_location(&program, "u_light_positions")
+    ///                   .unwrap();
+    /// let lights: [[f32; 3]; 2] = [[0.0, 1.0, 0.0], [1.0, 1.0, 0.0]];
+    ///
+    /// let (gl_program, gl) = gl.use_program(&mut program);
+    /// gl.set_uniform_array(&gl_program, positions, &lights);
+    ///
+    /// // Only update the last light
+    /// gl.set_uniform_array(&gl_program, positions.element(1), &lights[1..]);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glUniform`](http://docs.gl/es2/glUniform) OpenGL docs
+    fn set_uniform_array<T>(&self,
+                            _gl_program: &ProgramBinding,
+                            uniform: ProgramUniform,
+                            vals: &[T])
+        where T: UniformDatum
+    {
+        _set_uniform(uniform, vals);
+    }
+
+    /// Look up the location of each uniform of the [`Uniforms`]
+    /// (../../uniform_data/trait.Uniforms.html) type `U` within a program.
+    /// Uniforms that are not active in the program (usually because they
+    /// were unused and optimized out) have no location, and are skipped
+    /// by [`gl.set_uniforms`](trait.ContextProgramExt.html#method.set_uniforms).
+    ///
+    /// # See also
+    /// [`glGetUniformLocation`](http://docs.gl/es2/glGetUniformLocation)
+    /// OpenGL docs
+    fn get_uniform_locations<U>(&self, program: &Program) -> UniformLocations
+        where U: Uniforms
+    {
+        let mut locations = vec![];
+        U::visit_uniforms(|name| {
+            locations.push(self.get_uniform_location(program, name).ok());
+        });
+        UniformLocations { locations: locations }
+    }
+
+    /// Set every uniform of `uniforms` within the provided program object
+    /// binding, using locations that were looked up with
+    /// [`gl.get_uniform_locations`]
+    /// (trait.ContextProgramExt.html#method.get_uniform_locations).
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error is generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glUniform`](http://docs.gl/es2/glUniform) OpenGL docs
+    fn set_uniforms<U>(&self,
+                       gl_program: &ProgramBinding,
+                       uniforms: &U,
+                       locations: &UniformLocations)
+        where U: Uniforms, Self: Sized
+    {
+        uniforms.apply(self, gl_program, locations);
+    }
+
+    /// Retrieve a program uniform block's index by name, or return an error
+    /// if the uniform block was not found within the program.
+    ///
+    /// # Note
+    /// Uniform blocks require OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glGetUniformBlockIndex`](http://docs.gl/es3/glGetUniformBlockIndex)
+    /// OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn get_uniform_block_index<'a>(&self, program: &Program, name: &'a str)
+        -> Result<ProgramUniformBlock, UnknownProgramUniformBlock<'a>>
+    {
+        let err = Err(UnknownProgramUniformBlock { name: name });
+
+        let c_str = match CString::new(name) {
+            Ok(s) => { s },
+            Err(_) => { return err; }
+        };
+
+        let str_ptr = c_str.as_ptr() as *const GLchar;
         unsafe {
-            match T::uniform_datum_type() {
-                UniformDatumType::Vec1(p) => {
-                    match p {
-                        UniformPrimitiveType::Float => {
-                            gl::Uniform1fv(idx, count, ptr as *const GLfloat);
-                        },
-                        UniformPrimitiveType::Int => {
-                            gl::Uniform1iv(idx, count, ptr as *const GLint);
-                        }
-                    }
-                },
-                UniformDatumType::Vec2(p) => {
-                    match p {
-                        UniformPrimitiveType::Float => {
-                            gl::Uniform2fv(idx, count, ptr as *const GLfloat);
-                        },
-                        UniformPrimitiveType::Int => {
-                            gl::Uniform2iv(idx, count, ptr as *const GLint);
-                        }
-                    }
-                },
-                UniformDatumType::Vec3(p) => {
-                    match p {
-                        UniformPrimitiveType::Float => {
-                            gl::Uniform3fv(idx, count, ptr as *const GLfloat);
-                        },
-                        UniformPrimitiveType::Int => {
-                            gl::Uniform3iv(idx, count, ptr as *const GLint);
-                        }
-                    }
-                },
-                UniformDatumType::Vec4(p) => {
-                    match p {
-                        UniformPrimitiveType::Float => {
-                            gl::Uniform4fv(idx, count, ptr as *const GLfloat);
-                        },
-                        UniformPrimitiveType::Int => {
-                            gl::Uniform4iv(idx, count, ptr as *const GLint);
-                        }
-                    }
-                },
-                UniformDatumType::Matrix2x2 => {
-                    gl::UniformMatrix2fv(idx,
-                                         count,
-                                         gl::FALSE,
-                                         ptr as *const GLfloat);
-                },
-                UniformDatumType::Matrix3x3 => {
-                    gl::UniformMatrix3fv(idx,
-                                         count,
-                                         gl::FALSE,
-                                         ptr as *const GLfloat);
-                },
-                UniformDatumType::Matrix4x4 => {
-                    gl::UniformMatrix4fv(idx,
-                                         count,
-                                         gl::FALSE,
-                                         ptr as *const GLfloat);
-                },
+            let index = gl::GetUniformBlockIndex(program.id(), str_ptr);
+            dbg_gl_error! {
+                call "glGetUniformBlockIndex";
+                args (program.id(), name);
+                GLError::InvalidValue => "`program` is not a value generated by OpenGL",
+                GLError::InvalidOperation => "`program` is not a program object",
+                _ => "Unknown error"
+            }
+
+            if index != gl::INVALID_INDEX {
+                Ok(ProgramUniformBlock { gl_index: index })
             }
+            else {
+                err
+            }
+        }
+    }
+
+    /// Get the minimum size (in bytes) of a buffer that backs a
+    /// program's uniform block.
+    ///
+    /// # Note
+    /// Uniform blocks require OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glGetActiveUniformBlockiv`](http://docs.gl/es3/glGetActiveUniformBlockiv)
+    /// OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn get_uniform_block_data_size(&self,
+                                   program: &Program,
+                                   block: ProgramUniformBlock)
+        -> usize
+    {
+        let mut size: GLint = 0;
+        unsafe {
+            gl::GetActiveUniformBlockiv(program.id(),
+                                        block.gl_index,
+                                        gl::UNIFORM_BLOCK_DATA_SIZE,
+                                        &mut size);
+            dbg_gl_error! {
+                call "glGetActiveUniformBlockiv";
+                args (program.id(), block.gl_index, gl::UNIFORM_BLOCK_DATA_SIZE);
+                GLError::InvalidValue => "`block` is not an active uniform block of `program`",
+                GLError::InvalidOperation => "`program` is not a program object",
+                _ => "Unknown error"
+            }
+        }
+        size as usize
+    }
+
+    /// Get the memory layout of a program's uniform block, which describes
+    /// where each of the block's active uniforms is stored within a buffer
+    /// that backs the block.
+    ///
+    /// # Note
+    /// Uniform blocks require OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature. Uniforms whose names contain an
+    /// invalid UTF-8 sequence will be omitted.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// # let gl = unsafe { glitter::Context::current_context() };
+    /// # let program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+    /// # let view_projection = [[0.0f32; 4]; 4];
+    /// let camera = gl.get_uniform_block_index(&program, "Camera").unwrap();
+    /// let layout = gl.get_uniform_block_layout(&program, camera);
+    ///
+    /// let mut data = layout.new_data();
+    /// layout.write(&mut data, "view_projection", &view_projection).unwrap();
+    /// layout.write(&mut data, "exposure", &1.5f32).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glGetActiveUniformBlockiv`](http://docs.gl/es3/glGetActiveUniformBlockiv)
+    /// and [`glGetActiveUniformsiv`](http://docs.gl/es3/glGetActiveUniformsiv)
+    /// OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn get_uniform_block_layout(&self,
+                                program: &Program,
+                                block: ProgramUniformBlock)
+        -> UniformBlockLayout
+    {
+        unsafe {
+            _get_uniform_block_layout(program, block)
+        }
+    }
 
+    /// Set the uniform buffer binding point that a program's uniform block
+    /// reads from. A buffer can then be bound to the binding point with
+    /// [`gl.bind_buffer_base`]
+    /// (../buffer_context/trait.ContextBufferExt.html#method.bind_buffer_base)
+    /// and the `UNIFORM_BUFFER` target, so that the same uniform values
+    /// can be shared between many programs.
+    ///
+    /// # Note
+    /// Uniform blocks require OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// # let mut program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+    /// const CAMERA_BINDING: u32 = 0;
+    ///
+    /// let camera = gl.get_uniform_block_index(&program, "Camera").unwrap();
+    /// gl.uniform_block_binding(&mut program, camera, CAMERA_BINDING);
+    ///
+    /// let mut camera_buffer = gl.gen_buffer();
+    /// let size = gl.get_uniform_block_data_size(&program, camera);
+    /// {
+    ///     let (mut gl_buffer, gl) = (&mut gl).bind_array_buffer(&mut camera_buffer);
+    ///     gl.buffer_bytes(&mut gl_buffer,
+    ///                     &vec![0u8; size],
+    ///                     glitter::DYNAMIC_DRAW);
+    /// }
+    /// gl.bind_buffer_base(glitter::UNIFORM_BUFFER,
+    ///                     CAMERA_BINDING,
+    ///                     &camera_buffer);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glUniformBlockBinding`](http://docs.gl/es3/glUniformBlockBinding)
+    /// OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn uniform_block_binding(&self,
+                             program: &mut Program,
+                             block: ProgramUniformBlock,
+                             binding: u32)
+    {
+        unsafe {
+            gl::UniformBlockBinding(program.id(),
+                                    block.gl_index,
+                                    binding as GLuint);
             dbg_gl_error! {
-                GLError::InvalidOperation => "Invalid uniform operation",
-                GLError::InvalidValue => "`count` < 0 or `transpose` is not GL_FALSE",
+                call "glUniformBlockBinding";
+                args (program.id(), block.gl_index, binding);
+                GLError::InvalidValue => "`block` is not an active uniform block of `program`, or `binding` is greater than or equal to GL_MAX_UNIFORM_BUFFER_BINDINGS",
                 _ => "Unknown error"
             }
         }
     }
+
+    /// Return a list of all of the active shader storage blocks within a
+    /// linked program object.
+    ///
+    /// # Note
+    /// Shader storage blocks require OpenGL ES 3.1 or OpenGL 4.3, so this
+    /// method is only available with the `gles31` feature. Blocks whose
+    /// names contain an invalid UTF-8 sequence will be omitted.
+    ///
+    /// # See also
+    /// [`glGetProgramResource`](http://docs.gl/es3/glGetProgramResource)
+    /// OpenGL docs
+    #[cfg(feature = "gles31")]
+    fn get_shader_storage_blocks(&self, program: &Program)
+        -> Vec<StorageBlock>
+    {
+        unsafe {
+            _get_shader_storage_blocks(program)
+        }
+    }
+
+    /// Find an active shader storage block within a linked program object by
+    /// name, or return `None` if there is no active block with that name.
+    ///
+    /// # Note
+    /// Shader storage blocks require OpenGL ES 3.1 or OpenGL 4.3, so this
+    /// method is only available with the `gles31` feature.
+    #[cfg(feature = "gles31")]
+    fn get_shader_storage_block(&self, program: &Program, name: &str)
+        -> Option<StorageBlock>
+    {
+        self.get_shader_storage_blocks(program).into_iter()
+            .find(|block| block.name == name)
+    }
+
+    /// Return a list of all of the atomic counter buffer binding points that
+    /// are used by the `atomic_uint` variables of a linked program object.
+    ///
+    /// # Note
+    /// Atomic counters require OpenGL ES 3.1 or OpenGL 4.2, so this method
+    /// is only available with the `gles31` feature.
+    ///
+    /// # See also
+    /// [`glGetProgramResource`](http://docs.gl/es3/glGetProgramResource)
+    /// OpenGL docs
+    #[cfg(feature = "gles31")]
+    fn get_atomic_counter_bindings(&self, program: &Program)
+        -> Vec<AtomicCounterBinding>
+    {
+        unsafe {
+            _get_atomic_counter_bindings(program)
+        }
+    }
 }
 
 impl<C: BaseContext> ContextProgramExt for C {
 
 }
 
+// Upload `val` to the uniform `uniform` of the program in use. `val` can be
+// an unsized slice of uniform data, which is uploaded as an array.
+fn _set_uniform<T>(uniform: ProgramUniform, val: &T)
+    where T: UniformData + ?Sized
+{
+    let idx = uniform.gl_index as GLint;
+    let count = val.uniform_elements() as GLsizei;
+
+    // OpenGL ES 2 doesn't support transposing matrices when uploading
+    // them, so row-major matrices are transposed here instead
+    let order = T::matrix_order().unwrap_or_else(matrix_order::order);
+    let matrix_size = match T::uniform_datum_type() {
+        UniformDatumType::Matrix2x2 => Some(2),
+        UniformDatumType::Matrix3x3 => Some(3),
+        UniformDatumType::Matrix4x4 => Some(4),
+        _ => None
+    };
+    let transposed = match (order, matrix_size) {
+        (MatrixOrder::RowMajor, Some(n)) => {
+            Some(_transpose_matrices(val.uniform_bytes(), n))
+        },
+        _ => None
+    };
+    let ptr = match transposed {
+        Some(ref matrices) => matrices.as_ptr() as *const u8,
+        None => val.uniform_bytes().as_ptr()
+    };
+    unsafe {
+        match T::uniform_datum_type() {
+            UniformDatumType::Vec1(p) => {
+                match p {
+                    UniformPrimitiveType::Float => {
+                        gl::Uniform1fv(idx, count, ptr as *const GLfloat);
+                    },
+                    UniformPrimitiveType::Int => {
+                        gl::Uniform1iv(idx, count, ptr as *const GLint);
+                    }
+                }
+            },
+            UniformDatumType::Vec2(p) => {
+                match p {
+                    UniformPrimitiveType::Float => {
+                        gl::Uniform2fv(idx, count, ptr as *const GLfloat);
+                    },
+                    UniformPrimitiveType::Int => {
+                        gl::Uniform2iv(idx, count, ptr as *const GLint);
+                    }
+                }
+            },
+            UniformDatumType::Vec3(p) => {
+                match p {
+                    UniformPrimitiveType::Float => {
+                        gl::Uniform3fv(idx, count, ptr as *const GLfloat);
+                    },
+                    UniformPrimitiveType::Int => {
+                        gl::Uniform3iv(idx, count, ptr as *const GLint);
+                    }
+                }
+            },
+            UniformDatumType::Vec4(p) => {
+                match p {
+                    UniformPrimitiveType::Float => {
+                        gl::Uniform4fv(idx, count, ptr as *const GLfloat);
+                    },
+                    UniformPrimitiveType::Int => {
+                        gl::Uniform4iv(idx, count, ptr as *const GLint);
+                    }
+                }
+            },
+            UniformDatumType::Matrix2x2 => {
+                gl::UniformMatrix2fv(idx,
+                                     count,
+                                     gl::FALSE,
+                                     ptr as *const GLfloat);
+            },
+            UniformDatumType::Matrix3x3 => {
+                gl::UniformMatrix3fv(idx,
+                                     count,
+                                     gl::FALSE,
+                                     ptr as *const GLfloat);
+            },
+            UniformDatumType::Matrix4x4 => {
+                gl::UniformMatrix4fv(idx,
+                                     count,
+                                     gl::FALSE,
+                                     ptr as *const GLfloat);
+            },
+        }
+
+        dbg_gl_error! {
+            call "glUniform";
+            args (idx, count, ptr);
+            GLError::InvalidOperation => "Invalid uniform operation",
+            GLError::InvalidValue => "`count` < 0 or `transpose` is not GL_FALSE",
+            _ => "Unknown error"
+        }
+    }
+}
+
+fn _transpose_matrices(bytes: &[u8], n: usize) -> Vec<GLfloat> {
+    let floats = unsafe {
+        slice::from_raw_parts(bytes.as_ptr() as *const GLfloat,
+                              bytes.len() / mem::size_of::<GLfloat>())
+    };
+
+    let mut transposed = Vec::with_capacity(floats.len());
+    for matrix in floats.chunks(n * n) {
+        for col in 0..n {
+            for row in 0..n {
+                transposed.push(matrix[row * n + col]);
+            }
+        }
+    }
+    transposed
+}
+
 
 
 /// An OpenGL context that has a free program binding.
@@ -432,6 +1834,22 @@ pub trait ProgramContext: AContext {
         let (mut binder, rest) = self.split_program();
         (binder.borrow_mut().bind(program), rest)
     }
+
+    /// Bind a program to this context for the duration of `f`, which is called
+    /// with the binding and the remaining context. This is a scoped alternative
+    /// to [`use_program`](#method.use_program), which can be easier to use when
+    /// nesting short-lived bindings.
+    ///
+    /// # See also
+    /// [`glUseProgram`](http://docs.gl/es2/glUseProgram) OpenGL docs
+    fn with_program<'a, U, C>(self, program: &'a mut Program, f: C)
+        -> U
+        where Self: Sized,
+              C: FnOnce(ProgramBinding<'a>, Self::Rest) -> U
+    {
+        let (gl_program, gl) = self.use_program(program);
+        f(gl_program, gl)
+    }
 }
 
 impl<B, F, P, R, T> ProgramContext for ContextOf<B, F, P, R, T>
@@ -446,9 +1864,9 @@ impl<B, F, P, R, T> ProgramContext for ContextOf<B, F, P, R, T>
 }
 
 impl<'a, B, F, P, R, T> ProgramContext for &'a mut ContextOf<B, F, P, R, T>
-    where &'a mut P: BorrowMut<ProgramBinder>
+    where P: BorrowMut<ProgramBinder>
 {
-    type Binder = &'a mut P;
+    type Binder = &'a mut ProgramBinder;
     type Rest = ContextOf<&'a mut B, &'a mut F, (), &'a mut R, &'a mut T>;
 
     fn split_program(self) -> (Self::Binder, Self::Rest) {
@@ -461,6 +1879,7 @@ impl<'a, B, F, P, R, T> ProgramContext for &'a mut ContextOf<B, F, P, R, T>
 
 /// Represents a program that has been bound to the context.
 pub struct ProgramBinding<'a> {
+    previous: Option<GLuint>,
     _phantom_ref: PhantomData<&'a mut Program>,
     _phantom_ptr: PhantomData<*mut ()>
 }
@@ -482,21 +1901,39 @@ impl ProgramBinder {
     pub fn bind<'a>(&mut self, program: &'a mut Program) -> ProgramBinding<'a>
     {
         let binding = ProgramBinding {
+            previous: restore_bindings::previous(gl::CURRENT_PROGRAM),
             _phantom_ref: PhantomData,
             _phantom_ptr: PhantomData
         };
-        unsafe {
-            gl::UseProgram(program.id());
-            dbg_gl_error! {
-                GLError::InvalidValue => "`program` is neither 0 nor an object generated by OpenGL",
-                GLError::InvalidOperation => "`program` is not a program object or `program` could not be made part of the current state",
-                _ => "Unknown error"
-            }
-        }
+        _use_program(program.id());
         binding
     }
 }
 
+fn _use_program(id: GLuint) {
+    if !state_cache::use_program(id) {
+        return;
+    }
+    unsafe {
+        gl::UseProgram(id);
+        dbg_gl_error! {
+            call "glUseProgram";
+            args (id);
+            GLError::InvalidValue => "`program` is neither 0 nor an object generated by OpenGL",
+            GLError::InvalidOperation => "`program` is not a program object or `program` could not be made part of the current state",
+            _ => "Unknown error"
+        }
+    }
+}
+
+impl<'a> Drop for ProgramBinding<'a> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            _use_program(previous);
+        }
+    }
+}
+
 
 
 /// An error that represents a program attribute that could not be found.
@@ -536,3 +1973,104 @@ impl<'a> error::Error for UnknownProgramUniform<'a> {
         "The desired program uniform was not found"
     }
 }
+
+
+
+/// An error that represents a program uniform block that could not
+/// be found.
+///
+/// # Note
+/// Uniform blocks require OpenGL ES 3.0, so this type is only available
+/// with the `gles3` feature.
+#[cfg(feature = "gles3")]
+#[derive(Debug)]
+pub struct UnknownProgramUniformBlock<'a> {
+    name: &'a str
+}
+
+#[cfg(feature = "gles3")]
+impl<'a> fmt::Display for UnknownProgramUniformBlock<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Unknown program uniform block: {:?}", self.name)
+    }
+}
+
+#[cfg(feature = "gles3")]
+impl<'a> error::Error for UnknownProgramUniformBlock<'a> {
+    fn description(&self) -> &str {
+        "The desired program uniform block was not found"
+    }
+}
+
+
+
+/// An error generated when verifying explicit uniform locations with
+/// [`gl.check_uniform_locations`]
+/// (trait.ContextProgramExt.html#method.check_uniform_locations).
+#[derive(Debug)]
+pub enum UniformLocationError {
+    /// Two different uniforms were declared with the same location.
+    Conflict {
+        /// The location that was declared twice.
+        location: GLuint,
+
+        /// The names of the conflicting uniforms.
+        names: (&'static str, &'static str)
+    },
+
+    /// The uniform was not found in the program (it may have been
+    /// optimized out).
+    Missing(&'static str),
+
+    /// The uniform's location in the program does not match its
+    /// declared location.
+    WrongLocation {
+        /// The name of the uniform.
+        name: &'static str,
+
+        /// The declared location.
+        expected: GLuint,
+
+        /// The location within the program.
+        actual: GLuint
+    },
+
+    /// The uniform's type in the program does not match its declared type.
+    WrongType {
+        /// The name of the uniform.
+        name: &'static str,
+
+        /// The raw OpenGL type of the uniform within the program.
+        gl_type: GLenum
+    }
+}
+
+impl fmt::Display for UniformLocationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            UniformLocationError::Conflict { location, names: (a, b) } => {
+                write!(f, "Uniforms {:?} and {:?} are both declared with location {}", a, b, location)
+            },
+            UniformLocationError::Missing(name) => {
+                write!(f, "Uniform {:?} was not found in the program", name)
+            },
+            UniformLocationError::WrongLocation { name, expected, actual } => {
+                write!(f, "Uniform {:?} was declared with location {}, but has location {}", name, expected, actual)
+            },
+            UniformLocationError::WrongType { name, gl_type } => {
+                write!(f, "Uniform {:?} has a mismatched type (0x{:04X})", name, gl_type)
+            }
+        }
+    }
+}
+
+impl error::Error for UniformLocationError {
+    fn description(&self) -> &str {
+        match *self {
+            UniformLocationError::Conflict { .. } => "Two uniforms were declared with the same location",
+            UniformLocationError::Missing(_) => "A declared uniform was not found in the program",
+            UniformLocationError::WrongLocation { .. } => "A uniform's location did not match its declared location",
+            UniformLocationError::WrongType { .. } => "A uniform's type did not match its declared type"
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Contains the OpenGL methods for query objects, such as timer queries.
+
+use std::time::Duration;
+use gl;
+use gl::types::*;
+use context::BaseContext;
+use query::{Query, QueryTarget};
+use types::{GLObject, GLError};
+
+/// Convert a GPU time (in nanoseconds), as returned by a timer query, to
+/// a `Duration`.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+///
+/// let time = glitter::nanoseconds_to_duration(1_500_000_000);
+/// assert_eq!(time, Duration::new(1, 500_000_000));
+/// ```
+pub fn nanoseconds_to_duration(nanoseconds: u64) -> Duration {
+    Duration::new(nanoseconds / 1_000_000_000,
+                  (nanoseconds % 1_000_000_000) as u32)
+}
+
+/// An extension trait that includes query-object-related OpenGL methods.
+///
+/// # Note
+/// Timer queries require OpenGL 3.3 (OpenGL ES only supports them through
+/// the `GL_EXT_disjoint_timer_query` extension), so this trait is only
+/// available with the `gl33` feature.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::ContextQueryExt;
+///
+/// # fn main() {
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let mut query = gl.gen_query();
+///
+/// gl.begin_query(glitter::TIME_ELAPSED, &mut query);
+/// // Draw something...
+/// gl.end_query(glitter::TIME_ELAPSED);
+///
+/// // Later (such as next frame):
+/// if let Some(time) = gl.try_get_query_result(&query) {
+///     println!("Drawing took {:?}", glitter::nanoseconds_to_duration(time));
+/// }
+/// # }
+/// ```
+pub trait ContextQueryExt: BaseContext {
+    /// Create a new query object.
+    ///
+    /// # See also
+    /// [`glGenQueries`](http://docs.gl/gl4/glGenQueries) OpenGL docs
+    fn gen_query(&self) -> Query {
+        let mut id : GLuint = 0;
+
+        unsafe {
+            gl::GenQueries(1, &mut id as *mut GLuint);
+        }
+        dbg_gl_sanity_check! {
+            call "glGenQueries";
+            args (id);
+            GLError::InvalidValue => "`n` is negative",
+            _ => "Unknown error"
+        }
+
+        unsafe { Query::from_raw(id) }
+    }
+
+    /// Start measuring `target` with a query, until [`gl.end_query`]
+    /// (trait.ContextQueryExt.html#method.end_query) is called with the
+    /// same target. Only one query can be active for each target at a time.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glBeginQuery`](http://docs.gl/gl4/glBeginQuery) OpenGL docs
+    fn begin_query(&self, target: QueryTarget, query: &mut Query) {
+        unsafe {
+            gl::BeginQuery(target.gl_enum(), query.id());
+            dbg_gl_error! {
+                call "glBeginQuery";
+                args (target, query.id());
+                GLError::InvalidEnum => "`target` is not an allowed value",
+                GLError::InvalidOperation => "A query is already active for `target`, `query` is already active, or `query` was used with a different target",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Stop measuring `target` with the active query.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glEndQuery`](http://docs.gl/gl4/glEndQuery) OpenGL docs
+    fn end_query(&self, target: QueryTarget) {
+        unsafe {
+            gl::EndQuery(target.gl_enum());
+            dbg_gl_error! {
+                call "glEndQuery";
+                args (target);
+                GLError::InvalidEnum => "`target` is not an allowed value",
+                GLError::InvalidOperation => "No query is active for `target`",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Record the GPU time (in nanoseconds) at which all previous commands
+    /// have finished into a query. Unlike `TIME_ELAPSED` queries, timestamp
+    /// queries can be nested and overlapped freely.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glQueryCounter`](http://docs.gl/gl4/glQueryCounter) OpenGL docs
+    fn query_timestamp(&self, query: &mut Query) {
+        unsafe {
+            gl::QueryCounter(query.id(), gl::TIMESTAMP);
+            dbg_gl_error! {
+                call "glQueryCounter";
+                args (query.id(), gl::TIMESTAMP);
+                GLError::InvalidOperation => "`query` is active, or was used with a different target",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Get the current GPU time (in nanoseconds), without waiting for
+    /// previous commands to finish.
+    ///
+    /// # See also
+    /// [`glGet`](http://docs.gl/gl4/glGet) OpenGL docs
+    fn get_timestamp(&self) -> u64 {
+        let mut timestamp: GLint64 = 0;
+        unsafe {
+            gl::GetInteger64v(gl::TIMESTAMP, &mut timestamp);
+        }
+        timestamp as u64
+    }
+
+    /// Returns `true` if the result of a query is available, meaning that
+    /// reading it won't wait for the GPU.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glGetQueryObject`](http://docs.gl/gl4/glGetQueryObject) OpenGL docs
+    fn is_query_result_available(&self, query: &Query) -> bool {
+        let mut available: GLuint = 0;
+        unsafe {
+            gl::GetQueryObjectuiv(query.id(),
+                                  gl::QUERY_RESULT_AVAILABLE,
+                                  &mut available);
+            dbg_gl_error! {
+                call "glGetQueryObjectuiv";
+                args (query.id(), gl::QUERY_RESULT_AVAILABLE);
+                GLError::InvalidOperation => "`query` is not a query object, or is active",
+                _ => "Unknown error"
+            }
+        }
+        available != 0
+    }
+
+    /// Get the result of a query, waiting for the GPU to finish the
+    /// measured commands if necessary.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glGetQueryObject`](http://docs.gl/gl4/glGetQueryObject) OpenGL docs
+    fn get_query_result(&self, query: &Query) -> u64 {
+        let mut result: GLuint64 = 0;
+        unsafe {
+            gl::GetQueryObjectui64v(query.id(), gl::QUERY_RESULT, &mut result);
+            dbg_gl_error! {
+                call "glGetQueryObjectui64v";
+                args (query.id(), gl::QUERY_RESULT);
+                GLError::InvalidOperation => "`query` is not a query object, or is active",
+                _ => "Unknown error"
+            }
+        }
+        result as u64
+    }
+
+    /// Get the result of a query, or return `None` if the result isn't
+    /// available yet.
+    fn try_get_query_result(&self, query: &Query) -> Option<u64> {
+        if self.is_query_result_available(query) {
+            Some(self.get_query_result(query))
+        }
+        else {
+            None
+        }
+    }
+}
+
+impl<C: BaseContext> ContextQueryExt for C {
+
+}
@@ -8,6 +8,7 @@ use context::{AContext, BaseContext, ContextOf};
 use renderbuffer::{Renderbuffer, RenderbufferTarget};
 use image_data::{RenderbufferFormat};
 use types::{GLObject, GLError};
+use restore_bindings;
 
 /// Provides a safe wrapper for creating renderbuffer objects. A
 /// `RenderbufferBuilder` can be created using the [`gl.build_renderbuffer`]
@@ -112,6 +113,8 @@ pub trait ContextRenderbufferExt: BaseContext {
 
         gl::GenRenderbuffers(1, &mut id as *mut GLuint);
         dbg_gl_sanity_check! {
+            call "glGenRenderbuffers";
+            args (id);
             GLError::InvalidValue => "`n` is negative",
             _ => "Unknown error"
         }
@@ -141,6 +144,8 @@ pub trait ContextRenderbufferExt: BaseContext {
                                     width as GLint,
                                     height as GLint);
             dbg_gl_sanity_check! {
+                call "glRenderbufferStorage";
+                args (gl_rbo.target(), format, width, height);
                 GLError::InvalidEnum => "`target` is not `GL_RENDERBUFFER` or `internalformat` is not an accepted format",
                 GLError::InvalidValue => "`width` or `height` is less than zero or greater than `GL_MAX_RENDERBUFFER_SIZE`",
                 GLError::OutOfMemory => "Unable to allocate enough memory for requested size",
@@ -149,6 +154,71 @@ pub trait ContextRenderbufferExt: BaseContext {
             }
         }
     }
+
+    /// Initialize a renderbuffer object's storage with `samples` samples
+    /// per pixel, for multisample anti-aliasing. A multisampled
+    /// renderbuffer can't be sampled as a texture; instead, its contents
+    /// are resolved into a single-sampled framebuffer with
+    /// [`gl.resolve_framebuffer`]
+    /// (../framebuffer_context/trait.ContextFramebufferExt.html#method.resolve_framebuffer).
+    ///
+    /// - `gl_rbo`: The binding of the renderbuffer to set up storage for.
+    /// - `samples`: The number of samples per pixel, which must be no more
+    ///   than [`gl.get_max_samples()`]
+    ///   (trait.ContextRenderbufferExt.html#method.get_max_samples).
+    /// - `format`: The storage format to use for the renderbuffer.
+    /// - `width`: The storage width of the renderbuffer, in pixels.
+    /// - `height`: The storage height of the renderbuffer, in pixels.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glRenderbufferStorageMultisample`](http://docs.gl/es3/glRenderbufferStorageMultisample)
+    /// OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn storage_multisample(&self,
+                           gl_rbo: &mut RenderbufferBinding,
+                           samples: u32,
+                           format: RenderbufferFormat,
+                           width: u32,
+                           height: u32)
+    {
+        unsafe {
+            gl::RenderbufferStorageMultisample(gl_rbo.target().gl_enum(),
+                                               samples as GLsizei,
+                                               format.gl_enum(),
+                                               width as GLint,
+                                               height as GLint);
+            dbg_gl_error! {
+                call "glRenderbufferStorageMultisample";
+                args (gl_rbo.target(), samples, format, width, height);
+                GLError::InvalidEnum => "`target` is not `GL_RENDERBUFFER` or `internalformat` is not an accepted format",
+                GLError::InvalidValue => "`width` or `height` is greater than `GL_MAX_RENDERBUFFER_SIZE`",
+                GLError::OutOfMemory => "Unable to allocate enough memory for requested size",
+                GLError::InvalidOperation => "`samples` is greater than the maximum number of samples supported for `internalformat`, or renderbuffer object 0 is bound",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Get the maximum number of samples per pixel that can be used with
+    /// [`gl.storage_multisample`]
+    /// (trait.ContextRenderbufferExt.html#method.storage_multisample)
+    /// (`GL_MAX_SAMPLES`).
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    #[cfg(feature = "gles3")]
+    fn get_max_samples(&self) -> u32 {
+        let mut max_samples: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_SAMPLES, &mut max_samples);
+        }
+        if max_samples < 0 { 0 } else { max_samples as u32 }
+    }
 }
 
 impl<C: BaseContext> ContextRenderbufferExt for C {
@@ -181,6 +251,22 @@ pub trait RenderbufferContext: AContext {
         let (mut binder, rest) = self.split_renderbuffer();
         (binder.borrow_mut().bind(rbo), rest)
     }
+
+    /// Bind a renderbuffer to this context for the duration of `f`, which is
+    /// called with the binding and the remaining context. This is a scoped
+    /// alternative to [`bind_renderbuffer`](#method.bind_renderbuffer), which
+    /// can be easier to use when nesting short-lived bindings.
+    ///
+    /// # See also
+    /// [`glBindRenderbuffer`](http://docs.gl/es2/glBindRenderbuffer) OpenGL docs
+    fn with_renderbuffer<'a, U, C>(self, rbo: &'a mut Renderbuffer, f: C)
+        -> U
+        where Self: Sized,
+              C: FnOnce(RenderbufferBinding<'a>, Self::Rest) -> U
+    {
+        let (gl_rbo, gl) = self.bind_renderbuffer(rbo);
+        f(gl_rbo, gl)
+    }
 }
 
 impl<B, F, P, R, T> RenderbufferContext for ContextOf<B, F, P, R, T>
@@ -212,6 +298,7 @@ impl<'a, B, F, P, R, T> RenderbufferContext
 /// Represents a renderbuffer that has been bound to the `GL_RENDERBUFFER`
 /// binding target.
 pub struct RenderbufferBinding<'a> {
+    previous: Option<GLuint>,
     _phantom_ref: PhantomData<&'a mut Renderbuffer>,
     _phantom_ptr: PhantomData<*mut ()>
 }
@@ -222,6 +309,26 @@ impl<'a> RenderbufferBinding<'a> {
     }
 }
 
+impl<'a> Drop for RenderbufferBinding<'a> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            _bind_renderbuffer(self.target(), previous);
+        }
+    }
+}
+
+fn _bind_renderbuffer(target: RenderbufferTarget, id: GLuint) {
+    unsafe {
+        gl::BindRenderbuffer(target.gl_enum(), id);
+        dbg_gl_sanity_check! {
+            call "glBindRenderbuffer";
+            args (target, id);
+            GLError::InvalidEnum => "`target` is not `GL_RENDERBUFFER`",
+            _ => "Unknown error"
+        }
+    }
+}
+
 /// The OpenGL state representing the `GL_RENDERBUFFER` target.
 pub struct RenderbufferBinder {
     _phantom: PhantomData<*mut ()>
@@ -246,17 +353,11 @@ impl RenderbufferBinder {
         -> RenderbufferBinding<'a>
     {
         let binding = RenderbufferBinding {
+            previous: restore_bindings::previous(gl::RENDERBUFFER_BINDING),
             _phantom_ref: PhantomData,
             _phantom_ptr: PhantomData
         };
-        unsafe {
-            gl::BindRenderbuffer(binding.target().gl_enum(),
-                                 renderbuffer.id());
-            dbg_gl_sanity_check! {
-                GLError::InvalidEnum => "`target` is not `GL_RENDERBUFFER`",
-                _ => "Unknown error"
-            }
-        }
+        _bind_renderbuffer(binding.target(), renderbuffer.id());
         binding
     }
 }
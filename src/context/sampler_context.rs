@@ -0,0 +1,293 @@
+//! Contains the OpenGL methods for sampler objects.
+
+use gl;
+use gl::types::*;
+use context::{BaseContext, TextureBinding};
+use sampler::{Sampler, SamplerParams};
+use texture::{TextureMipmapFilter, TextureFilter, TextureWrapMode,
+              TextureBindingTarget};
+use types::{GLObject, GLError};
+
+/// An extension trait that includes sampler-object-related OpenGL methods.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+///
+/// # fn main() {
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let mut texture: glitter::Texture2d = unsafe { gl.gen_texture() };
+/// let mut pixelated = gl.gen_sampler();
+/// gl.set_sampler_min_filter(&mut pixelated, glitter::NEAREST);
+/// gl.set_sampler_mag_filter(&mut pixelated, glitter::NEAREST);
+/// gl.set_sampler_wrap_s(&mut pixelated, glitter::CLAMP_TO_EDGE);
+/// gl.set_sampler_wrap_t(&mut pixelated, glitter::CLAMP_TO_EDGE);
+///
+/// let (gl_tex_unit, gl) = gl.active_texture_0();
+/// let unit = gl_tex_unit.gl_idx();
+/// let (mut gl_texture, _) = gl_tex_unit.bind_texture_2d(&mut texture);
+/// gl.bind_sampler(unit, &mut gl_texture, &pixelated);
+/// # }
+/// ```
+pub trait ContextSamplerExt: BaseContext {
+    /// Create a new sampler. If sampler objects aren't supported by the
+    /// context, the sampler will be [emulated]
+    /// (../../sampler/struct.Sampler.html#method.is_emulated).
+    ///
+    /// # See also
+    /// [`glGenSamplers`](http://docs.gl/es3/glGenSamplers) OpenGL docs
+    fn gen_sampler(&self) -> Sampler {
+        unsafe { Sampler::from_raw(_gen_sampler_id()) }
+    }
+
+    /// Set a sampler's minifying filter.
+    ///
+    /// # See also
+    /// [`glSamplerParameter`](http://docs.gl/es3/glSamplerParameter) OpenGL
+    /// docs
+    fn set_sampler_min_filter<F>(&self, sampler: &mut Sampler, filter: F)
+        where F: Into<TextureMipmapFilter>
+    {
+        let filter = filter.into();
+        sampler._params_mut().min_filter = filter;
+        unsafe {
+            _sampler_parameter_i(sampler,
+                                 gl::TEXTURE_MIN_FILTER,
+                                 filter.gl_enum() as GLint);
+        }
+    }
+
+    /// Set a sampler's magnifying filter.
+    ///
+    /// # See also
+    /// [`glSamplerParameter`](http://docs.gl/es3/glSamplerParameter) OpenGL
+    /// docs
+    fn set_sampler_mag_filter(&self,
+                              sampler: &mut Sampler,
+                              filter: TextureFilter)
+    {
+        sampler._params_mut().mag_filter = filter;
+        unsafe {
+            _sampler_parameter_i(sampler,
+                                 gl::TEXTURE_MAG_FILTER,
+                                 filter.gl_enum() as GLint);
+        }
+    }
+
+    /// Set a sampler's wrap mode for the s-coordinate.
+    ///
+    /// # See also
+    /// [`glSamplerParameter`](http://docs.gl/es3/glSamplerParameter) OpenGL
+    /// docs
+    fn set_sampler_wrap_s(&self,
+                          sampler: &mut Sampler,
+                          wrap_mode: TextureWrapMode)
+    {
+        sampler._params_mut().wrap_s = wrap_mode;
+        unsafe {
+            _sampler_parameter_i(sampler,
+                                 gl::TEXTURE_WRAP_S,
+                                 wrap_mode.gl_enum() as GLint);
+        }
+    }
+
+    /// Set a sampler's wrap mode for the t-coordinate.
+    ///
+    /// # See also
+    /// [`glSamplerParameter`](http://docs.gl/es3/glSamplerParameter) OpenGL
+    /// docs
+    fn set_sampler_wrap_t(&self,
+                          sampler: &mut Sampler,
+                          wrap_mode: TextureWrapMode)
+    {
+        sampler._params_mut().wrap_t = wrap_mode;
+        unsafe {
+            _sampler_parameter_i(sampler,
+                                 gl::TEXTURE_WRAP_T,
+                                 wrap_mode.gl_enum() as GLint);
+        }
+    }
+
+    /// Set a sampler's wrap mode for the r-coordinate, which is only used
+    /// by 3D textures. When the sampler is emulated, this parameter is
+    /// ignored without the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glSamplerParameter`](http://docs.gl/es3/glSamplerParameter) OpenGL
+    /// docs
+    fn set_sampler_wrap_r(&self,
+                          sampler: &mut Sampler,
+                          wrap_mode: TextureWrapMode)
+    {
+        sampler._params_mut().wrap_r = wrap_mode;
+        unsafe {
+            _sampler_parameter_i(sampler,
+                                 gl::TEXTURE_WRAP_R,
+                                 wrap_mode.gl_enum() as GLint);
+        }
+    }
+
+    /// Use a sampler's parameters when sampling the texture bound to
+    /// the texture unit `unit`. If the sampler is a sampler object, it
+    /// is bound to the texture unit (and will be used for any texture
+    /// bound to the unit, until [`gl.unbind_sampler`]
+    /// (#method.unbind_sampler) is called). If the sampler is [emulated]
+    /// (../../sampler/struct.Sampler.html#method.is_emulated), the
+    /// sampler's parameters are copied to `gl_texture`'s parameters
+    /// instead.
+    ///
+    /// - `unit`: The index of the texture unit that `gl_texture` is bound
+    ///   to (see [`TextureUnitBindingOf::gl_idx`]
+    ///   (../texture_units/struct.TextureUnitBindingOf.html#method.gl_idx)).
+    /// - `gl_texture`: The texture that the sampler will be used with.
+    /// - `sampler`: The sampler to use.
+    ///
+    /// # See also
+    /// [`glBindSampler`](http://docs.gl/es3/glBindSampler) OpenGL docs
+    fn bind_sampler<T>(&self,
+                       unit: u32,
+                       gl_texture: &mut T,
+                       sampler: &Sampler)
+        where T: TextureBinding
+    {
+        unsafe {
+            if sampler.is_emulated() {
+                _apply_sampler_params(gl_texture.target(), sampler.params());
+            }
+            else {
+                _bind_sampler(unit, sampler.id());
+            }
+        }
+    }
+
+    /// Unbind any sampler object from the texture unit `unit`, so that the
+    /// unit's texture is sampled with its own parameters again.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glBindSampler`](http://docs.gl/es3/glBindSampler) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn unbind_sampler(&self, unit: u32) {
+        if gl::BindSampler::is_loaded() {
+            unsafe {
+                _bind_sampler(unit, 0);
+            }
+        }
+    }
+}
+
+impl<C: BaseContext> ContextSamplerExt for C {
+
+}
+
+
+
+#[cfg(feature = "gles3")]
+unsafe fn _gen_sampler_id() -> GLuint {
+    if !gl::GenSamplers::is_loaded() {
+        return 0;
+    }
+
+    let mut id : GLuint = 0;
+    gl::GenSamplers(1, &mut id as *mut GLuint);
+    dbg_gl_sanity_check! {
+        call "glGenSamplers";
+        args (id);
+        GLError::InvalidValue => "`n` is negative",
+        _ => "Unknown error"
+    }
+    id
+}
+
+#[cfg(not(feature = "gles3"))]
+unsafe fn _gen_sampler_id() -> GLuint {
+    0
+}
+
+#[cfg(feature = "gles3")]
+unsafe fn _sampler_parameter_i(sampler: &Sampler,
+                               pname: GLenum,
+                               param: GLint)
+{
+    if sampler.is_emulated() {
+        return;
+    }
+
+    gl::SamplerParameteri(sampler.id(), pname, param);
+    dbg_gl_sanity_check! {
+        call "glSamplerParameteri";
+        args (sampler.id(), pname, param);
+        GLError::InvalidEnum => "`pname` is not an accepted value, or `param` should have defined a symbolic constant and does not",
+        GLError::InvalidOperation => "`sampler` is not the name of a sampler object",
+        _ => "Unknown error"
+    }
+}
+
+// Without sampler objects, a sampler's parameters are only tracked by glitter
+#[cfg(not(feature = "gles3"))]
+unsafe fn _sampler_parameter_i(_sampler: &Sampler,
+                               _pname: GLenum,
+                               _param: GLint)
+{
+
+}
+
+#[cfg(feature = "gles3")]
+unsafe fn _bind_sampler(unit: u32, id: GLuint) {
+    gl::BindSampler(unit, id);
+    dbg_gl_error! {
+        call "glBindSampler";
+        args (unit, id);
+        GLError::InvalidValue => "`unit` is greater than the number of texture units",
+        GLError::InvalidOperation => "`sampler` is not zero or the name of a sampler object",
+        _ => "Unknown error"
+    }
+}
+
+// An emulated sampler is never bound
+#[cfg(not(feature = "gles3"))]
+unsafe fn _bind_sampler(_unit: u32, _id: GLuint) {
+
+}
+
+unsafe fn _apply_sampler_params(target: TextureBindingTarget,
+                                params: &SamplerParams)
+{
+    let target = target.gl_enum();
+    gl::TexParameteri(target,
+                      gl::TEXTURE_MIN_FILTER,
+                      params.min_filter.gl_enum() as GLint);
+    gl::TexParameteri(target,
+                      gl::TEXTURE_MAG_FILTER,
+                      params.mag_filter.gl_enum() as GLint);
+    gl::TexParameteri(target,
+                      gl::TEXTURE_WRAP_S,
+                      params.wrap_s.gl_enum() as GLint);
+    gl::TexParameteri(target,
+                      gl::TEXTURE_WRAP_T,
+                      params.wrap_t.gl_enum() as GLint);
+    _apply_sampler_wrap_r(target, params);
+    dbg_gl_sanity_check! {
+        call "glTexParameteri";
+        args (target, params);
+        GLError::InvalidEnum => "`target` or `pname` is not an accepted defined value, or `param` should have defined a symbolic constant and does not",
+        _ => "Unknown error"
+    }
+}
+
+#[cfg(feature = "gles3")]
+unsafe fn _apply_sampler_wrap_r(target: GLenum, params: &SamplerParams) {
+    gl::TexParameteri(target,
+                      gl::TEXTURE_WRAP_R,
+                      params.wrap_r.gl_enum() as GLint);
+}
+
+// `GL_TEXTURE_WRAP_R` requires OpenGL ES 3.0
+#[cfg(not(feature = "gles3"))]
+unsafe fn _apply_sampler_wrap_r(_target: GLenum, _params: &SamplerParams) {
+
+}
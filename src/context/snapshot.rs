@@ -0,0 +1,555 @@
+//! Contains the [`StateSnapshot`](struct.StateSnapshot.html) type, a
+//! machine-readable dump of the current OpenGL state.
+
+use std::fmt;
+use std::ffi::CStr;
+use gl;
+use gl::types::*;
+use context::ContextOf;
+use error_policy;
+
+// `GL_NVX_gpu_memory_info`
+const GPU_MEMORY_INFO_TOTAL_AVAILABLE_MEMORY_NVX: GLenum = 0x9048;
+const GPU_MEMORY_INFO_CURRENT_AVAILABLE_VIDMEM_NVX: GLenum = 0x9049;
+
+// `GL_ATI_meminfo`
+const TEXTURE_FREE_MEMORY_ATI: GLenum = 0x87FC;
+
+unsafe fn _get_string(name: GLenum) -> Option<String> {
+    let ptr = gl::GetString(name);
+    if ptr.is_null() {
+        None
+    }
+    else {
+        let c_str = CStr::from_ptr(ptr as *const _);
+        Some(c_str.to_string_lossy().into_owned())
+    }
+}
+
+unsafe fn _get_integer(pname: GLenum) -> GLint {
+    let mut value: GLint = 0;
+    gl::GetIntegerv(pname, &mut value as *mut GLint);
+    value
+}
+
+unsafe fn _get_integers<A: AsMut<[GLint]>>(pname: GLenum, mut values: A)
+    -> A
+{
+    gl::GetIntegerv(pname, values.as_mut().as_mut_ptr());
+    values
+}
+
+unsafe fn _get_floats<A: AsMut<[GLfloat]>>(pname: GLenum, mut values: A)
+    -> A
+{
+    gl::GetFloatv(pname, values.as_mut().as_mut_ptr());
+    values
+}
+
+unsafe fn _get_boolean(pname: GLenum) -> bool {
+    let mut value: GLboolean = gl::FALSE;
+    gl::GetBooleanv(pname, &mut value as *mut GLboolean);
+    value == gl::TRUE
+}
+
+unsafe fn _get_memory(has_nvx_memory_info: bool, has_ati_meminfo: bool)
+    -> Option<MemorySnapshot>
+{
+    if has_nvx_memory_info {
+        let total = GPU_MEMORY_INFO_TOTAL_AVAILABLE_MEMORY_NVX;
+        let available = GPU_MEMORY_INFO_CURRENT_AVAILABLE_VIDMEM_NVX;
+        Some(MemorySnapshot {
+            total_kb: Some(_get_integer(total)),
+            available_kb: _get_integer(available)
+        })
+    }
+    else if has_ati_meminfo {
+        // The first value is the total free memory in the pool
+        let free = _get_integers(TEXTURE_FREE_MEMORY_ATI, [0; 4]);
+        Some(MemorySnapshot {
+            total_kb: None,
+            available_kb: free[0]
+        })
+    }
+    else {
+        None
+    }
+}
+
+// Push a `StateChange` for each of the listed fields that differs between
+// `$before` and `$after`, using `$prefix` as the start of the path.
+macro_rules! diff_fields {
+    ($changes:expr, $prefix:tt, $before:expr, $after:expr, [$($field:ident),*]) => {
+        $(
+            if $before.$field != $after.$field {
+                $changes.push(StateChange::new(
+                    concat!($prefix, stringify!($field)),
+                    &$before.$field,
+                    &$after.$field
+                ));
+            }
+        )*
+    }
+}
+
+
+
+/// A snapshot of the current OpenGL state, which can be created with
+/// [`gl.dump_state`](../struct.ContextOf.html#method.dump_state). This type
+/// is mainly meant for debugging, such as for attaching to bug reports.
+///
+/// Raw OpenGL enum values (such as the depth function) are stored as plain
+/// integers. When the `serialize` feature is enabled, all of the snapshot
+/// types implement `serde::Serialize`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct StateSnapshot {
+    /// The OpenGL version string (`GL_VERSION`).
+    pub version: Option<String>,
+
+    /// The company responsible for the OpenGL implementation (`GL_VENDOR`).
+    pub vendor: Option<String>,
+
+    /// The name of the renderer (`GL_RENDERER`).
+    pub renderer: Option<String>,
+
+    /// The shading language version string
+    /// (`GL_SHADING_LANGUAGE_VERSION`).
+    pub shading_language_version: Option<String>,
+
+    /// The list of supported OpenGL extensions.
+    pub extensions: Vec<String>,
+
+    /// Implementation-dependent limits.
+    pub limits: LimitsSnapshot,
+
+    /// The currently-bound OpenGL objects.
+    pub bindings: BindingsSnapshot,
+
+    /// The state of each server-side capability.
+    pub capabilities: CapabilitiesSnapshot,
+
+    /// Blending state.
+    pub blend: BlendSnapshot,
+
+    /// Depth buffer state.
+    pub depth: DepthSnapshot,
+
+    /// Stencil buffer state (for front-facing polygons).
+    pub stencil: StencilSnapshot,
+
+    /// The current viewport, as `[x, y, width, height]`.
+    pub viewport: [GLint; 4],
+
+    /// The current color clear value, as `[r, g, b, a]`.
+    pub clear_color: [GLfloat; 4],
+
+    /// Video memory statistics, if supported by the implementation.
+    pub memory: Option<MemorySnapshot>
+}
+
+impl StateSnapshot {
+    /// Compare this snapshot with a later snapshot, returning each piece of
+    /// state that is different in `other`. Changes are returned in the same
+    /// order as the fields of `StateSnapshot`, followed by any extensions
+    /// that were added or removed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let working = gl.dump_state();
+    /// // Render a frame...
+    /// let broken = gl.dump_state();
+    /// for change in working.diff(&broken) {
+    ///     println!("{}", change);
+    /// }
+    /// ```
+    pub fn diff(&self, other: &StateSnapshot) -> Vec<StateChange> {
+        let mut changes = vec![];
+
+        diff_fields!(changes, "", self, other,
+                     [version, vendor, renderer, shading_language_version]);
+        diff_fields!(changes, "limits.", self.limits, other.limits,
+                     [max_texture_size, max_cube_map_texture_size,
+                      max_renderbuffer_size, max_vertex_attribs,
+                      max_texture_image_units,
+                      max_combined_texture_image_units, max_viewport_dims]);
+        diff_fields!(changes, "bindings.", self.bindings, other.bindings,
+                     [array_buffer, element_array_buffer, framebuffer,
+                      renderbuffer, program, active_texture, texture_2d,
+                      texture_cube_map]);
+        diff_fields!(changes, "capabilities.",
+                     self.capabilities, other.capabilities,
+                     [blend, cull_face, depth_test, dither,
+                      polygon_offset_fill, sample_alpha_to_coverage,
+                      sample_coverage, scissor_test, stencil_test]);
+        diff_fields!(changes, "blend.", self.blend, other.blend,
+                     [src_rgb, dst_rgb, src_alpha, dst_alpha, equation_rgb,
+                      equation_alpha, color]);
+        diff_fields!(changes, "depth.", self.depth, other.depth,
+                     [func, write_mask, range, clear_value]);
+        diff_fields!(changes, "stencil.", self.stencil, other.stencil,
+                     [func, reference, value_mask, write_mask, fail,
+                      pass_depth_fail, pass_depth_pass, clear_value]);
+        diff_fields!(changes, "", self, other,
+                     [viewport, clear_color, memory]);
+
+        for extension in &self.extensions {
+            if !other.extensions.contains(extension) {
+                changes.push(StateChange {
+                    path: format!("extensions.{}", extension),
+                    before: "supported".into(),
+                    after: "unsupported".into()
+                });
+            }
+        }
+        for extension in &other.extensions {
+            if !self.extensions.contains(extension) {
+                changes.push(StateChange {
+                    path: format!("extensions.{}", extension),
+                    before: "unsupported".into(),
+                    after: "supported".into()
+                });
+            }
+        }
+
+        changes
+    }
+}
+
+/// A single piece of OpenGL state that differs between two
+/// [`StateSnapshots`](struct.StateSnapshot.html), as returned by
+/// [`StateSnapshot::diff`](struct.StateSnapshot.html#method.diff).
+///
+/// A `StateChange` is displayed as `path: before -> after`, such as
+/// `depth.write_mask: true -> false`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateChange {
+    /// The path of the changed field within the snapshot, such as
+    /// `"depth.write_mask"`.
+    pub path: String,
+
+    /// The value in the earlier snapshot, formatted with `Debug`.
+    pub before: String,
+
+    /// The value in the later snapshot, formatted with `Debug`.
+    pub after: String
+}
+
+impl StateChange {
+    fn new<T: fmt::Debug>(path: &str, before: &T, after: &T) -> Self {
+        StateChange {
+            path: path.into(),
+            before: format!("{:?}", before),
+            after: format!("{:?}", after)
+        }
+    }
+}
+
+impl fmt::Display for StateChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.path, self.before, self.after)
+    }
+}
+
+/// Implementation-dependent limits captured in a [`StateSnapshot`]
+/// (struct.StateSnapshot.html).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct LimitsSnapshot {
+    /// `GL_MAX_TEXTURE_SIZE`
+    pub max_texture_size: GLint,
+
+    /// `GL_MAX_CUBE_MAP_TEXTURE_SIZE`
+    pub max_cube_map_texture_size: GLint,
+
+    /// `GL_MAX_RENDERBUFFER_SIZE`
+    pub max_renderbuffer_size: GLint,
+
+    /// `GL_MAX_VERTEX_ATTRIBS`
+    pub max_vertex_attribs: GLint,
+
+    /// `GL_MAX_TEXTURE_IMAGE_UNITS`
+    pub max_texture_image_units: GLint,
+
+    /// `GL_MAX_COMBINED_TEXTURE_IMAGE_UNITS`
+    pub max_combined_texture_image_units: GLint,
+
+    /// `GL_MAX_VIEWPORT_DIMS`, as `[width, height]`
+    pub max_viewport_dims: [GLint; 2]
+}
+
+/// The names of the OpenGL objects that were bound when a [`StateSnapshot`]
+/// (struct.StateSnapshot.html) was taken. A value of `0` means that
+/// no object was bound.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct BindingsSnapshot {
+    /// `GL_ARRAY_BUFFER_BINDING`
+    pub array_buffer: GLint,
+
+    /// `GL_ELEMENT_ARRAY_BUFFER_BINDING`
+    pub element_array_buffer: GLint,
+
+    /// `GL_FRAMEBUFFER_BINDING`
+    pub framebuffer: GLint,
+
+    /// `GL_RENDERBUFFER_BINDING`
+    pub renderbuffer: GLint,
+
+    /// `GL_CURRENT_PROGRAM`
+    pub program: GLint,
+
+    /// `GL_ACTIVE_TEXTURE`, as a raw enum value (such as `GL_TEXTURE0`)
+    pub active_texture: GLint,
+
+    /// `GL_TEXTURE_BINDING_2D` (for the active texture unit)
+    pub texture_2d: GLint,
+
+    /// `GL_TEXTURE_BINDING_CUBE_MAP` (for the active texture unit)
+    pub texture_cube_map: GLint
+}
+
+/// Whether each server-side capability was enabled when a [`StateSnapshot`]
+/// (struct.StateSnapshot.html) was taken. See the [`Capability`]
+/// (../../types/enum.Capability.html) docs for more details about
+/// each capability.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct CapabilitiesSnapshot {
+    /// `GL_BLEND`
+    pub blend: bool,
+
+    /// `GL_CULL_FACE`
+    pub cull_face: bool,
+
+    /// `GL_DEPTH_TEST`
+    pub depth_test: bool,
+
+    /// `GL_DITHER`
+    pub dither: bool,
+
+    /// `GL_POLYGON_OFFSET_FILL`
+    pub polygon_offset_fill: bool,
+
+    /// `GL_SAMPLE_ALPHA_TO_COVERAGE`
+    pub sample_alpha_to_coverage: bool,
+
+    /// `GL_SAMPLE_COVERAGE`
+    pub sample_coverage: bool,
+
+    /// `GL_SCISSOR_TEST`
+    pub scissor_test: bool,
+
+    /// `GL_STENCIL_TEST`
+    pub stencil_test: bool
+}
+
+/// Blending state captured in a [`StateSnapshot`]
+/// (struct.StateSnapshot.html).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct BlendSnapshot {
+    /// `GL_BLEND_SRC_RGB`
+    pub src_rgb: GLint,
+
+    /// `GL_BLEND_DST_RGB`
+    pub dst_rgb: GLint,
+
+    /// `GL_BLEND_SRC_ALPHA`
+    pub src_alpha: GLint,
+
+    /// `GL_BLEND_DST_ALPHA`
+    pub dst_alpha: GLint,
+
+    /// `GL_BLEND_EQUATION_RGB`
+    pub equation_rgb: GLint,
+
+    /// `GL_BLEND_EQUATION_ALPHA`
+    pub equation_alpha: GLint,
+
+    /// `GL_BLEND_COLOR`, as `[r, g, b, a]`
+    pub color: [GLfloat; 4]
+}
+
+/// Depth buffer state captured in a [`StateSnapshot`]
+/// (struct.StateSnapshot.html).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct DepthSnapshot {
+    /// `GL_DEPTH_FUNC`
+    pub func: GLint,
+
+    /// `GL_DEPTH_WRITEMASK`
+    pub write_mask: bool,
+
+    /// `GL_DEPTH_RANGE`, as `[near, far]`
+    pub range: [GLfloat; 2],
+
+    /// `GL_DEPTH_CLEAR_VALUE`
+    pub clear_value: GLfloat
+}
+
+/// Stencil buffer state captured in a [`StateSnapshot`]
+/// (struct.StateSnapshot.html).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct StencilSnapshot {
+    /// `GL_STENCIL_FUNC`
+    pub func: GLint,
+
+    /// `GL_STENCIL_REF`
+    pub reference: GLint,
+
+    /// `GL_STENCIL_VALUE_MASK`
+    pub value_mask: GLint,
+
+    /// `GL_STENCIL_WRITEMASK`
+    pub write_mask: GLint,
+
+    /// `GL_STENCIL_FAIL`
+    pub fail: GLint,
+
+    /// `GL_STENCIL_PASS_DEPTH_FAIL`
+    pub pass_depth_fail: GLint,
+
+    /// `GL_STENCIL_PASS_DEPTH_PASS`
+    pub pass_depth_pass: GLint,
+
+    /// `GL_STENCIL_CLEAR_VALUE`
+    pub clear_value: GLint
+}
+
+/// Video memory statistics captured in a [`StateSnapshot`]
+/// (struct.StateSnapshot.html). These statistics are only available
+/// with the `GL_NVX_gpu_memory_info` or `GL_ATI_meminfo` extensions.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct MemorySnapshot {
+    /// The total amount of video memory, in kilobytes (if known).
+    pub total_kb: Option<GLint>,
+
+    /// The amount of video memory currently available, in kilobytes.
+    pub available_kb: GLint
+}
+
+impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
+    /// Capture a snapshot of the current OpenGL state, including the
+    /// OpenGL version, supported extensions, implementation limits, current
+    /// bindings, enabled capabilities, blend/depth/stencil state, and memory
+    /// statistics (when available).
+    ///
+    /// # Note
+    /// Some queries may not be supported by every OpenGL implementation, so
+    /// any OpenGL errors generated while taking the snapshot are cleared.
+    /// This means that errors generated *before* calling `dump_state` will
+    /// be cleared as well.
+    ///
+    /// # Example
+    /// ```no_run
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let snapshot = gl.dump_state();
+    /// println!("{:#?}", snapshot);
+    /// ```
+    ///
+    /// # See also
+    /// [`glGet`](http://docs.gl/es2/glGet) OpenGL docs
+    pub fn dump_state(&self) -> StateSnapshot {
+        unsafe {
+            let memory = _get_memory(
+                self.extensions().has("GL_NVX_gpu_memory_info"),
+                self.extensions().has("GL_ATI_meminfo")
+            );
+            let mut extensions: Vec<String> = self.extensions()
+                                                  .iter()
+                                                  .map(String::from)
+                                                  .collect();
+            extensions.sort();
+
+            let snapshot = StateSnapshot {
+                version: _get_string(gl::VERSION),
+                vendor: _get_string(gl::VENDOR),
+                renderer: _get_string(gl::RENDERER),
+                shading_language_version:
+                    _get_string(gl::SHADING_LANGUAGE_VERSION),
+                limits: LimitsSnapshot {
+                    max_texture_size:
+                        _get_integer(gl::MAX_TEXTURE_SIZE),
+                    max_cube_map_texture_size:
+                        _get_integer(gl::MAX_CUBE_MAP_TEXTURE_SIZE),
+                    max_renderbuffer_size:
+                        _get_integer(gl::MAX_RENDERBUFFER_SIZE),
+                    max_vertex_attribs:
+                        _get_integer(gl::MAX_VERTEX_ATTRIBS),
+                    max_texture_image_units:
+                        _get_integer(gl::MAX_TEXTURE_IMAGE_UNITS),
+                    max_combined_texture_image_units:
+                        _get_integer(gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS),
+                    max_viewport_dims:
+                        _get_integers(gl::MAX_VIEWPORT_DIMS, [0; 2])
+                },
+                bindings: BindingsSnapshot {
+                    array_buffer:
+                        _get_integer(gl::ARRAY_BUFFER_BINDING),
+                    element_array_buffer:
+                        _get_integer(gl::ELEMENT_ARRAY_BUFFER_BINDING),
+                    framebuffer: _get_integer(gl::FRAMEBUFFER_BINDING),
+                    renderbuffer: _get_integer(gl::RENDERBUFFER_BINDING),
+                    program: _get_integer(gl::CURRENT_PROGRAM),
+                    active_texture: _get_integer(gl::ACTIVE_TEXTURE),
+                    texture_2d: _get_integer(gl::TEXTURE_BINDING_2D),
+                    texture_cube_map:
+                        _get_integer(gl::TEXTURE_BINDING_CUBE_MAP)
+                },
+                capabilities: CapabilitiesSnapshot {
+                    blend: _get_boolean(gl::BLEND),
+                    cull_face: _get_boolean(gl::CULL_FACE),
+                    depth_test: _get_boolean(gl::DEPTH_TEST),
+                    dither: _get_boolean(gl::DITHER),
+                    polygon_offset_fill:
+                        _get_boolean(gl::POLYGON_OFFSET_FILL),
+                    sample_alpha_to_coverage:
+                        _get_boolean(gl::SAMPLE_ALPHA_TO_COVERAGE),
+                    sample_coverage: _get_boolean(gl::SAMPLE_COVERAGE),
+                    scissor_test: _get_boolean(gl::SCISSOR_TEST),
+                    stencil_test: _get_boolean(gl::STENCIL_TEST)
+                },
+                blend: BlendSnapshot {
+                    src_rgb: _get_integer(gl::BLEND_SRC_RGB),
+                    dst_rgb: _get_integer(gl::BLEND_DST_RGB),
+                    src_alpha: _get_integer(gl::BLEND_SRC_ALPHA),
+                    dst_alpha: _get_integer(gl::BLEND_DST_ALPHA),
+                    equation_rgb: _get_integer(gl::BLEND_EQUATION_RGB),
+                    equation_alpha: _get_integer(gl::BLEND_EQUATION_ALPHA),
+                    color: _get_floats(gl::BLEND_COLOR, [0.0; 4])
+                },
+                depth: DepthSnapshot {
+                    func: _get_integer(gl::DEPTH_FUNC),
+                    write_mask: _get_boolean(gl::DEPTH_WRITEMASK),
+                    range: _get_floats(gl::DEPTH_RANGE, [0.0; 2]),
+                    clear_value: _get_floats(gl::DEPTH_CLEAR_VALUE, [0.0])[0]
+                },
+                stencil: StencilSnapshot {
+                    func: _get_integer(gl::STENCIL_FUNC),
+                    reference: _get_integer(gl::STENCIL_REF),
+                    value_mask: _get_integer(gl::STENCIL_VALUE_MASK),
+                    write_mask: _get_integer(gl::STENCIL_WRITEMASK),
+                    fail: _get_integer(gl::STENCIL_FAIL),
+                    pass_depth_fail:
+                        _get_integer(gl::STENCIL_PASS_DEPTH_FAIL),
+                    pass_depth_pass:
+                        _get_integer(gl::STENCIL_PASS_DEPTH_PASS),
+                    clear_value: _get_integer(gl::STENCIL_CLEAR_VALUE)
+                },
+                viewport: _get_integers(gl::VIEWPORT, [0; 4]),
+                clear_color: _get_floats(gl::COLOR_CLEAR_VALUE, [0.0; 4]),
+                memory: memory,
+                extensions: extensions
+            };
+
+            error_policy::discard_errors();
+
+            snapshot
+        }
+    }
+}
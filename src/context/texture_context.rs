@@ -4,13 +4,26 @@ use std::ptr;
 use std::marker::PhantomData;
 use gl;
 use gl::types::*;
-use context::{BaseContext, TextureUnit0Context, TextureUnitBinding2d};
+use context::{BaseContext, ContextOf,
+              TextureUnit0Context, TextureUnitBinding2d};
 use texture::{TextureMipmapFilter, TextureFilter, TextureWrapMode,
-              Texture, Texture2d, TextureCubeMap,
+              TextureParams, Texture, Texture2d, TextureCubeMap,
               Tx2d, TxCubeMap, TextureType, Tx2dImageTarget,
               ImageTargetType, TextureBindingTarget};
-use image_data::{Image2d, TexelFormat, ImageFormat};
-use types::{GLObject, GLError};
+use image_data::{Image2d, TexelFormat, ImageFormat,
+                 CompressedTextureFormat, CompressedTextureFamily};
+#[cfg(feature = "gles3")] use image_data::TexelType;
+#[cfg(feature = "gles3")] use types::DepthFunction;
+#[cfg(feature = "gles3")] use std::ops::Range;
+#[cfg(feature = "gles3")] use context::Context;
+#[cfg(feature = "gles3")] use texture::{Texture3d, Tx3d, TextureSwizzle,
+                                        mip_level_count, mip_level_size};
+#[cfg(feature = "gles3")] use types::UnsupportedFunction;
+#[cfg(feature = "image")] use image;
+#[cfg(feature = "image")] use image_features::DynamicImageData;
+use types::{GLObject, GLError, UnsupportedCompressedFormat};
+use state_cache;
+use restore_bindings;
 
 /// Provide a safe interface for building a 2D texture
 /// object that is checked to be complete. A `Texture2dBuilder`
@@ -226,6 +239,8 @@ pub trait ContextTextureExt: BaseContext {
 
         gl::GenTextures(1, &mut id as *mut GLuint);
         dbg_gl_sanity_check! {
+            call "glGenTextures";
+            args (id);
             GLError::InvalidValue => "`n` is negative",
             _ => "Unknown error"
         }
@@ -293,7 +308,309 @@ pub trait ContextTextureExt: BaseContext {
         }
     }
 
-    /// Generate a set of mipmaps for a texture object.
+    /// Set a texture's wrap mode for the r-coordinate, which is the third
+    /// texture coordinate of a 3D texture.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glTexParameter`](http://docs.gl/es3/glTexParameter) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn set_wrap_r<T>(&self, gl_texture: &mut T, wrap_mode: TextureWrapMode)
+        where T: TextureBinding
+    {
+        let gl_int = wrap_mode.gl_enum() as GLint;
+        unsafe {
+            _tex_parameter_iv(gl_texture.target(),
+                              gl::TEXTURE_WRAP_R,
+                              &gl_int as *const GLint);
+        }
+    }
+
+    /// Set whether sampling a depth texture compares the texture's depth
+    /// values against a reference value (the third texture coordinate)
+    /// with `func`, returning `0.0` or `1.0`, or returns the depth values
+    /// directly (with `None`). Comparing is used to sample shadow maps
+    /// with a `sampler2DShadow` uniform.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::ImageFormat;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let mut shadow_map = gl.build_texture_2d()
+    ///                       .empty(ImageFormat::depth24(), 1024, 1024)
+    ///                       .min_filter(glitter::LINEAR)
+    ///                       .mag_filter(glitter::LINEAR)
+    ///                       .unwrap();
+    /// let shadow_fbo = gl.build_framebuffer()
+    ///                    .texture_2d(glitter::DEPTH_ATTACHMENT, &mut shadow_map, 0)
+    ///                    .unwrap();
+    ///
+    /// let (gl_tex_unit, gl) = gl.active_texture_0();
+    /// let (mut gl_shadow_map, _) = gl_tex_unit.bind_texture_2d(&mut shadow_map);
+    /// gl.set_compare_func(&mut gl_shadow_map, Some(glitter::LEQUAL));
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glTexParameter`](http://docs.gl/es3/glTexParameter) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn set_compare_func<T>(&self,
+                           gl_texture: &mut T,
+                           func: Option<DepthFunction>)
+        where T: TextureBinding
+    {
+        let (mode, func) = match func {
+            Some(func) => (gl::COMPARE_REF_TO_TEXTURE, func),
+            None => (gl::NONE, DepthFunction::LessOrEqual)
+        };
+        let gl_mode = mode as GLint;
+        let gl_func = func.gl_enum() as GLint;
+        unsafe {
+            _tex_parameter_iv(gl_texture.target(),
+                              gl::TEXTURE_COMPARE_MODE,
+                              &gl_mode as *const GLint);
+            _tex_parameter_iv(gl_texture.target(),
+                              gl::TEXTURE_COMPARE_FUNC,
+                              &gl_func as *const GLint);
+        }
+    }
+
+    /// Set where the red, green, blue, and alpha components returned when
+    /// sampling a texture are read from. Swizzling lets a texture be read
+    /// as if it had a different format, without changing its shaders. For
+    /// example, [`LUMINANCE_SWIZZLE`](../../texture/constant.LUMINANCE_SWIZZLE.html)
+    /// samples a single-channel texture as a grayscale texture, and
+    /// [`ALPHA_SWIZZLE`](../../texture/constant.ALPHA_SWIZZLE.html) samples it
+    /// as an alpha mask.
+    ///
+    /// - `gl_texture`: The binding of the texture object.
+    /// - `swizzle`: The sources of the red, green, blue, and alpha
+    ///   components, in that order.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::ImageFormat;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let mut glyphs = gl.build_texture_2d()
+    ///                    .empty(ImageFormat::rgba8(), 512, 512)
+    ///                    .min_filter(glitter::LINEAR)
+    ///                    .unwrap();
+    ///
+    /// // Sample the glyph coverage in the red component as an alpha mask
+    /// let (gl_tex_unit, gl) = gl.active_texture_0();
+    /// let (mut gl_glyphs, _) = gl_tex_unit.bind_texture_2d(&mut glyphs);
+    /// gl.set_swizzle(&mut gl_glyphs, glitter::ALPHA_SWIZZLE);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glTexParameter`](http://docs.gl/es3/glTexParameter) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn set_swizzle<T>(&self, gl_texture: &mut T, swizzle: [TextureSwizzle; 4])
+        where T: TextureBinding
+    {
+        unsafe {
+            _tex_swizzle(gl_texture.target(), &swizzle);
+        }
+    }
+
+    /// Set all of a texture's sampling parameters at once. The wrap mode
+    /// for the r-coordinate, the swizzle, and the comparison function are
+    /// only set with the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::{ImageFormat, TextureParams};
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let mut font_atlas = gl.build_texture_2d()
+    ///                        .empty(ImageFormat::rgba8(), 512, 512)
+    ///                        .min_filter(glitter::LINEAR)
+    ///                        .unwrap();
+    /// let params = TextureParams {
+    ///     min_filter: glitter::LINEAR.into(),
+    ///     wrap_s: glitter::CLAMP_TO_EDGE,
+    ///     wrap_t: glitter::CLAMP_TO_EDGE,
+    ///     ..TextureParams::default()
+    /// };
+    ///
+    /// let (gl_tex_unit, gl) = gl.active_texture_0();
+    /// let (mut gl_font_atlas, _) = gl_tex_unit.bind_texture_2d(&mut font_atlas);
+    /// gl.set_texture_params(&mut gl_font_atlas, &params);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glTexParameter`](http://docs.gl/es2/glTexParameter) OpenGL docs
+    fn set_texture_params<T>(&self, gl_texture: &mut T, params: &TextureParams)
+        where T: TextureBinding
+    {
+        self.set_min_filter(gl_texture, params.min_filter);
+        self.set_mag_filter(gl_texture, params.mag_filter);
+        self.set_wrap_s(gl_texture, params.wrap_s);
+        self.set_wrap_t(gl_texture, params.wrap_t);
+        unsafe {
+            _set_gles3_texture_params(gl_texture.target(), params);
+        }
+    }
+
+    /// Set the lowest mipmap level of a texture that will be sampled
+    /// (`0` by default).
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glTexParameter`](http://docs.gl/es3/glTexParameter) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn set_base_level<T>(&self, gl_texture: &mut T, level: u32)
+        where T: TextureBinding
+    {
+        let gl_int = level as GLint;
+        unsafe {
+            _tex_parameter_iv(gl_texture.target(),
+                              gl::TEXTURE_BASE_LEVEL,
+                              &gl_int as *const GLint);
+        }
+    }
+
+    /// Set the highest mipmap level of a texture that will be sampled
+    /// (`1000` by default). A texture only needs image data from its
+    /// base level to its max level to be complete, so this can be used
+    /// to sample a texture with a partial mipmap chain.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::ImageFormat;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let mut texture = gl.build_texture_2d()
+    ///                     .empty(ImageFormat::rgba8(), 256, 256)
+    ///                     .min_filter(glitter::LINEAR)
+    ///                     .unwrap();
+    ///
+    /// let (gl_tex_unit, gl) = gl.active_texture_0();
+    /// let (mut gl_texture, _) = gl_tex_unit.bind_texture_2d(&mut texture);
+    /// gl.generate_mipmap(&mut gl_texture);
+    ///
+    /// // Use trilinear filtering, but skip the smallest 4 mipmap levels
+    /// gl.set_min_filter(&mut gl_texture, glitter::LINEAR_MIPMAP_LINEAR);
+    /// gl.set_max_level(&mut gl_texture, 4);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glTexParameter`](http://docs.gl/es3/glTexParameter) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn set_max_level<T>(&self, gl_texture: &mut T, level: u32)
+        where T: TextureBinding
+    {
+        let gl_int = level as GLint;
+        unsafe {
+            _tex_parameter_iv(gl_texture.target(),
+                              gl::TEXTURE_MAX_LEVEL,
+                              &gl_int as *const GLint);
+        }
+    }
+
+    /// Set the lowest level-of-detail that will be used when sampling a
+    /// texture (`-1000.0` by default). Levels of detail are mipmap levels,
+    /// relative to the base level, with fractional values blending
+    /// between two levels.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glTexParameter`](http://docs.gl/es3/glTexParameter) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn set_min_lod<T>(&self, gl_texture: &mut T, lod: f32)
+        where T: TextureBinding
+    {
+        unsafe {
+            _tex_parameter_fv(gl_texture.target(),
+                              gl::TEXTURE_MIN_LOD,
+                              &lod as *const GLfloat);
+        }
+    }
+
+    /// Set the highest level-of-detail that will be used when sampling a
+    /// texture (`1000.0` by default). See [`gl.set_min_lod`]
+    /// (#method.set_min_lod) for more details.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glTexParameter`](http://docs.gl/es3/glTexParameter) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn set_max_lod<T>(&self, gl_texture: &mut T, lod: f32)
+        where T: TextureBinding
+    {
+        unsafe {
+            _tex_parameter_fv(gl_texture.target(),
+                              gl::TEXTURE_MAX_LOD,
+                              &lod as *const GLfloat);
+        }
+    }
+
+    /// Set a bias that is added to the level-of-detail when sampling a
+    /// texture (`0.0` by default). A positive bias makes the texture
+    /// blurrier, and a negative bias makes the texture sharper.
+    ///
+    /// # Note
+    /// `GL_TEXTURE_LOD_BIAS` is not supported by OpenGL ES, so this method
+    /// is only available with the `gl33` feature.
+    ///
+    /// # See also
+    /// [`glTexParameter`](http://docs.gl/gl3/glTexParameter) OpenGL docs
+    #[cfg(feature = "gl33")]
+    fn set_lod_bias<T>(&self, gl_texture: &mut T, bias: f32)
+        where T: TextureBinding
+    {
+        unsafe {
+            _tex_parameter_fv(gl_texture.target(),
+                              gl::TEXTURE_LOD_BIAS,
+                              &bias as *const GLfloat);
+        }
+    }
+
+    /// Generate a set of mipmaps for a texture object. Afterwards, the
+    /// texture's [`mip_levels`](../../texture/struct.Texture.html#method.mip_levels)
+    /// will be the full [`mip_level_count`]
+    /// (../../texture/fn.mip_level_count.html) of its base level.
     ///
     /// # See also
     /// [`glGenerateMipmap`](http://docs.gl/es2/glGenerateMipmap) OpenGL docs
@@ -303,16 +620,19 @@ pub trait ContextTextureExt: BaseContext {
         unsafe {
             gl::GenerateMipmap(gl_texture.target().gl_enum())
         }
+        gl_texture.texture_mut()._set_mipmapped();
     }
 
-    /// Upload 2D image data to a texture object's image target.
+    /// Upload 2D image data to a texture object's image target. The
+    /// texture's tracked size and format are updated when uploading to
+    /// mipmap level 0.
     ///
-    /// - `_gl_texture`: The binding of the texture object.
+    /// - `gl_texture`: The binding of the texture object.
     /// - `target`: The texture's 2D image target to upload the image data to.
     /// - `level`: The mipmap level to upload the image data to.
     /// - `img`: The image data to upload.
     fn tex_image_2d<T, U, I: ?Sized>(&self,
-                                     _gl_texture: &mut T,
+                                     gl_texture: &mut T,
                                      target: U,
                                      level: u32,
                                      img: &I)
@@ -330,12 +650,17 @@ pub trait ContextTextureExt: BaseContext {
                           img.format(),
                           img.texel_bytes().as_ptr());
         }
+        gl_texture.texture_mut()._set_level(level,
+                                            img.format(),
+                                            img.width() as u32,
+                                            img.height() as u32);
     }
 
     /// Set a texture object's image target to an empty image
-    /// with the specified parameters.
+    /// with the specified parameters. The texture's tracked size and format
+    /// are updated when setting mipmap level 0.
     ///
-    /// - `_gl_texture`: The binding of the texture object.
+    /// - `gl_texture`: The binding of the texture object.
     /// - `target`: The texture's 2D image target to set.
     /// - `level`: The mipmap level to set.
     /// - `format`: The image format to use to use for the
@@ -343,7 +668,7 @@ pub trait ContextTextureExt: BaseContext {
     /// - `width`: The width to set for the texture's data store.
     /// - `height`: The height to set for the texture's data store.
     fn tex_image_2d_empty<T, I>(&self,
-                                _gl_texture: &mut T,
+                                gl_texture: &mut T,
                                 target: I,
                                 level: u32,
                                 format: ImageFormat,
@@ -361,6 +686,290 @@ pub trait ContextTextureExt: BaseContext {
                           format,
                           ptr::null());
         }
+        gl_texture.texture_mut()._set_level(level, format, width, height);
+    }
+
+    /// Allocate immutable storage for all of a texture object's mipmap
+    /// levels at once. The contents of each level are initially undefined,
+    /// and can be set with [`gl.tex_sub_image_2d`](#method.tex_sub_image_2d).
+    /// Afterwards, the texture's size, format, and number of levels can't
+    /// be changed (so setting a level with [`gl.tex_image_2d`]
+    /// (#method.tex_image_2d) is an error), but it can be used to create
+    /// texture views (see [`Texture::view`]
+    /// (../../texture/struct.Texture.html#method.view)).
+    ///
+    /// - `gl_texture`: The binding of the 2D or cubemap texture object.
+    /// - `levels`: The number of mipmap levels to allocate.
+    /// - `format`: The image format to use for the texture's data store.
+    /// - `width`, `height`: The size of the base level.
+    ///
+    /// # Panics
+    /// This function will panic if `levels` is 0, or is greater than the
+    /// [`mip_level_count`](../../texture/fn.mip_level_count.html) of
+    /// `width` and `height`.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glTexStorage2D`](http://docs.gl/es3/glTexStorage2D) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn tex_storage_2d<T>(&self,
+                         gl_texture: &mut T,
+                         levels: u32,
+                         format: ImageFormat,
+                         width: u32,
+                         height: u32)
+        where T: TextureBinding
+    {
+        assert!(levels > 0 && levels <= mip_level_count(width, height),
+                "Invalid number of mipmap levels for the texture's size");
+
+        let target = gl_texture.target().gl_enum();
+        let internal_format = _gl_sized_internal_format(format);
+        unsafe {
+            gl::TexStorage2D(target,
+                             levels as GLsizei,
+                             internal_format,
+                             width as GLsizei,
+                             height as GLsizei);
+            dbg_gl_error! {
+                call "glTexStorage2D";
+                args (target, levels, internal_format, width, height);
+                GLError::InvalidEnum => "`internalformat` is not a sized internal format",
+                GLError::InvalidValue => "`width`, `height`, or `levels` is less than 1",
+                GLError::InvalidOperation => "The texture already has immutable storage, or `levels` is too large",
+                _ => "Unknown error"
+            }
+        }
+
+        let texture = gl_texture.texture_mut();
+        for level in 0..levels {
+            let (level_width, level_height) = mip_level_size(width,
+                                                             height,
+                                                             level);
+            texture._set_level(level, format, level_width, level_height);
+        }
+        texture._set_immutable();
+    }
+
+    /// Replace the base level of a 2D texture with an image loaded with the
+    /// `image` crate, optionally regenerating the texture's mipmaps. The
+    /// image is converted the same way as with [`Texture2d::from_image`]
+    /// (../../texture/struct.Texture.html#method.from_image).
+    ///
+    /// # Note
+    /// This method is only available with the `image` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// extern crate image;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let img = image::open("crate.png").unwrap();
+    /// let mut texture = glitter::Texture2d::from_image(&mut gl, &img, true)
+    ///                                      .unwrap();
+    ///
+    /// let reloaded = image::open("crate.png").unwrap();
+    /// let (gl_tex_unit, gl) = gl.active_texture_0();
+    /// let (mut gl_texture, _) = gl_tex_unit.bind_texture_2d(&mut texture);
+    /// gl.update_texture_from_image(&mut gl_texture, &reloaded, true);
+    /// # }
+    /// ```
+    #[cfg(feature = "image")]
+    fn update_texture_from_image<T>(&self,
+                                    gl_texture: &mut T,
+                                    img: &image::DynamicImage,
+                                    generate_mipmap: bool)
+        where T: TextureBinding<TextureType = Tx2d>
+    {
+        let data = DynamicImageData::new(img);
+        self.tex_image_2d(gl_texture, Tx2dImageTarget::Texture2d, 0, &data);
+        if generate_mipmap {
+            self.generate_mipmap(gl_texture);
+        }
+    }
+
+    /// Upload 3D image data to a 3D texture object. The texels in `data`
+    /// are laid out as `depth` consecutive 2D images of `width * height`
+    /// texels each. The texture's tracked size and format are updated when
+    /// uploading to mipmap level 0.
+    ///
+    /// - `gl_texture`: The binding of the 3D texture object.
+    /// - `level`: The mipmap level to upload the image data to.
+    /// - `format`: The image format of `data`.
+    /// - `width`, `height`, `depth`: The dimensions of the image data.
+    /// - `data`: The texel data to upload.
+    ///
+    /// # Panics
+    /// This function will panic if the length of `data` is not
+    /// `width * height * depth` texels of `format`.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::ImageFormat;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let mut lut: glitter::Texture3d = unsafe { gl.gen_texture() };
+    /// // An identity color grading lookup table, 16 texels on each side
+    /// let mut data = Vec::with_capacity(16 * 16 * 16 * 4);
+    /// for b in 0..16 {
+    ///     for g in 0..16 {
+    ///         for r in 0..16 {
+    ///             data.extend_from_slice(&[r * 17, g * 17, b * 17, 255]);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let (gl_tex_unit, gl) = gl.active_texture_0();
+    /// let (mut gl_lut, _) = gl_tex_unit.bind_texture_3d(&mut lut);
+    /// gl.tex_image_3d(&mut gl_lut, 0, ImageFormat::rgba8(), 16, 16, 16, &data);
+    /// gl.set_min_filter(&mut gl_lut, glitter::LINEAR);
+    /// gl.set_mag_filter(&mut gl_lut, glitter::LINEAR);
+    /// gl.set_wrap_s(&mut gl_lut, glitter::CLAMP_TO_EDGE);
+    /// gl.set_wrap_t(&mut gl_lut, glitter::CLAMP_TO_EDGE);
+    /// gl.set_wrap_r(&mut gl_lut, glitter::CLAMP_TO_EDGE);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glTexImage3D`](http://docs.gl/es3/glTexImage3D) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn tex_image_3d<T>(&self,
+                       gl_texture: &mut T,
+                       level: u32,
+                       format: ImageFormat,
+                       width: u32,
+                       height: u32,
+                       depth: u32,
+                       data: &[u8])
+        where T: TextureBinding<TextureType = Tx3d>
+    {
+        let expected_len = width as usize * height as usize * depth as usize
+                         * format.texel_size();
+        assert!(data.len() == expected_len,
+                "Expected {} bytes of 3D image data, got {}",
+                expected_len,
+                data.len());
+        unsafe {
+            _tex_image_3d(level, width, height, depth, format, data.as_ptr());
+        }
+        gl_texture.texture_mut()._set_level_3d(level,
+                                               format,
+                                               width,
+                                               height,
+                                               depth);
+    }
+
+    /// Set a 3D texture object's data store to an empty image with the
+    /// specified parameters. The texture's tracked size and format are
+    /// updated when setting mipmap level 0.
+    ///
+    /// - `gl_texture`: The binding of the 3D texture object.
+    /// - `level`: The mipmap level to set.
+    /// - `format`: The image format to use for the texture's data store.
+    /// - `width`, `height`, `depth`: The dimensions to set for the
+    ///   texture's data store.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glTexImage3D`](http://docs.gl/es3/glTexImage3D) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn tex_image_3d_empty<T>(&self,
+                             gl_texture: &mut T,
+                             level: u32,
+                             format: ImageFormat,
+                             width: u32,
+                             height: u32,
+                             depth: u32)
+        where T: TextureBinding<TextureType = Tx3d>
+    {
+        unsafe {
+            _tex_image_3d(level, width, height, depth, format, ptr::null());
+        }
+        gl_texture.texture_mut()._set_level_3d(level,
+                                               format,
+                                               width,
+                                               height,
+                                               depth);
+    }
+
+    /// Replace a box-shaped region of a 3D texture object's image data.
+    /// The texels in `data` are laid out the same way as in
+    /// [`tex_image_3d`](#method.tex_image_3d).
+    ///
+    /// - `gl_texture`: The binding of the 3D texture object.
+    /// - `level`: The mipmap level to update.
+    /// - `x`, `y`, `z`: The offset of the region to update.
+    /// - `width`, `height`, `depth`: The dimensions of the region to update.
+    /// - `format`: The image format of `data`.
+    /// - `data`: The texel data to upload.
+    ///
+    /// # Panics
+    /// This function will panic if the length of `data` is not
+    /// `width * height * depth` texels of `format`.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    ///
+    /// # See also
+    /// [`glTexSubImage3D`](http://docs.gl/es3/glTexSubImage3D) OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn tex_sub_image_3d<T>(&self,
+                           gl_texture: &mut T,
+                           level: u32,
+                           x: u32,
+                           y: u32,
+                           z: u32,
+                           width: u32,
+                           height: u32,
+                           depth: u32,
+                           format: ImageFormat,
+                           data: &[u8])
+        where T: TextureBinding<TextureType = Tx3d>
+    {
+        let expected_len = width as usize * height as usize * depth as usize
+                         * format.texel_size();
+        assert!(data.len() == expected_len,
+                "Expected {} bytes of 3D image data, got {}",
+                expected_len,
+                data.len());
+        unsafe {
+            gl::TexSubImage3D(gl_texture.target().gl_enum(),
+                              level as GLint,
+                              x as GLint,
+                              y as GLint,
+                              z as GLint,
+                              width as GLsizei,
+                              height as GLsizei,
+                              depth as GLsizei,
+                              _gl_pixel_format(format.texel_format),
+                              format.texel_type.gl_enum(),
+                              data.as_ptr() as *const GLvoid);
+            dbg_gl_sanity_check! {
+                call "glTexSubImage3D";
+                args (level, x, y, z, width, height, depth, format.texel_format, format.texel_type);
+                GLError::InvalidEnum => "`format` or `type` is not an accepted value",
+                GLError::InvalidValue => "`level` is invalid, or the region extends outside of the texture's image",
+                GLError::InvalidOperation => "`format` or `type` is not compatible with the texture's internal format",
+                _ => "Unknown error"
+            }
+        }
     }
 }
 
@@ -368,6 +977,333 @@ impl<C: BaseContext> ContextTextureExt for C {
 
 }
 
+impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
+    /// Returns `true` if compressed texture data with the given format can
+    /// be uploaded. A format is supported if the context lists it in
+    /// `GL_COMPRESSED_TEXTURE_FORMATS` (see the context's [`capabilities`]
+    /// (struct.ContextOf.html#method.capabilities)), or if the extension for
+    /// the format's family is supported (see [`extensions`]
+    /// (struct.ContextOf.html#method.extensions)).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use glitter::CompressedTextureFormat;
+    ///
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let formats = [CompressedTextureFormat::RGBAAstc4x4,
+    ///                CompressedTextureFormat::RGBA8Etc2Eac,
+    ///                CompressedTextureFormat::RGBAS3tcDxt5];
+    /// let best_format = formats.iter().find(|&&format| {
+    ///     gl.supports_compressed_format(format)
+    /// });
+    /// ```
+    pub fn supports_compressed_format(&self, format: CompressedTextureFormat)
+        -> bool
+    {
+        if self.capabilities().compressed_texture_formats.contains(&format) {
+            return true;
+        }
+
+        let extensions = self.extensions();
+        match format.family() {
+            CompressedTextureFamily::S3tc => {
+                extensions.texture_compression_s3tc()
+            },
+            CompressedTextureFamily::Etc2 => {
+                extensions.texture_compression_etc2()
+            },
+            CompressedTextureFamily::Astc => {
+                extensions.texture_compression_astc()
+            }
+        }
+    }
+
+    /// Upload compressed 2D image data to a texture object's image target.
+    /// The texture's tracked size is updated when uploading to mipmap
+    /// level 0.
+    ///
+    /// - `gl_texture`: The binding of the texture object.
+    /// - `target`: The texture's 2D image target to upload the image data to.
+    /// - `level`: The mipmap level to upload the image data to.
+    /// - `format`: The compressed format of `data`.
+    /// - `width`, `height`: The size of the image, in texels.
+    /// - `data`: The compressed image data to upload.
+    ///
+    /// # Failures
+    /// An error will be returned (and no data will be uploaded) if `format`
+    /// is not supported by the context (see [`gl.supports_compressed_format`]
+    /// (struct.ContextOf.html#method.supports_compressed_format)).
+    ///
+    /// # Panics
+    /// This function will panic if the length of `data` is not the
+    /// [`image_size`]
+    /// (../image_data/enum.CompressedTextureFormat.html#method.image_size)
+    /// of `format` for the given size.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::CompressedTextureFormat;
+    ///
+    /// # fn main() {
+    /// # let dxt5_data = vec![0u8; 16384];
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let mut texture: glitter::Texture2d = unsafe { gl.gen_texture() };
+    ///
+    /// let (gl_tex_unit, gl) = gl.active_texture_0();
+    /// let (mut gl_texture, _) = gl_tex_unit.bind_texture_2d(&mut texture);
+    /// gl.compressed_tex_image_2d(&mut gl_texture,
+    ///                            glitter::TEXTURE_2D,
+    ///                            0,
+    ///                            CompressedTextureFormat::RGBAS3tcDxt5,
+    ///                            128,
+    ///                            128,
+    ///                            &dxt5_data).unwrap();
+    /// gl.set_min_filter(&mut gl_texture, glitter::LINEAR);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glCompressedTexImage2D`](http://docs.gl/es2/glCompressedTexImage2D)
+    /// OpenGL docs
+    pub fn compressed_tex_image_2d<X, U>(&self,
+                                         gl_texture: &mut X,
+                                         target: U,
+                                         level: u32,
+                                         format: CompressedTextureFormat,
+                                         width: u32,
+                                         height: u32,
+                                         data: &[u8])
+        -> Result<(), UnsupportedCompressedFormat>
+        where X: TextureBinding,
+              U: Into<<X::TextureType as TextureType>::ImageTargetType>
+    {
+        if !self.supports_compressed_format(format) {
+            return Err(UnsupportedCompressedFormat { format: format });
+        }
+        _check_compressed_size(format, width, height, data);
+
+        let target = target.into();
+        unsafe {
+            gl::CompressedTexImage2D(target.gl_enum(),
+                                     level as GLint,
+                                     format.gl_enum(),
+                                     width as GLsizei,
+                                     height as GLsizei,
+                                     0,
+                                     data.len() as GLsizei,
+                                     data.as_ptr() as *const GLvoid);
+            dbg_gl_sanity_check! {
+                call "glCompressedTexImage2D";
+                args (target.gl_enum(), level, format, width, height, data.len());
+                GLError::InvalidEnum => "`internalformat` is not a supported compressed texture format",
+                GLError::InvalidValue => "`target`, `level`, `width`, `height`, or `imageSize` is an invalid value",
+                GLError::InvalidOperation => "`imageSize` is not consistent with the format and dimensions",
+                _ => "Unknown error"
+            }
+        }
+        gl_texture.texture_mut()._set_compressed_level(level, width, height);
+        Ok(())
+    }
+
+    /// Replace a rectangular region of a texture object's compressed image
+    /// data. The region must usually be aligned to the format's
+    /// [`block_dimensions`]
+    /// (../image_data/enum.CompressedTextureFormat.html#method.block_dimensions),
+    /// and `format` must match the texture's existing compressed format.
+    ///
+    /// - `gl_texture`: The binding of the texture object.
+    /// - `target`: The texture's 2D image target to update.
+    /// - `level`: The mipmap level to update.
+    /// - `x`, `y`: The offset of the region to update, in texels.
+    /// - `width`, `height`: The size of the region to update, in texels.
+    /// - `format`: The compressed format of `data`.
+    /// - `data`: The compressed image data to upload.
+    ///
+    /// # Failures
+    /// An error will be returned (and no data will be uploaded) if `format`
+    /// is not supported by the context (see [`gl.supports_compressed_format`]
+    /// (struct.ContextOf.html#method.supports_compressed_format)).
+    ///
+    /// # Panics
+    /// This function will panic if the length of `data` is not the
+    /// [`image_size`]
+    /// (../image_data/enum.CompressedTextureFormat.html#method.image_size)
+    /// of `format` for the region's size.
+    ///
+    /// # See also
+    /// [`glCompressedTexSubImage2D`]
+    /// (http://docs.gl/es2/glCompressedTexSubImage2D) OpenGL docs
+    pub fn compressed_tex_sub_image_2d<X, U>(&self,
+                                             _gl_texture: &mut X,
+                                             target: U,
+                                             level: u32,
+                                             x: u32,
+                                             y: u32,
+                                             width: u32,
+                                             height: u32,
+                                             format: CompressedTextureFormat,
+                                             data: &[u8])
+        -> Result<(), UnsupportedCompressedFormat>
+        where X: TextureBinding,
+              U: Into<<X::TextureType as TextureType>::ImageTargetType>
+    {
+        if !self.supports_compressed_format(format) {
+            return Err(UnsupportedCompressedFormat { format: format });
+        }
+        _check_compressed_size(format, width, height, data);
+
+        let target = target.into();
+        unsafe {
+            gl::CompressedTexSubImage2D(target.gl_enum(),
+                                        level as GLint,
+                                        x as GLint,
+                                        y as GLint,
+                                        width as GLsizei,
+                                        height as GLsizei,
+                                        format.gl_enum(),
+                                        data.len() as GLsizei,
+                                        data.as_ptr() as *const GLvoid);
+            dbg_gl_sanity_check! {
+                call "glCompressedTexSubImage2D";
+                args (target.gl_enum(), level, x, y, width, height, format, data.len());
+                GLError::InvalidEnum => "`target` or `format` is not an accepted value",
+                GLError::InvalidValue => "`level` is invalid, or the region extends outside of the texture's image",
+                GLError::InvalidOperation => "`format` does not match the texture's format, or the region is not aligned to the format's blocks",
+                _ => "Unknown error"
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "gles3")]
+impl<T: TextureType> Texture<T> {
+    /// Create a texture view, which is a new texture that shares some of
+    /// this texture's image data. The view interprets the data with a
+    /// different (but compatible) format, such as viewing an `RGBA8`
+    /// texture's data as `SRGB8_ALPHA8`, and can expose a subset of the
+    /// texture's mipmap levels (such as a single mipmap level, to read it
+    /// as its own texture).
+    ///
+    /// - `gl`: The current context.
+    /// - `format`: The format of the view, which must be in the same
+    ///   compatibility class as the texture's format.
+    /// - `levels`: The range of this texture's mipmap levels that the view
+    ///   will contain. The view's level 0 is this texture's level
+    ///   `levels.start`.
+    /// - `layers`: The range of this texture's layers that the view will
+    ///   contain. This should be `0..1` for a 2D texture, and `0..6` for a
+    ///   cubemap texture.
+    ///
+    /// # Failures
+    /// An error will be returned if this texture doesn't have immutable
+    /// storage (see [`gl.tex_storage_2d`]
+    /// (../context/texture_context/trait.ContextTextureExt.html#method.tex_storage_2d)),
+    /// if `levels` or `layers` is empty or out of range, or if texture
+    /// views aren't supported by the context.
+    ///
+    /// # Note
+    /// This method requires OpenGL 4.3 or the `GL_ARB_texture_view`
+    /// extension, and is only available with the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::{ImageFormat, TexelFormat};
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let mut albedo: glitter::Texture2d = unsafe { gl.gen_texture() };
+    /// {
+    ///     let (gl_tex_unit, gl) = gl.active_texture_0();
+    ///     let (mut gl_albedo, _) = gl_tex_unit.bind_texture_2d(&mut albedo);
+    ///     gl.tex_storage_2d(&mut gl_albedo, 1, ImageFormat::rgba8(), 256, 256);
+    /// }
+    ///
+    /// // Sample the same texels, decoding them from sRGB
+    /// let srgb_format = ImageFormat {
+    ///     texel_format: TexelFormat::SRGBAlpha,
+    ///     ..ImageFormat::rgba8()
+    /// };
+    /// let albedo_srgb = albedo.view(&gl, srgb_format, 0..1, 0..1).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glTextureView`](http://docs.gl/gl4/glTextureView) OpenGL docs
+    pub fn view<C>(&self,
+                   gl: &C,
+                   format: ImageFormat,
+                   levels: Range<u32>,
+                   layers: Range<u32>)
+        -> Result<Texture<T>, GLError>
+        where C: ContextTextureExt
+    {
+        if !gl::TextureView::is_loaded() {
+            return Err(UnsupportedFunction {
+                call: "glTextureView",
+                requires: "OpenGL 4.3 or GL_ARB_texture_view"
+            }.into());
+        }
+        if !self.is_immutable() {
+            let msg = "A texture view can only be created from a texture with immutable storage";
+            return Err(GLError::Message(msg.to_owned()));
+        }
+        if levels.start >= levels.end || levels.end > self.mip_levels() {
+            let msg = "Invalid mipmap level range for a texture view";
+            return Err(GLError::Message(msg.to_owned()));
+        }
+        if layers.start >= layers.end {
+            let msg = "Invalid layer range for a texture view";
+            return Err(GLError::Message(msg.to_owned()));
+        }
+
+        let target = T::target().gl_enum();
+        let internal_format = _gl_sized_internal_format(format);
+        let mut view: Texture<T> = unsafe { gl.gen_texture() };
+        unsafe {
+            gl::TextureView(view.id(),
+                            target,
+                            self.id(),
+                            internal_format,
+                            levels.start,
+                            levels.end - levels.start,
+                            layers.start,
+                            layers.end - layers.start);
+        }
+        if let Some(err) = Context::get_error() {
+            return Err(err);
+        }
+
+        let (width, height) = self.dimensions();
+        let (width, height) = mip_level_size(width, height, levels.start);
+        for level in 0..(levels.end - levels.start) {
+            let (level_width, level_height) = mip_level_size(width,
+                                                             height,
+                                                             level);
+            view._set_level(level, format, level_width, level_height);
+        }
+        view._set_immutable();
+        Ok(view)
+    }
+}
+
+fn _check_compressed_size(format: CompressedTextureFormat,
+                          width: u32,
+                          height: u32,
+                          data: &[u8])
+{
+    let expected_len = format.image_size(width, height);
+    assert!(data.len() == expected_len,
+            "Expected {} bytes of {:?} image data, got {}",
+            expected_len,
+            format,
+            data.len());
+}
+
 
 
 unsafe fn _tex_parameter_iv(target: TextureBindingTarget,
@@ -376,11 +1312,125 @@ unsafe fn _tex_parameter_iv(target: TextureBindingTarget,
 {
     gl::TexParameteriv(target.gl_enum(), pname, params);
     dbg_gl_sanity_check! {
+        call "glTexParameteriv";
+        args (target, pname, params);
         GLError::InvalidEnum => "`target` or `pname` is not an accepted defined value, or `params` should have defined a symbolic constant and does not",
         _ => "Unknown error"
     }
 }
 
+#[cfg(feature = "gles3")]
+unsafe fn _tex_parameter_fv(target: TextureBindingTarget,
+                            pname: GLenum,
+                            params: *const GLfloat)
+{
+    gl::TexParameterfv(target.gl_enum(), pname, params);
+    dbg_gl_sanity_check! {
+        call "glTexParameterfv";
+        args (target, pname, params);
+        GLError::InvalidEnum => "`target` or `pname` is not an accepted defined value",
+        _ => "Unknown error"
+    }
+}
+
+#[cfg(feature = "gles3")]
+unsafe fn _set_gles3_texture_params(target: TextureBindingTarget,
+                                    params: &TextureParams)
+{
+    let (compare_mode, compare_func) = match params.compare_func {
+        Some(func) => (gl::COMPARE_REF_TO_TEXTURE, func),
+        None => (gl::NONE, DepthFunction::LessOrEqual)
+    };
+
+    let int_params = [
+        (gl::TEXTURE_WRAP_R, params.wrap_r.gl_enum() as GLint),
+        (gl::TEXTURE_COMPARE_MODE, compare_mode as GLint),
+        (gl::TEXTURE_COMPARE_FUNC, compare_func.gl_enum() as GLint)
+    ];
+    for &(pname, param) in &int_params {
+        _tex_parameter_iv(target, pname, &param as *const GLint);
+    }
+    _tex_swizzle(target, &params.swizzle);
+}
+
+#[cfg(feature = "gles3")]
+unsafe fn _tex_swizzle(target: TextureBindingTarget,
+                       swizzle: &[TextureSwizzle; 4])
+{
+    let swizzle_pnames = [gl::TEXTURE_SWIZZLE_R,
+                          gl::TEXTURE_SWIZZLE_G,
+                          gl::TEXTURE_SWIZZLE_B,
+                          gl::TEXTURE_SWIZZLE_A];
+    for (&pname, component) in swizzle_pnames.iter().zip(swizzle) {
+        let param = component.gl_enum() as GLint;
+        _tex_parameter_iv(target, pname, &param as *const GLint);
+    }
+}
+
+// `GL_TEXTURE_WRAP_R`, swizzling, and depth comparisons require OpenGL ES 3
+#[cfg(not(feature = "gles3"))]
+unsafe fn _set_gles3_texture_params(_target: TextureBindingTarget,
+                                    _params: &TextureParams)
+{
+
+}
+
+// OpenGL ES 3 and desktop OpenGL only accept sized sRGB and depth internal
+// formats, and expect the pixel data's format to be the base format.
+#[cfg(feature = "gles3")]
+fn _gl_internal_format(format: ImageFormat) -> GLenum {
+    match (format.texel_format, format.texel_type) {
+        (TexelFormat::SRGB, _) => gl::SRGB8,
+        (TexelFormat::SRGBAlpha, _) => gl::SRGB8_ALPHA8,
+        (TexelFormat::DepthComponent, TexelType::UnsignedShort) => {
+            gl::DEPTH_COMPONENT16
+        },
+        (TexelFormat::DepthComponent, TexelType::Float) => {
+            gl::DEPTH_COMPONENT32F
+        },
+        (TexelFormat::DepthComponent, _) => gl::DEPTH_COMPONENT24,
+        (TexelFormat::DepthStencil, _) => gl::DEPTH24_STENCIL8,
+        (texel_format, _) => texel_format.gl_enum()
+    }
+}
+
+// Immutable storage and texture views require a sized internal format,
+// even for formats that have an unsized internal format
+#[cfg(feature = "gles3")]
+fn _gl_sized_internal_format(format: ImageFormat) -> GLenum {
+    match (format.texel_format, format.texel_type) {
+        (TexelFormat::RGBA, TexelType::UnsignedByte) => gl::RGBA8,
+        (TexelFormat::RGBA, TexelType::UnsignedShort4444) => gl::RGBA4,
+        (TexelFormat::RGBA, TexelType::UnsignedShort5551) => gl::RGB5_A1,
+        (TexelFormat::RGBA, TexelType::Float) => gl::RGBA32F,
+        (TexelFormat::RGB, TexelType::UnsignedByte) => gl::RGB8,
+        (TexelFormat::RGB, TexelType::UnsignedShort565) => gl::RGB565,
+        (TexelFormat::RGB, TexelType::Float) => gl::RGB32F,
+        _ => _gl_internal_format(format)
+    }
+}
+
+#[cfg(feature = "gles3")]
+fn _gl_pixel_format(format: TexelFormat) -> GLenum {
+    match format {
+        TexelFormat::SRGB => gl::RGB,
+        TexelFormat::SRGBAlpha => gl::RGBA,
+        _ => format.gl_enum()
+    }
+}
+
+// With `GL_EXT_sRGB` and `GL_OES_depth_texture`, the internal format and
+// the pixel data's format are both the unsized format.
+#[cfg(not(feature = "gles3"))]
+fn _gl_internal_format(format: ImageFormat) -> GLenum {
+    format.texel_format.gl_enum()
+}
+
+#[cfg(not(feature = "gles3"))]
+fn _gl_pixel_format(format: TexelFormat) -> GLenum {
+    format.gl_enum()
+}
+
 unsafe fn _tex_image_2d<T: ImageTargetType>(target: T,
                                             level: u32,
                                             internal_format: TexelFormat,
@@ -392,14 +1442,16 @@ unsafe fn _tex_image_2d<T: ImageTargetType>(target: T,
     debug_assert!(internal_format == format.texel_format);
     gl::TexImage2D(target.gl_enum(),
                    level as GLint,
-                   internal_format.gl_enum() as GLint,
+                   _gl_internal_format(format) as GLint,
                    width as GLint,
                    height as GLint,
                    border as GLint,
-                   format.texel_format.gl_enum(),
+                   _gl_pixel_format(format.texel_format),
                    format.texel_type.gl_enum(),
                    image_ptr as *const GLvoid);
     dbg_gl_sanity_check! {
+        call "glTexImage2D";
+        args (target.gl_enum(), level, internal_format, width, height, border, format.texel_type, image_ptr);
         GLError::InvalidEnum => "`target`, `format`, or `type` is not an accepted value",
         GLError::InvalidValue => "`target`, `level`, `internalformat`, `width`, `height`, or `border` is an invalid value",
         GLError::InvalidOperation => "`format` conflicts with either `internalformat` or `type`",
@@ -407,6 +1459,33 @@ unsafe fn _tex_image_2d<T: ImageTargetType>(target: T,
     }
 }
 
+#[cfg(feature = "gles3")]
+unsafe fn _tex_image_3d(level: u32,
+                        width: u32,
+                        height: u32,
+                        depth: u32,
+                        format: ImageFormat,
+                        image_ptr: *const u8) {
+    gl::TexImage3D(gl::TEXTURE_3D,
+                   level as GLint,
+                   _gl_internal_format(format) as GLint,
+                   width as GLsizei,
+                   height as GLsizei,
+                   depth as GLsizei,
+                   0,
+                   _gl_pixel_format(format.texel_format),
+                   format.texel_type.gl_enum(),
+                   image_ptr as *const GLvoid);
+    dbg_gl_sanity_check! {
+        call "glTexImage3D";
+        args (level, width, height, depth, format.texel_format, format.texel_type, image_ptr);
+        GLError::InvalidEnum => "`format` or `type` is not an accepted value",
+        GLError::InvalidValue => "`level`, `width`, `height`, or `depth` is an invalid value",
+        GLError::InvalidOperation => "`format` conflicts with either `internalformat` or `type`",
+        _ => "Unknown error"
+    }
+}
+
 /// Represents a texture that has been bound to a texture unit.
 pub trait TextureBinding {
     /// The type of texture that this binding represents.
@@ -414,12 +1493,19 @@ pub trait TextureBinding {
 
     /// The OpenGL texture target of this binding.
     fn target(&self) -> TextureBindingTarget;
+
+    /// Get the texture that is bound.
+    fn texture(&self) -> &Texture<Self::TextureType>;
+
+    /// Get the texture that is bound, as a mutable reference.
+    fn texture_mut(&mut self) -> &mut Texture<Self::TextureType>;
 }
 
 /// Represents a texture that has been bound to the `GL_TEXTURE_2D` binding
 /// target of a texture unit.
 pub struct Texture2dBinding<'a> {
-    _phantom_ref: PhantomData<&'a mut Texture2d>,
+    previous: Option<PreviousTexture>,
+    texture: &'a mut Texture2d,
     _phantom_ptr: PhantomData<*mut ()>
 }
 
@@ -429,12 +1515,31 @@ impl<'a> TextureBinding for Texture2dBinding<'a> {
     fn target(&self) -> TextureBindingTarget {
         Tx2d::target()
     }
+
+    fn texture(&self) -> &Texture<Tx2d> {
+        &*self.texture
+    }
+
+    fn texture_mut(&mut self) -> &mut Texture<Tx2d> {
+        &mut *self.texture
+    }
+}
+
+impl<'a> Drop for Texture2dBinding<'a> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            unsafe {
+                _restore_texture(Tx2d::target(), previous);
+            }
+        }
+    }
 }
 
 /// Represents a texture that has been bound to the `GL_TEXTURE_CUBE_MAP`
 /// binding target of a texture unit.
 pub struct TextureCubeMapBinding<'a> {
-    _phantom_ref: PhantomData<&'a mut TextureCubeMap>,
+    previous: Option<PreviousTexture>,
+    texture: &'a mut TextureCubeMap,
     _phantom_ptr: PhantomData<*mut ()>
 }
 
@@ -444,13 +1549,129 @@ impl<'a> TextureBinding for TextureCubeMapBinding<'a> {
     fn target(&self) -> TextureBindingTarget {
         TxCubeMap::target()
     }
+
+    fn texture(&self) -> &Texture<TxCubeMap> {
+        &*self.texture
+    }
+
+    fn texture_mut(&mut self) -> &mut Texture<TxCubeMap> {
+        &mut *self.texture
+    }
+}
+
+impl<'a> Drop for TextureCubeMapBinding<'a> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            unsafe {
+                _restore_texture(TxCubeMap::target(), previous);
+            }
+        }
+    }
+}
+
+
+/// Represents a texture that has been bound to the `GL_TEXTURE_3D`
+/// binding target of a texture unit.
+///
+/// # Note
+/// 3D textures require OpenGL ES 3.0, so this type is only available with
+/// the `gles3` feature.
+#[cfg(feature = "gles3")]
+pub struct Texture3dBinding<'a> {
+    previous: Option<PreviousTexture>,
+    texture: &'a mut Texture3d,
+    _phantom_ptr: PhantomData<*mut ()>
+}
+
+#[cfg(feature = "gles3")]
+impl<'a> TextureBinding for Texture3dBinding<'a> {
+    type TextureType = Tx3d;
+
+    fn target(&self) -> TextureBindingTarget {
+        Tx3d::target()
+    }
+
+    fn texture(&self) -> &Texture<Tx3d> {
+        &*self.texture
+    }
+
+    fn texture_mut(&mut self) -> &mut Texture<Tx3d> {
+        &mut *self.texture
+    }
+}
+
+#[cfg(feature = "gles3")]
+impl<'a> Drop for Texture3dBinding<'a> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            unsafe {
+                _restore_texture(Tx3d::target(), previous);
+            }
+        }
+    }
+}
+
+
+// The texture unit that was active and the texture that was bound before a
+// texture binding was made, which are restored when the binding is dropped
+#[derive(Clone, Copy)]
+struct PreviousTexture {
+    unit: u32,
+    id: GLuint
+}
+
+fn _previous_texture(target: TextureBindingTarget) -> Option<PreviousTexture> {
+    let pname = match target {
+        TextureBindingTarget::Texture2d => gl::TEXTURE_BINDING_2D,
+        TextureBindingTarget::TextureCubeMap => gl::TEXTURE_BINDING_CUBE_MAP,
+        #[cfg(feature = "gles3")]
+        TextureBindingTarget::Texture3d => gl::TEXTURE_BINDING_3D
+    };
+    let unit = restore_bindings::previous(gl::ACTIVE_TEXTURE);
+    let id = restore_bindings::previous(pname);
+    match (unit, id) {
+        (Some(unit), Some(id)) => {
+            Some(PreviousTexture { unit: unit - gl::TEXTURE0, id: id })
+        },
+        _ => None
+    }
 }
 
+// Rebind the previous texture to the texture unit it was bound to, then
+// make the currently active texture unit active again
+unsafe fn _restore_texture(target: TextureBindingTarget,
+                           previous: PreviousTexture)
+{
+    let mut active: GLint = 0;
+    gl::GetIntegerv(gl::ACTIVE_TEXTURE, &mut active);
+    let active = active as GLuint - gl::TEXTURE0;
 
+    if state_cache::active_texture(previous.unit) {
+        gl::ActiveTexture(gl::TEXTURE0 + previous.unit);
+    }
+    if state_cache::bind_texture(target.gl_enum(), previous.id) {
+        gl::BindTexture(target.gl_enum(), previous.id);
+        dbg_gl_error! {
+            call "glBindTexture";
+            args (target, previous.id);
+            GLError::InvalidEnum => "`target` is not one of the allowed values",
+            GLError::InvalidOperation => "`texture` was created with a target that doesn't match `target`",
+            _ => "Unknown error"
+        }
+    }
+    if state_cache::active_texture(active) {
+        gl::ActiveTexture(gl::TEXTURE0 + active);
+    }
+}
 
 unsafe fn _bind_texture<T: TextureType>(texture: &mut Texture<T>) {
+    if !state_cache::bind_texture(T::target().gl_enum(), texture.id()) {
+        return;
+    }
     gl::BindTexture(T::target().gl_enum(), texture.id());
     dbg_gl_error! {
+        call "glBindTexture";
+        args (T::target(), texture.id());
         GLError::InvalidEnum => "`target` is not one of the allowed values",
         GLError::InvalidOperation => "`texture` was created with a target that doesn't match `target`",
         _ => "Unknown error"
@@ -478,14 +1699,16 @@ impl Texture2dBinder {
 
     /// Bind a texture to the `GL_TEXTURE_2D` target,
     /// returning a binding.
-    pub fn bind<'a>(&mut self, texture: &mut Texture2d)
+    pub fn bind<'a>(&mut self, texture: &'a mut Texture2d)
         -> Texture2dBinding<'a>
     {
+        let previous = _previous_texture(Tx2d::target());
         unsafe {
             _bind_texture(texture);
         }
         Texture2dBinding {
-            _phantom_ref: PhantomData,
+            previous: previous,
+            texture: texture,
             _phantom_ptr: PhantomData
         }
     }
@@ -515,11 +1738,54 @@ impl TextureCubeMapBinder {
     pub fn bind<'a>(&mut self, texture: &'a mut TextureCubeMap)
         -> TextureCubeMapBinding<'a>
     {
+        let previous = _previous_texture(TxCubeMap::target());
         unsafe {
             _bind_texture(texture);
         }
         TextureCubeMapBinding {
-            _phantom_ref: PhantomData,
+            previous: previous,
+            texture: texture,
+            _phantom_ptr: PhantomData
+        }
+    }
+}
+
+/// The OpenGL texture unit state that represents the `GL_TEXTURE_3D`
+/// target. 3D textures can only be bound with the `gles3` feature.
+pub struct Texture3dBinder {
+    _phantom: PhantomData<*mut ()>
+}
+
+impl Texture3dBinder {
+    /// Get the current `GL_TEXTURE_3D` binder.
+    ///
+    /// # Safety
+    /// The same rules apply to this method as the
+    /// [`ContextOf::current_context()`]
+    /// (../struct.ContextOf.html#method.current_context) method.
+    pub unsafe fn current() -> Self {
+        Texture3dBinder {
+            _phantom: PhantomData
+        }
+    }
+
+    /// Bind a texture to the `GL_TEXTURE_3D` target,
+    /// returning a binding.
+    ///
+    /// # Note
+    /// This method requires OpenGL ES 3.0, so it is only available with
+    /// the `gles3` feature.
+    #[cfg(feature = "gles3")]
+    pub fn bind<'a>(&mut self, texture: &'a mut Texture3d)
+        -> Texture3dBinding<'a>
+    {
+        let previous = _previous_texture(Tx3d::target());
+        unsafe {
+            _bind_texture(texture);
+        }
+        Texture3dBinding {
+            previous: previous,
+            texture: texture,
             _phantom_ptr: PhantomData
         }
     }
@@ -4,17 +4,25 @@ use std::borrow::BorrowMut;
 use std::marker::PhantomData;
 use gl;
 use gl::types::*;
-use context::{AContext, ContextOf,
-              Texture2dBinder, TextureCubeMapBinder,
+use context::{AContext, BaseContext, ContextOf, TextureBinding,
+              Texture2dBinder, TextureCubeMapBinder, Texture3dBinder,
               Texture2dBinding, TextureCubeMapBinding};
+#[cfg(feature = "gles3")] use context::Texture3dBinding;
 use texture::{Texture2d, TextureCubeMap};
+#[cfg(feature = "gles3")] use texture::Texture3d;
 use uniform_data::{UniformDatum, UniformDatumType, UniformPrimitiveType};
 use types::GLError;
 use to_ref::{ToRef, ToMut};
+use state_cache;
 
 unsafe fn _active_texture(idx: u32) {
+    if !state_cache::active_texture(idx) {
+        return;
+    }
     gl::ActiveTexture(gl::TEXTURE0 + (idx as GLenum));
     dbg_gl_error! {
+        call "glActiveTexture";
+        args (idx);
         GLError::InvalidEnum => "`texture` is out of bounds (expected to be GL_TEXTUREi, 0 <= i < GL_MAX_TEXTURE_IMAGE_UNITS)",
         _ => "Unknown error"
     }
@@ -89,6 +97,44 @@ impl TextureUnit for TextureUnit5 { fn idx(&self) -> u32 { 5 } }
 impl TextureUnit for TextureUnit6 { fn idx(&self) -> u32 { 6 } }
 impl TextureUnit for TextureUnit7 { fn idx(&self) -> u32 { 7 } }
 
+impl<'a, U: TextureUnit> TextureUnit for &'a mut U {
+    fn idx(&self) -> u32 { (**self).idx() }
+}
+
+/// Tracks which texture unit is currently active. Texture bindings and
+/// texture operations always apply to the active texture unit, so
+/// [`UnitTextureBinding`](struct.UnitTextureBinding.html) uses this type
+/// to make its texture unit active again before it is used.
+pub struct ActiveTextureUnit {
+    _phantom: PhantomData<*mut ()>
+}
+
+impl ActiveTextureUnit {
+    /// Get the index of the texture unit that is currently active.
+    ///
+    /// # See also
+    /// [`glGet`](http://docs.gl/es2/glGet) with `GL_ACTIVE_TEXTURE` OpenGL
+    /// docs
+    pub fn current() -> u32 {
+        let mut active: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::ACTIVE_TEXTURE, &mut active);
+        }
+        active as u32 - gl::TEXTURE0
+    }
+
+    /// Make the texture unit at index `idx` active, if it isn't already.
+    ///
+    /// # Safety
+    /// Any texture bindings that were made on the previously active texture
+    /// unit (other than [`UnitTextureBinding`]
+    /// (struct.UnitTextureBinding.html)s) will apply to the newly active
+    /// texture unit instead.
+    pub unsafe fn set(idx: u32) {
+        _active_texture(idx);
+    }
+}
+
 // NOTE: Ensure the number of each texture unit matches its index in the tuple
 // TODO: Use macros + integer-level types to refactor this
 /// This type holds all of the OpenGL texture units. Each type parameter
@@ -1362,23 +1408,26 @@ impl<'a, B, F, P, R, T0, T1, T2, T3, T4, T5, T6, T7> TextureUnit7Context
 // TODO: Make `idx` a type-level integer parameter
 /// A texture unit that has been made active, and can have textures
 /// bound to it.
-pub struct TextureUnitBindingOf<T2, TC> {
+pub struct TextureUnitBindingOf<T2, TC, T3> {
     idx: u32,
     texture_2d: T2,
     texture_cube_map: TC,
+    texture_3d: T3,
     _phantom: PhantomData<*mut ()>
 }
 
 /// A fresh texture unit binding, that has all free texture bindings.
 pub type TextureUnitBinding = TextureUnitBindingOf<Texture2dBinder,
-                                                   TextureCubeMapBinder>;
+                                                   TextureCubeMapBinder,
+                                                   Texture3dBinder>;
 
-impl<T2, TC> TextureUnitBindingOf<T2, TC> {
+impl<T2, TC, T3> TextureUnitBindingOf<T2, TC, T3> {
     unsafe fn current_at_idx(idx: u32) -> TextureUnitBinding {
         TextureUnitBinding {
             idx: idx,
             texture_2d: Texture2dBinder::current(),
             texture_cube_map: TextureCubeMapBinder::current(),
+            texture_3d: Texture3dBinder::current(),
             _phantom: PhantomData
         }
     }
@@ -1395,39 +1444,57 @@ impl<T2, TC> TextureUnitBindingOf<T2, TC> {
         TextureSampler { idx: self.idx as i32 }
     }
 
-    fn split_texture_2d(self) -> (T2, TextureUnitBindingOf<(), TC>) {
+    fn split_texture_2d(self) -> (T2, TextureUnitBindingOf<(), TC, T3>) {
         (
             self.texture_2d,
             TextureUnitBindingOf {
                 idx: self.idx,
                 texture_2d: (),
                 texture_cube_map: self.texture_cube_map,
+                texture_3d: self.texture_3d,
                 _phantom: PhantomData
             }
         )
     }
 
-    fn split_texture_cube_map(self) -> (TC, TextureUnitBindingOf<T2, ()>) {
+    fn split_texture_cube_map(self) -> (TC, TextureUnitBindingOf<T2, (), T3>) {
         (
             self.texture_cube_map,
             TextureUnitBindingOf {
                 idx: self.idx,
                 texture_2d: self.texture_2d,
                 texture_cube_map: (),
+                texture_3d: self.texture_3d,
+                _phantom: PhantomData
+            }
+        )
+    }
+
+    #[cfg_attr(not(feature = "gles3"), allow(dead_code))]
+    fn split_texture_3d(self) -> (T3, TextureUnitBindingOf<T2, TC, ()>) {
+        (
+            self.texture_3d,
+            TextureUnitBindingOf {
+                idx: self.idx,
+                texture_2d: self.texture_2d,
+                texture_cube_map: self.texture_cube_map,
+                texture_3d: (),
                 _phantom: PhantomData
             }
         )
     }
 
-    fn borrowed_mut<'a, B2, BC>(&'a mut self)
-        -> TextureUnitBindingOf<&'a mut B2, &'a mut BC>
+    fn borrowed_mut<'a, B2, BC, B3>(&'a mut self)
+        -> TextureUnitBindingOf<&'a mut B2, &'a mut BC, &'a mut B3>
         where T2: BorrowMut<B2>,
-              TC: BorrowMut<BC>
+              TC: BorrowMut<BC>,
+              T3: BorrowMut<B3>
     {
         TextureUnitBindingOf {
             idx: self.idx,
             texture_2d: self.texture_2d.borrow_mut(),
             texture_cube_map: self.texture_cube_map.borrow_mut(),
+            texture_3d: self.texture_3d.borrow_mut(),
             _phantom: PhantomData
         }
     }
@@ -1445,12 +1512,14 @@ pub unsafe trait ATextureUnitBinding {
 
 }
 
-unsafe impl<T2, TC> ATextureUnitBinding for TextureUnitBindingOf<T2, TC> {
+unsafe impl<T2, TC, T3> ATextureUnitBinding
+    for TextureUnitBindingOf<T2, TC, T3>
+{
 
 }
 
-unsafe impl<'a, T2, TC> ATextureUnitBinding
-    for &'a mut TextureUnitBindingOf<T2, TC>
+unsafe impl<'a, T2, TC, T3> ATextureUnitBinding
+    for &'a mut TextureUnitBindingOf<T2, TC, T3>
 {
 
 }
@@ -1475,6 +1544,19 @@ pub trait TextureUnitBinding2d: ATextureUnitBinding {
         let (mut binder, rest) = self.split_texture_2d();
         (binder.borrow_mut().bind(tex), rest)
     }
+
+    /// Bind a 2D texture to this texture unit for the duration of `f`, which is
+    /// called with the binding and the remaining context. This is a scoped
+    /// alternative to [`bind_texture_2d`](#method.bind_texture_2d), which can
+    /// be easier to use when nesting short-lived bindings.
+    fn with_texture_2d<'a, U, C>(self, tex: &'a mut Texture2d, f: C)
+        -> U
+        where Self: Sized,
+              C: FnOnce(Texture2dBinding<'a>, Self::Rest) -> U
+    {
+        let (gl_tex, gl) = self.bind_texture_2d(tex);
+        f(gl_tex, gl)
+    }
 }
 
 /// A texture unit binding that has a free `GL_TEXTURE_CUBE_MAP` binding.
@@ -1497,25 +1579,79 @@ pub trait TextureUnitBindingCubeMap: ATextureUnitBinding {
         let (mut binder, rest) = self.split_texture_cube_map();
         (binder.borrow_mut().bind(tex), rest)
     }
+
+    /// Bind a cubemap texture to this texture unit for the duration of `f`,
+    /// which is called with the binding and the remaining context. This is a
+    /// scoped alternative to
+    /// [`bind_texture_cube_map`](#method.bind_texture_cube_map), which can be
+    /// easier to use when nesting short-lived bindings.
+    fn with_texture_cube_map<'a, U, C>(self, tex: &'a mut TextureCubeMap, f: C)
+        -> U
+        where Self: Sized,
+              C: FnOnce(TextureCubeMapBinding<'a>, Self::Rest) -> U
+    {
+        let (gl_tex, gl) = self.bind_texture_cube_map(tex);
+        f(gl_tex, gl)
+    }
+}
+
+/// A texture unit binding that has a free `GL_TEXTURE_3D` binding.
+///
+/// # Note
+/// 3D textures require OpenGL ES 3.0, so this trait is only available with
+/// the `gles3` feature.
+#[cfg(feature = "gles3")]
+pub trait TextureUnitBinding3d: ATextureUnitBinding {
+    /// The type of binder this texture unit contains.
+    type Binder: BorrowMut<Texture3dBinder>;
+
+    /// The texture unit that will be returned after binding the texture.
+    type Rest: ATextureUnitBinding;
+
+    /// Split the texture unit into a binder and the remaining texture unit.
+    fn split_texture_3d(self) -> (Self::Binder, Self::Rest);
+
+    /// Bind a 3D texture to this texture unit, returning a binding
+    /// and the remaining texture unit.
+    fn bind_texture_3d<'a>(self, tex: &'a mut Texture3d)
+        -> (Texture3dBinding<'a>, Self::Rest)
+        where Self: Sized
+    {
+        let (mut binder, rest) = self.split_texture_3d();
+        (binder.borrow_mut().bind(tex), rest)
+    }
+
+    /// Bind a 3D texture to this texture unit for the duration of `f`, which is
+    /// called with the binding and the remaining context. This is a scoped
+    /// alternative to [`bind_texture_3d`](#method.bind_texture_3d), which can
+    /// be easier to use when nesting short-lived bindings.
+    fn with_texture_3d<'a, U, C>(self, tex: &'a mut Texture3d, f: C)
+        -> U
+        where Self: Sized,
+              C: FnOnce(Texture3dBinding<'a>, Self::Rest) -> U
+    {
+        let (gl_tex, gl) = self.bind_texture_3d(tex);
+        f(gl_tex, gl)
+    }
 }
 
-impl<T2, TC> TextureUnitBinding2d for TextureUnitBindingOf<T2, TC>
+impl<T2, TC, T3> TextureUnitBinding2d for TextureUnitBindingOf<T2, TC, T3>
     where T2: BorrowMut<Texture2dBinder>
 {
     type Binder = T2;
-    type Rest = TextureUnitBindingOf<(), TC>;
+    type Rest = TextureUnitBindingOf<(), TC, T3>;
 
     fn split_texture_2d(self) -> (Self::Binder, Self::Rest) {
         self.split_texture_2d()
     }
 }
 
-impl<'a, T2, TC> TextureUnitBinding2d
-    for &'a mut TextureUnitBindingOf<T2, TC>
+impl<'a, T2, TC, T3> TextureUnitBinding2d
+    for &'a mut TextureUnitBindingOf<T2, TC, T3>
     where T2: BorrowMut<Texture2dBinder>
 {
     type Binder = &'a mut Texture2dBinder;
-    type Rest = TextureUnitBindingOf<(), &'a mut TC>;
+    type Rest = TextureUnitBindingOf<(), &'a mut TC, &'a mut T3>;
 
     fn split_texture_2d(self) -> (Self::Binder, Self::Rest) {
         let gl_tex_unit = self.borrowed_mut();
@@ -1523,23 +1659,23 @@ impl<'a, T2, TC> TextureUnitBinding2d
     }
 }
 
-impl<T2, TC> TextureUnitBindingCubeMap for TextureUnitBindingOf<T2, TC>
+impl<T2, TC, T3> TextureUnitBindingCubeMap for TextureUnitBindingOf<T2, TC, T3>
     where TC: BorrowMut<TextureCubeMapBinder>
 {
     type Binder = TC;
-    type Rest = TextureUnitBindingOf<T2, ()>;
+    type Rest = TextureUnitBindingOf<T2, (), T3>;
 
     fn split_texture_cube_map(self) -> (Self::Binder, Self::Rest) {
         self.split_texture_cube_map()
     }
 }
 
-impl<'a, T2, TC> TextureUnitBindingCubeMap
-    for &'a mut TextureUnitBindingOf<T2, TC>
+impl<'a, T2, TC, T3> TextureUnitBindingCubeMap
+    for &'a mut TextureUnitBindingOf<T2, TC, T3>
     where TC: BorrowMut<TextureCubeMapBinder>
 {
     type Binder = &'a mut TextureCubeMapBinder;
-    type Rest = TextureUnitBindingOf<&'a mut T2, ()>;
+    type Rest = TextureUnitBindingOf<&'a mut T2, (), &'a mut T3>;
 
     fn split_texture_cube_map(self) -> (Self::Binder, Self::Rest) {
         let gl_tex_unit = self.borrowed_mut();
@@ -1547,6 +1683,32 @@ impl<'a, T2, TC> TextureUnitBindingCubeMap
     }
 }
 
+#[cfg(feature = "gles3")]
+impl<T2, TC, T3> TextureUnitBinding3d for TextureUnitBindingOf<T2, TC, T3>
+    where T3: BorrowMut<Texture3dBinder>
+{
+    type Binder = T3;
+    type Rest = TextureUnitBindingOf<T2, TC, ()>;
+
+    fn split_texture_3d(self) -> (Self::Binder, Self::Rest) {
+        self.split_texture_3d()
+    }
+}
+
+#[cfg(feature = "gles3")]
+impl<'a, T2, TC, T3> TextureUnitBinding3d
+    for &'a mut TextureUnitBindingOf<T2, TC, T3>
+    where T3: BorrowMut<Texture3dBinder>
+{
+    type Binder = &'a mut Texture3dBinder;
+    type Rest = TextureUnitBindingOf<&'a mut T2, &'a mut TC, ()>;
+
+    fn split_texture_3d(self) -> (Self::Binder, Self::Rest) {
+        let gl_tex_unit = self.borrowed_mut();
+        gl_tex_unit.split_texture_3d()
+    }
+}
+
 /// A newtype wrapper representing a texture sampler, which can be
 /// used to set a uniform variable, using [`gl.set_uniform`]
 /// (../program_context/trait.ContextProgramExt.html#method.set_uniform).
@@ -1559,3 +1721,134 @@ unsafe impl UniformDatum for TextureSampler {
         UniformDatumType::Vec1(UniformPrimitiveType::Int)
     }
 }
+
+
+
+/// A texture that can be bound to a texture unit with
+/// [`gl.bind_texture_to_unit`]
+/// (trait.ContextTextureUnitExt.html#method.bind_texture_to_unit).
+pub trait BindToUnit<'a> {
+    /// The binding that is returned after binding the texture.
+    type Binding: TextureBinding;
+
+    /// Bind the texture to the given (active) texture unit.
+    fn bind_to_unit(self, gl_tex_unit: TextureUnitBinding) -> Self::Binding;
+}
+
+impl<'a> BindToUnit<'a> for &'a mut Texture2d {
+    type Binding = Texture2dBinding<'a>;
+
+    fn bind_to_unit(self, gl_tex_unit: TextureUnitBinding) -> Self::Binding {
+        let (gl_texture, _) = gl_tex_unit.bind_texture_2d(self);
+        gl_texture
+    }
+}
+
+impl<'a> BindToUnit<'a> for &'a mut TextureCubeMap {
+    type Binding = TextureCubeMapBinding<'a>;
+
+    fn bind_to_unit(self, gl_tex_unit: TextureUnitBinding) -> Self::Binding {
+        let (gl_texture, _) = gl_tex_unit.bind_texture_cube_map(self);
+        gl_texture
+    }
+}
+
+#[cfg(feature = "gles3")]
+impl<'a> BindToUnit<'a> for &'a mut Texture3d {
+    type Binding = Texture3dBinding<'a>;
+
+    fn bind_to_unit(self, gl_tex_unit: TextureUnitBinding) -> Self::Binding {
+        let (gl_texture, _) = gl_tex_unit.bind_texture_3d(self);
+        gl_texture
+    }
+}
+
+/// A texture binding that is tied to the texture unit it was bound to.
+///
+/// The texture unit is borrowed for as long as the binding is alive, so
+/// no other texture can be bound to the same unit. Getting the binding with
+/// [`binding_mut`](struct.UnitTextureBinding.html#method.binding_mut)
+/// makes the texture unit active again first, so that operations on the
+/// binding never apply to a different texture unit that was made active
+/// in the meantime.
+pub struct UnitTextureBinding<'u, B: TextureBinding> {
+    idx: u32,
+    binding: B,
+    _unit: PhantomData<&'u mut ()>
+}
+
+impl<'u, B: TextureBinding> UnitTextureBinding<'u, B> {
+    /// Get the index of the texture unit that the texture is bound to.
+    pub fn gl_idx(&self) -> u32 {
+        self.idx
+    }
+
+    /// Get the texture unit as a [`TextureSampler`]
+    /// (struct.TextureSampler.html), which can be used to set a uniform
+    /// variable.
+    pub fn sampler(&self) -> TextureSampler {
+        TextureSampler { idx: self.idx as i32 }
+    }
+
+    /// Get the texture binding, without changing the active texture unit.
+    pub fn binding(&self) -> &B {
+        &self.binding
+    }
+
+    /// Make the binding's texture unit active, then get the texture
+    /// binding, so that it can be used with the texture methods of
+    /// [`ContextTextureExt`](../texture_context/trait.ContextTextureExt.html).
+    pub fn binding_mut(&mut self) -> &mut B {
+        unsafe {
+            ActiveTextureUnit::set(self.idx);
+        }
+        &mut self.binding
+    }
+}
+
+/// An extension trait that adds a method for binding a texture to a
+/// specific texture unit.
+pub trait ContextTextureUnitExt: BaseContext {
+    /// Make `unit` active, and bind `texture` to it, returning a binding
+    /// that is tied to the texture unit.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// # let mut albedo: glitter::Texture2d = unsafe { ::std::mem::uninitialized() };
+    /// # let mut normals: glitter::Texture2d = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let (mut unit_0, gl) = gl.split_tex_unit_0();
+    /// let (mut unit_1, gl) = gl.split_tex_unit_1();
+    ///
+    /// let mut gl_albedo = gl.bind_texture_to_unit(&mut unit_0, &mut albedo);
+    /// let gl_normals = gl.bind_texture_to_unit(&mut unit_1, &mut normals);
+    ///
+    /// // Texture unit 1 is active, but this still applies to unit 0
+    /// gl.set_min_filter(gl_albedo.binding_mut(), glitter::LINEAR);
+    /// # let _ = gl_normals;
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glActiveTexture`](http://docs.gl/es2/glActiveTexture) and
+    /// [`glBindTexture`](http://docs.gl/es2/glBindTexture) OpenGL docs
+    fn bind_texture_to_unit<'u, U, T>(&self, unit: &'u mut U, texture: T)
+        -> UnitTextureBinding<'u, T::Binding>
+        where U: TextureUnit, T: BindToUnit<'u>
+    {
+        let gl_tex_unit = unit.active();
+        UnitTextureBinding {
+            idx: gl_tex_unit.gl_idx(),
+            binding: texture.bind_to_unit(gl_tex_unit),
+            _unit: PhantomData
+        }
+    }
+}
+
+impl<C: BaseContext> ContextTextureUnitExt for C {
+
+}
@@ -0,0 +1,125 @@
+//! Contains the [`ContextBound`](struct.ContextBound.html) type, which ties
+//! an OpenGL object to the context that created it.
+
+use std::mem;
+use context::{ContextOf, ContextHandle};
+use types::GLObject;
+
+/// An OpenGL object that is tied to the context that created it.
+///
+/// OpenGL objects are deleted when they are dropped, which is only valid
+/// if the context that created the object (or a context that shares
+/// objects with it) is still current. A `ContextBound` object can only be
+/// accessed while its context is alive, and if it is dropped after its
+/// context has died, the object is leaked instead of being deleted, so
+/// that an object with the same name in a different context isn't deleted
+/// by mistake.
+///
+/// A context is considered dead once it has been lost, or forgotten with
+/// [`ContextOf::forget_current_context`]
+/// (../context/struct.ContextOf.html#method.forget_current_context) (see
+/// [`ContextHandle`](../context/struct.ContextHandle.html)). Dropping every
+/// `ContextOf` on the thread doesn't kill the context, since the OpenGL
+/// context itself is still alive.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::ContextBound;
+///
+/// # fn main() {
+/// let mut gl = unsafe { glitter::Context::current_context() };
+/// let mut buffer = ContextBound::new(&gl, gl.gen_buffer());
+///
+/// if let Some(buffer) = buffer.get_mut() {
+///     let (mut gl_buffer, gl) = (&mut gl).bind_array_buffer(buffer);
+///     gl.buffer_bytes(&mut gl_buffer, &[1, 2, 3], glitter::STATIC_DRAW);
+/// }
+///
+/// drop(gl);
+/// // The OpenGL context is still alive, so the buffer can still be used
+/// assert!(buffer.get().is_some());
+///
+/// // Destroy the window and its context...
+/// glitter::Context::forget_current_context();
+/// // The context has died, so the buffer can't be used anymore
+/// assert!(buffer.get().is_none());
+/// # }
+/// ```
+pub struct ContextBound<T: GLObject> {
+    object: Option<T>,
+    context: ContextHandle
+}
+
+impl<T: GLObject> ContextBound<T> {
+    /// Tie `object` to the context `gl`. The object should have been
+    /// created with `gl`, or with a context that shares objects with it.
+    pub fn new<B, F, P, R, TU>(gl: &ContextOf<B, F, P, R, TU>, object: T)
+        -> Self
+    {
+        ContextBound::with_handle(gl.handle(), object)
+    }
+
+    /// Tie `object` to the context that `context` is a handle to.
+    pub fn with_handle(context: ContextHandle, object: T) -> Self {
+        ContextBound {
+            object: Some(object),
+            context: context
+        }
+    }
+
+    /// Returns `true` if the object's context is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.context.is_alive()
+    }
+
+    /// Get a reference to the object, or `None` if the object's context
+    /// has died.
+    pub fn get(&self) -> Option<&T> {
+        if self.is_alive() {
+            self.object.as_ref()
+        }
+        else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to the object, or `None` if the object's
+    /// context has died.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if self.is_alive() {
+            self.object.as_mut()
+        }
+        else {
+            None
+        }
+    }
+
+    /// Take the object out of the `ContextBound`, or `None` if the
+    /// object's context has died (in which case the object is leaked).
+    pub fn into_inner(mut self) -> Option<T> {
+        if self.is_alive() {
+            self.object.take()
+        }
+        else {
+            None
+        }
+    }
+
+    /// Get a handle to the object's context.
+    pub fn context(&self) -> &ContextHandle {
+        &self.context
+    }
+}
+
+impl<T: GLObject> Drop for ContextBound<T> {
+    fn drop(&mut self) {
+        if let Some(object) = self.object.take() {
+            // The object's name may belong to another context by now
+            if !self.context.is_alive() {
+                mem::forget(object);
+            }
+        }
+    }
+}
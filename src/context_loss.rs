@@ -0,0 +1,88 @@
+// Thread-local state for detecting context loss, and the callbacks that
+// recreate resources once a new context is current.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use gl;
+use gl::types::*;
+use context::Context;
+
+type Hook = Rc<RefCell<Box<FnMut(&mut Context)>>>;
+
+thread_local! {
+    static LOST: Cell<bool> = Cell::new(false);
+    static GENERATION: Cell<u64> = Cell::new(0);
+    static NEXT_HOOK: Cell<usize> = Cell::new(0);
+    static HOOKS: RefCell<Vec<(usize, Hook)>> = RefCell::new(vec![]);
+}
+
+pub fn is_lost() -> bool {
+    LOST.with(|lost| lost.get())
+}
+
+// The number of times resources have been restored on this thread. Objects
+// record the generation they were created in, so that objects from a lost
+// context are never deleted with the context that replaces it.
+pub fn generation() -> u64 {
+    GENERATION.with(|generation| generation.get())
+}
+
+pub fn mark_lost() {
+    LOST.with(|lost| lost.set(true));
+}
+
+// Query the reset status of the current context. `glGetGraphicsResetStatus`
+// is only available with OpenGL 4.5, OpenGL ES 3.2, or a robustness
+// extension, so `GL_NO_ERROR` is returned if it wasn't loaded.
+pub unsafe fn reset_status() -> GLenum {
+    if !gl::GetGraphicsResetStatus::is_loaded() {
+        return gl::NO_ERROR;
+    }
+
+    let status = gl::GetGraphicsResetStatus();
+    if status != gl::NO_ERROR {
+        mark_lost();
+    }
+    status
+}
+
+pub fn add_hook(hook: Box<FnMut(&mut Context)>) -> usize {
+    let id = NEXT_HOOK.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    HOOKS.with(|hooks| {
+        hooks.borrow_mut().push((id, Rc::new(RefCell::new(hook))));
+    });
+    id
+}
+
+pub fn remove_hook(id: usize) -> bool {
+    HOOKS.with(|hooks| {
+        let mut hooks = hooks.borrow_mut();
+        let old_len = hooks.len();
+        hooks.retain(|&(hook_id, _)| hook_id != id);
+        hooks.len() != old_len
+    })
+}
+
+// Start a new generation and clear the lost flag, then call every hook (in
+// the order they were added) with the new context, returning the number of
+// hooks that were called. Objects from the lost context (including ones
+// that a hook replaces) belong to an older generation, so they're discarded
+// instead of being deleted. The hooks are cloned first, so that a hook can
+// add or remove hooks.
+pub fn restore(gl: &mut Context) -> usize {
+    GENERATION.with(|generation| generation.set(generation.get() + 1));
+    LOST.with(|lost| lost.set(false));
+
+    let hooks: Vec<Hook> = HOOKS.with(|hooks| {
+        hooks.borrow().iter().map(|&(_, ref hook)| hook.clone()).collect()
+    });
+    for hook in &hooks {
+        let mut hook = hook.borrow_mut();
+        (&mut **hook)(gl);
+    }
+    hooks.len()
+}
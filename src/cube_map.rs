@@ -0,0 +1,350 @@
+//! Contains convenience constructors for [`TextureCubeMap`]
+//! (../texture/type.TextureCubeMap.html) textures.
+
+use std::mem;
+use std::slice;
+use gl;
+use gl::types::*;
+use context::{Context, ContextExt, ContextBufferExt, ContextFramebufferExt,
+              ContextProgramExt, ContextProgramBuilderExt,
+              ContextTextureExt, ArrayBufferContext, FramebufferContext,
+              ProgramContext, TextureUnit0Context, TextureUnitBinding2d,
+              TextureUnitBindingCubeMap, COLOR_ATTACHMENT0};
+use shader::ContextShaderBuilderExt;
+use buffer::STATIC_DRAW;
+use texture::{Texture, TextureCubeMap, Texture2d, TxCubeMap,
+              TxCubeMapImageTarget, LINEAR, LINEAR_MIPMAP_LINEAR,
+              CLAMP_TO_EDGE};
+use image_data::{Image2d, ImageFormat};
+use types::{GLError, Viewport, DataType, DrawingMode};
+
+// The image targets of each face of a cubemap, in the order that faces
+// are passed to `TextureCubeMap::from_faces`
+const FACES: [TxCubeMapImageTarget; 6] = [
+    TxCubeMapImageTarget::CubeMapPositiveX,
+    TxCubeMapImageTarget::CubeMapNegativeX,
+    TxCubeMapImageTarget::CubeMapPositiveY,
+    TxCubeMapImageTarget::CubeMapNegativeY,
+    TxCubeMapImageTarget::CubeMapPositiveZ,
+    TxCubeMapImageTarget::CubeMapNegativeZ
+];
+
+// The direction through the center of each face, and the directions of the
+// face's s and t texture coordinates, as `(forward, right, up)`
+const FACE_BASES: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+    ([ 1.0,  0.0,  0.0], [ 0.0,  0.0, -1.0], [ 0.0, -1.0,  0.0]),
+    ([-1.0,  0.0,  0.0], [ 0.0,  0.0,  1.0], [ 0.0, -1.0,  0.0]),
+    ([ 0.0,  1.0,  0.0], [ 1.0,  0.0,  0.0], [ 0.0,  0.0,  1.0]),
+    ([ 0.0, -1.0,  0.0], [ 1.0,  0.0,  0.0], [ 0.0,  0.0, -1.0]),
+    ([ 0.0,  0.0,  1.0], [ 1.0,  0.0,  0.0], [ 0.0, -1.0,  0.0]),
+    ([ 0.0,  0.0, -1.0], [-1.0,  0.0,  0.0], [ 0.0, -1.0,  0.0])
+];
+
+// A quad that covers the whole viewport, drawn as a triangle strip
+const QUAD: [f32; 8] = [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0];
+
+const EQUIRECT_VERTEX_SOURCE: &'static str = r##"#version 100
+    attribute vec2 position;
+    varying vec2 _position;
+
+    void main() {
+        gl_Position = vec4(position, 0.0, 1.0);
+        _position = position;
+    }
+"##;
+
+const EQUIRECT_FRAGMENT_SOURCE: &'static str = r##"#version 100
+    precision highp float;
+
+    uniform sampler2D equirect;
+    uniform vec3 forward;
+    uniform vec3 right;
+    uniform vec3 up;
+    varying vec2 _position;
+
+    const float PI = 3.14159265358979;
+
+    void main() {
+        vec3 dir = normalize(forward + _position.x * right + _position.y * up);
+        vec2 uv = vec2(atan(dir.z, dir.x) / (2.0 * PI) + 0.5,
+                       0.5 - asin(clamp(dir.y, -1.0, 1.0)) / PI);
+        gl_FragColor = texture2D(equirect, uv);
+    }
+"##;
+
+// The texel data of a single cubemap face
+struct FaceData<'a> {
+    size: usize,
+    format: ImageFormat,
+    bytes: &'a [u8]
+}
+
+impl<'a> Image2d for FaceData<'a> {
+    fn width(&self) -> usize {
+        self.size
+    }
+
+    fn height(&self) -> usize {
+        self.size
+    }
+
+    fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    fn texel_bytes(&self) -> &[u8] {
+        self.bytes
+    }
+}
+
+fn _current_viewport() -> Viewport {
+    let mut viewport: [GLint; 4] = [0; 4];
+    unsafe {
+        gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+    }
+    Viewport::new(viewport[0] as u32,
+                  viewport[1] as u32,
+                  viewport[2] as u32,
+                  viewport[3] as u32)
+}
+
+fn _message<E: ::std::fmt::Display>(e: E) -> GLError {
+    GLError::Message(format!("{}", e))
+}
+
+impl Texture<TxCubeMap> {
+    /// Create a cubemap texture from the texel data of its six faces, using
+    /// linear filtering and clamping to the edge of each face.
+    ///
+    /// - `gl`: A context where the 0th texture unit is free.
+    /// - `faces`: The texel data of each face, in the order +X, -X, +Y,
+    ///   -Y, +Z, -Z. Each row of a face must be padded to a multiple of
+    ///   4 bytes (the default `GL_UNPACK_ALIGNMENT`).
+    /// - `format`: The format of the texel data.
+    /// - `size`: The width and height of each face.
+    /// - `generate_mipmap`: Whether to generate mipmaps for the texture
+    ///   (and use trilinear filtering).
+    ///
+    /// # Failures
+    /// An error will be returned if the length of any face's data doesn't
+    /// match `size` and `format`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// extern crate glitter;
+    /// use glitter::ImageFormat;
+    ///
+    /// # fn main() {
+    /// # let load_face = |_: &str| vec![0; 256 * 256 * 4];
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let faces = [load_face("px"), load_face("nx"),
+    ///              load_face("py"), load_face("ny"),
+    ///              load_face("pz"), load_face("nz")];
+    /// let skybox = glitter::TextureCubeMap::from_faces(
+    ///     gl,
+    ///     [&faces[0][..], &faces[1][..], &faces[2][..],
+    ///      &faces[3][..], &faces[4][..], &faces[5][..]],
+    ///     ImageFormat::rgba8(),
+    ///     256,
+    ///     false
+    /// ).unwrap();
+    /// # }
+    /// ```
+    pub fn from_faces<C>(gl: C,
+                         faces: [&[u8]; 6],
+                         format: ImageFormat,
+                         size: u32,
+                         generate_mipmap: bool)
+        -> Result<TextureCubeMap, GLError>
+        where C: TextureUnit0Context
+    {
+        let row = size as usize * format.texel_size();
+        let padded_row = (row + 3) / 4 * 4;
+        let face_size = padded_row * size as usize;
+        if faces.iter().any(|face| face.len() != face_size) {
+            let msg = "Error building cubemap: face data does not match the cubemap's size and format";
+            return Err(GLError::Message(msg.to_owned()));
+        }
+
+        let mut texture: TextureCubeMap = unsafe { gl.gen_texture() };
+        {
+            let (gl_tex_unit, gl) = gl.active_texture_0();
+            let (mut gl_texture, _) =
+                gl_tex_unit.bind_texture_cube_map(&mut texture);
+
+            for (&target, &bytes) in FACES.iter().zip(&faces) {
+                let face = FaceData {
+                    size: size as usize,
+                    format: format,
+                    bytes: bytes
+                };
+                gl.tex_image_2d(&mut gl_texture, target, 0, &face);
+            }
+
+            gl.set_mag_filter(&mut gl_texture, LINEAR);
+            gl.set_wrap_s(&mut gl_texture, CLAMP_TO_EDGE);
+            gl.set_wrap_t(&mut gl_texture, CLAMP_TO_EDGE);
+            if generate_mipmap {
+                gl.set_min_filter(&mut gl_texture, LINEAR_MIPMAP_LINEAR);
+                gl.generate_mipmap(&mut gl_texture);
+            }
+            else {
+                gl.set_min_filter(&mut gl_texture, LINEAR);
+            }
+        }
+
+        Ok(texture)
+    }
+
+    /// Create a cubemap texture by rendering an equirectangular (also
+    /// known as latitude/longitude) panorama into each of its faces. The
+    /// top row of `equirect` is the +Y direction, and its center is the
+    /// +X direction. The cubemap uses linear filtering and clamps to the
+    /// edge of each face.
+    ///
+    /// The faces are rendered with an internal program and framebuffer,
+    /// which are deleted afterwards. This changes the program, array
+    /// buffer, and framebuffer bindings, the 2D texture bound to the 0th
+    /// texture unit, and the vertex attribute state; the viewport is
+    /// restored afterwards.
+    ///
+    /// - `gl`: The current context.
+    /// - `equirect`: The panorama to render into the cubemap. It should
+    ///   have a non-mipmap minifying filter, or have mipmaps.
+    /// - `format`: The format of the cubemap's faces, which must be
+    ///   color-renderable.
+    /// - `size`: The width and height of each face.
+    ///
+    /// # Failures
+    /// An error will be returned if the internal program couldn't be built,
+    /// or if a face of the cubemap can't be rendered to.
+    ///
+    /// # Example
+    /// ```no_run
+    /// extern crate glitter;
+    /// extern crate image;
+    /// use glitter::ImageFormat;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let img = image::open("sky.png").unwrap();
+    /// let mut panorama = glitter::Texture2d::from_image(&mut gl, &img, false)
+    ///                                       .unwrap();
+    /// let skybox = glitter::TextureCubeMap::from_equirectangular(
+    ///     &mut gl,
+    ///     &mut panorama,
+    ///     ImageFormat::rgba8(),
+    ///     512
+    /// ).unwrap();
+    /// # }
+    /// ```
+    pub fn from_equirectangular(gl: &mut Context,
+                                equirect: &mut Texture2d,
+                                format: ImageFormat,
+                                size: u32)
+        -> Result<TextureCubeMap, GLError>
+    {
+        let vertex_shader = try! {
+            gl.build_vertex_shader(EQUIRECT_VERTEX_SOURCE).try_unwrap()
+        };
+        let fragment_shader = try! {
+            gl.build_fragment_shader(EQUIRECT_FRAGMENT_SOURCE).try_unwrap()
+        };
+        let mut program = try! {
+            gl.build_program(&[vertex_shader, fragment_shader]).try_unwrap()
+        };
+        let position = try! {
+            gl.get_attrib_location(&program, "position").map_err(_message)
+        };
+        let equirect_uniform = try! {
+            gl.get_uniform_location(&program, "equirect").map_err(_message)
+        };
+        let forward_uniform = try! {
+            gl.get_uniform_location(&program, "forward").map_err(_message)
+        };
+        let right_uniform = try! {
+            gl.get_uniform_location(&program, "right").map_err(_message)
+        };
+        let up_uniform = try! {
+            gl.get_uniform_location(&program, "up").map_err(_message)
+        };
+
+        let mut cube_map: TextureCubeMap = unsafe { gl.gen_texture() };
+        {
+            let (gl_tex_unit, gl) = gl.active_texture_0();
+            let (mut gl_cube_map, _) =
+                gl_tex_unit.bind_texture_cube_map(&mut cube_map);
+            for &target in &FACES {
+                gl.tex_image_2d_empty(&mut gl_cube_map,
+                                      target,
+                                      0,
+                                      format,
+                                      size,
+                                      size);
+            }
+            gl.set_min_filter(&mut gl_cube_map, LINEAR);
+            gl.set_mag_filter(&mut gl_cube_map, LINEAR);
+            gl.set_wrap_s(&mut gl_cube_map, CLAMP_TO_EDGE);
+            gl.set_wrap_t(&mut gl_cube_map, CLAMP_TO_EDGE);
+        }
+
+        let quad_bytes = unsafe {
+            slice::from_raw_parts(QUAD.as_ptr() as *const u8,
+                                  mem::size_of_val(&QUAD))
+        };
+        let mut quad = gl.gen_buffer();
+        let mut fbo = unsafe { gl.gen_framebuffer() };
+        let saved_viewport = _current_viewport();
+
+        let result = {
+            let (gl_tex_unit, gl) = gl.active_texture_0();
+            let sampler = gl_tex_unit.sampler();
+            let (_gl_equirect, _) = gl_tex_unit.bind_texture_2d(equirect);
+            let (mut gl_fbo, gl) = gl.bind_framebuffer(&mut fbo);
+            let (gl_program, gl) = gl.use_program(&mut program);
+            let (mut gl_quad, gl) = gl.bind_array_buffer(&mut quad);
+
+            gl.buffer_bytes(&mut gl_quad, quad_bytes, STATIC_DRAW);
+            unsafe {
+                gl.vertex_attrib_pointer(position,
+                                         2,
+                                         DataType::Float,
+                                         false,
+                                         0,
+                                         0);
+            }
+            gl.enable_vertex_attrib_array(position);
+            gl.set_uniform(&gl_program, equirect_uniform, sampler);
+            gl.viewport(Viewport::new(0, 0, size, size));
+
+            let mut result: Result<(), GLError> = Ok(());
+            for (&target, &(forward, right, up)) in FACES.iter()
+                                                         .zip(&FACE_BASES)
+            {
+                gl.framebuffer_texture_2d(&mut gl_fbo,
+                                          COLOR_ATTACHMENT0,
+                                          target,
+                                          &mut cube_map,
+                                          0);
+                if let Some(err) = gl.check_framebuffer_status(&gl_fbo) {
+                    result = Err(err.into());
+                    break;
+                }
+
+                gl.set_uniform(&gl_program, forward_uniform, forward);
+                gl.set_uniform(&gl_program, right_uniform, right);
+                gl.set_uniform(&gl_program, up_uniform, up);
+                unsafe {
+                    gl.draw_arrays_range(&gl_quad,
+                                         DrawingMode::TriangleStrip,
+                                         0,
+                                         4);
+                }
+            }
+            result
+        };
+        gl.viewport(saved_viewport);
+
+        result.map(|_| cube_map)
+    }
+}
@@ -0,0 +1,237 @@
+//! Contains the [`DebugDraw`](struct.DebugDraw.html) type, which draws
+//! lines, boxes, and spheres for debug visualizations.
+
+use std::f32;
+use std::mem;
+use context::{Context, ContextProgramExt, ContextProgramBuilderExt,
+              ProgramContext};
+use shader::ContextShaderBuilderExt;
+use program::{Program, ProgramUniform};
+use vertex_buffer::{VertexBuffer, AttribBinder, ContextVertexBufferExt,
+                    VertexBufferContext};
+use vertex_data::{VertexData, VertexDatum, VertexAttribute};
+use buffer::STREAM_DRAW;
+use types::{Color, GLError, LINES};
+
+// The number of line segments used for each circle of a sphere
+const CIRCLE_SEGMENTS: usize = 24;
+
+const DEBUG_VERTEX_SOURCE: &'static str = r##"#version 100
+    uniform mat4 view_projection;
+    attribute vec3 position;
+    attribute vec4 color;
+    varying vec4 _color;
+
+    void main() {
+        gl_Position = view_projection * vec4(position, 1.0);
+        _color = color;
+    }
+"##;
+
+const DEBUG_FRAGMENT_SOURCE: &'static str = r##"#version 100
+    precision mediump float;
+    varying vec4 _color;
+
+    void main() {
+        gl_FragColor = _color;
+    }
+"##;
+
+fn _message<E: ::std::fmt::Display>(e: E) -> GLError {
+    GLError::Message(e.to_string())
+}
+
+// A single vertex of a debug line
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct DebugVertex {
+    position: [f32; 3],
+    color: [f32; 4]
+}
+
+unsafe impl VertexData for DebugVertex {
+    fn visit_attributes<F>(mut f: F)
+        where F: FnMut(VertexAttribute)
+    {
+        let stride = mem::size_of::<DebugVertex>();
+        f(VertexAttribute {
+            ty: <[f32; 3] as VertexDatum>::attrib_type(),
+            name: "position".into(),
+            offset: 0,
+            stride: stride
+        });
+        f(VertexAttribute {
+            ty: <[f32; 4] as VertexDatum>::attrib_type(),
+            name: "color".into(),
+            offset: mem::size_of::<[f32; 3]>(),
+            stride: stride
+        });
+    }
+}
+
+/// An immediate-mode debug drawing helper, for visualizing things like
+/// physics shapes or AI paths.
+///
+/// Shapes can be added at any point during a frame, and are collected
+/// into a single list of lines. Calling [`flush`]
+/// (struct.DebugDraw.html#method.flush) uploads all of the lines to a
+/// streaming vertex buffer, draws them with a single draw call using an
+/// internal unlit shader, and clears the list for the next frame.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::{Color, DebugDraw};
+///
+/// # fn main() {
+/// # let view_projection = [[0.0; 4]; 4];
+/// let mut gl = unsafe { glitter::Context::current_context() };
+/// let mut debug_draw = DebugDraw::new(&mut gl).unwrap();
+///
+/// // Each frame:
+/// debug_draw.aabb([-1.0, -1.0, -1.0],
+///                 [1.0, 1.0, 1.0],
+///                 Color { r: 0.0, g: 1.0, b: 0.0, a: 1.0 });
+/// debug_draw.axes([0.0, 0.0, 0.0], 2.0);
+/// debug_draw.flush(&mut gl, view_projection);
+/// # }
+/// ```
+pub struct DebugDraw {
+    vertices: Vec<DebugVertex>,
+    vbo: VertexBuffer<DebugVertex>,
+    program: Program,
+    view_projection: ProgramUniform
+}
+
+impl DebugDraw {
+    /// Create a new debug drawing helper, compiling its shader.
+    ///
+    /// # Failures
+    /// An error will be returned if the internal shader fails to compile
+    /// or link.
+    pub fn new(gl: &mut Context) -> Result<Self, GLError> {
+        let vertex_shader = try! {
+            gl.build_vertex_shader(DEBUG_VERTEX_SOURCE).try_unwrap()
+        };
+        let fragment_shader = try! {
+            gl.build_fragment_shader(DEBUG_FRAGMENT_SOURCE).try_unwrap()
+        };
+        let program = try! {
+            gl.build_program(&[vertex_shader, fragment_shader]).try_unwrap()
+        };
+        let position = try! {
+            gl.get_attrib_location(&program, "position").map_err(_message)
+        };
+        let color = try! {
+            gl.get_attrib_location(&program, "color").map_err(_message)
+        };
+        let view_projection = try! {
+            gl.get_uniform_location(&program, "view_projection")
+              .map_err(_message)
+        };
+
+        let mut attribs = AttribBinder::new();
+        attribs.add("position", position).unwrap();
+        attribs.add("color", color).unwrap();
+        let mut vbo = gl.new_vertex_buffer();
+        vbo.bind_attrib_pointers(attribs);
+
+        Ok(DebugDraw {
+            vertices: vec![],
+            vbo: vbo,
+            program: program,
+            view_projection: view_projection
+        })
+    }
+
+    fn _push_line(&mut self, p0: [f32; 3], p1: [f32; 3], color: [f32; 4]) {
+        self.vertices.push(DebugVertex { position: p0, color: color });
+        self.vertices.push(DebugVertex { position: p1, color: color });
+    }
+
+    /// Add a line from `p0` to `p1`.
+    pub fn line(&mut self, p0: [f32; 3], p1: [f32; 3], color: Color) {
+        self._push_line(p0, p1, [color.r, color.g, color.b, color.a]);
+    }
+
+    /// Add the edges of an axis-aligned box, from the corner `min` to the
+    /// corner `max`.
+    pub fn aabb(&mut self, min: [f32; 3], max: [f32; 3], color: Color) {
+        let color = [color.r, color.g, color.b, color.a];
+        let corner = |i: usize| {
+            [
+                if i & 1 == 0 { min[0] } else { max[0] },
+                if i & 2 == 0 { min[1] } else { max[1] },
+                if i & 4 == 0 { min[2] } else { max[2] }
+            ]
+        };
+
+        // Each edge connects two corners that differ along one axis
+        for i in 0..8 {
+            for &axis in &[1, 2, 4] {
+                if i & axis == 0 {
+                    self._push_line(corner(i), corner(i | axis), color);
+                }
+            }
+        }
+    }
+
+    /// Add a wireframe sphere, drawn as a circle around each axis.
+    pub fn sphere(&mut self, center: [f32; 3], radius: f32, color: Color) {
+        let color = [color.r, color.g, color.b, color.a];
+        let point = |axis: usize, angle: f32| {
+            let (sin, cos) = angle.sin_cos();
+            let mut point = center;
+            point[(axis + 1) % 3] += cos * radius;
+            point[(axis + 2) % 3] += sin * radius;
+            point
+        };
+
+        let step = 2.0 * f32::consts::PI / CIRCLE_SEGMENTS as f32;
+        for axis in 0..3 {
+            for i in 0..CIRCLE_SEGMENTS {
+                self._push_line(point(axis, i as f32 * step),
+                                point(axis, (i + 1) as f32 * step),
+                                color);
+            }
+        }
+    }
+
+    /// Add a line of length `size` from `origin` along each axis, colored
+    /// red, green, and blue for the X, Y, and Z axes, respectively.
+    pub fn axes(&mut self, origin: [f32; 3], size: f32) {
+        for axis in 0..3 {
+            let mut end = origin;
+            end[axis] += size;
+
+            let mut color = [0.0, 0.0, 0.0, 1.0];
+            color[axis] = 1.0;
+            self._push_line(origin, end, color);
+        }
+    }
+
+    /// Get the number of lines that have been added since the last flush.
+    pub fn line_count(&self) -> usize {
+        self.vertices.len() / 2
+    }
+
+    /// Draw all of the lines that have been added since the last flush,
+    /// transformed by `view_projection`, then clear the list of lines.
+    /// The lines are drawn to the currently-bound framebuffer, using the
+    /// current depth and blend state.
+    pub fn flush(&mut self, gl: &mut Context, view_projection: [[f32; 4]; 4]) {
+        if self.vertices.is_empty() {
+            return;
+        }
+
+        {
+            let (mut gl_vbo, gl) = gl.bind_vertex_buffer(&mut self.vbo);
+            let (gl_program, gl) = gl.use_program(&mut self.program);
+            gl.buffer_vertices(&mut gl_vbo, &self.vertices, STREAM_DRAW);
+            gl.set_uniform(&gl_program, self.view_projection, view_projection);
+            gl.draw_arrays_vbo(&gl_vbo, LINES);
+        }
+        self.vertices.clear();
+    }
+}
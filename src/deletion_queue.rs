@@ -0,0 +1,226 @@
+// Per-thread queue of OpenGL objects that have been dropped. Dropping an
+// object may happen at any point (even while no context is current), so
+// instead of being deleted immediately, each object is queued on the OpenGL
+// context that created it. Since a context is current per-thread, the
+// context is tracked per-thread too: it outlives the `ContextOf` values
+// that use it, so acquiring it again keeps the same queue, until it is
+// forgotten with `ContextOf::forget_current_context`. Queued objects are
+// deleted by `ContextOf::cleanup`, at the end of each `Frame`, when the
+// context is acquired, when the last `ContextOf` on the thread is dropped,
+// and whenever the queue grows past `AUTO_FLUSH_LEN` while a context is
+// acquired. Each object records its `Owner` when it is created, so an
+// object that outlives its context is leaked instead of deleting whatever
+// object reuses its name in the context that replaced it.
+
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+use gl;
+use gl::types::*;
+use context_loss;
+
+// An OpenGL object that should be deleted, along with the kind of object
+// it is (since each kind has its own deletion function).
+#[derive(Debug, Clone, Copy)]
+pub enum Deleted {
+    Buffer(GLuint),
+    Framebuffer(GLuint),
+    Program(GLuint),
+    #[cfg(feature = "gl33")]
+    Query(GLuint),
+    Renderbuffer(GLuint),
+    Sampler(GLuint),
+    Shader(GLuint),
+    #[cfg(feature = "gles3")]
+    Sync(GLsync),
+    Texture(GLuint),
+    #[cfg(feature = "gles3")]
+    VertexArray(GLuint)
+}
+
+impl Deleted {
+    unsafe fn delete(&self) {
+        match *self {
+            Deleted::Buffer(id) => gl::DeleteBuffers(1, &id),
+            Deleted::Framebuffer(id) => gl::DeleteFramebuffers(1, &id),
+            Deleted::Program(id) => gl::DeleteProgram(id),
+            #[cfg(feature = "gl33")]
+            Deleted::Query(id) => gl::DeleteQueries(1, &id),
+            Deleted::Renderbuffer(id) => gl::DeleteRenderbuffers(1, &id),
+            Deleted::Sampler(id) => gl::DeleteSamplers(1, &id),
+            Deleted::Shader(id) => gl::DeleteShader(id),
+            #[cfg(feature = "gles3")]
+            Deleted::Sync(sync) => gl::DeleteSync(sync),
+            Deleted::Texture(id) => gl::DeleteTextures(1, &id),
+            #[cfg(feature = "gles3")]
+            Deleted::VertexArray(id) => gl::DeleteVertexArrays(1, &id)
+        }
+    }
+}
+
+// The id of the next context. Ids are unique across threads (and 0 is never
+// used), so that an object dropped on another thread is never deleted with
+// that thread's context.
+static NEXT_ID: AtomicUsize = ATOMIC_USIZE_INIT;
+
+// The number of queued objects at which the queue is flushed immediately,
+// if a context is acquired on the thread.
+const AUTO_FLUSH_LEN: usize = 256;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Rc<ThreadContext>>> = RefCell::new(None);
+}
+
+// Get the context on this thread. If no context has been acquired on this
+// thread (or it has been forgotten), a new one is only created if `create`
+// is `true`.
+fn _current(create: bool) -> Option<Rc<ThreadContext>> {
+    CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        if current.is_none() && create {
+            *current = Some(ThreadContext::new());
+        }
+        current.clone()
+    })
+}
+
+// Forget the context on this thread, so that objects created with it are
+// leaked instead of being deleted.
+pub fn forget_current() {
+    CURRENT.with(|current| *current.borrow_mut() = None);
+}
+
+// The OpenGL context on a thread, which holds the objects that are waiting
+// to be deleted with it.
+pub struct ThreadContext {
+    id: usize,
+    acquired: Cell<usize>,
+    queue: RefCell<Vec<(Owner, Deleted)>>,
+    _phantom: PhantomData<*mut ()>
+}
+
+impl ThreadContext {
+    fn new() -> Rc<Self> {
+        Rc::new(ThreadContext {
+            id: NEXT_ID.fetch_add(1, Ordering::SeqCst) + 1,
+            acquired: Cell::new(0),
+            queue: RefCell::new(vec![]),
+            _phantom: PhantomData
+        })
+    }
+
+    // Returns `true` if this is still the context on this thread, i.e. it
+    // hasn't been forgotten since it was created.
+    pub fn is_current(&self) -> bool {
+        _current(false).map_or(false, |context| context.id == self.id)
+    }
+
+    pub fn pending(&self) -> usize {
+        self.queue.borrow().len()
+    }
+
+    // Delete every queued object, returning the number of objects deleted.
+    // The queue is taken first, in case deleting an object drops another
+    // one. Objects whose context was lost or forgotten after they were
+    // queued are discarded, since they were either destroyed along with it
+    // or can't be deleted anymore (and their names may be reused by the
+    // context that replaced it).
+    pub unsafe fn flush(&self) -> usize {
+        let queued = self.queue.borrow_mut().split_off(0);
+        let mut deleted = 0;
+        for &(owner, ref object) in &queued {
+            if owner.is_alive() {
+                object.delete();
+                deleted += 1;
+            }
+        }
+        deleted
+    }
+}
+
+// The context that an object was created in, and the context loss
+// generation it was created in. An object created while no context has
+// been acquired on the thread has no owner, and is never deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Owner {
+    context: usize,
+    generation: u64
+}
+
+impl Owner {
+    pub fn current() -> Self {
+        Owner {
+            context: _current(false).map_or(0, |context| context.id),
+            generation: context_loss::generation()
+        }
+    }
+
+    // Returns `true` if the object can still be deleted, i.e. its context
+    // is still the context on this thread, and it hasn't been lost since
+    // the object was created.
+    pub fn is_alive(&self) -> bool {
+        !context_loss::is_lost() &&
+        self.generation == context_loss::generation() &&
+        _current(false).map_or(false, |context| context.id == self.context)
+    }
+}
+
+// Marks that a context (and every context derived from it) has been
+// acquired on this thread. The queue is flushed once the last acquired
+// context on the thread is dropped.
+pub struct ContextAlive {
+    context: Rc<ThreadContext>
+}
+
+impl ContextAlive {
+    // Create the marker for a newly-acquired context, which shares the
+    // thread's context with any other acquired context
+    pub fn new() -> Rc<Self> {
+        let context = _current(true).expect("Thread context wasn't created");
+        context.acquired.set(context.acquired.get() + 1);
+        Rc::new(ContextAlive { context: context })
+    }
+
+    pub fn context(&self) -> &Rc<ThreadContext> {
+        &self.context
+    }
+
+    pub fn pending(&self) -> usize {
+        self.context.pending()
+    }
+
+    pub unsafe fn flush(&self) -> usize {
+        self.context.flush()
+    }
+}
+
+impl Drop for ContextAlive {
+    fn drop(&mut self) {
+        let acquired = self.context.acquired.get() - 1;
+        self.context.acquired.set(acquired);
+        if acquired == 0 {
+            unsafe {
+                self.context.flush();
+            }
+        }
+    }
+}
+
+// Queue an object to be deleted by the context that owns it. The object is
+// leaked if its context has been forgotten, and discarded if the context
+// has been lost since the object was created. If a context is acquired and
+// the queue has grown too long, it is flushed straight away.
+pub fn delete(owner: Owner, object: Deleted) {
+    if !owner.is_alive() {
+        return;
+    }
+    if let Some(context) = _current(false) {
+        context.queue.borrow_mut().push((owner, object));
+        if context.acquired.get() > 0 && context.pending() >= AUTO_FLUSH_LEN {
+            unsafe {
+                context.flush();
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Contains the [`DepthPrepass`](struct.DepthPrepass.html) helper, which
+//! configures the OpenGL state for rendering with a depth pre-pass.
+
+use std::ops::{Deref, DerefMut};
+use gl;
+use gl::types::*;
+use context::AContext;
+use types::{Capability, DepthFunction};
+
+// The depth and color state that is changed by a depth pre-pass, so that
+// it can be restored afterwards.
+struct SavedState {
+    depth_test: bool,
+    depth_func: DepthFunction,
+    depth_mask: bool,
+    color_mask: [bool; 4]
+}
+
+impl SavedState {
+    fn current() -> Self {
+        unsafe {
+            let mut depth_func : GLint = 0;
+            let mut depth_mask : GLboolean = gl::TRUE;
+            let mut color_mask : [GLboolean; 4] = [gl::TRUE; 4];
+            gl::GetIntegerv(gl::DEPTH_FUNC, &mut depth_func);
+            gl::GetBooleanv(gl::DEPTH_WRITEMASK, &mut depth_mask);
+            gl::GetBooleanv(gl::COLOR_WRITEMASK, color_mask.as_mut_ptr());
+
+            SavedState {
+                depth_test: gl::IsEnabled(gl::DEPTH_TEST) == gl::TRUE,
+                depth_func: DepthFunction::from_gl(depth_func as GLenum)
+                    .unwrap_or(DepthFunction::Less),
+                depth_mask: depth_mask == gl::TRUE,
+                color_mask: [color_mask[0] == gl::TRUE,
+                             color_mask[1] == gl::TRUE,
+                             color_mask[2] == gl::TRUE,
+                             color_mask[3] == gl::TRUE]
+            }
+        }
+    }
+
+    fn restore<C: AContext>(&self, gl: &mut C) {
+        if self.depth_test {
+            gl.enable(Capability::DepthTest);
+        }
+        else {
+            gl.disable(Capability::DepthTest);
+        }
+        gl.depth_func(self.depth_func);
+        gl.depth_mask(self.depth_mask);
+        gl.color_mask(self.color_mask[0],
+                      self.color_mask[1],
+                      self.color_mask[2],
+                      self.color_mask[3]);
+    }
+}
+
+/// A helper for rendering with a depth pre-pass, which is a common
+/// optimization for scenes with a lot of overdraw.
+///
+/// With a depth pre-pass, the scene's geometry is drawn twice. The first pass
+/// (the "depth pass") only writes to the depth buffer, with color writes
+/// disabled. The second pass (the "color pass") draws the same geometry with
+/// full shading, but with depth writes disabled and a depth test of
+/// `GL_EQUAL` (by default), so that the (potentially expensive) fragment
+/// shader only runs once for each visible pixel.
+///
+/// Each pass returns a guard, which configures the OpenGL state when
+/// created, and restores the previous state when dropped. The guard
+/// dereferences to the context, so it can be used for drawing.
+///
+/// # Note
+/// The geometry drawn in both passes must produce exactly the same depth
+/// values (e.g. by using the same vertex shader computations), or the
+/// `GL_EQUAL` depth test may fail. If this is difficult to guarantee, use
+/// [`DepthPrepass::with_color_pass_func`]
+/// (struct.DepthPrepass.html#method.with_color_pass_func)
+/// with `glitter::LEQUAL`.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::DepthPrepass;
+///
+/// # fn main() {
+/// let mut gl = unsafe { glitter::Context::current_context() };
+/// let prepass = DepthPrepass::new();
+///
+/// gl.clear(glitter::COLOR_BUFFER_BIT | glitter::DEPTH_BUFFER_BIT);
+/// {
+///     let gl = prepass.depth_pass(&mut gl);
+///     // Draw the scene's geometry (with a cheap program)
+/// }
+/// {
+///     let gl = prepass.color_pass(&mut gl);
+///     // Draw the scene's geometry again (with full shading)
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DepthPrepass {
+    depth_pass_func: DepthFunction,
+    color_pass_func: DepthFunction
+}
+
+impl DepthPrepass {
+    /// Create a new depth pre-pass helper, which uses a depth test of
+    /// `GL_LESS` in the depth pass and `GL_EQUAL` in the color pass.
+    pub fn new() -> Self {
+        DepthPrepass {
+            depth_pass_func: DepthFunction::Less,
+            color_pass_func: DepthFunction::Equal
+        }
+    }
+
+    /// Use a different depth test for the depth pass.
+    pub fn with_depth_pass_func(self, func: DepthFunction) -> Self {
+        DepthPrepass { depth_pass_func: func, ..self }
+    }
+
+    /// Use a different depth test for the color pass.
+    pub fn with_color_pass_func(self, func: DepthFunction) -> Self {
+        DepthPrepass { color_pass_func: func, ..self }
+    }
+
+    /// Configure the context for the depth pass: depth testing and depth
+    /// writes are enabled, and color writes are disabled. The previous
+    /// state is restored when the returned guard is dropped.
+    pub fn depth_pass<'a, C: AContext>(&self, gl: &'a mut C)
+        -> DepthPrepassGuard<'a, C>
+    {
+        let saved = SavedState::current();
+        gl.enable(Capability::DepthTest);
+        gl.depth_func(self.depth_pass_func);
+        gl.depth_mask(true);
+        gl.color_mask(false, false, false, false);
+
+        DepthPrepassGuard { gl: gl, saved: saved }
+    }
+
+    /// Configure the context for the color pass: depth testing and color
+    /// writes are enabled, and depth writes are disabled. The previous
+    /// state is restored when the returned guard is dropped.
+    pub fn color_pass<'a, C: AContext>(&self, gl: &'a mut C)
+        -> DepthPrepassGuard<'a, C>
+    {
+        let saved = SavedState::current();
+        gl.enable(Capability::DepthTest);
+        gl.depth_func(self.color_pass_func);
+        gl.depth_mask(false);
+        gl.color_mask(true, true, true, true);
+
+        DepthPrepassGuard { gl: gl, saved: saved }
+    }
+}
+
+/// A guard for one of the passes of a [`DepthPrepass`]
+/// (struct.DepthPrepass.html). The depth and color state is restored when
+/// the guard is dropped. The guard dereferences to the context, so it can
+/// be used to make OpenGL calls.
+pub struct DepthPrepassGuard<'a, C: AContext + 'a> {
+    gl: &'a mut C,
+    saved: SavedState
+}
+
+impl<'a, C: AContext + 'a> Deref for DepthPrepassGuard<'a, C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        self.gl
+    }
+}
+
+impl<'a, C: AContext + 'a> DerefMut for DepthPrepassGuard<'a, C> {
+    fn deref_mut(&mut self) -> &mut C {
+        self.gl
+    }
+}
+
+impl<'a, C: AContext + 'a> Drop for DepthPrepassGuard<'a, C> {
+    fn drop(&mut self) {
+        self.saved.restore(self.gl);
+    }
+}
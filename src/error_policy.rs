@@ -0,0 +1,77 @@
+// Thread-local storage for the active `ErrorPolicy`, and for any error that
+// was recorded while the `Result` policy was in effect.
+
+use std::cell::{Cell, RefCell};
+use gl;
+use context_loss;
+use types::{ErrorPolicy, GLError};
+
+// The most pending errors that `discard_errors` will discard. Each error
+// flag is only reported once, but a lost context may keep reporting
+// `GL_CONTEXT_LOST`, so draining errors can't loop until there are none.
+const MAX_PENDING_ERRORS: usize = 16;
+
+thread_local! {
+    static POLICY: Cell<ErrorPolicy> = Cell::new(ErrorPolicy::Panic);
+    static RECORDED: RefCell<Option<GLError>> = RefCell::new(None);
+}
+
+pub fn policy() -> ErrorPolicy {
+    POLICY.with(|policy| policy.get())
+}
+
+pub fn replace_policy(new_policy: ErrorPolicy) -> ErrorPolicy {
+    POLICY.with(|policy| {
+        let old_policy = policy.get();
+        policy.set(new_policy);
+        old_policy
+    })
+}
+
+// Only the first error is kept, since later errors are usually
+// a consequence of the first.
+pub fn record(err: GLError) {
+    RECORDED.with(|recorded| {
+        let mut recorded = recorded.borrow_mut();
+        if recorded.is_none() {
+            *recorded = Some(err);
+        }
+    });
+}
+
+pub fn take() -> Option<GLError> {
+    RECORDED.with(|recorded| recorded.borrow_mut().take())
+}
+
+// Discard the OpenGL errors that are pending, stopping early if the context
+// has been lost (which is remembered instead).
+pub fn discard_errors() {
+    for _ in 0..MAX_PENDING_ERRORS {
+        match GLError::from_gl_enum(unsafe { gl::GetError() }) {
+            None => { break; },
+            Some(GLError::ContextLost) => {
+                context_loss::mark_lost();
+                break;
+            },
+            Some(_) => { }
+        }
+    }
+}
+
+// Restores the previous policy when dropped, so that a panic within
+// `ContextOf::checked` doesn't leave the `Result` policy active.
+pub struct PolicyGuard {
+    previous: ErrorPolicy
+}
+
+impl PolicyGuard {
+    pub fn new(policy: ErrorPolicy) -> Self {
+        PolicyGuard { previous: replace_policy(policy) }
+    }
+}
+
+impl Drop for PolicyGuard {
+    fn drop(&mut self) {
+        replace_policy(self.previous);
+    }
+}
@@ -0,0 +1,231 @@
+//! Contains the [`Fence`](struct.Fence.html) type, which is used to find
+//! out when the GPU has finished a set of commands.
+
+use std::mem;
+use std::ptr;
+use std::time::Duration;
+use gl;
+use gl::types::*;
+use context::BaseContext;
+use types::GLError;
+use deletion_queue::{self, Deleted, Owner};
+
+/// An OpenGL fence sync object, which is signaled once the GPU has
+/// finished all of the commands that were issued before the fence was
+/// created.
+///
+/// Fences are used to avoid overwriting data that the GPU is still reading
+/// (such as a streaming buffer), or to find out when the results of an
+/// asynchronous operation (such as a readback) are ready.
+///
+/// A fence will automatically be deleted after going out of scope.
+///
+/// # Note
+/// Fences require OpenGL ES 3.0 or OpenGL 3.2, so this type is only
+/// available with the `gles3` feature.
+///
+/// # See also
+/// [`gl.fence`](trait.ContextFenceExt.html#method.fence): Create a
+/// new fence.
+pub struct Fence {
+    sync: GLsync,
+    owner: Owner
+}
+
+impl Fence {
+    /// Get the raw OpenGL sync object of the fence.
+    pub fn sync(&self) -> GLsync {
+        self.sync
+    }
+
+    /// Consume the fence without deleting it, returning the raw OpenGL
+    /// sync object.
+    pub fn into_sync(self) -> GLsync {
+        let sync = self.sync;
+        mem::forget(self);
+        sync
+    }
+
+    /// Create a fence from a raw OpenGL sync object.
+    ///
+    /// # Safety
+    /// `sync` must be a sync object that isn't owned by any other `Fence`.
+    pub unsafe fn from_sync(sync: GLsync) -> Self {
+        Fence { sync: sync, owner: Owner::current() }
+    }
+}
+
+impl Drop for Fence {
+    fn drop(&mut self) {
+        deletion_queue::delete(self.owner, Deleted::Sync(self.sync));
+    }
+}
+
+gl_enum! {
+    /// The outcome of waiting for a fence with [`gl.wait_client`]
+    /// (trait.ContextFenceExt.html#method.wait_client).
+    pub gl_enum FenceStatus {
+        /// The fence was already signaled when the wait began.
+        pub const AlreadySignaled as ALREADY_SIGNALED = gl::ALREADY_SIGNALED,
+
+        /// The fence was signaled while waiting.
+        pub const ConditionSatisfied as CONDITION_SATISFIED =
+            gl::CONDITION_SATISFIED,
+
+        /// The timeout expired before the fence was signaled.
+        pub const TimeoutExpired as TIMEOUT_EXPIRED = gl::TIMEOUT_EXPIRED,
+
+        /// An error occurred while waiting.
+        pub const WaitFailed as WAIT_FAILED = gl::WAIT_FAILED
+    }
+}
+
+impl FenceStatus {
+    /// Returns `true` if the fence has been signaled.
+    pub fn is_signaled(&self) -> bool {
+        match *self {
+            FenceStatus::AlreadySignaled |
+            FenceStatus::ConditionSatisfied => true,
+            FenceStatus::TimeoutExpired |
+            FenceStatus::WaitFailed => false
+        }
+    }
+}
+
+fn _duration_to_nanoseconds(duration: Duration) -> GLuint64 {
+    let nanoseconds = duration.as_secs()
+        .saturating_mul(1_000_000_000)
+        .saturating_add(duration.subsec_nanos() as u64);
+    nanoseconds as GLuint64
+}
+
+/// An extension trait that includes fence-related OpenGL methods.
+///
+/// # Note
+/// Fences require OpenGL ES 3.0 or OpenGL 3.2, so this trait is only
+/// available with the `gles3` feature.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use std::time::Duration;
+/// use glitter::prelude::*;
+/// use glitter::ContextFenceExt;
+///
+/// # fn main() {
+/// let gl = unsafe { glitter::Context::current_context() };
+/// // Issue some commands...
+/// let fence = gl.fence();
+///
+/// // Later:
+/// if gl.is_fence_signaled(&fence) {
+///     // The commands have finished
+/// }
+///
+/// // Or block for up to 10 milliseconds:
+/// let status = gl.wait_client(&fence, Duration::from_millis(10));
+/// if status.is_signaled() {
+///     // The commands have finished
+/// }
+/// # }
+/// ```
+pub trait ContextFenceExt: BaseContext {
+    /// Create a new fence, which is signaled once all of the commands that
+    /// have been issued so far are finished.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glFenceSync`](http://docs.gl/es3/glFenceSync) OpenGL docs
+    fn fence(&self) -> Fence {
+        let sync = unsafe {
+            gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0)
+        };
+        dbg_gl_sanity_check! {
+            call "glFenceSync";
+            args (gl::SYNC_GPU_COMMANDS_COMPLETE, 0);
+            GLError::InvalidEnum => "`condition` is not an allowed value",
+            GLError::InvalidValue => "`flags` is not 0",
+            _ => "Unknown error"
+        }
+        Fence { sync: sync, owner: Owner::current() }
+    }
+
+    /// Block the CPU until a fence is signaled, or until `timeout` has
+    /// passed. Commands are flushed before waiting, so the fence will
+    /// eventually be signaled.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glClientWaitSync`](http://docs.gl/es3/glClientWaitSync) OpenGL docs
+    fn wait_client(&self, fence: &Fence, timeout: Duration) -> FenceStatus {
+        let timeout = _duration_to_nanoseconds(timeout);
+        let status = unsafe {
+            gl::ClientWaitSync(fence.sync, gl::SYNC_FLUSH_COMMANDS_BIT, timeout)
+        };
+        dbg_gl_error! {
+            call "glClientWaitSync";
+            args (fence.sync, gl::SYNC_FLUSH_COMMANDS_BIT, timeout);
+            GLError::InvalidValue => "`sync` is not a sync object, or `flags` is invalid",
+            _ => "Unknown error"
+        }
+        FenceStatus::from_gl(status).unwrap_or(FenceStatus::WaitFailed)
+    }
+
+    /// Make the GPU wait until a fence is signaled before executing any
+    /// commands issued after this call. This doesn't block the CPU, and is
+    /// useful for synchronizing between contexts that share objects.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glWaitSync`](http://docs.gl/es3/glWaitSync) OpenGL docs
+    fn wait_server(&self, fence: &Fence) {
+        unsafe {
+            gl::WaitSync(fence.sync, 0, gl::TIMEOUT_IGNORED);
+            dbg_gl_error! {
+                call "glWaitSync";
+                args (fence.sync, 0, gl::TIMEOUT_IGNORED);
+                GLError::InvalidValue => "`sync` is not a sync object",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Returns `true` if a fence has been signaled, without waiting.
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glGetSynciv`](http://docs.gl/es3/glGetSynciv) OpenGL docs
+    fn is_fence_signaled(&self, fence: &Fence) -> bool {
+        let mut status: GLint = 0;
+        unsafe {
+            gl::GetSynciv(fence.sync,
+                          gl::SYNC_STATUS,
+                          1,
+                          ptr::null_mut(),
+                          &mut status);
+            dbg_gl_error! {
+                call "glGetSynciv";
+                args (fence.sync, gl::SYNC_STATUS);
+                GLError::InvalidValue => "`sync` is not a sync object",
+                _ => "Unknown error"
+            }
+        }
+        status as GLenum == gl::SIGNALED
+    }
+}
+
+impl<C: BaseContext> ContextFenceExt for C {
+
+}
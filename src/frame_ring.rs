@@ -0,0 +1,79 @@
+// The ring of per-frame regions shared by `StreamBuffer` and
+// `PersistentMap`. Each region is guarded by a fence, which is placed at the
+// end of the frame that wrote to it, and waited on before the region is
+// written to again.
+
+use std::cmp;
+use std::time::Duration;
+use context::BaseContext;
+use fence::{Fence, FenceStatus, ContextFenceExt};
+
+pub struct FrameRing {
+    frame_size: usize,
+    current: usize,
+    used: usize,
+    fences: Vec<Option<Fence>>
+}
+
+impl FrameRing {
+    // Create a ring of `frames` regions of `frame_size` bytes. The current
+    // region starts out full, so nothing can be allocated until the first
+    // frame begins.
+    pub fn new(frame_size: usize, frames: usize) -> Self {
+        FrameRing {
+            frame_size: frame_size,
+            current: frames - 1,
+            used: frame_size,
+            fences: (0..frames).map(|_| None).collect()
+        }
+    }
+
+    pub fn frames(&self) -> usize {
+        self.fences.len()
+    }
+
+    pub fn frame_size(&self) -> usize {
+        self.frame_size
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.frame_size - self.used
+    }
+
+    // Advance to the next region, waiting for its fence if necessary
+    pub fn begin_frame<C: BaseContext>(&mut self, gl: &C) {
+        self.current = (self.current + 1) % self.fences.len();
+        self.used = 0;
+
+        if let Some(fence) = self.fences[self.current].take() {
+            let timeout = Duration::from_secs(1);
+            loop {
+                match gl.wait_client(&fence, timeout) {
+                    FenceStatus::TimeoutExpired => { },
+                    _ => { break; }
+                }
+            }
+        }
+    }
+
+    pub fn end_frame<C: BaseContext>(&mut self, gl: &C) {
+        self.fences[self.current] = Some(gl.fence());
+    }
+
+    // Allocate `size` bytes from the current region, at an offset (within
+    // the whole ring) that is a multiple of `alignment`. Returns `None` if
+    // there isn't enough room left in the current region.
+    pub fn allocate(&mut self, size: usize, alignment: usize)
+        -> Option<usize>
+    {
+        let start = self.current * self.frame_size;
+        let alignment = cmp::max(alignment, 1);
+        let offset = (start + self.used + alignment - 1) / alignment * alignment;
+        if offset + size > start + self.frame_size {
+            return None;
+        }
+
+        self.used = offset + size - start;
+        Some(offset)
+    }
+}
@@ -4,7 +4,8 @@
 use std::marker::PhantomData;
 use gl;
 use gl::types::*;
-use types::GLObject;
+use types::{GLObject, Labeled};
+use deletion_queue::{self, Deleted, Owner};
 
 /// An OpenGL framebuffer object.
 ///
@@ -32,14 +33,13 @@ use types::GLObject;
 /// (../context/framebuffer_context/struct.FramebufferBinding.html) type.
 pub struct Framebuffer {
     gl_id: GLuint,
+    owner: Owner,
     _phantom: PhantomData<*mut ()>
 }
 
 impl Drop for Framebuffer {
     fn drop(&mut self) {
-        unsafe {
-            gl::DeleteFramebuffers(1, &self.gl_id as *const GLuint);
-        }
+        deletion_queue::delete(self.owner, Deleted::Framebuffer(self.gl_id));
     }
 }
 
@@ -49,6 +49,7 @@ impl GLObject for Framebuffer {
     unsafe fn from_raw(id: Self::Id) -> Self {
         Framebuffer {
             gl_id: id,
+            owner: Owner::current(),
             _phantom: PhantomData
         }
     }
@@ -57,3 +58,9 @@ impl GLObject for Framebuffer {
         self.gl_id
     }
 }
+
+impl Labeled for Framebuffer {
+    fn label_identifier() -> GLenum {
+        gl::FRAMEBUFFER
+    }
+}
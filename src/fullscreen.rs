@@ -0,0 +1,203 @@
+//! Contains helpers for drawing a single triangle that covers the whole
+//! viewport, which is the basis of most post-processing and blit passes.
+
+use std::mem;
+use context::Context;
+use mesh::GpuMesh;
+use vertex_buffer::AttribBinder;
+use vertex_data::{VertexData, VertexDatum, VertexAttribute};
+use types::TRIANGLES;
+
+#[cfg(feature = "gles3")] use std::cell::Cell;
+#[cfg(feature = "gles3")] use gl;
+#[cfg(feature = "gles3")] use gl::types::*;
+#[cfg(feature = "gles3")] use context::{AContext, ProgramBinding};
+#[cfg(feature = "gles3")] use mesh::gpu::{_gen_vertex_array, _bind_vertex_array};
+#[cfg(feature = "gles3")] use types::GLError;
+
+// The empty vertex array object that `draw_fullscreen` binds, which is
+// created the first time it's needed and then reused (0 if it hasn't been
+// created yet). It belongs to the context that was current when it was
+// created, so it's forgotten (without being deleted) whenever the current
+// context changes or is restored.
+#[cfg(feature = "gles3")]
+thread_local! {
+    static FULLSCREEN_VAO: Cell<GLuint> = Cell::new(0);
+}
+
+/// A vertex of the [`FULLSCREEN_TRIANGLE`](constant.FULLSCREEN_TRIANGLE.html),
+/// with a single `position` attribute in clip space.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct FullscreenVertex {
+    /// The position of the vertex, in clip space.
+    pub position: [f32; 2]
+}
+
+unsafe impl VertexData for FullscreenVertex {
+    fn visit_attributes<F>(mut f: F)
+        where F: FnMut(VertexAttribute)
+    {
+        f(VertexAttribute {
+            ty: <[f32; 2] as VertexDatum>::attrib_type(),
+            name: "position".into(),
+            offset: 0,
+            stride: mem::size_of::<FullscreenVertex>()
+        });
+    }
+}
+
+/// A triangle that covers the whole of clip space, from `(-1, -1)` to
+/// `(1, 1)`. A single triangle is used instead of a quad, so that no
+/// fragments along the quad's diagonal are shaded twice.
+pub const FULLSCREEN_TRIANGLE: [FullscreenVertex; 3] = [
+    FullscreenVertex { position: [-1.0, -1.0] },
+    FullscreenVertex { position: [ 3.0, -1.0] },
+    FullscreenVertex { position: [-1.0,  3.0] }
+];
+
+/// A GLSL ES 1.00 vertex shader for drawing the [`FULLSCREEN_TRIANGLE`]
+/// (constant.FULLSCREEN_TRIANGLE.html). The shader reads the `position`
+/// attribute, and writes a texture coordinate covering the viewport from
+/// `(0, 0)` to `(1, 1)` to the `v_tex_coord` varying.
+pub const FULLSCREEN_VERTEX_SOURCE: &'static str = r##"#version 100
+    attribute vec2 position;
+    varying vec2 v_tex_coord;
+
+    void main() {
+        v_tex_coord = position * 0.5 + 0.5;
+        gl_Position = vec4(position, 0.0, 1.0);
+    }
+"##;
+
+/// A GLSL ES 3.00 vertex shader for use with [`gl.draw_fullscreen`]
+/// (trait.ContextFullscreenExt.html#method.draw_fullscreen). The shader
+/// computes the fullscreen triangle's positions from `gl_VertexID`, so no
+/// vertex attributes are needed, and writes a texture coordinate covering
+/// the viewport from `(0, 0)` to `(1, 1)` to the `v_tex_coord` output.
+///
+/// # Note
+/// This shader requires OpenGL ES 3.0, so it is only available with the
+/// `gles3` feature.
+#[cfg(feature = "gles3")]
+pub const FULLSCREEN_VERTEX_ID_SOURCE: &'static str = r##"#version 300 es
+    out vec2 v_tex_coord;
+
+    void main() {
+        vec2 position = vec2(float((gl_VertexID & 1) << 2) - 1.0,
+                             float((gl_VertexID & 2) << 1) - 1.0);
+        v_tex_coord = position * 0.5 + 0.5;
+        gl_Position = vec4(position, 0.0, 1.0);
+    }
+"##;
+
+impl GpuMesh<FullscreenVertex> {
+    /// Upload the [`FULLSCREEN_TRIANGLE`](constant.FULLSCREEN_TRIANGLE.html)
+    /// to a new mesh. `attribs` should map the `position` attribute to a
+    /// program attribute, such as the one used by
+    /// [`FULLSCREEN_VERTEX_SOURCE`](constant.FULLSCREEN_VERTEX_SOURCE.html).
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::GpuMesh;
+    ///
+    /// # fn main() {
+    /// # let mut blit_program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let attribs = attrib_pointers! {
+    ///     position => gl.get_attrib_location(&blit_program, "position").unwrap()
+    /// };
+    /// let mut fullscreen = GpuMesh::fullscreen_triangle(&mut gl, attribs);
+    ///
+    /// let (gl_program, gl) = gl.use_program(&mut blit_program);
+    /// fullscreen.draw(gl, &gl_program);
+    /// # }
+    /// ```
+    pub fn fullscreen_triangle(gl: &mut Context, attribs: AttribBinder)
+        -> Self
+    {
+        GpuMesh::new(gl, TRIANGLES, &FULLSCREEN_TRIANGLE, attribs)
+    }
+}
+
+/// An extension trait that adds a method for drawing a fullscreen triangle
+/// without any vertex data.
+///
+/// # Note
+/// `gl_VertexID` requires OpenGL ES 3.0, so this trait is only available
+/// with the `gles3` feature. Use [`GpuMesh::fullscreen_triangle`]
+/// (../mesh/gpu/struct.GpuMesh.html#method.fullscreen_triangle) otherwise.
+#[cfg(feature = "gles3")]
+pub trait ContextFullscreenExt: AContext {
+    /// Draw a triangle that covers the whole viewport, using the program
+    /// that is currently in use. The program's vertex shader should
+    /// compute each vertex's position from `gl_VertexID` (see
+    /// [`FULLSCREEN_VERTEX_ID_SOURCE`]
+    /// (constant.FULLSCREEN_VERTEX_ID_SOURCE.html)), since no vertex
+    /// attributes are enabled.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::ContextFullscreenExt;
+    ///
+    /// # fn main() {
+    /// # let mut tonemap_program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let (gl_program, gl) = gl.use_program(&mut tonemap_program);
+    /// gl.draw_fullscreen(&gl_program);
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glDrawArrays`](http://docs.gl/es3/glDrawArrays) OpenGL docs
+    fn draw_fullscreen(&self, _gl_program: &ProgramBinding) {
+        // Core profiles can't draw without a vertex array object, so an
+        // empty one is bound for the draw call
+        _bind_vertex_array(_fullscreen_vertex_array());
+        unsafe {
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+            dbg_gl_error! {
+                call "glDrawArrays";
+                args (gl::TRIANGLES, 0, 3);
+                GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+                _ => "Unknown error"
+            }
+        }
+        _bind_vertex_array(0);
+    }
+}
+
+#[cfg(feature = "gles3")]
+impl<C: AContext> ContextFullscreenExt for C {
+
+}
+
+// Get the vertex array object used by `draw_fullscreen`, creating it if
+// it hasn't been created for the current context yet
+#[cfg(feature = "gles3")]
+fn _fullscreen_vertex_array() -> GLuint {
+    FULLSCREEN_VAO.with(|cached| {
+        if cached.get() == 0 {
+            cached.set(_gen_vertex_array());
+        }
+        cached.get()
+    })
+}
+
+// Forget the vertex array object used by `draw_fullscreen`, so that a new
+// one is created for the next context
+#[cfg(feature = "gles3")]
+#[doc(hidden)]
+pub fn _forget_fullscreen_vertex_array() {
+    FULLSCREEN_VAO.with(|cached| cached.set(0));
+}
+
+#[cfg(not(feature = "gles3"))]
+#[doc(hidden)]
+pub fn _forget_fullscreen_vertex_array() {
+
+}
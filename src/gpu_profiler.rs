@@ -0,0 +1,258 @@
+//! Contains the [`GpuProfiler`](struct.GpuProfiler.html) type, which
+//! measures the GPU time of named scopes within each frame.
+
+use std::time::Duration;
+use context::{BaseContext, ContextQueryExt, FrameHook,
+              nanoseconds_to_duration};
+use query::Query;
+
+/// The GPU time spent on a scope, as measured by a [`GpuProfiler`]
+/// (struct.GpuProfiler.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeTime {
+    /// The name of the scope.
+    pub name: String,
+
+    /// How many scopes the scope was nested within (`0` for scopes that
+    /// were not nested).
+    pub depth: usize,
+
+    /// The GPU time between the start and the end of the scope.
+    pub time: Duration
+}
+
+struct PendingScope {
+    name: String,
+    depth: usize,
+    begin: Query,
+    end: Option<Query>
+}
+
+struct PendingFrame {
+    index: u64,
+    scopes: Vec<PendingScope>
+}
+
+/// Measures the GPU time of named scopes (such as render passes), without
+/// stalling the CPU.
+///
+/// Each scope is measured with a pair of timestamp queries, so scopes can
+/// be nested. The queries of each frame are kept for `frames` frames
+/// before their results are read, so by the time the results are read the
+/// GPU has almost always finished the frame. If the results of a frame
+/// still aren't available, that frame's results are skipped rather than
+/// waiting for the GPU. The queries are reused once their results
+/// are read.
+///
+/// # Note
+/// Timer queries require OpenGL 3.3, so this type is only available with
+/// the `gl33` feature.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::GpuProfiler;
+///
+/// # fn main() {
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let mut profiler = GpuProfiler::new();
+///
+/// loop {
+///     profiler.begin_frame(&gl);
+///
+///     profiler.begin_scope(&gl, "shadows");
+///     // Draw the shadow maps...
+///     profiler.end_scope(&gl);
+///
+///     profiler.begin_scope(&gl, "lighting");
+///     // Draw the scene...
+///     profiler.end_scope(&gl);
+///
+///     profiler.end_frame(&gl);
+///
+///     for scope in profiler.results() {
+///         println!("{}: {:?}", scope.name, scope.time);
+///     }
+/// #   break;
+/// }
+/// # }
+/// ```
+pub struct GpuProfiler {
+    frames: Vec<Option<PendingFrame>>,
+    current: usize,
+    next_index: u64,
+    in_frame: bool,
+    open: Vec<usize>,
+    free: Vec<Query>,
+    results: Vec<ScopeTime>,
+    results_frame: Option<u64>
+}
+
+impl GpuProfiler {
+    /// Create a new profiler, which reads the results of each frame two
+    /// frames later.
+    pub fn new() -> Self {
+        GpuProfiler::with_frames(2)
+    }
+
+    /// Create a new profiler, which reads the results of each frame
+    /// `frames` frames later. More frames make it less likely that results
+    /// are skipped, at the cost of more latency.
+    ///
+    /// # Panics
+    /// This function will panic if `frames` is 0.
+    pub fn with_frames(frames: usize) -> Self {
+        assert!(frames > 0, "A `GpuProfiler` needs at least one frame");
+
+        GpuProfiler {
+            frames: (0..frames).map(|_| None).collect(),
+            current: frames - 1,
+            next_index: 0,
+            in_frame: false,
+            open: vec![],
+            free: vec![],
+            results: vec![],
+            results_frame: None
+        }
+    }
+
+    /// Begin measuring a new frame. This reads the results of the frame
+    /// that was measured `frames` frames ago, if they are available.
+    ///
+    /// # Panics
+    /// This function will panic if the previous frame wasn't ended with
+    /// [`end_frame`](struct.GpuProfiler.html#method.end_frame).
+    pub fn begin_frame<C: BaseContext>(&mut self, gl: &C) {
+        assert!(!self.in_frame, "`GpuProfiler::begin_frame` was called twice without `end_frame`");
+
+        self.current = (self.current + 1) % self.frames.len();
+        let frame = self.frames[self.current].take();
+        if let Some(frame) = frame {
+            self._collect(gl, frame);
+        }
+
+        self.frames[self.current] = Some(PendingFrame {
+            index: self.next_index,
+            scopes: vec![]
+        });
+        self.next_index += 1;
+        self.in_frame = true;
+    }
+
+    fn _collect<C: BaseContext>(&mut self, gl: &C, frame: PendingFrame) {
+        let available = frame.scopes.iter().all(|scope| {
+            match scope.end {
+                Some(ref end) => gl.is_query_result_available(end),
+                None => false
+            }
+        });
+
+        if available {
+            self.results = frame.scopes.iter().map(|scope| {
+                let begin = gl.get_query_result(&scope.begin);
+                let end = match scope.end {
+                    Some(ref end) => gl.get_query_result(end),
+                    None => begin
+                };
+                ScopeTime {
+                    name: scope.name.clone(),
+                    depth: scope.depth,
+                    time: nanoseconds_to_duration(end.saturating_sub(begin))
+                }
+            }).collect();
+            self.results_frame = Some(frame.index);
+        }
+
+        for scope in frame.scopes {
+            self.free.push(scope.begin);
+            if let Some(end) = scope.end {
+                self.free.push(end);
+            }
+        }
+    }
+
+    fn _timestamp<C: BaseContext>(&mut self, gl: &C) -> Query {
+        let mut query = match self.free.pop() {
+            Some(query) => query,
+            None => gl.gen_query()
+        };
+        gl.query_timestamp(&mut query);
+        query
+    }
+
+    /// Begin measuring a named scope. Scopes can be nested, and must be
+    /// ended in the reverse order that they were begun.
+    ///
+    /// # Panics
+    /// This function will panic if no frame has been begun.
+    pub fn begin_scope<C: BaseContext>(&mut self, gl: &C, name: &str) {
+        assert!(self.in_frame, "`GpuProfiler::begin_scope` was called outside of a frame");
+
+        let begin = self._timestamp(gl);
+        let depth = self.open.len();
+        let frame = self.frames[self.current].as_mut()
+            .expect("Current frame is missing");
+        frame.scopes.push(PendingScope {
+            name: name.to_owned(),
+            depth: depth,
+            begin: begin,
+            end: None
+        });
+        self.open.push(frame.scopes.len() - 1);
+    }
+
+    /// End measuring the most recently begun scope.
+    ///
+    /// # Panics
+    /// This function will panic if there is no scope to end.
+    pub fn end_scope<C: BaseContext>(&mut self, gl: &C) {
+        let scope = self.open.pop()
+            .expect("`GpuProfiler::end_scope` was called without a matching `begin_scope`");
+
+        let end = self._timestamp(gl);
+        let frame = self.frames[self.current].as_mut()
+            .expect("Current frame is missing");
+        frame.scopes[scope].end = Some(end);
+    }
+
+    /// Finish measuring the current frame.
+    ///
+    /// # Panics
+    /// This function will panic if a scope is still open, or if no frame
+    /// has been begun.
+    pub fn end_frame<C: BaseContext>(&mut self, _gl: &C) {
+        assert!(self.in_frame, "`GpuProfiler::end_frame` was called outside of a frame");
+        assert!(self.open.is_empty(), "`GpuProfiler::end_frame` was called with an open scope");
+        self.in_frame = false;
+    }
+
+    /// Get the times of the scopes in the most recent frame whose results
+    /// were read, in the order that the scopes were begun.
+    pub fn results(&self) -> &[ScopeTime] {
+        &self.results
+    }
+
+    /// Get the number of the frame (starting from 0) that [`results`]
+    /// (struct.GpuProfiler.html#method.results) were measured in, or `None`
+    /// if no results have been read yet.
+    pub fn results_frame(&self) -> Option<u64> {
+        self.results_frame
+    }
+}
+
+impl<C: BaseContext> FrameHook<C> for GpuProfiler {
+    fn begin_frame(&mut self, gl: &mut C, _index: u64) {
+        GpuProfiler::begin_frame(self, &*gl);
+    }
+
+    fn end_frame(&mut self, gl: &mut C, _index: u64) {
+        GpuProfiler::end_frame(self, &*gl);
+    }
+}
+
+impl Default for GpuProfiler {
+    fn default() -> Self {
+        GpuProfiler::new()
+    }
+}
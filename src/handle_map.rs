@@ -0,0 +1,249 @@
+//! Contains the [`HandleMap`](struct.HandleMap.html) type, which owns
+//! OpenGL objects and gives out generation-checked handles to them, so that
+//! using an object after it has been deleted is a recoverable error.
+
+use std::fmt;
+use std::error;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use types::GLObject;
+
+/// A handle to an object in a [`HandleMap`](struct.HandleMap.html). A
+/// handle is a slot index along with the slot's generation, which changes
+/// every time an object is removed from the slot, so a handle to a removed
+/// object never refers to a new object that reuses its slot.
+pub struct Handle<O> {
+    index: usize,
+    generation: u32,
+    _phantom: PhantomData<fn() -> O>
+}
+
+impl<O> Handle<O> {
+    /// Get the index of the handle's slot.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the generation of the handle's slot when the handle was created.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl<O> Clone for Handle<O> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<O> Copy for Handle<O> {
+
+}
+
+impl<O> PartialEq for Handle<O> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<O> Eq for Handle<O> {
+
+}
+
+impl<O> Hash for Handle<O> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<O> fmt::Debug for Handle<O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Handle({}v{})", self.index, self.generation)
+    }
+}
+
+/// The error returned when using a [`Handle`](struct.Handle.html) to an
+/// object that has been removed from its [`HandleMap`]
+/// (struct.HandleMap.html) (or that belongs to a different map).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaleHandle {
+    /// The index of the handle's slot.
+    pub index: usize,
+
+    /// The generation of the handle.
+    pub generation: u32
+}
+
+impl fmt::Display for StaleHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "Handle {}v{} refers to an object that has been deleted",
+               self.index,
+               self.generation)
+    }
+}
+
+impl error::Error for StaleHandle {
+    fn description(&self) -> &str {
+        "Handle refers to an object that has been deleted"
+    }
+}
+
+struct Slot<O> {
+    generation: u32,
+    object: Option<O>
+}
+
+/// A collection that owns OpenGL objects (such as [`Texture2d`]
+/// (../texture/type.Texture2d.html)s or [`Buffer`](../buffer/struct.Buffer.html)s),
+/// and refers to them with [`Handle`](struct.Handle.html)s instead of
+/// references or raw OpenGL names.
+///
+/// Handles can be copied and stored freely, which is useful when objects
+/// are created and deleted while a program is running (such as in an
+/// editor). Removing an object deletes it, and any handle to a removed
+/// object returns a [`StaleHandle`](struct.StaleHandle.html) error,
+/// instead of referring to a deleted object (or to a new object that
+/// OpenGL created with the same name).
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::{HandleMap, Buffer};
+///
+/// # fn main() {
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let mut buffers: HandleMap<Buffer> = HandleMap::new();
+///
+/// let handle = buffers.insert(gl.gen_buffer());
+/// {
+///     let buffer = buffers.get_mut(handle).unwrap();
+///     let (mut gl_buffer, gl) = gl.bind_array_buffer(buffer);
+///     gl.buffer_bytes(&mut gl_buffer, &[1, 2, 3], glitter::STATIC_DRAW);
+/// }
+///
+/// // Delete the buffer
+/// buffers.remove(handle).unwrap();
+///
+/// // Using the handle again is an error, rather than undefined behavior
+/// assert!(buffers.get(handle).is_err());
+/// # }
+/// ```
+pub struct HandleMap<O: GLObject> {
+    slots: Vec<Slot<O>>,
+    free: Vec<usize>,
+    len: usize
+}
+
+impl<O: GLObject> HandleMap<O> {
+    /// Create a new, empty handle map.
+    pub fn new() -> Self {
+        HandleMap {
+            slots: vec![],
+            free: vec![],
+            len: 0
+        }
+    }
+
+    fn _slot(&self, handle: Handle<O>) -> Result<&O, StaleHandle> {
+        let stale = StaleHandle {
+            index: handle.index,
+            generation: handle.generation
+        };
+        match self.slots.get(handle.index) {
+            Some(&Slot { generation, object: Some(ref object) })
+                if generation == handle.generation => Ok(object),
+            _ => Err(stale)
+        }
+    }
+
+    /// Take ownership of an object, returning a handle to it.
+    pub fn insert(&mut self, object: O) -> Handle<O> {
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                slot.object = Some(object);
+                Handle {
+                    index: index,
+                    generation: slot.generation,
+                    _phantom: PhantomData
+                }
+            },
+            None => {
+                self.slots.push(Slot { generation: 0, object: Some(object) });
+                Handle {
+                    index: self.slots.len() - 1,
+                    generation: 0,
+                    _phantom: PhantomData
+                }
+            }
+        }
+    }
+
+    /// Get a reference to the object for a handle.
+    ///
+    /// # Failures
+    /// An error will be returned if the object has been removed.
+    pub fn get(&self, handle: Handle<O>) -> Result<&O, StaleHandle> {
+        self._slot(handle)
+    }
+
+    /// Get a mutable reference to the object for a handle, such as to
+    /// bind the object.
+    ///
+    /// # Failures
+    /// An error will be returned if the object has been removed.
+    pub fn get_mut(&mut self, handle: Handle<O>)
+        -> Result<&mut O, StaleHandle>
+    {
+        try!(self._slot(handle));
+        Ok(self.slots[handle.index].object.as_mut().unwrap())
+    }
+
+    /// Get the OpenGL name of the object for a handle.
+    ///
+    /// # Failures
+    /// An error will be returned if the object has been removed.
+    pub fn id(&self, handle: Handle<O>) -> Result<O::Id, StaleHandle> {
+        self._slot(handle).map(|object| object.id())
+    }
+
+    /// Returns `true` if the object for a handle hasn't been removed.
+    pub fn contains(&self, handle: Handle<O>) -> bool {
+        self._slot(handle).is_ok()
+    }
+
+    /// Remove the object for a handle, returning it. Dropping the returned
+    /// object deletes it. Afterwards, every copy of the handle is stale.
+    ///
+    /// # Failures
+    /// An error will be returned if the object has already been removed.
+    pub fn remove(&mut self, handle: Handle<O>) -> Result<O, StaleHandle> {
+        try!(self._slot(handle));
+
+        let slot = &mut self.slots[handle.index];
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        self.len -= 1;
+        Ok(slot.object.take().unwrap())
+    }
+
+    /// Get the number of objects in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no objects.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<O: GLObject> Default for HandleMap<O> {
+    fn default() -> Self {
+        HandleMap::new()
+    }
+}
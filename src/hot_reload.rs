@@ -0,0 +1,275 @@
+//! Contains the [`HotReload`](struct.HotReload.html) type, which rebuilds
+//! programs when their shader files change.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use context::{AContext, ContextProgramBuilderExt, ContextProgramExt};
+use shader::{ShaderType, ContextShaderBuilderExt};
+use shader_source::ShaderSource;
+use program::{Program, ProgramUniform};
+use handle_map::{Handle, HandleMap, StaleHandle};
+use types::GLError;
+
+/// The outcome of rebuilding a program whose shader files changed, as
+/// returned by [`HotReload::poll`](struct.HotReload.html#method.poll).
+#[derive(Debug)]
+pub struct Reload {
+    /// The program that was rebuilt.
+    pub program: Handle<Program>,
+
+    /// `Ok` if the new program replaced the old one, or the error that
+    /// occurred while loading, compiling, or linking the new program (in
+    /// which case the old program is kept).
+    pub result: Result<(), GLError>
+}
+
+struct Watched {
+    shaders: Vec<(ShaderType, PathBuf)>,
+
+    // The last resolved source of each shader (or the error from loading
+    // it), which is compared to decide whether the program has changed
+    sources: Vec<Result<String, String>>,
+
+    uniforms: HashMap<String, Option<ProgramUniform>>
+}
+
+fn _load_sources(shaders: &[(ShaderType, PathBuf)])
+    -> Vec<Result<String, String>>
+{
+    shaders.iter().map(|&(_, ref path)| {
+        let source = try! {
+            ShaderSource::from_file(path).map_err(|err| {
+                format!("{}: {}", path.display(), err)
+            })
+        };
+        source.resolve()
+            .map(|resolved| resolved.source)
+            .map_err(|err| format!("{}: {}", path.display(), err))
+    }).collect()
+}
+
+fn _build_program<C>(gl: &C,
+                     shaders: &[(ShaderType, PathBuf)],
+                     sources: &[Result<String, String>])
+    -> Result<Program, GLError>
+    where C: AContext
+{
+    let mut built = Vec::with_capacity(shaders.len());
+    for (&(ty, ref path), source) in shaders.iter().zip(sources) {
+        let source = match *source {
+            Ok(ref source) => source,
+            Err(ref err) => { return Err(GLError::Message(err.clone())); }
+        };
+        let shader = try! {
+            gl.build_shader(ty, source).try_unwrap().map_err(|err| {
+                GLError::Message(format!("{}: {}", path.display(), err))
+            })
+        };
+        built.push(shader);
+    }
+    gl.build_program(&built).try_unwrap()
+}
+
+/// Watches the shader files of programs, and rebuilds each program when any
+/// of its files (including files included with `#include`) change.
+///
+/// Programs are owned by the `HotReload`, and are referred to with
+/// [`Handle`](../handle_map/struct.Handle.html)s. When a program is
+/// rebuilt, the new program replaces the old one only after it has compiled
+/// and linked successfully, so the handle always refers to a working
+/// program. If rebuilding fails, the error is reported by [`poll`]
+/// (struct.HotReload.html#method.poll), and the old program is kept.
+///
+/// Files are checked by loading them again (at most once per
+/// [`interval`](struct.HotReload.html#method.set_interval)), and comparing
+/// them to the sources that the program was last built from.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::HotReload;
+///
+/// # fn main() {
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let mut hot_reload = HotReload::new();
+/// let handle = hot_reload.load(&gl, &[
+///     (glitter::VERTEX_SHADER, "shaders/sprite.vert"),
+///     (glitter::FRAGMENT_SHADER, "shaders/sprite.frag")
+/// ]).unwrap();
+///
+/// loop {
+///     for reload in hot_reload.poll(&gl) {
+///         if let Err(err) = reload.result {
+///             println!("Couldn't reload shaders: {}", err);
+///         }
+///     }
+///
+///     let color = hot_reload.uniform(&gl, handle, "color").unwrap();
+///     let program = hot_reload.program_mut(handle).unwrap();
+///     // Draw with the program...
+/// #   break;
+/// }
+/// # }
+/// ```
+pub struct HotReload {
+    programs: HandleMap<Program>,
+    watched: HashMap<Handle<Program>, Watched>,
+    interval: Duration,
+    last_poll: Option<Instant>
+}
+
+impl HotReload {
+    /// Create a new `HotReload`, which checks for changes at most
+    /// every 500 milliseconds.
+    pub fn new() -> Self {
+        HotReload {
+            programs: HandleMap::new(),
+            watched: HashMap::new(),
+            interval: Duration::from_millis(500),
+            last_poll: None
+        }
+    }
+
+    /// Set the minimum time between checking the shader files for changes.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Build a program from a list of shader types and the paths of their
+    /// source files, and watch the files for changes.
+    ///
+    /// # Failures
+    /// An error will be returned if a file could not be loaded, or if the
+    /// program could not be compiled or linked.
+    pub fn load<C, P>(&mut self, gl: &C, shaders: &[(ShaderType, P)])
+        -> Result<Handle<Program>, GLError>
+        where C: AContext, P: AsRef<Path>
+    {
+        let shaders: Vec<_> = shaders.iter().map(|&(ty, ref path)| {
+            (ty, path.as_ref().to_path_buf())
+        }).collect();
+        let sources = _load_sources(&shaders);
+        let program = try!(_build_program(gl, &shaders, &sources));
+
+        let handle = self.programs.insert(program);
+        self.watched.insert(handle, Watched {
+            shaders: shaders,
+            sources: sources,
+            uniforms: HashMap::new()
+        });
+        Ok(handle)
+    }
+
+    /// Stop watching a program, and return it.
+    ///
+    /// # Failures
+    /// An error will be returned if the program was already removed.
+    pub fn remove(&mut self, handle: Handle<Program>)
+        -> Result<Program, StaleHandle>
+    {
+        self.watched.remove(&handle);
+        self.programs.remove(handle)
+    }
+
+    /// Get the current version of a program.
+    ///
+    /// # Failures
+    /// An error will be returned if the program was removed.
+    pub fn program(&self, handle: Handle<Program>)
+        -> Result<&Program, StaleHandle>
+    {
+        self.programs.get(handle)
+    }
+
+    /// Get the current version of a program as a mutable reference, such as
+    /// to use the program with [`gl.use_program`]
+    /// (../context/program_context/trait.ProgramContext.html#method.use_program).
+    ///
+    /// # Failures
+    /// An error will be returned if the program was removed.
+    pub fn program_mut(&mut self, handle: Handle<Program>)
+        -> Result<&mut Program, StaleHandle>
+    {
+        self.programs.get_mut(handle)
+    }
+
+    /// Get the location of a uniform in the current version of a program,
+    /// or `None` if the program has no active uniform with that name. The
+    /// location is cached, and the cache is updated whenever the program
+    /// is rebuilt.
+    ///
+    /// # Failures
+    /// An error will be returned if the program was removed.
+    pub fn uniform<C>(&mut self, gl: &C, handle: Handle<Program>, name: &str)
+        -> Result<Option<ProgramUniform>, StaleHandle>
+        where C: AContext
+    {
+        let program = try!(self.programs.get(handle));
+        let watched = self.watched.get_mut(&handle)
+            .expect("Watched program is missing");
+
+        if let Some(uniform) = watched.uniforms.get(name) {
+            return Ok(*uniform);
+        }
+        let uniform = gl.get_uniform_location(program, name).ok();
+        watched.uniforms.insert(name.to_owned(), uniform);
+        Ok(uniform)
+    }
+
+    /// Check the shader files of each program for changes, and rebuild the
+    /// programs that changed. Returns the outcome for each program that was
+    /// rebuilt. This should be called regularly, such as once per frame;
+    /// the files are only checked if the [`interval`]
+    /// (struct.HotReload.html#method.set_interval) has passed since
+    /// they were last checked.
+    ///
+    /// A program whose files can't be loaded or built is only reported
+    /// again after its files change again.
+    pub fn poll<C: AContext>(&mut self, gl: &C) -> Vec<Reload> {
+        let now = Instant::now();
+        if let Some(last_poll) = self.last_poll {
+            if now.duration_since(last_poll) < self.interval {
+                return vec![];
+            }
+        }
+        self.last_poll = Some(now);
+
+        let mut reloads = vec![];
+        for (&handle, watched) in &mut self.watched {
+            let sources = _load_sources(&watched.shaders);
+            if sources == watched.sources {
+                continue;
+            }
+
+            let result = _build_program(gl, &watched.shaders, &sources);
+            watched.sources = sources;
+            let result = match result {
+                Ok(program) => {
+                    let slot = self.programs.get_mut(handle)
+                        .expect("Watched program is missing");
+                    *slot = program;
+
+                    for (name, uniform) in &mut watched.uniforms {
+                        *uniform = gl.get_uniform_location(slot, name).ok();
+                    }
+                    info!("Reloaded program {:?}", handle);
+                    Ok(())
+                },
+                Err(err) => {
+                    warn!("Couldn't reload program {:?}: {}", handle, err);
+                    Err(err)
+                }
+            };
+            reloads.push(Reload { program: handle, result: result });
+        }
+        reloads
+    }
+}
+
+impl Default for HotReload {
+    fn default() -> Self {
+        HotReload::new()
+    }
+}
@@ -259,7 +259,23 @@ gl_enum! {
 
         /// Each texel is 16 bits, with 5-bit/5-bit/5-bit/1-bit components.
         pub const UnsignedShort5551 as UNSIGNED_SHORT_5_5_5_1 =
-            gl::UNSIGNED_SHORT_5_5_5_1
+            gl::UNSIGNED_SHORT_5_5_5_1,
+
+        /// Each component is 16 bits (such as a 16-bit depth value).
+        pub const UnsignedShort as UNSIGNED_SHORT_TEXEL =
+            gl::UNSIGNED_SHORT,
+
+        /// Each component is 32 bits (such as a 24-bit depth value).
+        pub const UnsignedInt as UNSIGNED_INT_TEXEL = gl::UNSIGNED_INT,
+
+        /// Each component is a 32-bit float (such as a 32-bit float depth
+        /// value).
+        pub const Float as FLOAT_TEXEL = gl::FLOAT,
+
+        /// Each texel is 32 bits, with a 24-bit depth component and an
+        /// 8-bit stencil component.
+        pub const UnsignedInt248 as UNSIGNED_INT_24_8 =
+            gl::UNSIGNED_INT_24_8
     }
 }
 
@@ -273,7 +289,62 @@ gl_enum! {
         pub const RGB as RGB = gl::RGB,
 
         /// A texel contains red, green, blue, and alpha components.
-        pub const RGBA as RGBA = gl::RGBA
+        pub const RGBA as RGBA = gl::RGBA,
+
+        /// A texel contains red, green, and blue components, which are
+        /// stored in the sRGB color space (and converted to linear values
+        /// when sampled).
+        ///
+        /// Without the `gles3` feature, this requires the `GL_EXT_sRGB`
+        /// extension (see [`Extensions::srgb`]
+        /// (../context/extensions/struct.Extensions.html#method.srgb)).
+        pub const SRGB as SRGB = gl::SRGB,
+
+        /// A texel contains red, green, and blue components, which are
+        /// stored in the sRGB color space, and a linear alpha component.
+        ///
+        /// Without the `gles3` feature, this requires the `GL_EXT_sRGB`
+        /// extension (see [`Extensions::srgb`]
+        /// (../context/extensions/struct.Extensions.html#method.srgb)).
+        pub const SRGBAlpha as SRGB_ALPHA = gl::SRGB_ALPHA,
+
+        /// A texel contains a depth component, which is used for shadow
+        /// maps (see [`ImageFormat::depth24`]
+        /// (struct.ImageFormat.html#method.depth24)).
+        ///
+        /// Without the `gles3` feature, this requires the
+        /// `GL_OES_depth_texture` extension (see [`Extensions::depth_texture`]
+        /// (../context/extensions/struct.Extensions.html#method.depth_texture)).
+        pub const DepthComponent as DEPTH_COMPONENT = gl::DEPTH_COMPONENT,
+
+        /// A texel contains a depth component and a stencil component (see
+        /// [`ImageFormat::depth24_stencil8`]
+        /// (struct.ImageFormat.html#method.depth24_stencil8)).
+        ///
+        /// Without the `gles3` feature, this requires the
+        /// `GL_OES_packed_depth_stencil` extension (see
+        /// [`Extensions::packed_depth_stencil`]
+        /// (../context/extensions/struct.Extensions.html#method.packed_depth_stencil)).
+        pub const DepthStencil as DEPTH_STENCIL = gl::DEPTH_STENCIL
+    }
+}
+
+impl TexelFormat {
+    /// Returns `true` if the format contains a depth component.
+    pub fn is_depth(&self) -> bool {
+        match *self {
+            TexelFormat::DepthComponent | TexelFormat::DepthStencil => true,
+            _ => false
+        }
+    }
+
+    /// Returns `true` if the color components of the format are stored in
+    /// the sRGB color space.
+    pub fn is_srgb(&self) -> bool {
+        match *self {
+            TexelFormat::SRGB | TexelFormat::SRGBAlpha => true,
+            _ => false
+        }
     }
 }
 
@@ -291,6 +362,14 @@ gl_enum! {
         /// alpha channel is stored with 1 bit.
         pub const RGB5A1 as RGB5_A1 = gl::RGB5_A1,
 
+        /// The red, green, and blue channels are stored with 8 bits in the
+        /// sRGB color space, and the alpha channel is stored with 8 bits.
+        ///
+        /// Without the `gles3` feature, this requires the `GL_EXT_sRGB`
+        /// extension (see [`Extensions::srgb`]
+        /// (../context/extensions/struct.Extensions.html#method.srgb)).
+        pub const SRGB8Alpha8 as SRGB8_ALPHA8 = gl::SRGB8_ALPHA8,
+
         /// The renderbuffer stores a 16-bit depth component.
         pub const DepthComponent16 as DEPTH_COMPONENT16 = gl::DEPTH_COMPONENT16,
 
@@ -317,4 +396,267 @@ impl ImageFormat {
             texel_format: TexelFormat::RGBA
         }
     }
+
+    /// Get the size of a single texel with this format, in bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use glitter::ImageFormat;
+    ///
+    /// assert_eq!(ImageFormat::rgba8().texel_size(), 4);
+    /// assert_eq!(ImageFormat::depth16().texel_size(), 2);
+    /// ```
+    pub fn texel_size(&self) -> usize {
+        let components = match self.texel_format {
+            TexelFormat::Alpha |
+            TexelFormat::DepthComponent |
+            TexelFormat::DepthStencil => 1,
+            TexelFormat::RGB | TexelFormat::SRGB => 3,
+            TexelFormat::RGBA | TexelFormat::SRGBAlpha => 4
+        };
+        match self.texel_type {
+            TexelType::UnsignedByte => components,
+            TexelType::UnsignedShort => components * 2,
+            TexelType::UnsignedInt | TexelType::Float => components * 4,
+            TexelType::UnsignedShort565 |
+            TexelType::UnsignedShort4444 |
+            TexelType::UnsignedShort5551 => 2,
+            TexelType::UnsignedInt248 => 4
+        }
+    }
+
+    /// Returns the depth image format with 16 bits per texel.
+    pub fn depth16() -> Self {
+        ImageFormat {
+            texel_type: TexelType::UnsignedShort,
+            texel_format: TexelFormat::DepthComponent
+        }
+    }
+
+    /// Returns the depth image format with 24 bits of precision (stored in
+    /// 32 bits per texel).
+    pub fn depth24() -> Self {
+        ImageFormat {
+            texel_type: TexelType::UnsignedInt,
+            texel_format: TexelFormat::DepthComponent
+        }
+    }
+
+    /// Returns the depth image format with a 32-bit float per texel.
+    ///
+    /// # Note
+    /// Float depth textures require OpenGL ES 3.0, so this format can only
+    /// be used with the `gles3` feature.
+    pub fn depth32f() -> Self {
+        ImageFormat {
+            texel_type: TexelType::Float,
+            texel_format: TexelFormat::DepthComponent
+        }
+    }
+
+    /// Returns the packed depth/stencil image format, with a 24-bit depth
+    /// component and an 8-bit stencil component per texel.
+    pub fn depth24_stencil8() -> Self {
+        ImageFormat {
+            texel_type: TexelType::UnsignedInt248,
+            texel_format: TexelFormat::DepthStencil
+        }
+    }
+}
+
+// `GL_EXT_texture_compression_s3tc`
+const COMPRESSED_RGB_S3TC_DXT1_EXT: u32 = 0x83F0;
+const COMPRESSED_RGBA_S3TC_DXT1_EXT: u32 = 0x83F1;
+const COMPRESSED_RGBA_S3TC_DXT3_EXT: u32 = 0x83F2;
+const COMPRESSED_RGBA_S3TC_DXT5_EXT: u32 = 0x83F3;
+
+// `GL_KHR_texture_compression_astc_ldr`
+const COMPRESSED_RGBA_ASTC_4X4_KHR: u32 = 0x93B0;
+const COMPRESSED_RGBA_ASTC_5X5_KHR: u32 = 0x93B2;
+const COMPRESSED_RGBA_ASTC_6X6_KHR: u32 = 0x93B4;
+const COMPRESSED_RGBA_ASTC_8X8_KHR: u32 = 0x93B7;
+const COMPRESSED_RGBA_ASTC_10X10_KHR: u32 = 0x93BB;
+const COMPRESSED_RGBA_ASTC_12X12_KHR: u32 = 0x93BD;
+
+gl_enum! {
+    /// The block-compressed texture formats, which can be uploaded with
+    /// [`gl.compressed_tex_image_2d`]
+    /// (../context/struct.ContextOf.html#method.compressed_tex_image_2d).
+    /// Each format stores fixed-size blocks of texels, so compressed image
+    /// data is uploaded directly, rather than as an [`ImageFormat`]
+    /// (struct.ImageFormat.html).
+    ///
+    /// Support for each family of formats depends on the OpenGL
+    /// implementation (see [`gl.supports_compressed_format`]
+    /// (../context/struct.ContextOf.html#method.supports_compressed_format)).
+    #[cfg_attr(feature = "serialize", derive(Serialize))]
+    pub gl_enum CompressedTextureFormat {
+        /// S3TC/DXT1 with red, green, and blue components, stored in 4x4
+        /// blocks of 64 bits (also known as BC1).
+        pub const RGBS3tcDxt1 as COMPRESSED_RGB_S3TC_DXT1 =
+            COMPRESSED_RGB_S3TC_DXT1_EXT,
+
+        /// S3TC/DXT1 with red, green, blue, and 1-bit alpha components,
+        /// stored in 4x4 blocks of 64 bits (also known as BC1).
+        pub const RGBAS3tcDxt1 as COMPRESSED_RGBA_S3TC_DXT1 =
+            COMPRESSED_RGBA_S3TC_DXT1_EXT,
+
+        /// S3TC/DXT3 with red, green, blue, and explicit 4-bit alpha
+        /// components, stored in 4x4 blocks of 128 bits (also known as BC2).
+        pub const RGBAS3tcDxt3 as COMPRESSED_RGBA_S3TC_DXT3 =
+            COMPRESSED_RGBA_S3TC_DXT3_EXT,
+
+        /// S3TC/DXT5 with red, green, blue, and interpolated alpha
+        /// components, stored in 4x4 blocks of 128 bits (also known as BC3).
+        pub const RGBAS3tcDxt5 as COMPRESSED_RGBA_S3TC_DXT5 =
+            COMPRESSED_RGBA_S3TC_DXT5_EXT,
+
+        /// ETC2 with red, green, and blue components, stored in 4x4 blocks
+        /// of 64 bits.
+        pub const RGB8Etc2 as COMPRESSED_RGB8_ETC2 = gl::COMPRESSED_RGB8_ETC2,
+
+        /// ETC2 with red, green, and blue components in the sRGB color
+        /// space, stored in 4x4 blocks of 64 bits.
+        pub const SRGB8Etc2 as COMPRESSED_SRGB8_ETC2 =
+            gl::COMPRESSED_SRGB8_ETC2,
+
+        /// ETC2 with red, green, blue, and 1-bit alpha components, stored
+        /// in 4x4 blocks of 64 bits.
+        pub const RGB8PunchthroughAlpha1Etc2
+            as COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2 =
+            gl::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+
+        /// ETC2/EAC with red, green, blue, and alpha components, stored in
+        /// 4x4 blocks of 128 bits.
+        pub const RGBA8Etc2Eac as COMPRESSED_RGBA8_ETC2_EAC =
+            gl::COMPRESSED_RGBA8_ETC2_EAC,
+
+        /// ETC2/EAC with red, green, and blue components in the sRGB color
+        /// space and a linear alpha component, stored in 4x4 blocks of
+        /// 128 bits.
+        pub const SRGB8Alpha8Etc2Eac as COMPRESSED_SRGB8_ALPHA8_ETC2_EAC =
+            gl::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC,
+
+        /// ASTC with red, green, blue, and alpha components, stored in 4x4
+        /// blocks of 128 bits.
+        pub const RGBAAstc4x4 as COMPRESSED_RGBA_ASTC_4X4 =
+            COMPRESSED_RGBA_ASTC_4X4_KHR,
+
+        /// ASTC with red, green, blue, and alpha components, stored in 5x5
+        /// blocks of 128 bits.
+        pub const RGBAAstc5x5 as COMPRESSED_RGBA_ASTC_5X5 =
+            COMPRESSED_RGBA_ASTC_5X5_KHR,
+
+        /// ASTC with red, green, blue, and alpha components, stored in 6x6
+        /// blocks of 128 bits.
+        pub const RGBAAstc6x6 as COMPRESSED_RGBA_ASTC_6X6 =
+            COMPRESSED_RGBA_ASTC_6X6_KHR,
+
+        /// ASTC with red, green, blue, and alpha components, stored in 8x8
+        /// blocks of 128 bits.
+        pub const RGBAAstc8x8 as COMPRESSED_RGBA_ASTC_8X8 =
+            COMPRESSED_RGBA_ASTC_8X8_KHR,
+
+        /// ASTC with red, green, blue, and alpha components, stored in
+        /// 10x10 blocks of 128 bits.
+        pub const RGBAAstc10x10 as COMPRESSED_RGBA_ASTC_10X10 =
+            COMPRESSED_RGBA_ASTC_10X10_KHR,
+
+        /// ASTC with red, green, blue, and alpha components, stored in
+        /// 12x12 blocks of 128 bits.
+        pub const RGBAAstc12x12 as COMPRESSED_RGBA_ASTC_12X12 =
+            COMPRESSED_RGBA_ASTC_12X12_KHR
+    }
+}
+
+/// The families of [`CompressedTextureFormat`]
+/// (enum.CompressedTextureFormat.html)s. Support for compressed formats
+/// is usually provided for a whole family at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompressedTextureFamily {
+    /// S3TC/DXT formats, which are widely supported by desktop GPUs.
+    S3tc,
+
+    /// ETC2/EAC formats, which are supported by all OpenGL ES 3.0
+    /// implementations (and OpenGL 4.3).
+    Etc2,
+
+    /// ASTC formats, which are supported by many newer mobile GPUs.
+    Astc
+}
+
+impl CompressedTextureFormat {
+    /// Get the family that the format belongs to.
+    pub fn family(&self) -> CompressedTextureFamily {
+        match *self {
+            CompressedTextureFormat::RGBS3tcDxt1 |
+            CompressedTextureFormat::RGBAS3tcDxt1 |
+            CompressedTextureFormat::RGBAS3tcDxt3 |
+            CompressedTextureFormat::RGBAS3tcDxt5 => {
+                CompressedTextureFamily::S3tc
+            },
+            CompressedTextureFormat::RGB8Etc2 |
+            CompressedTextureFormat::SRGB8Etc2 |
+            CompressedTextureFormat::RGB8PunchthroughAlpha1Etc2 |
+            CompressedTextureFormat::RGBA8Etc2Eac |
+            CompressedTextureFormat::SRGB8Alpha8Etc2Eac => {
+                CompressedTextureFamily::Etc2
+            },
+            CompressedTextureFormat::RGBAAstc4x4 |
+            CompressedTextureFormat::RGBAAstc5x5 |
+            CompressedTextureFormat::RGBAAstc6x6 |
+            CompressedTextureFormat::RGBAAstc8x8 |
+            CompressedTextureFormat::RGBAAstc10x10 |
+            CompressedTextureFormat::RGBAAstc12x12 => {
+                CompressedTextureFamily::Astc
+            }
+        }
+    }
+
+    /// Get the size of a single block of the format, in texels, as
+    /// `(width, height)`.
+    pub fn block_dimensions(&self) -> (u32, u32) {
+        match *self {
+            CompressedTextureFormat::RGBAAstc5x5 => (5, 5),
+            CompressedTextureFormat::RGBAAstc6x6 => (6, 6),
+            CompressedTextureFormat::RGBAAstc8x8 => (8, 8),
+            CompressedTextureFormat::RGBAAstc10x10 => (10, 10),
+            CompressedTextureFormat::RGBAAstc12x12 => (12, 12),
+            _ => (4, 4)
+        }
+    }
+
+    /// Get the size of a single block of the format, in bytes.
+    pub fn block_size(&self) -> usize {
+        match *self {
+            CompressedTextureFormat::RGBS3tcDxt1 |
+            CompressedTextureFormat::RGBAS3tcDxt1 |
+            CompressedTextureFormat::RGB8Etc2 |
+            CompressedTextureFormat::SRGB8Etc2 |
+            CompressedTextureFormat::RGB8PunchthroughAlpha1Etc2 => 8,
+            _ => 16
+        }
+    }
+
+    /// Get the size of a compressed image with this format, in bytes. Images
+    /// that aren't a multiple of the block size are padded to a whole
+    /// number of blocks.
+    ///
+    /// # Example
+    /// ```
+    /// use glitter::CompressedTextureFormat;
+    ///
+    /// let dxt1 = CompressedTextureFormat::RGBS3tcDxt1;
+    /// assert_eq!(dxt1.image_size(256, 256), 64 * 64 * 8);
+    /// assert_eq!(dxt1.image_size(1, 1), 8);
+    ///
+    /// let astc = CompressedTextureFormat::RGBAAstc6x6;
+    /// assert_eq!(astc.image_size(64, 64), 11 * 11 * 16);
+    /// ```
+    pub fn image_size(&self, width: u32, height: u32) -> usize {
+        let (block_width, block_height) = self.block_dimensions();
+        let blocks_x = width / block_width + (width % block_width != 0) as u32;
+        let blocks_y = height / block_height + (height % block_height != 0) as u32;
+        blocks_x as usize * blocks_y as usize * self.block_size()
+    }
 }
@@ -0,0 +1,130 @@
+use image::{self, GenericImage};
+use context::ContextTextureBuilderExt;
+use texture::{Texture, Texture2d, Tx2d, LINEAR, LINEAR_MIPMAP_LINEAR};
+use image_data::{Image2d, ImageFormat, TexelFormat, TexelType};
+use types::GLError;
+
+// The texel data of a `DynamicImage`, converted to a format that can be
+// uploaded directly, with its rows padded to 4 bytes (the default
+// `GL_UNPACK_ALIGNMENT`)
+pub struct DynamicImageData {
+    width: usize,
+    height: usize,
+    format: ImageFormat,
+    bytes: Vec<u8>
+}
+
+impl DynamicImageData {
+    pub fn new(img: &image::DynamicImage) -> Self {
+        let (width, height) = img.dimensions();
+        let (width, height) = (width as usize, height as usize);
+        let (texel_format, bytes) = match *img {
+            image::DynamicImage::ImageRgba8(ref buffer) => {
+                (TexelFormat::RGBA, buffer.clone().into_raw())
+            },
+            image::DynamicImage::ImageRgb8(ref buffer) => {
+                (TexelFormat::RGB, buffer.clone().into_raw())
+            },
+            image::DynamicImage::ImageLuma8(_) => {
+                (TexelFormat::RGB, img.to_rgb().into_raw())
+            },
+            image::DynamicImage::ImageLumaA8(_) => {
+                (TexelFormat::RGBA, img.to_rgba().into_raw())
+            }
+        };
+        let format = ImageFormat {
+            texel_type: TexelType::UnsignedByte,
+            texel_format: texel_format
+        };
+
+        let row = width * format.texel_size();
+        let padded_row = (row + 3) / 4 * 4;
+        let bytes = if row == padded_row {
+            bytes
+        }
+        else {
+            let mut padded = Vec::with_capacity(padded_row * height);
+            for texel_row in bytes.chunks(row) {
+                padded.extend_from_slice(texel_row);
+                padded.extend_from_slice(&[0, 0, 0][..padded_row - row]);
+            }
+            padded
+        };
+
+        DynamicImageData {
+            width: width,
+            height: height,
+            format: format,
+            bytes: bytes
+        }
+    }
+}
+
+impl Image2d for DynamicImageData {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    fn texel_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl Texture<Tx2d> {
+    /// Create a 2D texture from an image loaded with the `image` crate,
+    /// using linear filtering. Grayscale images are uploaded as RGB, and
+    /// grayscale images with alpha are uploaded as RGBA.
+    ///
+    /// - `gl`: A context where the 0th texture unit is free.
+    /// - `img`: The image to upload.
+    /// - `generate_mipmap`: Whether to generate mipmaps for the texture
+    ///   (and use trilinear filtering).
+    ///
+    /// # Failures
+    /// An error will be returned if the texture could not be built (see
+    /// [`Texture2dBuilder::try_unwrap`]
+    /// (../context/texture_context/struct.Texture2dBuilder.html#method.try_unwrap)).
+    ///
+    /// # Note
+    /// This function is only available with the `image` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// extern crate glitter;
+    /// extern crate image;
+    ///
+    /// # fn main() {
+    /// let mut gl = unsafe { glitter::Context::current_context() };
+    /// let img = image::open("crate.png").unwrap();
+    /// let texture = glitter::Texture2d::from_image(&mut gl, &img, true)
+    ///                                  .unwrap();
+    /// # }
+    /// ```
+    pub fn from_image<C>(gl: C,
+                         img: &image::DynamicImage,
+                         generate_mipmap: bool)
+        -> Result<Texture2d, GLError>
+        where C: ContextTextureBuilderExt
+    {
+        let data = DynamicImageData::new(img);
+        let builder = gl.build_texture_2d()
+                        .image_2d(&data)
+                        .mag_filter(LINEAR);
+        if generate_mipmap {
+            builder.min_filter(LINEAR_MIPMAP_LINEAR)
+                   .generate_mipmap()
+                   .try_unwrap()
+        }
+        else {
+            builder.min_filter(LINEAR).try_unwrap()
+        }
+    }
+}
@@ -1 +1,4 @@
 mod image_conversions;
+mod image_textures;
+
+pub use self::image_textures::DynamicImageData;
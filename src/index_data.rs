@@ -10,7 +10,12 @@ pub enum IndexDatumType {
     UnsignedByte,
 
     /// Unsigned 16-bit index data.
-    UnsignedShort
+    UnsignedShort,
+
+    /// Unsigned 32-bit index data. Requires the `gles3` feature (or
+    /// the `GL_OES_element_index_uint` extension).
+    #[cfg(feature = "gles3")]
+    UnsignedInt
 }
 
 /// Indicates that a type can be coerced to a `u8` slice that can
@@ -50,6 +55,11 @@ unsafe impl IndexDatum for u16 {
     fn index_datum_type() -> IndexDatumType { IndexDatumType::UnsignedShort }
 }
 
+#[cfg(feature = "gles3")]
+unsafe impl IndexDatum for u32 {
+    fn index_datum_type() -> IndexDatumType { IndexDatumType::UnsignedInt }
+}
+
 impl<T: IndexDatum> IndexData for [T] {
     fn index_datum_type() -> IndexDatumType {
         T::index_datum_type()
@@ -66,3 +76,45 @@ impl<T: IndexDatum> IndexData for [T] {
         self.len()
     }
 }
+
+fn _range<T: Copy + Into<u32>>(values: &[T]) -> Option<(u32, u32)> {
+    values.iter().fold(None, |range, &value| {
+        let value = value.into();
+        match range {
+            Some((min, max)) => {
+                Some((if value < min { value } else { min },
+                      if value > max { value } else { max }))
+            },
+            None => Some((value, value))
+        }
+    })
+}
+
+/// Get the smallest and largest index in a list of indices, or `None` if
+/// the list is empty. This is the range of vertices that the indices refer
+/// to, which can be passed to [`gl.draw_range_elements`]
+/// (../context/buffer_context/trait.ContextBufferExt.html#method.draw_range_elements).
+///
+/// # Example
+/// ```
+/// assert_eq!(glitter::index_range(&[3u16, 1, 4, 1, 5]), Some((1, 5)));
+/// assert_eq!(glitter::index_range::<u8>(&[]), None);
+/// ```
+pub fn index_range<I: IndexDatum>(indices: &[I]) -> Option<(u32, u32)> {
+    let len = indices.len();
+    let ptr = indices.as_ptr();
+    unsafe {
+        match I::index_datum_type() {
+            IndexDatumType::UnsignedByte => {
+                _range(slice::from_raw_parts(ptr as *const u8, len))
+            },
+            IndexDatumType::UnsignedShort => {
+                _range(slice::from_raw_parts(ptr as *const u16, len))
+            },
+            #[cfg(feature = "gles3")]
+            IndexDatumType::UnsignedInt => {
+                _range(slice::from_raw_parts(ptr as *const u32, len))
+            }
+        }
+    }
+}
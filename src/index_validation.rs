@@ -0,0 +1,43 @@
+// Thread-local flag for whether indexed draw calls check their indices
+// against the number of vertices that can be drawn.
+
+use std::cell::Cell;
+use error_policy;
+use types::{ErrorPolicy, GLError};
+
+thread_local! {
+    static VALIDATE: Cell<bool> = Cell::new(false);
+}
+
+pub fn enabled() -> bool {
+    VALIDATE.with(|validate| validate.get())
+}
+
+pub fn replace_enabled(enabled: bool) -> bool {
+    VALIDATE.with(|validate| {
+        let old_enabled = validate.get();
+        validate.set(enabled);
+        old_enabled
+    })
+}
+
+// Report an error using the current error policy if `max_index` refers to
+// a vertex beyond `vertex_count`. Does nothing if validation is disabled.
+pub fn check(call: &str, max_index: Option<u32>, vertex_count: usize) {
+    if !enabled() {
+        return;
+    }
+
+    let max_index = match max_index {
+        Some(max_index) if max_index as usize >= vertex_count => max_index,
+        _ => { return; }
+    };
+
+    let msg = format!("Index {} used by {} is out of bounds of {} vertices",
+                      max_index, call, vertex_count);
+    match error_policy::policy() {
+        ErrorPolicy::Panic => panic!("{}", msg),
+        ErrorPolicy::Result => error_policy::record(GLError::Message(msg)),
+        ErrorPolicy::Ignore => { }
+    }
+}
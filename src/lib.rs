@@ -46,6 +46,38 @@
 //! (struct.VertexBuffer.html) to use vertex array objects, and to fall back
 //! to vertex buffer objects when vertex array objects are unavailable.
 //!
+//! APIs that aren't part of OpenGL ES 2 are only compiled when the
+//! corresponding Cargo feature is enabled, so that using a function that
+//! isn't available for the targeted OpenGL version is a compile error.
+//! Each feature implies the features for the versions that it includes:
+//!
+//! - `gles2`: OpenGL ES 2.0. This is the baseline, and is always available.
+//! - `gles3`: OpenGL ES 3.0, which adds 32-bit (`u32`) index data,
+//!   the [`PerFrameUbo`](per_frame_ubo/struct.PerFrameUbo.html) type,
+//!   the [`UploadThread`](upload_thread/struct.UploadThread.html) type,
+//!   the [`TextureStreamer`](texture_streamer/struct.TextureStreamer.html)
+//!   type,
+//!   the [`ProgramBinary`](program_binary/struct.ProgramBinary.html) type,
+//!   the [`Fence`](fence/struct.Fence.html) type,
+//!   the [`StreamBuffer`](stream_buffer/struct.StreamBuffer.html) type,
+//!   [`gl.draw_fullscreen`](fullscreen/trait.ContextFullscreenExt.html#method.draw_fullscreen), and
+//!   uniform blocks (see [`UniformBlockLayout`]
+//!   (uniform_block/struct.UniformBlockLayout.html)).
+//! - `gles31`: OpenGL ES 3.1, which adds compute shaders (see
+//!   [`ContextComputeExt`](context/compute_context/trait.ContextComputeExt.html))
+//!   indirect drawing (see [`ContextIndirectExt`]
+//!   (context/indirect_context/trait.ContextIndirectExt.html)), and atomic
+//!   counters (see [`gl.gen_atomic_counter_buffer`]
+//!   (context/buffer_context/trait.ContextBufferExt.html#method.gen_atomic_counter_buffer)).
+//! - `gl33`: OpenGL 3.3 (core profile), which adds timer queries (see
+//!   [`ContextQueryExt`](context/query_context/trait.ContextQueryExt.html))
+//!   and the [`GpuProfiler`](gpu_profiler/struct.GpuProfiler.html) type.
+//! - `gl45`: OpenGL 4.5 (core profile), which includes everything in
+//!   OpenGL ES 3.1, and adds immutable buffer storage (see
+//!   [`ContextBufferStorageExt`]
+//!   (persistent_map/trait.ContextBufferStorageExt.html)) and the
+//!   [`PersistentMap`](persistent_map/struct.PersistentMap.html) type.
+//!
 //! # Thread Safety
 //! Eventually, glitter should support proper thread safety using the [`Send`]
 //! (https://doc.rust-lang.org/std/marker/trait.Send.html) and [`Sync`]
@@ -53,6 +85,16 @@
 //! For now, most types have been marked as `!Send` and `!Sync`, meaning that
 //! they cannot be sent or shared across threads.
 //!
+//! # Tracing
+//! When the `trace` feature is enabled, each OpenGL call made through glitter
+//! is logged using the [`tracing`](https://docs.rs/tracing) crate, with the
+//! target `glitter::gl`. Successful calls are logged at the `TRACE` level,
+//! along with their arguments, and calls that generated an OpenGL error
+//! are logged at the `WARN` level, along with the error. While a subscriber
+//! is listening to the `glitter::gl` target, `glGetError` is called after
+//! every call (regardless of the current [`ErrorPolicy`]
+//! (enum.ErrorPolicy.html)), which may hurt performance.
+//!
 //! # The Future
 //! In its current form, glitter should be considered work-in-progress, and
 //! the API will likely undergo radical changes before a 1.0 version is
@@ -60,23 +102,68 @@
 
 #[macro_use] extern crate bitflags;
 extern crate gl;
+#[macro_use] extern crate log;
 #[cfg(feature = "cgmath")] extern crate cgmath;
 #[cfg(feature = "image")] extern crate image;
+#[cfg(feature = "serialize")] extern crate serde;
+#[cfg(feature = "serialize")] #[macro_use] extern crate serde_derive;
+#[cfg(feature = "trace")] extern crate tracing;
 
 mod to_ref;
 
+// Global state: OpenGL contexts are current per-thread, so glitter's own
+// global state (the error policy, matrix order, binding and state caches,
+// context loss tracking, and so on) lives in thread-locals, which each of
+// the following modules keeps to itself. Setting any of them only affects
+// the current thread.
+mod error_policy;
+mod matrix_order;
+mod restore_bindings;
+mod state_cache;
+mod deletion_queue;
+mod context_loss;
+mod index_validation;
+mod source_dump;
+mod call_trace;
+#[cfg(feature = "gles3")] mod frame_ring;
+
 #[macro_use] mod macros;
 pub mod context;
 pub mod buffer;
 pub mod shader;
+pub mod shader_source;
+pub mod shader_diagnostic;
+pub mod hot_reload;
 pub mod program;
 pub mod framebuffer;
 pub mod renderbuffer;
+pub mod sampler;
 pub mod texture;
 pub mod image_data;
 pub mod vertex_data;
 pub mod vertex_buffer;
+pub mod vertex_compression;
+pub mod depth_prepass;
+pub mod cube_map;
+pub mod render_target;
+pub mod fullscreen;
+pub mod post_process;
+pub mod debug_draw;
+pub mod context_bound;
+#[cfg(feature = "gles3")] pub mod per_frame_ubo;
+#[cfg(feature = "gles3")] pub mod upload_thread;
+#[cfg(feature = "gles3")] pub mod texture_streamer;
+#[cfg(feature = "gles3")] pub mod program_binary;
+#[cfg(feature = "gles3")] pub mod uniform_block;
+#[cfg(feature = "gles3")] pub mod fence;
+#[cfg(feature = "gles3")] pub mod stream_buffer;
+#[cfg(feature = "gl33")] pub mod query;
+#[cfg(feature = "gl33")] pub mod gpu_profiler;
+#[cfg(feature = "gl45")] pub mod persistent_map;
+#[cfg(feature = "texture_containers")] pub mod texture_container;
 pub mod index_data;
+pub mod mesh;
+pub mod handle_map;
 pub mod uniform_data;
 pub mod types;
 
@@ -86,14 +173,38 @@ pub mod types;
 pub use context::*;
 pub use buffer::*;
 pub use shader::*;
+pub use shader_source::*;
+pub use shader_diagnostic::*;
+pub use hot_reload::*;
 pub use program::*;
 pub use framebuffer::*;
 pub use renderbuffer::*;
+pub use sampler::*;
 pub use texture::*;
 pub use image_data::*;
 pub use vertex_data::*;
 pub use vertex_buffer::*;
+pub use vertex_compression::*;
+pub use depth_prepass::*;
+pub use render_target::*;
+pub use fullscreen::*;
+pub use post_process::*;
+pub use debug_draw::*;
+pub use context_bound::*;
+#[cfg(feature = "gles3")] pub use per_frame_ubo::*;
+#[cfg(feature = "gles3")] pub use upload_thread::*;
+#[cfg(feature = "gles3")] pub use texture_streamer::*;
+#[cfg(feature = "gles3")] pub use program_binary::*;
+#[cfg(feature = "gles3")] pub use uniform_block::*;
+#[cfg(feature = "gles3")] pub use fence::*;
+#[cfg(feature = "gles3")] pub use stream_buffer::*;
+#[cfg(feature = "gl33")] pub use query::*;
+#[cfg(feature = "gl33")] pub use gpu_profiler::*;
+#[cfg(feature = "gl45")] pub use persistent_map::*;
+#[cfg(feature = "texture_containers")] pub use texture_container::*;
 pub use index_data::*;
+pub use mesh::*;
+pub use handle_map::*;
 pub use uniform_data::*;
 pub use types::*;
 
@@ -120,6 +231,7 @@ pub mod prelude {
                       TextureUnit2Context, TextureUnit3Context,
                       TextureUnit4Context, TextureUnit5Context,
                       TextureUnit6Context, TextureUnit7Context};
+    #[cfg(feature = "gles3")] pub use context::TextureUnitBinding3d;
     pub use context::ext::*;
     pub use shader::ContextShaderBuilderExt;
     pub use vertex_buffer::{VertexBufferContext, IndexBufferContext,
@@ -1,22 +1,72 @@
-// Used to specify checks that shouldn't fail (but might in unsafe)
+// Used to specify checks that shouldn't fail (but might in unsafe).
+// The behavior depends on the current thread's `ErrorPolicy`. The `args`
+// are only used for tracing GL calls (with the `trace` feature).
 macro_rules! dbg_gl_error {
-    ($($pat:pat => $msg:expr),*) => {
-        if cfg!(debug_assertions) {
+    (call $call:expr; $($pat:pat => $msg:expr),*) => {
+        dbg_gl_error! { call $call; args (); $($pat => $msg),* }
+    };
+    (call $call:expr; args ($($arg:expr),*); $($pat:pat => $msg:expr),*) => {{
+        let policy = $crate::error_policy::policy();
+        let check = $crate::call_trace::enabled() || match policy {
+            $crate::types::ErrorPolicy::Panic => cfg!(debug_assertions),
+            $crate::types::ErrorPolicy::Result => true,
+            $crate::types::ErrorPolicy::Ignore => false
+        };
+        if check {
             let err = $crate::Context::get_error();
-            match err {
-                $(Some($pat) => {
-                    panic!("OpenGL error {:?} - {}", err, $msg)
-                }),*
-                None => { }
+            $crate::call_trace::trace_call($call,
+                                           &[$(&$arg as &::std::fmt::Debug),*],
+                                           err.as_ref());
+            if let Some(err) = err {
+                // Context loss isn't a programming error, so it never panics
+                let lost = match err {
+                    $crate::types::GLError::ContextLost => {
+                        $crate::context_loss::mark_lost();
+                        true
+                    },
+                    _ => false
+                };
+                match policy {
+                    $crate::types::ErrorPolicy::Panic => {
+                        if cfg!(debug_assertions) && !lost {
+                            match err {
+                                $($pat => {
+                                    panic!("OpenGL error {:?} in {} - {}",
+                                           err, $call, $msg)
+                                }),*
+                            }
+                        }
+                    },
+                    $crate::types::ErrorPolicy::Result => {
+                        let gl_enum = err.gl_enum().unwrap_or(0);
+                        let msg: &str = match err { $($pat => $msg),* };
+                        let call_err = $crate::types::GLCallError {
+                            call: $call,
+                            gl_enum: gl_enum,
+                            message: Some(msg.to_owned())
+                        };
+                        $crate::error_policy::record(
+                            $crate::types::GLError::Call(call_err)
+                        );
+                    },
+                    $crate::types::ErrorPolicy::Ignore => { }
+                }
             }
         }
-    }
+    }}
 }
 
 // Used to specify checks that should *never* be able to fail (even in unsafe!)
 macro_rules! dbg_gl_sanity_check {
-    ($($pat:pat => $msg:expr),*) => {
-        dbg_gl_error! { $($pat => concat!("Sanity check failed: ", $msg)),* }
+    (call $call:expr; $($pat:pat => $msg:expr),*) => {
+        dbg_gl_sanity_check! { call $call; args (); $($pat => $msg),* }
+    };
+    (call $call:expr; args ($($arg:expr),*); $($pat:pat => $msg:expr),*) => {
+        dbg_gl_error! {
+            call $call;
+            args ($($arg),*);
+            $($pat => concat!("Sanity check failed: ", $msg)),*
+        }
     }
 }
 
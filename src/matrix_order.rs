@@ -0,0 +1,20 @@
+// Thread-local storage for the active `MatrixOrder`.
+
+use std::cell::Cell;
+use uniform_data::MatrixOrder;
+
+thread_local! {
+    static ORDER: Cell<MatrixOrder> = Cell::new(MatrixOrder::ColumnMajor);
+}
+
+pub fn order() -> MatrixOrder {
+    ORDER.with(|order| order.get())
+}
+
+pub fn replace_order(new_order: MatrixOrder) -> MatrixOrder {
+    ORDER.with(|order| {
+        let old_order = order.get();
+        order.set(new_order);
+        old_order
+    })
+}
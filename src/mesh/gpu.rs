@@ -0,0 +1,404 @@
+//! Contains the [`GpuMesh`](struct.GpuMesh.html) type, which holds mesh
+//! data that has been uploaded to the GPU.
+
+use gl;
+use gl::types::*;
+use context::{Context, ArrayBufferContext, ElementArrayBufferContext,
+              ArrayBufferBinding, ProgramBinding, RawVertexSource};
+#[cfg(feature = "gles3")] use context::VertexSource;
+use vertex_buffer::{VertexBuffer, IndexBuffer, AttribBinder,
+                    VertexBufferBinding, ContextVertexBufferExt,
+                    VertexBufferContext, IndexBufferContext};
+use vertex_data::{VertexData, VertexBytes};
+use index_data::{IndexData, IndexDatum, IndexDatumType};
+use buffer::STATIC_DRAW;
+use types::{DrawingMode, GLError, TRIANGLES};
+use deletion_queue::Owner;
+#[cfg(feature = "gles3")] use deletion_queue::{self, Deleted};
+#[cfg(feature = "gles3")] use state_cache;
+use super::{Mesh, MeshIndex};
+
+/// A mesh that has been uploaded to the GPU, made up of a vertex buffer,
+/// an optional index buffer, and the drawing mode used to draw it.
+///
+/// With the `gles3` feature, the mesh also owns a vertex array object,
+/// which records the mesh's vertex attributes and index buffer when the
+/// mesh is created, so that drawing the mesh only needs to bind the
+/// vertex array object. Otherwise, the vertex attributes are set up from
+/// the mesh's [`AttribBinder`](../vertex_buffer/struct.AttribBinder.html)
+/// each time the mesh is drawn.
+///
+/// Non-indexed meshes use `u16` as their (unused) index type, so they can
+/// be named as `GpuMesh<V>`.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::GpuMesh;
+///
+/// #[derive(Clone, Copy)]
+/// struct Vertex {
+///     position: [f32; 3]
+/// }
+///
+/// impl_vertex_data!(Vertex, position);
+///
+/// # fn main() {
+/// # let mut program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+/// let mut gl = unsafe { glitter::Context::current_context() };
+/// let vertices = [
+///     Vertex { position: [-1.0, -1.0, 0.0] },
+///     Vertex { position: [ 1.0, -1.0, 0.0] },
+///     Vertex { position: [-1.0,  1.0, 0.0] },
+///     Vertex { position: [ 1.0,  1.0, 0.0] }
+/// ];
+/// let indices: [u16; 6] = [0, 1, 2, 2, 1, 3];
+/// let attribs = attrib_pointers! {
+///     position => gl.get_attrib_location(&program, "position").unwrap()
+/// };
+///
+/// let mut quad = GpuMesh::new_indexed(&mut gl,
+///                                     glitter::TRIANGLES,
+///                                     &vertices,
+///                                     &indices,
+///                                     attribs);
+///
+/// let (gl_program, gl) = gl.use_program(&mut program);
+/// quad.draw(gl, &gl_program);
+/// # }
+/// ```
+pub struct GpuMesh<V: VertexData, I: IndexDatum = u16> {
+    vbo: VertexBuffer<V>,
+    ibo: Option<IndexBuffer<I>>,
+    vao: GLuint,
+    owner: Owner,
+    mode: DrawingMode,
+    vertex_count: usize,
+    index_count: usize
+}
+
+#[cfg(feature = "gles3")]
+#[doc(hidden)]
+pub fn _gen_vertex_array() -> GLuint {
+    let mut vao: GLuint = 0;
+    unsafe {
+        gl::GenVertexArrays(1, &mut vao);
+        dbg_gl_sanity_check! {
+            call "glGenVertexArrays";
+            args (1, &mut vao);
+            GLError::InvalidValue => "`n` is negative",
+            _ => "Unknown error"
+        }
+    }
+    vao
+}
+
+// Vertex array objects require OpenGL ES 3.0
+#[cfg(not(feature = "gles3"))]
+#[doc(hidden)]
+pub fn _gen_vertex_array() -> GLuint {
+    0
+}
+
+// Bind a vertex array object through the state cache
+#[cfg(feature = "gles3")]
+#[doc(hidden)]
+pub fn _bind_vertex_array(vao: GLuint) {
+    if !state_cache::bind_vertex_array(vao) {
+        return;
+    }
+    unsafe {
+        gl::BindVertexArray(vao);
+        dbg_gl_sanity_check! {
+            call "glBindVertexArray";
+            args (vao);
+            GLError::InvalidOperation => "`array` is not a vertex array object",
+            _ => "Unknown error"
+        }
+    }
+}
+
+#[cfg(not(feature = "gles3"))]
+#[doc(hidden)]
+pub fn _bind_vertex_array(_vao: GLuint) {
+
+}
+
+#[cfg(feature = "gles3")]
+fn _delete_vertex_array(owner: Owner, vao: GLuint) {
+    state_cache::forget_vertex_array(vao);
+    deletion_queue::delete(owner, Deleted::VertexArray(vao));
+}
+
+#[cfg(not(feature = "gles3"))]
+fn _delete_vertex_array(_owner: Owner, _vao: GLuint) {
+
+}
+
+// Draw the mesh recorded in a vertex array object, with `index_type` set
+// if the vertex array object has an index buffer
+unsafe fn _draw_vertex_array(mode: DrawingMode,
+                             count: usize,
+                             index_type: Option<IndexDatumType>)
+{
+    match index_type {
+        Some(index_type) => {
+            let gl_index_type: GLenum = match index_type {
+                IndexDatumType::UnsignedByte => gl::UNSIGNED_BYTE,
+                IndexDatumType::UnsignedShort => gl::UNSIGNED_SHORT,
+                #[cfg(feature = "gles3")]
+                IndexDatumType::UnsignedInt => gl::UNSIGNED_INT
+            };
+            gl::DrawElements(mode.gl_enum(),
+                             count as GLsizei,
+                             gl_index_type,
+                             ::std::ptr::null());
+            dbg_gl_error! {
+                call "glDrawElements";
+                args (mode, count, gl_index_type, 0);
+                GLError::InvalidEnum => "`mode` or `type` is not an accepted value",
+                GLError::InvalidValue => "`count` is negative",
+                GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+                _ => "Unknown error"
+            }
+        },
+        None => {
+            gl::DrawArrays(mode.gl_enum(), 0, count as GLsizei);
+            dbg_gl_error! {
+                call "glDrawArrays";
+                args (mode, 0, count);
+                GLError::InvalidEnum => "`mode` is not an accepted value",
+                GLError::InvalidValue => "`count` is negative",
+                GLError::InvalidFramebufferOperation => "The current framebuffer is not framebuffer-complete",
+                _ => "Unknown error"
+            }
+        }
+    }
+}
+
+impl<V: VertexData> GpuMesh<V> {
+    /// Upload `vertices` to a new, non-indexed mesh. `attribs` describes
+    /// how each vertex attribute maps to a program attribute.
+    pub fn new(gl: &mut Context,
+               mode: DrawingMode,
+               vertices: &[V],
+               attribs: AttribBinder)
+        -> Self
+        where [V]: VertexBytes
+    {
+        GpuMesh::_new(gl, mode, vertices, None, attribs)
+    }
+}
+
+impl<V: VertexData, I: IndexDatum> GpuMesh<V, I> {
+    /// Upload `vertices` and `indices` to a new, indexed mesh. `attribs`
+    /// describes how each vertex attribute maps to a program attribute.
+    pub fn new_indexed(gl: &mut Context,
+                       mode: DrawingMode,
+                       vertices: &[V],
+                       indices: &[I],
+                       attribs: AttribBinder)
+        -> Self
+        where [V]: VertexBytes, [I]: IndexData
+    {
+        GpuMesh::_new(gl, mode, vertices, Some(indices), attribs)
+    }
+
+    fn _new(gl: &mut Context,
+            mode: DrawingMode,
+            vertices: &[V],
+            indices: Option<&[I]>,
+            attribs: AttribBinder)
+        -> Self
+        where [V]: VertexBytes, [I]: IndexData
+    {
+        let mut vbo = gl.new_vertex_buffer();
+        vbo.bind_attrib_pointers(attribs);
+        let mut ibo = indices.map(|_| gl.new_index_buffer());
+
+        let vao = _gen_vertex_array();
+        _bind_vertex_array(vao);
+        {
+            let (mut gl_vbo, gl) = gl.bind_vertex_buffer(&mut vbo);
+            gl.buffer_vertices(&mut gl_vbo, vertices, STATIC_DRAW);
+            if let (Some(ibo), Some(indices)) = (ibo.as_mut(), indices) {
+                let (mut gl_ibo, gl) = gl.bind_index_buffer(ibo);
+                gl.buffer_indices(&mut gl_ibo, indices, STATIC_DRAW);
+            }
+        }
+        _bind_vertex_array(0);
+
+        GpuMesh {
+            vbo: vbo,
+            ibo: ibo,
+            vao: vao,
+            owner: Owner::current(),
+            mode: mode,
+            vertex_count: vertices.len(),
+            index_count: indices.map_or(0, |indices| indices.len())
+        }
+    }
+
+    /// Draw the mesh, using the program that is currently in use.
+    ///
+    /// # Note
+    /// Without the `gles3` feature, drawing the mesh binds its vertex
+    /// buffer and index buffer, so `gl` must have free `GL_ARRAY_BUFFER` and
+    /// `GL_ELEMENT_ARRAY_BUFFER` bindings.
+    pub fn draw<C>(&mut self, gl: C, _gl_program: &ProgramBinding)
+        where C: ArrayBufferContext, C::Rest: ElementArrayBufferContext
+    {
+        if self.vao != 0 {
+            let (count, index_type) = match self.ibo {
+                Some(_) => (self.index_count, Some(I::index_datum_type())),
+                None => (self.vertex_count, None)
+            };
+            _bind_vertex_array(self.vao);
+            unsafe {
+                _draw_vertex_array(self.mode, count, index_type);
+            }
+            _bind_vertex_array(0);
+            return;
+        }
+
+        let mode = self.mode;
+        let (gl_vbo, gl) = gl.bind_vertex_buffer(&mut self.vbo);
+        match self.ibo {
+            Some(ref mut ibo) => {
+                let (gl_ibo, gl) = gl.bind_index_buffer(ibo);
+                gl.draw_elements_buffered_vbo(&gl_vbo, &gl_ibo, mode);
+            },
+            None => {
+                gl.draw_arrays_vbo(&gl_vbo, mode);
+            }
+        }
+    }
+
+    /// Bind the mesh's vertices for drawing, returning a binding that can
+    /// be used as a [`RawVertexSource`]
+    /// (../../context/draw_context/trait.RawVertexSource.html), and the
+    /// remaining context. With the `gles3` feature, the mesh's vertex array
+    /// object stays bound until the binding is dropped, and the binding is
+    /// also a [`VertexSource`]
+    /// (../../context/draw_context/trait.VertexSource.html), so it can be
+    /// drawn with the safe [`gl.draw`]
+    /// (../../context/draw_context/trait.ContextDrawExt.html#method.draw)
+    /// methods.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// # let mut program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+    /// # let mut mesh: glitter::GpuMesh<[f32; 2]> = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let (gl_program, gl) = gl.use_program(&mut program);
+    /// let (gl_mesh, gl) = mesh.bind_vertices(gl);
+    ///
+    /// // With the `gles3` feature, this can be `gl.draw_range(...)` instead
+    /// unsafe {
+    ///     gl.draw_range_raw(&gl_program, &gl_mesh, glitter::TRIANGLES, 0, 3);
+    /// }
+    /// # }
+    /// ```
+    pub fn bind_vertices<C>(&mut self, gl: C)
+        -> (GpuMeshBinding<V>, C::Rest)
+        where C: ArrayBufferContext
+    {
+        _bind_vertex_array(self.vao);
+        let (gl_vbo, gl) = gl.bind_vertex_buffer(&mut self.vbo);
+        (GpuMeshBinding { gl_vbo: gl_vbo, vao: self.vao }, gl)
+    }
+
+    /// Get the drawing mode used to draw the mesh.
+    pub fn mode(&self) -> DrawingMode {
+        self.mode
+    }
+
+    /// Set the drawing mode used to draw the mesh.
+    pub fn set_mode(&mut self, mode: DrawingMode) {
+        self.mode = mode;
+    }
+
+    /// Get the number of vertices in the mesh.
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    /// Get the number of indices in the mesh, or `None` if the mesh is
+    /// not indexed.
+    pub fn index_count(&self) -> Option<usize> {
+        self.ibo.as_ref().map(|_| self.index_count)
+    }
+
+    /// Get a reference to the mesh's vertex buffer.
+    pub fn vertex_buffer(&self) -> &VertexBuffer<V> {
+        &self.vbo
+    }
+
+    /// Get a reference to the mesh's index buffer, if it has one.
+    pub fn index_buffer(&self) -> Option<&IndexBuffer<I>> {
+        self.ibo.as_ref()
+    }
+}
+
+/// The vertices of a [`GpuMesh`](struct.GpuMesh.html) that have been
+/// bound for drawing with [`gpu_mesh.bind_vertices`]
+/// (struct.GpuMesh.html#method.bind_vertices).
+///
+/// With the `gles3` feature, the mesh's vertex array object is bound while
+/// the binding is alive, so the only vertex attribute arrays that are
+/// enabled are the mesh's own, and every one of them reads from the
+/// mesh's vertex buffer. This makes the binding a [`VertexSource`]
+/// (../../context/draw_context/trait.VertexSource.html).
+pub struct GpuMeshBinding<'a, V: VertexData + 'a> {
+    gl_vbo: VertexBufferBinding<'a, V>,
+    vao: GLuint
+}
+
+unsafe impl<'a, V: VertexData> RawVertexSource for GpuMeshBinding<'a, V> {
+    fn array_buffer(&self) -> &ArrayBufferBinding {
+        self.gl_vbo.array_buffer()
+    }
+
+    fn vertex_count(&self) -> usize {
+        self.gl_vbo.vertex_count()
+    }
+}
+
+// Every mesh has a vertex array object with the `gles3` feature, which
+// records only the mesh's own vertex attributes
+#[cfg(feature = "gles3")]
+unsafe impl<'a, V: VertexData> VertexSource for GpuMeshBinding<'a, V> {
+
+}
+
+impl<'a, V: VertexData> Drop for GpuMeshBinding<'a, V> {
+    fn drop(&mut self) {
+        if self.vao != 0 {
+            _bind_vertex_array(0);
+        }
+    }
+}
+
+impl<V: VertexData, I: MeshIndex> GpuMesh<V, I> {
+    /// Upload a [`Mesh`](struct.Mesh.html) to the GPU, to be drawn as
+    /// triangles.
+    pub fn from_mesh(gl: &mut Context, mesh: &Mesh<V, I>, attribs: AttribBinder)
+        -> Self
+        where [V]: VertexBytes, [I]: IndexData
+    {
+        GpuMesh::new_indexed(gl, TRIANGLES, &mesh.vertices, &mesh.indices, attribs)
+    }
+}
+
+impl<V: VertexData, I: IndexDatum> Drop for GpuMesh<V, I> {
+    fn drop(&mut self) {
+        if self.vao != 0 {
+            _delete_vertex_array(self.owner, self.vao);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Contains the [`MeshLod`](struct.MeshLod.html) type, which groups several
+//! levels of detail of a mesh into one set of vertices and indices, and
+//! selects a level of detail based on its projected size on screen.
+
+use std::f32;
+use vertex_data::VertexData;
+use super::{Mesh, MeshIndex};
+
+/// A single level of detail of a [`MeshLod`](struct.MeshLod.html), which
+/// is a range of the mesh's indices.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LodLevel {
+    /// The index of the first index of this level of detail.
+    pub first: usize,
+
+    /// The number of indices that make up this level of detail.
+    pub count: usize,
+
+    /// The geometric error of this level of detail, which is the largest
+    /// distance (in object space) between this level of detail and the
+    /// full-detail mesh. The full-detail level should have an error of 0.
+    pub error: f32
+}
+
+/// The camera that a [`MeshLod`](struct.MeshLod.html) level of detail is
+/// selected for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LodCamera {
+    /// The position of the camera, in world space.
+    pub position: [f32; 3],
+
+    /// The number of pixels covered by an object that is 1 unit tall and 1
+    /// unit away from the camera. For a perspective projection, this is
+    /// `viewport_height / (2 * tan(fov_y / 2))`.
+    pub projection_scale: f32
+}
+
+impl LodCamera {
+    /// Create a camera for a perspective projection, with the vertical
+    /// field of view `fov_y` (in radians), and a viewport that is
+    /// `viewport_height` pixels tall.
+    pub fn perspective(position: [f32; 3], fov_y: f32, viewport_height: f32)
+        -> Self
+    {
+        LodCamera {
+            position: position,
+            projection_scale: viewport_height / (2.0 * (fov_y / 2.0).tan())
+        }
+    }
+}
+
+/// A mesh with multiple levels of detail (LODs), where every level of
+/// detail shares the same vertices, and uses a different range of the same
+/// indices. This means that all of the levels of detail can be stored in a
+/// single vertex buffer and a single index buffer, and a level can be
+/// drawn with [`gl.draw_elements_range_buffered_vbo`]
+/// (../../vertex_buffer/trait.ContextVertexBufferExt.html#method.draw_elements_range_buffered_vbo).
+///
+/// A level of detail is selected by projecting each level's geometric
+/// error onto the screen, and choosing the coarsest level where the
+/// projected error is at most `max_pixel_error` pixels.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::{MeshLod, LodCamera};
+///
+/// #[derive(Clone, Copy)]
+/// struct Vertex {
+///     position: [f32; 3]
+/// }
+///
+/// impl_vertex_data!(Vertex, position);
+///
+/// # fn main() {
+/// # let vertices: Vec<Vertex> = vec![];
+/// # let (full_detail, low_detail): (Vec<u16>, Vec<u16>) = (vec![], vec![]);
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let lods = MeshLod::new(vertices,
+///                         vec![(full_detail, 0.0), (low_detail, 0.05)],
+///                         |vertex| vertex.position);
+///
+/// let mut vbo = gl.new_vertex_buffer();
+/// let mut ibo = gl.new_index_buffer();
+/// let (mut gl_vbo, gl) = gl.bind_vertex_buffer(&mut vbo);
+/// let (mut gl_ibo, gl) = gl.bind_index_buffer(&mut ibo);
+/// gl.buffer_vertices(&mut gl_vbo, &lods.mesh.vertices, glitter::STATIC_DRAW);
+/// gl.buffer_indices(&mut gl_ibo, &lods.mesh.indices, glitter::STATIC_DRAW);
+///
+/// // Each frame:
+/// let camera = LodCamera::perspective([0.0, 0.0, 10.0], 1.0, 720.0);
+/// let transform = [[1.0, 0.0, 0.0, 0.0],
+///                  [0.0, 1.0, 0.0, 0.0],
+///                  [0.0, 0.0, 1.0, 0.0],
+///                  [0.0, 0.0, 0.0, 1.0]];
+/// let level = lods.select(&camera, &transform);
+/// gl.draw_elements_range_buffered_vbo(&gl_vbo,
+///                                     &gl_ibo,
+///                                     glitter::TRIANGLES,
+///                                     level.first,
+///                                     level.count);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MeshLod<V: VertexData, I: MeshIndex> {
+    /// The vertices and indices shared by all of the levels of detail.
+    pub mesh: Mesh<V, I>,
+
+    /// The levels of detail, from the finest to the coarsest.
+    pub levels: Vec<LodLevel>,
+
+    /// The center of a sphere (in object space) that bounds the mesh.
+    pub center: [f32; 3],
+
+    /// The radius of a sphere (in object space) that bounds the mesh.
+    pub radius: f32,
+
+    /// The largest projected error (in pixels) that is allowed when
+    /// selecting a level of detail. Defaults to 1.0.
+    pub max_pixel_error: f32
+}
+
+impl<V: VertexData, I: MeshIndex> MeshLod<V, I> {
+    /// Create a new set of levels of detail from a list of vertices, and
+    /// a list of `(indices, error)` pairs for each level of detail (from the
+    /// finest to the coarsest). `position` is used to get the position
+    /// of each vertex, to compute the bounds of the mesh.
+    ///
+    /// # Panics
+    /// This function will panic if `levels` is empty.
+    pub fn new<F>(vertices: Vec<V>, levels: Vec<(Vec<I>, f32)>, position: F)
+        -> Self
+        where F: Fn(&V) -> [f32; 3]
+    {
+        assert!(!levels.is_empty(), "A `MeshLod` needs at least one level");
+
+        let mut indices = vec![];
+        let mut lod_levels = Vec::with_capacity(levels.len());
+        for (level_indices, error) in levels {
+            lod_levels.push(LodLevel {
+                first: indices.len(),
+                count: level_indices.len(),
+                error: error
+            });
+            indices.extend_from_slice(&level_indices);
+        }
+
+        let (center, radius) = _bounding_sphere(&vertices, position);
+
+        MeshLod {
+            mesh: Mesh::new(vertices, indices),
+            levels: lod_levels,
+            center: center,
+            radius: radius,
+            max_pixel_error: 1.0
+        }
+    }
+
+    /// Select the coarsest level of detail whose projected error is at most
+    /// `max_pixel_error` pixels, when the mesh is drawn with the
+    /// object-to-world `transform` (a column-major matrix) and viewed
+    /// from `camera`.
+    pub fn select(&self, camera: &LodCamera, transform: &[[f32; 4]; 4])
+        -> LodLevel
+    {
+        let t = transform;
+        let c = self.center;
+        let world_center = [
+            t[0][0] * c[0] + t[1][0] * c[1] + t[2][0] * c[2] + t[3][0],
+            t[0][1] * c[0] + t[1][1] * c[1] + t[2][1] * c[2] + t[3][1],
+            t[0][2] * c[0] + t[1][2] * c[1] + t[2][2] * c[2] + t[3][2]
+        ];
+
+        // Errors are scaled by the largest scale factor of the transform
+        let scale = (0..3).map(|i| {
+            (t[i][0] * t[i][0] + t[i][1] * t[i][1] + t[i][2] * t[i][2]).sqrt()
+        }).fold(0.0, |a: f32, b| a.max(b));
+
+        let to_camera = [world_center[0] - camera.position[0],
+                         world_center[1] - camera.position[1],
+                         world_center[2] - camera.position[2]];
+        let distance = (to_camera[0] * to_camera[0] +
+                        to_camera[1] * to_camera[1] +
+                        to_camera[2] * to_camera[2]).sqrt();
+
+        self.select_by_distance(distance - self.radius * scale,
+                                scale * camera.projection_scale)
+    }
+
+    /// Select the coarsest level of detail whose projected error is at most
+    /// `max_pixel_error` pixels, when the closest point of the mesh is
+    /// `distance` units away from the camera. `projection_scale` is the
+    /// number of pixels covered by 1 unit of object space, 1 unit away from
+    /// the camera (see [`LodCamera::projection_scale`]
+    /// (struct.LodCamera.html#structfield.projection_scale)).
+    pub fn select_by_distance(&self, distance: f32, projection_scale: f32)
+        -> LodLevel
+    {
+        // Avoid dividing by 0 when the camera is inside the bounds
+        let distance = distance.max(f32::EPSILON);
+
+        let mut selected = self.levels[0];
+        for level in &self.levels {
+            let pixel_error = level.error * projection_scale / distance;
+            if pixel_error <= self.max_pixel_error {
+                selected = *level;
+            }
+            else {
+                break;
+            }
+        }
+        selected
+    }
+}
+
+fn _bounding_sphere<V, F>(vertices: &[V], position: F) -> ([f32; 3], f32)
+    where F: Fn(&V) -> [f32; 3]
+{
+    if vertices.is_empty() {
+        return ([0.0; 3], 0.0);
+    }
+
+    // Use the center of the axis-aligned bounding box
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for vertex in vertices {
+        let p = position(vertex);
+        for i in 0..3 {
+            min[i] = min[i].min(p[i]);
+            max[i] = max[i].max(p[i]);
+        }
+    }
+    let center = [(min[0] + max[0]) / 2.0,
+                  (min[1] + max[1]) / 2.0,
+                  (min[2] + max[2]) / 2.0];
+
+    let radius = vertices.iter().map(|vertex| {
+        let p = position(vertex);
+        let d = [p[0] - center[0], p[1] - center[1], p[2] - center[2]];
+        (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+    }).fold(0.0, |a: f32, b| a.max(b));
+
+    (center, radius)
+}
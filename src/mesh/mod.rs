@@ -0,0 +1,144 @@
+//! Contains the [`Mesh`](struct.Mesh.html) type, which holds indexed
+//! triangle geometry on the CPU, and can optimize it before it is sent
+//! to the GPU, and the [`MeshLod`](struct.MeshLod.html) type, which holds
+//! multiple levels of detail of a mesh. Meshes can be uploaded to the GPU
+//! as a [`GpuMesh`](struct.GpuMesh.html).
+
+use index_data::IndexDatum;
+use vertex_data::VertexData;
+
+pub mod gpu;
+pub mod lod;
+pub mod optimize;
+
+pub use self::gpu::*;
+pub use self::lod::*;
+
+/// An index type that can be used by a [`Mesh`](struct.Mesh.html), and
+/// by the functions in the [`optimize`](optimize/index.html) module.
+pub trait MeshIndex: IndexDatum + Copy {
+    /// Convert the index to a `usize`.
+    fn to_usize(self) -> usize;
+
+    /// Convert a `usize` to an index. `index` will always be within the
+    /// range of indices that were already present in the mesh.
+    fn from_usize(index: usize) -> Self;
+}
+
+impl MeshIndex for u8 {
+    fn to_usize(self) -> usize { self as usize }
+    fn from_usize(index: usize) -> Self { index as u8 }
+}
+
+impl MeshIndex for u16 {
+    fn to_usize(self) -> usize { self as usize }
+    fn from_usize(index: usize) -> Self { index as u16 }
+}
+
+#[cfg(feature = "gles3")]
+impl MeshIndex for u32 {
+    fn to_usize(self) -> usize { self as usize }
+    fn from_usize(index: usize) -> Self { index as u32 }
+}
+
+/// A list of vertices, along with a list of indices into the vertices,
+/// where every 3 indices describe a triangle (as with `GL_TRIANGLES`).
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::Mesh;
+///
+/// #[derive(Clone, Copy)]
+/// struct Vertex {
+///     position: [f32; 3]
+/// }
+///
+/// impl_vertex_data!(Vertex, position);
+///
+/// # fn main() {
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let vertices = vec![
+///     Vertex { position: [-1.0, -1.0, 0.0] },
+///     Vertex { position: [ 1.0, -1.0, 0.0] },
+///     Vertex { position: [-1.0,  1.0, 0.0] },
+///     Vertex { position: [ 1.0,  1.0, 0.0] }
+/// ];
+/// let indices: Vec<u16> = vec![0, 1, 2, 2, 1, 3];
+///
+/// let mut mesh = Mesh::new(vertices, indices);
+/// mesh.optimize_with_overdraw(|vertex| vertex.position);
+///
+/// let mut vbo = gl.new_vertex_buffer();
+/// let mut ibo = gl.new_index_buffer();
+/// {
+///     let (mut gl_vbo, gl) = gl.bind_vertex_buffer(&mut vbo);
+///     let (mut gl_ibo, gl) = gl.bind_index_buffer(&mut ibo);
+///     gl.buffer_vertices(&mut gl_vbo, &mesh.vertices, glitter::STATIC_DRAW);
+///     gl.buffer_indices(&mut gl_ibo, &mesh.indices, glitter::STATIC_DRAW);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Mesh<V: VertexData, I: MeshIndex> {
+    /// The vertices of the mesh.
+    pub vertices: Vec<V>,
+
+    /// The indices of the mesh, where every 3 indices make up a triangle.
+    pub indices: Vec<I>
+}
+
+impl<V: VertexData, I: MeshIndex> Mesh<V, I> {
+    /// Create a new mesh from a list of vertices and a list of indices.
+    pub fn new(vertices: Vec<V>, indices: Vec<I>) -> Self {
+        Mesh {
+            vertices: vertices,
+            indices: indices
+        }
+    }
+
+    /// Reorder the mesh's indices and vertices to make better use of the
+    /// GPU's vertex caches. Vertices that aren't used by any triangle
+    /// are removed.
+    ///
+    /// # Panics
+    /// This function will panic if an index is out of bounds.
+    ///
+    /// # See also
+    /// [`optimize::optimize_vertex_cache`]
+    /// (optimize/fn.optimize_vertex_cache.html) and
+    /// [`optimize::optimize_vertex_fetch`]
+    /// (optimize/fn.optimize_vertex_fetch.html): The optimizations
+    /// that are performed.
+    pub fn optimize(&mut self) {
+        optimize::optimize_vertex_cache(&mut self.indices,
+                                        self.vertices.len());
+        self.vertices = optimize::optimize_vertex_fetch(&mut self.indices,
+                                                        &self.vertices);
+    }
+
+    /// Reorder the mesh's indices and vertices to make better use of the
+    /// GPU's vertex caches, and to reduce overdraw. `position` is used to
+    /// get the position of each vertex. Vertices that aren't used by any
+    /// triangle are removed.
+    ///
+    /// # Panics
+    /// This function will panic if an index is out of bounds.
+    ///
+    /// # See also
+    /// [`optimize::optimize_overdraw`](optimize/fn.optimize_overdraw.html):
+    /// The overdraw optimization that is performed (in addition to those
+    /// performed by [`Mesh::optimize`](struct.Mesh.html#method.optimize)).
+    pub fn optimize_with_overdraw<F>(&mut self, position: F)
+        where F: Fn(&V) -> [f32; 3]
+    {
+        optimize::optimize_vertex_cache(&mut self.indices,
+                                        self.vertices.len());
+        optimize::optimize_overdraw(&mut self.indices,
+                                    &self.vertices,
+                                    position);
+        self.vertices = optimize::optimize_vertex_fetch(&mut self.indices,
+                                                        &self.vertices);
+    }
+}
@@ -0,0 +1,406 @@
+//! Contains functions for optimizing static geometry before it is sent
+//! to the GPU, by reordering indices and vertices to make better use of
+//! the GPU's post-transform vertex cache, to reduce overdraw, and to
+//! improve the locality of vertex fetches.
+//!
+//! The functions should generally be called in the following order:
+//!
+//! 1. [`optimize_vertex_cache`](fn.optimize_vertex_cache.html)
+//! 2. [`optimize_overdraw`](fn.optimize_overdraw.html) (optional)
+//! 3. [`optimize_vertex_fetch`](fn.optimize_vertex_fetch.html)
+//!
+//! [`Mesh::optimize`](../struct.Mesh.html#method.optimize) and
+//! [`Mesh::optimize_with_overdraw`]
+//! (../struct.Mesh.html#method.optimize_with_overdraw) call these functions
+//! in the correct order.
+//!
+//! All of the functions expect the indices to describe a triangle list
+//! (`GL_TRIANGLES`). Any indices after the last complete triangle
+//! are ignored.
+
+use std::cmp::Ordering;
+use super::MeshIndex;
+
+// Tuning parameters from Tom Forsyth's "Linear-Speed Vertex Cache
+// Optimisation" (https://tomforsyth1000.github.io/papers/fast_vert_cache_opt.html)
+const CACHE_SIZE: usize = 32;
+const CACHE_DECAY_POWER: f32 = 1.5;
+const LAST_TRIANGLE_SCORE: f32 = 0.75;
+const VALENCE_BOOST_SCALE: f32 = 2.0;
+const VALENCE_BOOST_POWER: f32 = 0.5;
+
+// The cache size used to find cluster boundaries when optimizing overdraw
+const OVERDRAW_CACHE_SIZE: usize = 16;
+
+fn _vertex_score(cache_position: Option<usize>, remaining: usize) -> f32 {
+    if remaining == 0 {
+        return -1.0;
+    }
+
+    let cache_score = match cache_position {
+        None => 0.0,
+        Some(position) if position < 3 => LAST_TRIANGLE_SCORE,
+        Some(position) => {
+            let scale = 1.0 / (CACHE_SIZE - 3) as f32;
+            (1.0 - (position - 3) as f32 * scale).powf(CACHE_DECAY_POWER)
+        }
+    };
+    let valence_boost = (remaining as f32).powf(-VALENCE_BOOST_POWER);
+
+    cache_score + VALENCE_BOOST_SCALE * valence_boost
+}
+
+fn _triangle<I: MeshIndex>(indices: &[I], triangle: usize) -> [usize; 3] {
+    [indices[triangle * 3].to_usize(),
+     indices[triangle * 3 + 1].to_usize(),
+     indices[triangle * 3 + 2].to_usize()]
+}
+
+fn _sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn _dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn _cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1],
+     a[2] * b[0] - a[0] * b[2],
+     a[0] * b[1] - a[1] * b[0]]
+}
+
+// Returns the number of cache misses for each triangle, using a simulated
+// FIFO cache of the given size
+fn _simulate_fifo_cache<I: MeshIndex>(indices: &[I],
+                                      vertex_count: usize,
+                                      cache_size: usize)
+    -> Vec<u8>
+{
+    let triangle_count = indices.len() / 3;
+    let mut timestamps = vec![0; vertex_count];
+    let mut time = cache_size + 1;
+
+    (0..triangle_count).map(|triangle| {
+        let mut misses = 0;
+        for &vertex in &_triangle(indices, triangle) {
+            if time - timestamps[vertex] > cache_size {
+                timestamps[vertex] = time;
+                time += 1;
+                misses += 1;
+            }
+        }
+        misses
+    }).collect()
+}
+
+
+
+/// Reorder the triangles in an index buffer to make better use of the
+/// GPU's post-transform vertex cache, so that each vertex is processed by
+/// the vertex shader as few times as possible. `vertex_count` must be
+/// greater than the largest index.
+///
+/// This uses Tom Forsyth's [linear-speed vertex cache optimization]
+/// (https://tomforsyth1000.github.io/papers/fast_vert_cache_opt.html)
+/// algorithm, which doesn't depend on the exact size of the GPU's cache.
+///
+/// # Panics
+/// This function will panic if an index is greater than or equal
+/// to `vertex_count`.
+pub fn optimize_vertex_cache<I: MeshIndex>(indices: &mut [I],
+                                           vertex_count: usize)
+{
+    let triangle_count = indices.len() / 3;
+    if triangle_count == 0 {
+        return;
+    }
+
+    // Build a list of the (not-yet-emitted) triangles that use each vertex
+    let mut remaining = vec![0; vertex_count];
+    for index in &indices[..triangle_count * 3] {
+        remaining[index.to_usize()] += 1;
+    }
+
+    let mut offsets = Vec::with_capacity(vertex_count);
+    let mut offset = 0;
+    for &count in &remaining {
+        offsets.push(offset);
+        offset += count;
+    }
+
+    let mut adjacency = vec![0; triangle_count * 3];
+    {
+        let mut filled = vec![0; vertex_count];
+        for triangle in 0..triangle_count {
+            for &vertex in &_triangle(indices, triangle) {
+                adjacency[offsets[vertex] + filled[vertex]] = triangle;
+                filled[vertex] += 1;
+            }
+        }
+    }
+
+    let mut cache_positions: Vec<Option<usize>> = vec![None; vertex_count];
+    let mut vertex_scores: Vec<f32> = remaining.iter().map(|&count| {
+        _vertex_score(None, count)
+    }).collect();
+    let mut triangle_scores: Vec<f32> = (0..triangle_count).map(|triangle| {
+        let tri = _triangle(indices, triangle);
+        vertex_scores[tri[0]] + vertex_scores[tri[1]] + vertex_scores[tri[2]]
+    }).collect();
+
+    let mut emitted = vec![false; triangle_count];
+    let mut output = Vec::with_capacity(triangle_count * 3);
+    let mut cache: Vec<usize> = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut scan_position = 0;
+
+    let mut next_triangle = (0..triangle_count).max_by_key(|&triangle| {
+        // `f32` isn't `Ord`, but all of the scores are positive here
+        (triangle_scores[triangle] * 1_000_000.0) as u64
+    });
+
+    while let Some(triangle) = next_triangle {
+        let tri = _triangle(indices, triangle);
+        emitted[triangle] = true;
+        output.extend_from_slice(&indices[triangle * 3..triangle * 3 + 3]);
+
+        // Remove the triangle from each of its vertices' adjacency lists
+        for &vertex in &tri {
+            let start = offsets[vertex];
+            let end = start + remaining[vertex];
+            let position = adjacency[start..end].iter()
+                                                .position(|&t| t == triangle);
+            if let Some(position) = position {
+                adjacency.swap(start + position, end - 1);
+                remaining[vertex] -= 1;
+            }
+        }
+
+        // Move the triangle's vertices to the front of the cache
+        let mut new_cache = tri.to_vec();
+        new_cache.extend(cache.iter()
+                              .filter(|&&vertex| !tri.contains(&vertex))
+                              .cloned());
+        for (position, &vertex) in new_cache.iter().enumerate() {
+            cache_positions[vertex] = if position < CACHE_SIZE {
+                Some(position)
+            }
+            else {
+                None
+            };
+        }
+
+        // Update the scores of every vertex whose cache position changed,
+        // and the scores of their triangles
+        for &vertex in &new_cache {
+            let score = _vertex_score(cache_positions[vertex],
+                                      remaining[vertex]);
+            let delta = score - vertex_scores[vertex];
+            vertex_scores[vertex] = score;
+
+            let start = offsets[vertex];
+            for &adjacent in &adjacency[start..start + remaining[vertex]] {
+                triangle_scores[adjacent] += delta;
+            }
+        }
+
+        new_cache.truncate(CACHE_SIZE);
+        cache = new_cache;
+
+        // Pick the best triangle that uses a vertex in the cache
+        next_triangle = None;
+        let mut best_score = -1.0;
+        for &vertex in &cache {
+            let start = offsets[vertex];
+            for &adjacent in &adjacency[start..start + remaining[vertex]] {
+                if triangle_scores[adjacent] > best_score {
+                    best_score = triangle_scores[adjacent];
+                    next_triangle = Some(adjacent);
+                }
+            }
+        }
+
+        // If none of the cached vertices have any triangles left,
+        // start again from the next triangle that hasn't been emitted
+        if next_triangle.is_none() {
+            while scan_position < triangle_count && emitted[scan_position] {
+                scan_position += 1;
+            }
+            if scan_position < triangle_count {
+                next_triangle = Some(scan_position);
+            }
+        }
+    }
+
+    indices[..output.len()].copy_from_slice(&output);
+}
+
+/// Reorder groups of triangles in an index buffer to reduce overdraw, by
+/// drawing the triangles that are most likely to occlude other triangles
+/// first. This function should be called *after*
+/// [`optimize_vertex_cache`](fn.optimize_vertex_cache.html), since it
+/// preserves the locality of the vertex cache optimization by only
+/// reordering clusters of triangles.
+///
+/// `position` is used to get the position of each vertex.
+///
+/// # Panics
+/// This function will panic if an index is out of bounds of `vertices`.
+pub fn optimize_overdraw<I, V, F>(indices: &mut [I],
+                                  vertices: &[V],
+                                  position: F)
+    where I: MeshIndex, F: Fn(&V) -> [f32; 3]
+{
+    let triangle_count = indices.len() / 3;
+    if triangle_count == 0 {
+        return;
+    }
+
+    // Split the triangles into clusters, where each cluster starts at a
+    // triangle that doesn't share any vertices with the cache
+    let misses = _simulate_fifo_cache(indices,
+                                      vertices.len(),
+                                      OVERDRAW_CACHE_SIZE);
+    let mut cluster_starts = vec![0];
+    for triangle in 1..triangle_count {
+        if misses[triangle] == 3 {
+            cluster_starts.push(triangle);
+        }
+    }
+    if cluster_starts.len() == 1 {
+        return;
+    }
+
+    let mut mesh_centroid = [0.0; 3];
+    for index in &indices[..triangle_count * 3] {
+        let p = position(&vertices[index.to_usize()]);
+        mesh_centroid = [mesh_centroid[0] + p[0],
+                         mesh_centroid[1] + p[1],
+                         mesh_centroid[2] + p[2]];
+    }
+    let scale = 1.0 / (triangle_count * 3) as f32;
+    let mesh_centroid = [mesh_centroid[0] * scale,
+                         mesh_centroid[1] * scale,
+                         mesh_centroid[2] * scale];
+
+    // Clusters that face away from the center of the mesh are more likely
+    // to occlude other clusters, so they are sorted first
+    let cluster_count = cluster_starts.len();
+    let mut clusters: Vec<_> = (0..cluster_count).map(|cluster| {
+        let start = cluster_starts[cluster];
+        let end = cluster_starts.get(cluster + 1)
+                                .cloned()
+                                .unwrap_or(triangle_count);
+
+        let mut centroid = [0.0; 3];
+        let mut normal = [0.0; 3];
+        let mut area = 0.0;
+        for triangle in start..end {
+            let tri = _triangle(indices, triangle);
+            let p0 = position(&vertices[tri[0]]);
+            let p1 = position(&vertices[tri[1]]);
+            let p2 = position(&vertices[tri[2]]);
+
+            let n = _cross(_sub(p1, p0), _sub(p2, p0));
+            let tri_area = _dot(n, n).sqrt();
+            for i in 0..3 {
+                centroid[i] += (p0[i] + p1[i] + p2[i]) / 3.0 * tri_area;
+                normal[i] += n[i];
+            }
+            area += tri_area;
+        }
+
+        let key = if area > 0.0 {
+            let centroid = [centroid[0] / area,
+                            centroid[1] / area,
+                            centroid[2] / area];
+            let length = _dot(normal, normal).sqrt();
+            let normal = if length > 0.0 {
+                [normal[0] / length, normal[1] / length, normal[2] / length]
+            }
+            else {
+                normal
+            };
+            _dot(_sub(centroid, mesh_centroid), normal)
+        }
+        else {
+            0.0
+        };
+
+        (key, start, end)
+    }).collect();
+
+    clusters.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+
+    let mut output = Vec::with_capacity(triangle_count * 3);
+    for &(_, start, end) in &clusters {
+        output.extend_from_slice(&indices[start * 3..end * 3]);
+    }
+    indices[..output.len()].copy_from_slice(&output);
+}
+
+/// Reorder vertices in the order that they are first used by the index
+/// buffer (updating the indices to match), so that vertex fetches are more
+/// likely to hit the GPU's memory caches. Vertices that aren't used by
+/// any index are removed. Returns the reordered vertices.
+///
+/// This function should be called *after* all other optimizations, since
+/// it depends on the final order of the indices.
+///
+/// # Panics
+/// This function will panic if an index is out of bounds of `vertices`.
+pub fn optimize_vertex_fetch<I, V>(indices: &mut [I], vertices: &[V])
+    -> Vec<V>
+    where I: MeshIndex, V: Copy
+{
+    let mut remap: Vec<Option<usize>> = vec![None; vertices.len()];
+    let mut reordered = Vec::with_capacity(vertices.len());
+
+    for index in indices.iter_mut() {
+        let vertex = index.to_usize();
+        let new_vertex = match remap[vertex] {
+            Some(new_vertex) => new_vertex,
+            None => {
+                let new_vertex = reordered.len();
+                reordered.push(vertices[vertex]);
+                remap[vertex] = Some(new_vertex);
+                new_vertex
+            }
+        };
+        *index = I::from_usize(new_vertex);
+    }
+
+    reordered
+}
+
+/// Return the average cache miss ratio (ACMR) of an index buffer, which is
+/// the average number of vertices that need to be transformed for each
+/// triangle, using a simulated FIFO cache of size `cache_size`. The ACMR
+/// ranges from 3.0 (no vertices are reused) to around 0.5 (for an ideal
+/// regular grid), so lower values are better.
+///
+/// # Panics
+/// This function will panic if an index is greater than or equal
+/// to `vertex_count`.
+///
+/// # Example
+/// ```
+/// use glitter::mesh::optimize::average_cache_miss_ratio;
+///
+/// // Two triangles that share an edge
+/// let indices: [u16; 6] = [0, 1, 2, 2, 1, 3];
+/// assert_eq!(average_cache_miss_ratio(&indices, 4, 16), 2.0);
+/// ```
+pub fn average_cache_miss_ratio<I: MeshIndex>(indices: &[I],
+                                              vertex_count: usize,
+                                              cache_size: usize)
+    -> f32
+{
+    let misses = _simulate_fifo_cache(indices, vertex_count, cache_size);
+    if misses.is_empty() {
+        return 0.0;
+    }
+
+    let total = misses.iter().fold(0, |sum, &m| sum + m as usize);
+    total as f32 / misses.len() as f32
+}
@@ -0,0 +1,247 @@
+//! Contains the [`PerFrameUbo`](struct.PerFrameUbo.html) type, which
+//! stores one copy of a uniform block for each frame in flight.
+
+use std::mem;
+use std::ptr;
+use std::marker::PhantomData;
+use std::time::Duration;
+use gl;
+use gl::types::*;
+use context::{AContext, ContextBufferExt};
+use buffer::Buffer;
+use fence::{Fence, FenceStatus, ContextFenceExt};
+use types::{GLObject, GLError};
+use state_cache;
+
+/// A uniform buffer that holds `frames` copies of the uniform block `T`,
+/// one for each frame that can be "in flight" at once. Each frame, the next
+/// copy is written to and bound, so that the CPU never overwrites a copy
+/// that the GPU may still be reading from a previous frame.
+///
+/// Each copy is guarded by a fence. Writing to a copy will first wait for
+/// the fence that was placed after that copy was last used, which only
+/// blocks if the CPU is more than `frames` frames ahead of the GPU.
+///
+/// `T` must match the layout of the uniform block in the shader, which
+/// should use `layout(std140)`.
+///
+/// # Note
+/// Uniform buffers and fences require OpenGL ES 3.0 or OpenGL 3.2, so this
+/// type is only available with the `gles3` feature. Binding
+/// a copy with [`gl.bind_per_frame_ubo`]
+/// (trait.ContextPerFrameUboExt.html#method.bind_per_frame_ubo) doesn't
+/// set which binding index a program's uniform block reads from; use
+/// `layout(binding = N)` in the shader, or `glUniformBlockBinding`.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::ContextPerFrameUboExt;
+///
+/// #[derive(Clone, Copy)]
+/// #[repr(C)]
+/// struct Camera {
+///     view_projection: [[f32; 4]; 4]
+/// }
+///
+/// # fn main() {
+/// # let view_projection = [[0.0; 4]; 4];
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let mut camera_ubo = gl.new_per_frame_ubo::<Camera>(3);
+///
+/// // Each frame:
+/// gl.write_per_frame_ubo(&mut camera_ubo,
+///                        &Camera { view_projection: view_projection });
+/// gl.bind_per_frame_ubo(&camera_ubo, 0);
+/// // Draw everything that uses the uniform block...
+/// gl.fence_per_frame_ubo(&mut camera_ubo);
+/// # }
+/// ```
+pub struct PerFrameUbo<T: Copy> {
+    buffer: Buffer,
+    stride: usize,
+    current: usize,
+    fences: Vec<Option<Fence>>,
+    _phantom: PhantomData<*const T>
+}
+
+impl<T: Copy> PerFrameUbo<T> {
+    /// Get the underlying buffer, which holds all of the copies of
+    /// the uniform block.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Get the number of copies of the uniform block.
+    pub fn frames(&self) -> usize {
+        self.fences.len()
+    }
+
+    /// Get the index of the copy that was most recently written to.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Get the offset (in bytes) of the copy that was most recently
+    /// written to, within the underlying buffer.
+    pub fn offset(&self) -> usize {
+        self.current * self.stride
+    }
+
+    /// Get the distance (in bytes) between each copy of the uniform block,
+    /// which is the size of `T` rounded up to a multiple of
+    /// `GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT`.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+}
+
+fn _bind_uniform_buffer(buffer: &Buffer) {
+    if !state_cache::bind_buffer(gl::UNIFORM_BUFFER, buffer.id()) {
+        return;
+    }
+    unsafe {
+        gl::BindBuffer(gl::UNIFORM_BUFFER, buffer.id());
+        dbg_gl_sanity_check! {
+            call "glBindBuffer";
+            args (gl::UNIFORM_BUFFER, buffer.id());
+            GLError::InvalidEnum => "`target` is not an allowed value",
+            _ => "Unknown error"
+        }
+    }
+}
+
+/// An extension trait that adds [`PerFrameUbo`](struct.PerFrameUbo.html)
+/// methods to OpenGL contexts.
+///
+/// # Note
+/// These methods bind the `GL_UNIFORM_BUFFER` target, which isn't tracked
+/// by the context's bindings. Code that binds other buffers to
+/// `GL_UNIFORM_BUFFER` should not assume that its binding is preserved.
+pub trait ContextPerFrameUboExt: AContext {
+    /// Create a new uniform buffer with `frames` copies of the uniform
+    /// block `T`. The contents of each copy are initially undefined.
+    ///
+    /// # Panics
+    /// This function will panic if `frames` is 0.
+    ///
+    /// # See also
+    /// [`glBufferData`](http://docs.gl/es3/glBufferData) OpenGL docs
+    fn new_per_frame_ubo<T: Copy>(&self, frames: usize) -> PerFrameUbo<T> {
+        assert!(frames > 0, "A `PerFrameUbo` needs at least one frame");
+
+        let mut alignment: GLint = 0;
+        unsafe {
+            gl::GetIntegerv(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT,
+                            &mut alignment);
+        }
+        let alignment = if alignment > 0 { alignment as usize } else { 1 };
+        let size = mem::size_of::<T>();
+        let stride = (size + alignment - 1) / alignment * alignment;
+
+        let buffer = self.gen_buffer();
+        _bind_uniform_buffer(&buffer);
+        unsafe {
+            gl::BufferData(gl::UNIFORM_BUFFER,
+                           (stride * frames) as GLsizeiptr,
+                           ptr::null(),
+                           gl::DYNAMIC_DRAW);
+            dbg_gl_error! {
+                call "glBufferData";
+                args (gl::UNIFORM_BUFFER, stride * frames, gl::DYNAMIC_DRAW);
+                GLError::InvalidValue => "`size` is negative",
+                GLError::OutOfMemory => "Unable to create a large enough buffer",
+                _ => "Unknown error"
+            }
+        }
+
+        PerFrameUbo {
+            buffer: buffer,
+            stride: stride,
+            current: frames - 1,
+            fences: (0..frames).map(|_| None).collect(),
+            _phantom: PhantomData
+        }
+    }
+
+    /// Advance to the next copy of the uniform block, and write `data` to
+    /// it. If the GPU may still be reading from this copy (i.e. its fence
+    /// hasn't been signaled), this function blocks until it is done.
+    ///
+    /// # See also
+    /// [`glClientWaitSync`](http://docs.gl/es3/glClientWaitSync) and
+    /// [`glBufferSubData`](http://docs.gl/es3/glBufferSubData) OpenGL docs
+    fn write_per_frame_ubo<T: Copy>(&self,
+                                    ubo: &mut PerFrameUbo<T>,
+                                    data: &T)
+    {
+        ubo.current = (ubo.current + 1) % ubo.fences.len();
+
+        if let Some(fence) = ubo.fences[ubo.current].take() {
+            let timeout = Duration::from_secs(1);
+            loop {
+                match self.wait_client(&fence, timeout) {
+                    FenceStatus::TimeoutExpired => { },
+                    _ => { break; }
+                }
+            }
+        }
+
+        let offset = ubo.offset();
+        let size = mem::size_of::<T>();
+        _bind_uniform_buffer(&ubo.buffer);
+        unsafe {
+            gl::BufferSubData(gl::UNIFORM_BUFFER,
+                              offset as GLintptr,
+                              size as GLsizeiptr,
+                              data as *const T as *const GLvoid);
+            dbg_gl_error! {
+                call "glBufferSubData";
+                args (gl::UNIFORM_BUFFER, offset, size);
+                GLError::InvalidValue => "`offset` or `size` is out of range",
+                GLError::InvalidOperation => "Object 0 is bound to buffer target",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Bind the copy of the uniform block that was most recently written
+    /// to the uniform buffer binding index `index`.
+    ///
+    /// # See also
+    /// [`glBindBufferRange`](http://docs.gl/es3/glBindBufferRange)
+    /// OpenGL docs
+    fn bind_per_frame_ubo<T: Copy>(&self, ubo: &PerFrameUbo<T>, index: u32) {
+        let offset = ubo.offset();
+        let size = mem::size_of::<T>();
+        unsafe {
+            gl::BindBufferRange(gl::UNIFORM_BUFFER,
+                                index as GLuint,
+                                ubo.buffer.id(),
+                                offset as GLintptr,
+                                size as GLsizeiptr);
+            dbg_gl_error! {
+                call "glBindBufferRange";
+                args (gl::UNIFORM_BUFFER, index, ubo.buffer.id(), offset, size);
+                GLError::InvalidValue => "`index` is too large, or `offset` is not aligned",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Place a fence after all of the OpenGL commands that have been issued
+    /// so far, which guards the copy of the uniform block that was most
+    /// recently written. This should be called after the last draw call that
+    /// reads from the copy.
+    ///
+    /// # See also
+    /// [`glFenceSync`](http://docs.gl/es3/glFenceSync) OpenGL docs
+    fn fence_per_frame_ubo<T: Copy>(&self, ubo: &mut PerFrameUbo<T>) {
+        ubo.fences[ubo.current] = Some(self.fence());
+    }
+}
+
+impl<C: AContext> ContextPerFrameUboExt for C {
+
+}
@@ -0,0 +1,309 @@
+//! Contains the [`PersistentMap`](struct.PersistentMap.html) type, which
+//! keeps a buffer mapped across frames, and the [`ContextBufferStorageExt`]
+//! (trait.ContextBufferStorageExt.html) trait, which allocates immutable
+//! buffer storage.
+
+use std::mem;
+use std::ptr;
+use gl;
+use gl::types::*;
+use context::{BaseContext, ContextBufferExt, BufferBinding, FrameHook};
+use buffer::Buffer;
+use frame_ring::FrameRing;
+use stream_buffer::{StreamRange, _bind_copy_write_buffer};
+use types::{GLObject, GLError};
+use vertex_data::{VertexData, VertexBytes};
+
+bitflags! {
+    /// How the storage of a buffer allocated with [`gl.buffer_storage`]
+    /// (trait.ContextBufferStorageExt.html#method.buffer_storage) can be
+    /// used after it is created.
+    pub flags BufferStorageFlags: ::gl::types::GLbitfield {
+        /// The contents of the buffer can be updated with
+        /// `glBufferSubData`.
+        const DYNAMIC_STORAGE_BIT = ::gl::DYNAMIC_STORAGE_BIT,
+
+        /// The buffer can be mapped for reading.
+        const MAP_READ_BIT = ::gl::MAP_READ_BIT,
+
+        /// The buffer can be mapped for writing.
+        const MAP_WRITE_BIT = ::gl::MAP_WRITE_BIT,
+
+        /// The buffer can stay mapped while the GPU uses it.
+        const MAP_PERSISTENT_BIT = ::gl::MAP_PERSISTENT_BIT,
+
+        /// Writes to a persistently mapped buffer are visible to the GPU
+        /// without being flushed.
+        const MAP_COHERENT_BIT = ::gl::MAP_COHERENT_BIT,
+
+        /// Hints that the storage should be allocated in client memory.
+        const CLIENT_STORAGE_BIT = ::gl::CLIENT_STORAGE_BIT
+    }
+}
+
+/// An extension trait that includes methods for allocating immutable
+/// buffer storage.
+///
+/// # Note
+/// Immutable buffer storage requires OpenGL 4.4, so this trait is only
+/// available with the `gl45` feature.
+pub trait ContextBufferStorageExt: BaseContext {
+    /// Allocate immutable storage of `size` bytes for a buffer. Unlike
+    /// [`gl.buffer_bytes`]
+    /// (../context/buffer_context/trait.ContextBufferExt.html#method.buffer_bytes),
+    /// the storage can't be reallocated afterwards, but it can be mapped
+    /// persistently (see [`PersistentMap`](struct.PersistentMap.html)).
+    ///
+    /// # Panics
+    /// This function will panic if an OpenGL error was generated and
+    /// debug assertions are enabled.
+    ///
+    /// # See also
+    /// [`glBufferStorage`](http://docs.gl/gl4/glBufferStorage) OpenGL docs
+    fn buffer_storage<B>(&self,
+                         gl_buffer: &mut B,
+                         size: usize,
+                         flags: BufferStorageFlags)
+        where B: BufferBinding
+    {
+        unsafe {
+            gl::BufferStorage(gl_buffer.target().gl_enum(),
+                              size as GLsizeiptr,
+                              ptr::null(),
+                              flags.bits());
+            dbg_gl_error! {
+                call "glBufferStorage";
+                args (gl_buffer.target(), size, flags.bits());
+                GLError::InvalidEnum => "Invalid `target`",
+                GLError::InvalidValue => "`size` is not positive, or `flags` is invalid",
+                GLError::InvalidOperation => "Object 0 is bound to buffer target, or the buffer's storage is immutable",
+                GLError::OutOfMemory => "Unable to create a large enough buffer",
+                _ => "Unknown error"
+            }
+        }
+        gl_buffer.buffer_mut()._set_size(size);
+    }
+}
+
+impl<C: BaseContext> ContextBufferStorageExt for C {
+
+}
+
+/// A buffer that stays mapped for its whole lifetime, which is split into
+/// one region for each frame that can be "in flight" at once. This works
+/// like a [`StreamBuffer`](../stream_buffer/struct.StreamBuffer.html), but
+/// data is copied straight into the mapped memory instead of mapping the
+/// buffer each time it is written to.
+///
+/// The buffer is mapped with `MAP_PERSISTENT_BIT` and `MAP_COHERENT_BIT`,
+/// so the GPU sees writes without them being flushed. Each region is
+/// guarded by a fence, which is placed by [`end_frame`]
+/// (struct.PersistentMap.html#method.end_frame), and [`begin_frame`]
+/// (struct.PersistentMap.html#method.begin_frame) waits for the fence of
+/// the next region before it is written to.
+///
+/// # Note
+/// Persistent mapping requires OpenGL 4.4, so this type is only available
+/// with the `gl45` feature.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::PersistentMap;
+///
+/// # fn main() {
+/// # let vertices: Vec<[f32; 2]> = vec![];
+/// let mut gl = unsafe { glitter::Context::current_context() };
+/// let mut map = PersistentMap::new(&gl, 64 * 1024, 3);
+///
+/// // Each frame:
+/// map.begin_frame(&gl);
+/// let range = map.write_vertices(&gl, &vertices).unwrap();
+/// {
+///     let gl_buffer = unsafe { map.buffer_mut() };
+///     let (gl_buffer, gl) = (&mut gl).bind_array_buffer(gl_buffer);
+///     // Set up the vertex attributes, then draw the vertices:
+///     unsafe {
+///         gl.draw_arrays_range(&gl_buffer,
+///                              glitter::TRIANGLES,
+///                              range.first::<[f32; 2]>(),
+///                              range.count::<[f32; 2]>());
+///     }
+/// }
+/// map.end_frame(&gl);
+/// # }
+/// ```
+///
+/// # See also
+/// [`glMapBufferRange`](http://docs.gl/gl4/glMapBufferRange) OpenGL docs
+pub struct PersistentMap {
+    buffer: Buffer,
+    mapped_id: GLuint,
+    ptr: *mut u8,
+    ring: FrameRing
+}
+
+impl PersistentMap {
+    /// Create a new persistently mapped buffer with `frames` regions, each
+    /// of which can hold `frame_size` bytes of data.
+    ///
+    /// # Panics
+    /// This function will panic if `frames` or `frame_size` is 0, or if the
+    /// buffer couldn't be mapped.
+    pub fn new<C>(gl: &C, frame_size: usize, frames: usize) -> Self
+        where C: BaseContext
+    {
+        assert!(frames > 0, "A `PersistentMap` needs at least one frame");
+        assert!(frame_size > 0, "A `PersistentMap` can't have empty frames");
+
+        let mut buffer = gl.gen_buffer();
+        let size = frame_size * frames;
+        let flags = MAP_WRITE_BIT | MAP_PERSISTENT_BIT | MAP_COHERENT_BIT;
+        _bind_copy_write_buffer(&buffer);
+        let ptr = unsafe {
+            gl::BufferStorage(gl::COPY_WRITE_BUFFER,
+                              size as GLsizeiptr,
+                              ptr::null(),
+                              flags.bits());
+            dbg_gl_error! {
+                call "glBufferStorage";
+                args (gl::COPY_WRITE_BUFFER, size, flags.bits());
+                GLError::InvalidValue => "`size` is not positive",
+                GLError::OutOfMemory => "Unable to create a large enough buffer",
+                _ => "Unknown error"
+            }
+
+            let ptr = gl::MapBufferRange(gl::COPY_WRITE_BUFFER,
+                                         0,
+                                         size as GLsizeiptr,
+                                         flags.bits());
+            dbg_gl_error! {
+                call "glMapBufferRange";
+                args (gl::COPY_WRITE_BUFFER, 0, size, flags.bits());
+                GLError::InvalidValue => "The range is out of bounds of the buffer",
+                GLError::InvalidOperation => "The buffer is already mapped, or `access` is incompatible with the buffer's storage flags",
+                _ => "Unknown error"
+            }
+            ptr
+        };
+        assert!(!ptr.is_null(), "Unable to persistently map the buffer");
+        buffer._set_size(size);
+
+        PersistentMap {
+            mapped_id: buffer.id(),
+            buffer: buffer,
+            ptr: ptr as *mut u8,
+            ring: FrameRing::new(frame_size, frames)
+        }
+    }
+
+    /// Get the underlying buffer, which holds all of the regions.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Get a mutable reference to the underlying buffer, which can be used
+    /// to bind it for drawing.
+    ///
+    /// # Safety
+    /// The buffer stays mapped for the lifetime of the `PersistentMap`, so
+    /// it must not be replaced, reallocated, or unmapped through the
+    /// returned reference, since later writes would go through a dangling
+    /// mapping.
+    pub unsafe fn buffer_mut(&mut self) -> &mut Buffer {
+        &mut self.buffer
+    }
+
+    /// Get the number of regions.
+    pub fn frames(&self) -> usize {
+        self.ring.frames()
+    }
+
+    /// Get the size of each region, in bytes.
+    pub fn frame_size(&self) -> usize {
+        self.ring.frame_size()
+    }
+
+    /// Get the number of bytes that are still free in the current region.
+    pub fn remaining(&self) -> usize {
+        self.ring.remaining()
+    }
+
+    /// Advance to the next region, waiting for the GPU to finish reading
+    /// from it if necessary.
+    ///
+    /// # See also
+    /// [`glClientWaitSync`](http://docs.gl/gl4/glClientWaitSync) OpenGL docs
+    pub fn begin_frame<C: BaseContext>(&mut self, gl: &C) {
+        self.ring.begin_frame(gl);
+    }
+
+    /// Place a fence after all of the OpenGL commands that have been issued
+    /// so far, which guards the current region. This should be called after
+    /// the last draw call that reads from the region.
+    ///
+    /// # See also
+    /// [`glFenceSync`](http://docs.gl/gl4/glFenceSync) OpenGL docs
+    pub fn end_frame<C: BaseContext>(&mut self, gl: &C) {
+        self.ring.end_frame(gl);
+    }
+
+    /// Copy `bytes` into the current region, at an offset (within the
+    /// underlying buffer) that is a multiple of `alignment`. Returns the
+    /// range that was written to, or `None` if there isn't enough room
+    /// left in the current region.
+    ///
+    /// # Panics
+    /// This function will panic if the underlying buffer was replaced
+    /// through [`buffer_mut`](#method.buffer_mut), since the mapping would
+    /// no longer be valid.
+    pub fn write_bytes<C>(&mut self,
+                          _gl: &C,
+                          bytes: &[u8],
+                          alignment: usize)
+        -> Option<StreamRange>
+        where C: BaseContext
+    {
+        assert_eq!(self.buffer.id(), self.mapped_id);
+
+        let offset = match self.ring.allocate(bytes.len(), alignment) {
+            Some(offset) => offset,
+            None => { return None; }
+        };
+
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(),
+                                     self.ptr.offset(offset as isize),
+                                     bytes.len());
+        }
+        Some(StreamRange { offset: offset, size: bytes.len() })
+    }
+
+    /// Copy `vertices` into the current region, aligned so that the range's
+    /// [`first`](../stream_buffer/struct.StreamRange.html#method.first)
+    /// vertex can be used for drawing. Returns the range that was written
+    /// to, or `None` if there isn't enough room left in the current region.
+    pub fn write_vertices<C, T>(&mut self, gl: &C, vertices: &[T])
+        -> Option<StreamRange>
+        where C: BaseContext, T: VertexData, [T]: VertexBytes
+    {
+        let bytes: &[u8] = if vertices.is_empty() {
+            &[]
+        }
+        else {
+            vertices.vertex_bytes()
+        };
+        self.write_bytes(gl, bytes, mem::size_of::<T>())
+    }
+}
+
+impl<C: BaseContext> FrameHook<C> for PersistentMap {
+    fn begin_frame(&mut self, gl: &mut C, _index: u64) {
+        PersistentMap::begin_frame(self, &*gl);
+    }
+
+    fn end_frame(&mut self, gl: &mut C, _index: u64) {
+        PersistentMap::end_frame(self, &*gl);
+    }
+}
@@ -0,0 +1,299 @@
+//! Contains the [`PostProcessChain`](struct.PostProcessChain.html) type,
+//! which applies a sequence of fullscreen post-processing passes.
+
+use context::{Context, ContextProgramExt, ContextProgramBuilderExt,
+              ProgramContext, TextureUnit0Context, TextureUnitBinding2d};
+use shader::ContextShaderBuilderExt;
+use program::{Program, ProgramUniform};
+use texture::Texture2d;
+use image_data::ImageFormat;
+use mesh::GpuMesh;
+use vertex_buffer::AttribBinder;
+use uniform_data::UniformData;
+use fullscreen::{FullscreenVertex, FULLSCREEN_VERTEX_SOURCE};
+use render_target::RenderTarget;
+use types::GLError;
+
+fn _message<E: ::std::fmt::Display>(e: E) -> GLError {
+    GLError::Message(e.to_string())
+}
+
+// A single pass of a post-processing chain
+struct PostProcessPass {
+    program: Program,
+    fullscreen: GpuMesh<FullscreenVertex>,
+    source: ProgramUniform
+}
+
+/// A chain of fullscreen post-processing passes (such as bloom, FXAA, and
+/// tonemapping), which ping-pongs between two intermediate render targets.
+///
+/// Each pass draws a fullscreen triangle with its own program. The first
+/// pass reads from the chain's input texture, and each following pass
+/// reads from the output of the pass before it. A pass's program reads
+/// its input from a `sampler2D` uniform named `source`, and takes a
+/// `vec2` attribute named `position` (as in [`FULLSCREEN_VERTEX_SOURCE`]
+/// (../fullscreen/constant.FULLSCREEN_VERTEX_SOURCE.html)).
+///
+/// Uniform values are stored in each pass's program, so other uniforms
+/// only need to be set (with [`set_uniform`]
+/// (struct.PostProcessChain.html#method.set_uniform)) when they change.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::{ImageFormat, PostProcessChain, RenderTarget};
+///
+/// const TONEMAP_SOURCE: &'static str = r##"#version 100
+///     precision mediump float;
+///     uniform sampler2D source;
+///     uniform float exposure;
+///     varying vec2 v_tex_coord;
+///
+///     void main() {
+///         vec3 color = texture2D(source, v_tex_coord).rgb * exposure;
+///         gl_FragColor = vec4(color / (color + 1.0), 1.0);
+///     }
+/// "##;
+///
+/// # fn main() {
+/// # let mut scene: RenderTarget = unsafe { ::std::mem::uninitialized() };
+/// # let mut screen: RenderTarget = unsafe { ::std::mem::uninitialized() };
+/// # let fxaa_source = "";
+/// let mut gl = unsafe { glitter::Context::current_context() };
+/// let mut chain = PostProcessChain::new(&mut gl,
+///                                       1280,
+///                                       720,
+///                                       ImageFormat::rgba8())
+///                                  .unwrap();
+/// let tonemap = chain.add_pass(&mut gl, TONEMAP_SOURCE).unwrap();
+/// chain.add_pass(&mut gl, fxaa_source).unwrap();
+/// chain.set_uniform(&mut gl, tonemap, "exposure", 1.5f32).unwrap();
+///
+/// // Each frame, after drawing the scene:
+/// chain.apply_to(&mut gl, scene.color_texture_mut(0), &mut screen);
+/// # }
+/// ```
+pub struct PostProcessChain {
+    passes: Vec<PostProcessPass>,
+    targets: Vec<RenderTarget>,
+    format: ImageFormat
+}
+
+fn _draw_pass(gl: &mut Context,
+              pass: &mut PostProcessPass,
+              source: &mut Texture2d,
+              target: &mut RenderTarget)
+{
+    let (gl_tex_unit, gl) = gl.active_texture_0();
+    let sampler = gl_tex_unit.sampler();
+    let (_gl_source, _) = gl_tex_unit.bind_texture_2d(source);
+    let (_gl_fbo, gl) = target.bind_for_drawing(gl);
+    let (gl_program, gl) = gl.use_program(&mut pass.program);
+    gl.set_uniform(&gl_program, pass.source, sampler);
+    pass.fullscreen.draw(gl, &gl_program);
+}
+
+impl PostProcessChain {
+    /// Create a new, empty post-processing chain, with intermediate render
+    /// targets of `width` by `height` pixels with the given format. A
+    /// floating-point format can be used to keep HDR values between passes.
+    ///
+    /// # Failures
+    /// An error will be returned if the intermediate render targets
+    /// couldn't be created.
+    pub fn new(gl: &mut Context, width: u32, height: u32, format: ImageFormat)
+        -> Result<Self, GLError>
+    {
+        let ping = try!(RenderTarget::new(gl, width, height, &[format], None));
+        let pong = try!(RenderTarget::new(gl, width, height, &[format], None));
+
+        Ok(PostProcessChain {
+            passes: vec![],
+            targets: vec![ping, pong],
+            format: format
+        })
+    }
+
+    /// Add a pass to the end of the chain, using a fragment shader built
+    /// from `fragment_source` together with [`FULLSCREEN_VERTEX_SOURCE`]
+    /// (../fullscreen/constant.FULLSCREEN_VERTEX_SOURCE.html). The fragment
+    /// shader receives the texture coordinate in the `v_tex_coord` varying.
+    /// Returns the index of the new pass.
+    ///
+    /// # Failures
+    /// An error will be returned if the shader fails to compile, or if the
+    /// program fails to link.
+    pub fn add_pass(&mut self, gl: &mut Context, fragment_source: &str)
+        -> Result<usize, GLError>
+    {
+        let vertex_shader = try! {
+            gl.build_vertex_shader(FULLSCREEN_VERTEX_SOURCE).try_unwrap()
+        };
+        let fragment_shader = try! {
+            gl.build_fragment_shader(fragment_source).try_unwrap()
+        };
+        let program = try! {
+            gl.build_program(&[vertex_shader, fragment_shader]).try_unwrap()
+        };
+        self.add_program(gl, program)
+    }
+
+    /// Add a pass to the end of the chain that uses an existing program.
+    /// Returns the index of the new pass.
+    ///
+    /// # Failures
+    /// An error will be returned if the program doesn't have a `position`
+    /// attribute or a `source` uniform.
+    pub fn add_program(&mut self, gl: &mut Context, program: Program)
+        -> Result<usize, GLError>
+    {
+        let position = try! {
+            gl.get_attrib_location(&program, "position").map_err(_message)
+        };
+        let source = try! {
+            gl.get_uniform_location(&program, "source").map_err(_message)
+        };
+
+        let mut attribs = AttribBinder::new();
+        attribs.add("position", position).unwrap();
+
+        self.passes.push(PostProcessPass {
+            program: program,
+            fullscreen: GpuMesh::fullscreen_triangle(gl, attribs),
+            source: source
+        });
+        Ok(self.passes.len() - 1)
+    }
+
+    /// Set a uniform of the program used by the pass at index `pass`.
+    ///
+    /// # Failures
+    /// An error will be returned if the program doesn't have a uniform
+    /// named `name`.
+    ///
+    /// # Panics
+    /// This function will panic if `pass` is out of range.
+    pub fn set_uniform<T>(&mut self,
+                          gl: &mut Context,
+                          pass: usize,
+                          name: &str,
+                          value: T)
+        -> Result<(), GLError>
+        where T: UniformData
+    {
+        let program = &mut self.passes[pass].program;
+        let uniform = try! {
+            gl.get_uniform_location(program, name).map_err(_message)
+        };
+        let (gl_program, gl) = gl.use_program(program);
+        gl.set_uniform(&gl_program, uniform, value);
+        Ok(())
+    }
+
+    /// Get a mutable reference to the program used by the pass at index
+    /// `pass`.
+    ///
+    /// # Panics
+    /// This function will panic if `pass` is out of range.
+    pub fn program_mut(&mut self, pass: usize) -> &mut Program {
+        &mut self.passes[pass].program
+    }
+
+    /// Get the number of passes in the chain.
+    pub fn pass_count(&self) -> usize {
+        self.passes.len()
+    }
+
+    /// Get the format of the chain's intermediate render targets.
+    pub fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    /// Get the width and height of the chain's intermediate render
+    /// targets, in pixels.
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.targets[0].dimensions()
+    }
+
+    /// Resize the chain's intermediate render targets. This should be
+    /// called whenever the size of the chain's input changes.
+    ///
+    /// # Failures
+    /// An error will be returned if the render targets couldn't be
+    /// recreated.
+    pub fn resize(&mut self, gl: &mut Context, width: u32, height: u32)
+        -> Result<(), GLError>
+    {
+        for target in &mut self.targets {
+            try!(target.resize(gl, width, height));
+        }
+        Ok(())
+    }
+
+    // Run each pass, with the last pass drawing to `output` (if provided).
+    // Returns the index of the intermediate render target that the last
+    // pass drew to otherwise.
+    fn _apply(&mut self,
+              gl: &mut Context,
+              input: &mut Texture2d,
+              mut output: Option<&mut RenderTarget>)
+        -> usize
+    {
+        assert!(!self.passes.is_empty(),
+                "Post-processing chain has no passes");
+
+        let last = self.passes.len() - 1;
+        let (ping, pong) = self.targets.split_at_mut(1);
+        let (ping, pong) = (&mut ping[0], &mut pong[0]);
+        for (i, pass) in self.passes.iter_mut().enumerate() {
+            let (target, previous) = if i % 2 == 0 {
+                (&mut *ping, &mut *pong)
+            }
+            else {
+                (&mut *pong, &mut *ping)
+            };
+            let source = if i == 0 {
+                &mut *input
+            }
+            else {
+                previous.color_texture_mut(0)
+            };
+            let target = match (i == last, output.as_mut()) {
+                (true, Some(output)) => &mut **output,
+                _ => target
+            };
+
+            _draw_pass(gl, pass, source, target);
+        }
+        last % 2
+    }
+
+    /// Run each pass of the chain on `input`, returning the texture that
+    /// the last pass drew to. The returned texture is one of the chain's
+    /// intermediate render targets, so it will be overwritten the next
+    /// time the chain is applied.
+    ///
+    /// # Panics
+    /// This function will panic if the chain has no passes.
+    pub fn apply(&mut self, gl: &mut Context, input: &mut Texture2d)
+        -> &mut Texture2d
+    {
+        let result = self._apply(gl, input, None);
+        self.targets[result].color_texture_mut(0)
+    }
+
+    /// Run each pass of the chain on `input`, with the last pass drawing to
+    /// `output` instead of one of the chain's intermediate render targets.
+    ///
+    /// # Panics
+    /// This function will panic if the chain has no passes.
+    pub fn apply_to(&mut self,
+                    gl: &mut Context,
+                    input: &mut Texture2d,
+                    output: &mut RenderTarget)
+    {
+        self._apply(gl, input, Some(output));
+    }
+}
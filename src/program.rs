@@ -3,7 +3,10 @@
 use std::marker::PhantomData;
 use gl;
 use gl::types::*;
-use types::GLObject;
+use types::{GLObject, Labeled};
+use deletion_queue::{self, Deleted, Owner};
+use state_cache;
+use uniform_data::{UniformData, UniformDatumType};
 
 /// An OpenGL program object.
 ///
@@ -26,14 +29,14 @@ use types::GLObject;
 /// (../context/program_context/struct.ProgramBinding.html) type.
 pub struct Program {
     gl_id: GLuint,
+    owner: Owner,
     _phantom: PhantomData<*mut ()>
 }
 
 impl Drop for Program {
     fn drop(&mut self) {
-        unsafe {
-            gl::DeleteProgram(self.gl_id);
-        }
+        state_cache::forget_program(self.gl_id);
+        deletion_queue::delete(self.owner, Deleted::Program(self.gl_id));
     }
 }
 
@@ -43,6 +46,7 @@ impl GLObject for Program {
     unsafe fn from_raw(id: Self::Id) -> Self {
         Program {
             gl_id: id,
+            owner: Owner::current(),
             _phantom: PhantomData
         }
     }
@@ -52,6 +56,12 @@ impl GLObject for Program {
     }
 }
 
+impl Labeled for Program {
+    fn label_identifier() -> GLenum {
+        gl::PROGRAM
+    }
+}
+
 
 /// An OpenGL generic vertex attribute.
 ///
@@ -86,3 +96,228 @@ pub struct ProgramUniform {
     /// The index of the the program uniform.
     pub gl_index: GLuint
 }
+
+impl ProgramUniform {
+    /// Get the uniform for the element `index` of a uniform array, where
+    /// `self` is the first element of the array.
+    ///
+    /// # Note
+    /// This assumes that the elements of the array have consecutive
+    /// locations, which is only guaranteed for arrays of basic types (such
+    /// as `vec3 u_positions[4]`) with an explicit location. For arrays of
+    /// structs, look up the member of each element by its full name
+    /// instead, such as `u_lights[3].position`:
+    ///
+    /// ```no_run
+    /// use glitter::prelude::*;
+    ///
+    /// # let gl = unsafe { glitter::Context::current_context() };
+    /// # let program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+    /// let name = format!("u_lights[{}].position", 3);
+    /// let position = gl.get_uniform_location(&program, &name).ok();
+    /// ```
+    pub fn element(&self, index: u32) -> ProgramUniform {
+        ProgramUniform { gl_index: self.gl_index + index }
+    }
+}
+
+/// An OpenGL uniform block within a program, which reads its uniforms from
+/// a buffer bound to one of the uniform buffer binding points.
+///
+/// # Note
+/// Uniform blocks require OpenGL ES 3.0, so this type is only available
+/// with the `gles3` feature.
+///
+/// # See also
+/// [`gl.get_uniform_block_index`](context/program_context/trait.ContextProgramExt.html#method.get_uniform_block_index):
+/// Get a `ProgramUniformBlock` from a uniform block's name within a program.
+///
+/// [`gl.uniform_block_binding`](context/program_context/trait.ContextProgramExt.html#method.uniform_block_binding):
+/// Set the binding point that a program's uniform block reads from.
+#[cfg(feature = "gles3")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramUniformBlock {
+    /// The index of the uniform block.
+    pub gl_index: GLuint
+}
+
+/// A uniform with an explicit location, declared in GLSL with a
+/// `layout(location = N)` qualifier. A `UniformLocation` can be declared
+/// as a constant, so that setting the uniform doesn't require looking up
+/// its location by name. The type parameter `T` is the type of
+/// [`UniformData`](../uniform_data/trait.UniformData.html) that the uniform
+/// holds.
+///
+/// Explicit uniform locations require OpenGL 4.3 or the
+/// `ARB_explicit_uniform_location` extension.
+///
+/// # Example
+/// ```no_run
+/// use std::marker::PhantomData;
+/// use glitter::prelude::*;
+/// use glitter::UniformLocation;
+///
+/// // layout(location = 0) uniform mat4 u_transform;
+/// const TRANSFORM: UniformLocation<[[f32; 4]; 4]> = UniformLocation {
+///     name: "u_transform",
+///     location: 0,
+///     phantom: PhantomData
+/// };
+///
+/// # let gl = unsafe { glitter::Context::current_context() };
+/// # let shaders = vec![];
+/// let mut program = gl.build_program(&shaders)
+///                     .uniform_locations(&[TRANSFORM.declaration()])
+///                     .unwrap();
+/// let (gl_program, gl) = gl.use_program(&mut program);
+/// gl.set_uniform_at(&gl_program, &TRANSFORM, [[0.0; 4]; 4]);
+/// ```
+///
+/// # See also
+/// [`gl.check_uniform_locations`](context/program_context/trait.ContextProgramExt.html#method.check_uniform_locations):
+/// Verify declared uniform locations against a linked program.
+#[derive(Debug)]
+pub struct UniformLocation<T: UniformData> {
+    /// The name of the uniform, as declared in GLSL.
+    pub name: &'static str,
+
+    /// The explicit location of the uniform.
+    pub location: GLuint,
+
+    /// Marker for the type of uniform data.
+    pub phantom: PhantomData<T>
+}
+
+impl<T: UniformData> UniformLocation<T> {
+    /// Get the [`ProgramUniform`](struct.ProgramUniform.html) for
+    /// this location.
+    pub fn uniform(&self) -> ProgramUniform {
+        ProgramUniform { gl_index: self.location }
+    }
+
+    /// Get an untyped [`UniformDeclaration`](struct.UniformDeclaration.html)
+    /// for this location, which can be used to verify this location
+    /// against a program.
+    pub fn declaration(&self) -> UniformDeclaration {
+        UniformDeclaration {
+            name: self.name,
+            location: self.location,
+            datum_type: T::uniform_datum_type()
+        }
+    }
+}
+
+/// The name, location, and type of a uniform with an explicit location.
+/// Usually created using [`UniformLocation::declaration`]
+/// (struct.UniformLocation.html#method.declaration).
+#[derive(Debug, Clone, Copy)]
+pub struct UniformDeclaration {
+    /// The name of the uniform, as declared in GLSL.
+    pub name: &'static str,
+
+    /// The explicit location of the uniform.
+    pub location: GLuint,
+
+    /// The expected type of the uniform.
+    pub datum_type: UniformDatumType
+}
+
+/// The locations of each uniform of a [`Uniforms`]
+/// (../uniform_data/trait.Uniforms.html) type within a program, which are
+/// looked up once with [`gl.get_uniform_locations`]
+/// (context/program_context/trait.ContextProgramExt.html#method.get_uniform_locations).
+#[derive(Debug, Clone)]
+pub struct UniformLocations {
+    /// The location of each uniform, in the order that the `Uniforms` type
+    /// visits them, or `None` if the uniform is not active in the program.
+    pub locations: Vec<Option<ProgramUniform>>
+}
+
+impl UniformLocations {
+    /// Get the location of the uniform with the given index, or `None` if
+    /// the uniform is not active in the program.
+    pub fn get(&self, index: usize) -> Option<ProgramUniform> {
+        self.locations.get(index).and_then(|location| *location)
+    }
+}
+
+/// Describes an attribute or uniform variable that is active within a
+/// linked program. OpenGL drivers are free to remove variables that do not
+/// contribute to a program's output, so a variable declared in a shader
+/// may not be active.
+///
+/// # See also
+/// [`gl.get_active_attribs`](context/program_context/trait.ContextProgramExt.html#method.get_active_attribs):
+/// Get the list of a program's active attributes.
+///
+/// [`gl.get_active_uniforms`](context/program_context/trait.ContextProgramExt.html#method.get_active_uniforms):
+/// Get the list of a program's active uniforms.
+#[derive(Debug, Clone)]
+pub struct ActiveVariable {
+    /// The name of the variable. For arrays, OpenGL appends `[0]` to
+    /// the name of the variable.
+    pub name: String,
+
+    /// The number of elements of the variable. This is `1` for
+    /// variables that are not arrays.
+    pub size: GLint,
+
+    /// The raw OpenGL type of the variable (such as `GL_FLOAT_VEC3`).
+    pub gl_type: GLenum
+}
+
+/// A shader storage block (a `buffer` block in GLSL) that is active within
+/// a linked program.
+///
+/// # Note
+/// Shader storage blocks require OpenGL ES 3.1 or OpenGL 4.3, so this type
+/// is only available with the `gles31` feature.
+///
+/// # See also
+/// [`gl.get_shader_storage_blocks`](context/program_context/trait.ContextProgramExt.html#method.get_shader_storage_blocks):
+/// Get the list of a program's active shader storage blocks.
+#[cfg(feature = "gles31")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageBlock {
+    /// The name of the block.
+    pub name: String,
+
+    /// The index of the block within the program.
+    pub index: GLuint,
+
+    /// The shader storage buffer binding point that the block reads from,
+    /// which can be set with `layout(binding = N)`.
+    pub binding: GLuint,
+
+    /// The minimum size (in bytes) of a buffer that backs the block. If the
+    /// block ends with an unsized array, this is the size of the block
+    /// with one element in the array.
+    pub data_size: usize
+}
+
+/// An atomic counter buffer binding point that is used by the `atomic_uint`
+/// variables of a linked program.
+///
+/// # Note
+/// Atomic counters require OpenGL ES 3.1 or OpenGL 4.2, so this type is
+/// only available with the `gles31` feature.
+///
+/// # See also
+/// [`gl.get_atomic_counter_bindings`](context/program_context/trait.ContextProgramExt.html#method.get_atomic_counter_bindings):
+/// Get the list of the atomic counter buffers used by a program.
+#[cfg(feature = "gles31")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtomicCounterBinding {
+    /// The index of the atomic counter buffer within the program.
+    pub index: GLuint,
+
+    /// The atomic counter buffer binding point that the counters are read
+    /// from, which can be set with `layout(binding = N)`.
+    pub binding: GLuint,
+
+    /// The minimum size (in bytes) of a buffer that backs the counters.
+    pub data_size: usize,
+
+    /// The number of `atomic_uint` variables that use the binding point.
+    pub active_counters: usize
+}
@@ -0,0 +1,240 @@
+//! Contains the [`ProgramBinary`](struct.ProgramBinary.html) type, which
+//! holds the compiled binary of a linked program, so that it can be cached
+//! and loaded again without compiling the program's shaders.
+
+use std::ptr;
+use gl;
+use gl::types::*;
+use context::{BaseContext, ContextProgramExt};
+use program::Program;
+use types::{GLObject, GLError};
+
+/// The compiled binary of a linked program, which can be retrieved with
+/// [`gl.get_program_binary`]
+/// (trait.ContextProgramBinaryExt.html#method.get_program_binary) and
+/// loaded with [`gl.program_from_binary`]
+/// (trait.ContextProgramBinaryExt.html#method.program_from_binary).
+///
+/// The format of a program binary is specific to the OpenGL driver, so a
+/// binary should only be loaded by the same driver (and driver version)
+/// that created it. Loading a binary from a different driver will fail,
+/// after which the program should be compiled from its shaders again.
+///
+/// # Note
+/// Program binaries require OpenGL ES 3.0 or OpenGL 4.1, so this type is
+/// only available with the `gles3` feature.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use std::fs::File;
+/// use std::io::{Read, Write};
+/// use glitter::prelude::*;
+/// use glitter::{ContextProgramBinaryExt, ProgramBinary};
+///
+/// # fn main() {
+/// # let shaders: Vec<glitter::Shader> = vec![];
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let mut cached = vec![];
+/// let read = File::open("program.bin")
+///     .and_then(|mut file| file.read_to_end(&mut cached));
+/// let binary = match read {
+///     Ok(_) => ProgramBinary::from_bytes(&cached),
+///     Err(_) => None
+/// };
+///
+/// let program = binary.and_then(|binary| {
+///     gl.program_from_binary(&binary).ok()
+/// });
+/// let program = match program {
+///     Some(program) => program,
+///     None => {
+///         let program = gl.build_program(&shaders)
+///                         .binary_retrievable()
+///                         .unwrap();
+///         if let Some(binary) = gl.get_program_binary(&program) {
+///             let mut file = File::create("program.bin").unwrap();
+///             file.write_all(&binary.to_bytes()).unwrap();
+///         }
+///         program
+///     }
+/// };
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramBinary {
+    /// The driver-specific format of the binary.
+    pub format: GLenum,
+
+    /// The binary data.
+    pub data: Vec<u8>
+}
+
+impl ProgramBinary {
+    /// Convert the binary into bytes that can be written to a file, which
+    /// include the binary's format followed by its data.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let format = self.format as u32;
+        let mut bytes = Vec::with_capacity(4 + self.data.len());
+        bytes.extend_from_slice(&[format as u8,
+                                  (format >> 8) as u8,
+                                  (format >> 16) as u8,
+                                  (format >> 24) as u8]);
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// Read a binary from bytes that were created with [`to_bytes`]
+    /// (struct.ProgramBinary.html#method.to_bytes). Returns `None` if there
+    /// are too few bytes to contain a binary.
+    ///
+    /// # Example
+    /// ```
+    /// use glitter::ProgramBinary;
+    ///
+    /// let binary = ProgramBinary { format: 0x1234, data: vec![1, 2, 3] };
+    /// let bytes = binary.to_bytes();
+    /// assert_eq!(ProgramBinary::from_bytes(&bytes), Some(binary));
+    /// assert_eq!(ProgramBinary::from_bytes(&bytes[..2]), None);
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 4 {
+            return None;
+        }
+
+        let format = (bytes[0] as u32) |
+                     (bytes[1] as u32) << 8 |
+                     (bytes[2] as u32) << 16 |
+                     (bytes[3] as u32) << 24;
+        Some(ProgramBinary {
+            format: format as GLenum,
+            data: bytes[4..].to_vec()
+        })
+    }
+}
+
+/// An extension trait that adds methods for saving and loading program
+/// binaries to OpenGL contexts.
+pub trait ContextProgramBinaryExt: BaseContext {
+    /// Hint that the binary of the program will be retrieved with
+    /// [`gl.get_program_binary`]
+    /// (trait.ContextProgramBinaryExt.html#method.get_program_binary). This
+    /// should be called before the program is linked.
+    ///
+    /// # See also
+    /// [`glProgramParameteri`](http://docs.gl/es3/glProgramParameteri)
+    /// OpenGL docs
+    ///
+    /// [`ProgramBuilder::binary_retrievable`]
+    /// (../context/program_context/struct.ProgramBuilder.html#method.binary_retrievable):
+    /// Set this hint when building a program.
+    fn set_program_binary_retrievable(&self, program: &mut Program) {
+        unsafe {
+            gl::ProgramParameteri(program.id(),
+                                  gl::PROGRAM_BINARY_RETRIEVABLE_HINT,
+                                  gl::TRUE as GLint);
+            dbg_gl_error! {
+                call "glProgramParameteri";
+                args (program.id(), gl::PROGRAM_BINARY_RETRIEVABLE_HINT, gl::TRUE);
+                GLError::InvalidValue => "`program` is not a value generated by OpenGL",
+                GLError::InvalidOperation => "`program` is not a program object",
+                _ => "Unknown error"
+            }
+        }
+    }
+
+    /// Get the binary of a linked program, or `None` if the driver didn't
+    /// provide a binary for the program.
+    ///
+    /// # See also
+    /// [`glGetProgramBinary`](http://docs.gl/es3/glGetProgramBinary)
+    /// OpenGL docs
+    fn get_program_binary(&self, program: &Program) -> Option<ProgramBinary> {
+        unsafe {
+            let mut length: GLint = 0;
+            gl::GetProgramiv(program.id(),
+                             gl::PROGRAM_BINARY_LENGTH,
+                             &mut length);
+            if length <= 0 {
+                return None;
+            }
+
+            let mut format: GLenum = 0;
+            let mut written: GLsizei = 0;
+            let mut data = vec![0u8; length as usize];
+            gl::GetProgramBinary(program.id(),
+                                 length,
+                                 &mut written,
+                                 &mut format,
+                                 data.as_mut_ptr() as *mut GLvoid);
+            dbg_gl_error! {
+                call "glGetProgramBinary";
+                args (program.id(), length);
+                GLError::InvalidOperation => "`bufSize` is too small, or `program` is not linked",
+                _ => "Unknown error"
+            }
+
+            if written <= 0 {
+                return None;
+            }
+            data.truncate(written as usize);
+            Some(ProgramBinary { format: format, data: data })
+        }
+    }
+
+    /// Create a new program from a program binary. The returned program is
+    /// already linked.
+    ///
+    /// # Failures
+    /// An error will be returned if a program object could not be created,
+    /// or if the driver rejected the binary (such as when it was created by
+    /// a different driver, or an older version of the same driver).
+    ///
+    /// # See also
+    /// [`glProgramBinary`](http://docs.gl/es3/glProgramBinary) OpenGL docs
+    fn program_from_binary(&self, binary: &ProgramBinary)
+        -> Result<Program, GLError>
+    {
+        let program = try! {
+            unsafe { self.create_program() }.or_else(|_| {
+                let msg = "Error creating OpenGL program";
+                Err(GLError::Message(msg.to_owned()))
+            })
+        };
+
+        let link_status = unsafe {
+            let data = if binary.data.is_empty() {
+                ptr::null()
+            }
+            else {
+                binary.data.as_ptr() as *const GLvoid
+            };
+            gl::ProgramBinary(program.id(),
+                              binary.format,
+                              data,
+                              binary.data.len() as GLsizei);
+            // An unsupported format generates `GL_INVALID_ENUM`, which is
+            // reported below as a failed link instead
+            let _ = gl::GetError();
+
+            let mut link_status: GLint = 0;
+            gl::GetProgramiv(program.id(), gl::LINK_STATUS, &mut link_status);
+            link_status
+        };
+
+        if link_status == gl::TRUE as GLint {
+            Ok(program)
+        }
+        else {
+            let msg = match self.get_program_info_log(&program) {
+                Some(log) => format!("Error loading program binary: {}", log),
+                None => String::from("Error loading program binary")
+            };
+            Err(GLError::Message(msg))
+        }
+    }
+}
+
+impl<C: BaseContext> ContextProgramBinaryExt for C {
+
+}
@@ -0,0 +1,76 @@
+//! Exposes the OpenGL [`Query`](struct.Query.html) object, and related types.
+
+use std::marker::PhantomData;
+use gl;
+use gl::types::*;
+use types::{GLObject, Labeled};
+use deletion_queue::{self, Deleted, Owner};
+
+/// An OpenGL query object, which asynchronously measures something about
+/// the commands that the GPU executes (such as how long they took).
+///
+/// The result of a query is only available once the GPU has finished the
+/// measured commands, so results should usually be read a frame or two
+/// after the query was issued, to avoid stalling the CPU.
+///
+/// A query will automatically be deleted after going out of scope.
+///
+/// # See also
+/// [`gl.gen_query`](../context/query_context/trait.ContextQueryExt.html#method.gen_query) -
+/// Create a new query object.
+///
+/// [`GpuProfiler`](../gpu_profiler/struct.GpuProfiler.html): Measures the
+/// GPU time of named scopes using queries.
+pub struct Query {
+    gl_id: GLuint,
+    owner: Owner,
+    _phantom: PhantomData<*mut ()>
+}
+
+impl Drop for Query {
+    fn drop(&mut self) {
+        deletion_queue::delete(self.owner, Deleted::Query(self.gl_id));
+    }
+}
+
+impl GLObject for Query {
+    type Id = GLuint;
+
+    unsafe fn from_raw(id: Self::Id) -> Self {
+        Query {
+            gl_id: id,
+            owner: Owner::current(),
+            _phantom: PhantomData
+        }
+    }
+
+    fn id(&self) -> Self::Id {
+        self.gl_id
+    }
+}
+
+impl Labeled for Query {
+    fn label_identifier() -> GLenum {
+        gl::QUERY
+    }
+}
+
+
+
+gl_enum! {
+    /// The OpenGL targets that a query can measure between
+    /// [`gl.begin_query`](../context/query_context/trait.ContextQueryExt.html#method.begin_query)
+    /// and [`gl.end_query`](../context/query_context/trait.ContextQueryExt.html#method.end_query).
+    pub gl_enum QueryTarget {
+        /// Measures the time (in nanoseconds) that the GPU spent executing
+        /// the commands.
+        pub const TimeElapsed as TIME_ELAPSED = gl::TIME_ELAPSED,
+
+        /// Counts the number of samples that passed the depth test.
+        pub const SamplesPassed as SAMPLES_PASSED = gl::SAMPLES_PASSED,
+
+        /// Measures whether any samples passed the depth test.
+        pub const AnySamplesPassed as ANY_SAMPLES_PASSED =
+            gl::ANY_SAMPLES_PASSED
+    }
+}
@@ -0,0 +1,303 @@
+//! Contains the [`RenderTarget`](struct.RenderTarget.html) type, which
+//! bundles a framebuffer with the textures and renderbuffer that it
+//! renders to.
+
+use context::{Context, ContextExt, FramebufferContext, FramebufferBinding,
+              FramebufferAttachment, ContextFramebufferBuilderExt,
+              ContextRenderbufferBuilderExt, ContextTextureBuilderExt,
+              DEPTH_ATTACHMENT, STENCIL_ATTACHMENT};
+use framebuffer::Framebuffer;
+use renderbuffer::Renderbuffer;
+use texture::{Texture2d, LINEAR, CLAMP_TO_EDGE};
+use image_data::{ImageFormat, RenderbufferFormat};
+use types::{GLError, Viewport};
+
+/// An offscreen render target, made up of a framebuffer, one or more color
+/// textures, and an optional depth (or stencil) renderbuffer.
+///
+/// A render target takes care of creating each attachment with a matching
+/// size, attaching them to the framebuffer, and recreating them when the
+/// target is resized. After drawing to the target, its color textures
+/// can be sampled from in a later pass.
+///
+/// Color textures are created with `GL_LINEAR` filtering and
+/// `GL_CLAMP_TO_EDGE` wrapping, and without mipmaps.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::{ImageFormat, RenderTarget};
+///
+/// # fn main() {
+/// let mut gl = unsafe { glitter::Context::current_context() };
+/// let mut scene = RenderTarget::new(&mut gl,
+///                                   1280,
+///                                   720,
+///                                   &[ImageFormat::rgba8()],
+///                                   Some(glitter::DEPTH_COMPONENT16))
+///                              .unwrap();
+///
+/// {
+///     let (_gl_fbo, gl) = scene.bind_for_drawing(&mut gl);
+///     gl.clear(glitter::COLOR_BUFFER_BIT | glitter::DEPTH_BUFFER_BIT);
+///     // Draw the scene...
+/// }
+///
+/// let (gl_tex_unit, gl) = gl.active_texture_0();
+/// let (_gl_scene, _) = gl_tex_unit.bind_texture_2d(scene.color_texture_mut(0));
+/// // Draw a fullscreen pass, sampling from the scene...
+/// # }
+/// ```
+pub struct RenderTarget {
+    framebuffer: Framebuffer,
+    color_textures: Vec<Texture2d>,
+    depth_renderbuffer: Option<Renderbuffer>,
+    color_formats: Vec<ImageFormat>,
+    depth_format: Option<RenderbufferFormat>,
+    width: u32,
+    height: u32
+}
+
+// The attachments for a render target's framebuffer, before they are
+// attached to it
+struct Attachments {
+    color_textures: Vec<Texture2d>,
+    depth_renderbuffer: Option<Renderbuffer>
+}
+
+fn _depth_attachment(format: RenderbufferFormat) -> FramebufferAttachment {
+    match format {
+        RenderbufferFormat::StencilIndex8 => STENCIL_ATTACHMENT,
+        _ => DEPTH_ATTACHMENT
+    }
+}
+
+#[cfg(feature = "gles3")]
+fn _set_draw_buffers(gl: &mut Context,
+                     framebuffer: &mut Framebuffer,
+                     count: usize)
+    -> Result<(), GLError>
+{
+    if count <= 1 {
+        return Ok(());
+    }
+
+    let attachments: Vec<_> = (0..count as u32).filter_map(|i| {
+        FramebufferAttachment::color(i)
+    }).collect();
+
+    let (mut gl_fbo, gl) = gl.bind_framebuffer(framebuffer);
+    gl.draw_buffers(&mut gl_fbo, &attachments)
+}
+
+#[cfg(not(feature = "gles3"))]
+fn _set_draw_buffers(_gl: &mut Context,
+                     _framebuffer: &mut Framebuffer,
+                     count: usize)
+    -> Result<(), GLError>
+{
+    if count <= 1 {
+        Ok(())
+    }
+    else {
+        let msg = "Error creating render target: multiple color textures require the `gles3` feature";
+        Err(GLError::Message(msg.to_owned()))
+    }
+}
+
+fn _create_attachments(gl: &mut Context,
+                       width: u32,
+                       height: u32,
+                       color_formats: &[ImageFormat],
+                       depth_format: Option<RenderbufferFormat>)
+    -> Result<Attachments, GLError>
+{
+    let mut color_textures = Vec::with_capacity(color_formats.len());
+    for format in color_formats {
+        let texture = try! {
+            gl.build_texture_2d()
+              .empty(*format, width, height)
+              .min_filter(LINEAR)
+              .mag_filter(LINEAR)
+              .wrap_s(CLAMP_TO_EDGE)
+              .wrap_t(CLAMP_TO_EDGE)
+              .try_unwrap()
+        };
+        color_textures.push(texture);
+    }
+
+    let depth_renderbuffer = match depth_format {
+        Some(format) => {
+            let renderbuffer = try! {
+                gl.build_renderbuffer()
+                  .storage(format, width, height)
+                  .try_unwrap()
+            };
+            Some(renderbuffer)
+        },
+        None => None
+    };
+
+    Ok(Attachments {
+        color_textures: color_textures,
+        depth_renderbuffer: depth_renderbuffer
+    })
+}
+
+fn _create_framebuffer(gl: &mut Context,
+                       attachments: &mut Attachments,
+                       depth_format: Option<RenderbufferFormat>)
+    -> Result<Framebuffer, GLError>
+{
+    let mut builder = gl.build_framebuffer();
+    for (i, texture) in attachments.color_textures.iter_mut().enumerate() {
+        let attachment = match FramebufferAttachment::color(i as u32) {
+            Some(attachment) => attachment,
+            None => {
+                let msg = format!("Error creating render target: color attachment {} is not supported", i);
+                return Err(GLError::Message(msg));
+            }
+        };
+        builder = builder.texture_2d(attachment, texture, 0);
+    }
+
+    if let (Some(format), Some(renderbuffer)) =
+        (depth_format, attachments.depth_renderbuffer.as_mut())
+    {
+        builder = builder.renderbuffer(_depth_attachment(format), renderbuffer);
+    }
+
+    builder.try_unwrap()
+}
+
+impl RenderTarget {
+    /// Create a new render target of `width` by `height` pixels. A color
+    /// texture will be created and attached for each format in
+    /// `color_formats` (in order, starting from `COLOR_ATTACHMENT0`), and a
+    /// renderbuffer will be created and attached if `depth_format` is
+    /// provided. A `StencilIndex8` renderbuffer is attached to
+    /// `STENCIL_ATTACHMENT`, and any other format is attached to
+    /// `DEPTH_ATTACHMENT`.
+    ///
+    /// # Failures
+    /// An error will be returned if `color_formats` is empty, if any of the
+    /// attachments couldn't be created, or if the resulting framebuffer is
+    /// not complete. Without the `gles3` feature, an error will also be
+    /// returned if more than one color format is provided.
+    pub fn new(gl: &mut Context,
+               width: u32,
+               height: u32,
+               color_formats: &[ImageFormat],
+               depth_format: Option<RenderbufferFormat>)
+        -> Result<Self, GLError>
+    {
+        if color_formats.is_empty() {
+            let msg = "Error creating render target: no color formats were provided";
+            return Err(GLError::Message(msg.to_owned()));
+        }
+
+        let mut attachments = try! {
+            _create_attachments(gl, width, height, color_formats, depth_format)
+        };
+        let mut framebuffer = try! {
+            _create_framebuffer(gl, &mut attachments, depth_format)
+        };
+        try!(_set_draw_buffers(gl, &mut framebuffer, color_formats.len()));
+
+        Ok(RenderTarget {
+            framebuffer: framebuffer,
+            color_textures: attachments.color_textures,
+            depth_renderbuffer: attachments.depth_renderbuffer,
+            color_formats: color_formats.to_vec(),
+            depth_format: depth_format,
+            width: width,
+            height: height
+        })
+    }
+
+    /// Resize the render target to `width` by `height` pixels, recreating
+    /// all of its attachments. The contents of the previous attachments
+    /// are discarded. If the size hasn't changed, this does nothing.
+    ///
+    /// # Failures
+    /// An error will be returned if the new attachments or framebuffer
+    /// couldn't be created. In that case, the render target is left
+    /// unchanged.
+    pub fn resize(&mut self, gl: &mut Context, width: u32, height: u32)
+        -> Result<(), GLError>
+    {
+        if (width, height) == (self.width, self.height) {
+            return Ok(());
+        }
+
+        let resized = try! {
+            RenderTarget::new(gl,
+                              width,
+                              height,
+                              &self.color_formats,
+                              self.depth_format)
+        };
+        *self = resized;
+        Ok(())
+    }
+
+    /// Bind the render target's framebuffer, and set the viewport to cover
+    /// the whole render target.
+    ///
+    /// # See also
+    /// [`gl.bind_framebuffer`](../context/framebuffer_context/trait.FramebufferContext.html#method.bind_framebuffer):
+    /// Bind a framebuffer without changing the viewport.
+    pub fn bind_for_drawing<'a, C>(&'a mut self, gl: C)
+        -> (FramebufferBinding<'a>, C::Rest)
+        where C: FramebufferContext
+    {
+        let viewport = Viewport::new(0, 0, self.width, self.height);
+        let (gl_fbo, gl) = gl.bind_framebuffer(&mut self.framebuffer);
+        gl.viewport(viewport);
+        (gl_fbo, gl)
+    }
+
+    /// Get the color texture attached to `COLOR_ATTACHMENT{index}`.
+    ///
+    /// # Panics
+    /// This function will panic if `index` is out of range.
+    pub fn color_texture(&self, index: usize) -> &Texture2d {
+        &self.color_textures[index]
+    }
+
+    /// Get a mutable reference to the color texture attached to
+    /// `COLOR_ATTACHMENT{index}`, so that it can be bound.
+    ///
+    /// # Panics
+    /// This function will panic if `index` is out of range.
+    pub fn color_texture_mut(&mut self, index: usize) -> &mut Texture2d {
+        &mut self.color_textures[index]
+    }
+
+    /// Get all of the render target's color textures, in attachment order.
+    pub fn color_textures(&self) -> &[Texture2d] {
+        &self.color_textures
+    }
+
+    /// Get the render target's depth (or stencil) renderbuffer, if it has
+    /// one.
+    pub fn depth_renderbuffer(&self) -> Option<&Renderbuffer> {
+        self.depth_renderbuffer.as_ref()
+    }
+
+    /// Get the render target's framebuffer.
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.framebuffer
+    }
+
+    /// Get a mutable reference to the render target's framebuffer.
+    pub fn framebuffer_mut(&mut self) -> &mut Framebuffer {
+        &mut self.framebuffer
+    }
+
+    /// Get the width and height of the render target, in pixels.
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
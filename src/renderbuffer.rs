@@ -4,7 +4,8 @@
 use std::marker::PhantomData;
 use gl;
 use gl::types::*;
-use types::GLObject;
+use types::{GLObject, Labeled};
+use deletion_queue::{self, Deleted, Owner};
 
 /// An OpenGL renderbuffer object.
 ///
@@ -31,14 +32,13 @@ use types::GLObject;
 /// (../context/renderbuffer_context/struct.RenderbufferBinding.html) type
 pub struct Renderbuffer {
     gl_id: GLuint,
+    owner: Owner,
     _phantom: PhantomData<*mut ()>
 }
 
 impl Drop for Renderbuffer {
     fn drop(&mut self) {
-        unsafe {
-            gl::DeleteRenderbuffers(1, &self.gl_id as *const GLuint);
-        }
+        deletion_queue::delete(self.owner, Deleted::Renderbuffer(self.gl_id));
     }
 }
 
@@ -48,6 +48,7 @@ impl GLObject for Renderbuffer {
     unsafe fn from_raw(id: Self::Id) -> Self {
         Renderbuffer {
             gl_id: id,
+            owner: Owner::current(),
             _phantom: PhantomData
         }
     }
@@ -57,6 +58,12 @@ impl GLObject for Renderbuffer {
     }
 }
 
+impl Labeled for Renderbuffer {
+    fn label_identifier() -> GLenum {
+        gl::RENDERBUFFER
+    }
+}
+
 
 
 gl_enum! {
@@ -0,0 +1,38 @@
+// Thread-local flag for whether bindings restore the previously-bound object
+// when they are dropped. Restoring a binding goes behind the state cache's
+// back, so the state cache is bypassed while this is enabled.
+
+use std::cell::Cell;
+use gl;
+use gl::types::*;
+
+thread_local! {
+    static RESTORE: Cell<bool> = Cell::new(false);
+}
+
+pub fn enabled() -> bool {
+    RESTORE.with(|restore| restore.get())
+}
+
+pub fn replace_enabled(enabled: bool) -> bool {
+    RESTORE.with(|restore| {
+        let old_enabled = restore.get();
+        restore.set(enabled);
+        old_enabled
+    })
+}
+
+// Query the object bound to the binding named by `pname` (such as
+// `GL_ARRAY_BUFFER_BINDING`), so that it can be restored later. Returns
+// `None` without making any OpenGL calls if restoring is disabled.
+pub fn previous(pname: GLenum) -> Option<GLuint> {
+    if !enabled() {
+        return None;
+    }
+
+    let mut id: GLint = 0;
+    unsafe {
+        gl::GetIntegerv(pname, &mut id);
+    }
+    Some(id as GLuint)
+}
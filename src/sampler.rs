@@ -0,0 +1,118 @@
+//! Exposes the OpenGL [`Sampler`](struct.Sampler.html) object, and
+//! related types.
+
+use std::marker::PhantomData;
+use gl;
+use gl::types::*;
+use texture::{TextureMipmapFilter, TextureFilter, TextureWrapMode,
+              NEAREST_MIPMAP_LINEAR};
+use types::{GLObject, Labeled};
+use deletion_queue::{self, Deleted, Owner};
+
+/// The sampling state that is stored by a [`Sampler`](struct.Sampler.html).
+/// Each parameter starts with the same default value as the equivalent
+/// texture parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SamplerParams {
+    /// The minifying filter (`NEAREST_MIPMAP_LINEAR` by default).
+    pub min_filter: TextureMipmapFilter,
+
+    /// The magnifying filter (`LINEAR` by default).
+    pub mag_filter: TextureFilter,
+
+    /// The wrap mode for the s-coordinate (`REPEAT` by default).
+    pub wrap_s: TextureWrapMode,
+
+    /// The wrap mode for the t-coordinate (`REPEAT` by default).
+    pub wrap_t: TextureWrapMode,
+
+    /// The wrap mode for the r-coordinate, which is only used by 3D
+    /// textures (`REPEAT` by default).
+    pub wrap_r: TextureWrapMode
+}
+
+impl Default for SamplerParams {
+    fn default() -> Self {
+        SamplerParams {
+            min_filter: NEAREST_MIPMAP_LINEAR,
+            mag_filter: TextureFilter::Linear,
+            wrap_s: TextureWrapMode::Repeat,
+            wrap_t: TextureWrapMode::Repeat,
+            wrap_r: TextureWrapMode::Repeat
+        }
+    }
+}
+
+/// An OpenGL sampler object, which holds texture filtering and wrapping
+/// state separately from a texture. A sampler that is bound to a texture
+/// unit overrides the sampling parameters of the texture bound to the
+/// same unit, so one sampler can be shared between many textures (and
+/// one texture can be sampled in different ways).
+///
+/// Sampler objects require OpenGL ES 3.0 or OpenGL 3.3. Otherwise, glitter
+/// emulates samplers: the sampler's parameters are only tracked by glitter,
+/// and are copied to a texture's parameters when the sampler is bound
+/// with [`gl.bind_sampler`]
+/// (../context/sampler_context/trait.ContextSamplerExt.html#method.bind_sampler).
+///
+/// A sampler will automatically be deleted after going out of scope.
+///
+/// # See also
+/// [`gl.gen_sampler`](../context/sampler_context/trait.ContextSamplerExt.html#method.gen_sampler):
+/// Create a new sampler.
+pub struct Sampler {
+    gl_id: GLuint,
+    owner: Owner,
+    params: SamplerParams,
+    _phantom: PhantomData<*mut ()>
+}
+
+impl Sampler {
+    /// Get the sampler's current parameters.
+    pub fn params(&self) -> &SamplerParams {
+        &self.params
+    }
+
+    /// Returns `true` if the sampler is emulated with texture parameters,
+    /// because sampler objects aren't supported by the context.
+    pub fn is_emulated(&self) -> bool {
+        self.gl_id == 0
+    }
+
+    // Get the sampler's parameters, to record a parameter change
+    #[doc(hidden)]
+    pub fn _params_mut(&mut self) -> &mut SamplerParams {
+        &mut self.params
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        if self.gl_id != 0 {
+            deletion_queue::delete(self.owner, Deleted::Sampler(self.gl_id));
+        }
+    }
+}
+
+impl GLObject for Sampler {
+    type Id = GLuint;
+
+    unsafe fn from_raw(id: Self::Id) -> Self {
+        Sampler {
+            gl_id: id,
+            owner: Owner::current(),
+            params: SamplerParams::default(),
+            _phantom: PhantomData
+        }
+    }
+
+    fn id(&self) -> Self::Id {
+        self.gl_id
+    }
+}
+
+impl Labeled for Sampler {
+    fn label_identifier() -> GLenum {
+        gl::SAMPLER
+    }
+}
@@ -5,7 +5,10 @@ use std::ptr;
 use gl;
 use gl::types::*;
 use context::{AContext, BaseContext};
-use types::{GLObject, GLError};
+use types::{GLObject, GLError, Labeled};
+use deletion_queue::{self, Deleted, Owner};
+use source_dump::SourceDump;
+use shader_diagnostic::ShaderDiagnostic;
 
 /// An OpenGL shader object.
 ///
@@ -29,14 +32,13 @@ use types::{GLObject, GLError};
 /// Create a new, empty shader object.
 pub struct Shader {
     gl_id: GLuint,
+    owner: Owner,
     _phantom: PhantomData<*mut ()>
 }
 
 impl Drop for Shader {
     fn drop(&mut self) {
-        unsafe {
-            gl::DeleteShader(self.gl_id);
-        }
+        deletion_queue::delete(self.owner, Deleted::Shader(self.gl_id));
     }
 }
 
@@ -46,6 +48,7 @@ impl GLObject for Shader {
     unsafe fn from_raw(id: Self::Id) -> Self {
         Shader {
             gl_id: id,
+            owner: Owner::current(),
             _phantom: PhantomData
         }
     }
@@ -55,6 +58,12 @@ impl GLObject for Shader {
     }
 }
 
+impl Labeled for Shader {
+    fn label_identifier() -> GLenum {
+        gl::SHADER
+    }
+}
+
 
 
 unsafe fn _get_shader_iv(shader: &Shader,
@@ -63,6 +72,8 @@ unsafe fn _get_shader_iv(shader: &Shader,
 {
     gl::GetShaderiv(shader.id(), pname, params);
     dbg_gl_sanity_check! {
+        call "glGetShaderiv";
+        args (shader.id(), pname, params);
         GLError::InvalidEnum => "`pname` is not an accepted value",
         GLError::InvalidValue => "`shader` is not a value generated by OpenGL",
         GLError::InvalidOperation => "`shader` is not a shader object, or `pname` is GL_COMPILE_STATUS, GL_INFO_LOG_LENGTH or GL_SHADER_SOURCE_LENGTH but a shader compiler is not supported",
@@ -77,7 +88,8 @@ pub struct ShaderBuilder<'a, C: 'a>
 {
     gl: &'a C,
     ty: ShaderType,
-    source: &'a str
+    source: &'a str,
+    dump_source: bool
 }
 
 impl<'a, C: 'a> ShaderBuilder<'a, C>
@@ -86,7 +98,18 @@ impl<'a, C: 'a> ShaderBuilder<'a, C>
     fn new(gl: &'a C, ty: ShaderType, source: &'a str)
         -> Self
     {
-        ShaderBuilder { gl: gl, ty: ty, source: source }
+        ShaderBuilder { gl: gl, ty: ty, source: source, dump_source: false }
+    }
+
+    /// Write the shader source (with line numbers) to a file in the
+    /// temporary directory before compiling. If compilation fails, the
+    /// compiler errors are appended to the file, and the file's path is
+    /// included in the returned error. If compilation succeeds, the file is
+    /// removed. If the driver crashes while compiling, the file will
+    /// be left behind.
+    pub fn dump_source_on_failure(mut self) -> Self {
+        self.dump_source = true;
+        self
     }
 
     /// Try to compile a shader with the provided options, or `Err` if
@@ -101,9 +124,30 @@ impl<'a, C: 'a> ShaderBuilder<'a, C>
                 })
             };
 
+            let dump = if self.dump_source {
+                let label = format!("{:?}", self.ty);
+                let sources = [(label, self.source.to_owned())];
+                SourceDump::create("shader", &sources).ok()
+            }
+            else {
+                None
+            };
+
             self.gl.shader_source(&mut shader, self.source);
-            try!(self.gl.compile_shader(&mut shader));
-            Ok(shader)
+            match self.gl.compile_shader(&mut shader) {
+                Ok(()) => {
+                    if let Some(dump) = dump {
+                        dump.remove();
+                    }
+                    Ok(shader)
+                },
+                Err(err) => {
+                    match dump {
+                        Some(dump) => Err(dump.into_error(err)),
+                        None => Err(err)
+                    }
+                }
+            }
         }
     }
 
@@ -193,6 +237,36 @@ pub trait ContextShaderBuilderExt: AContext + Sized {
     {
         self.build_shader(ShaderType::VertexShader, source)
     }
+
+    /// Build a new compute shader with the provided shader source.
+    ///
+    /// # Note
+    /// Compute shaders require OpenGL ES 3.1 or OpenGL 4.3, so this method
+    /// is only available with the `gles31` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// let compute_source = r##"#version 310 es
+    ///     layout(local_size_x = 64) in;
+    ///
+    ///     void main() {
+    ///     }
+    /// "##;
+    ///
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let shader = gl.build_compute_shader(compute_source).unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "gles31")]
+    fn build_compute_shader<'a>(&'a self, source: &'a str)
+        -> ShaderBuilder<'a, Self>
+    {
+        self.build_shader(ShaderType::ComputeShader, source)
+    }
 }
 
 impl<C: AContext> ContextShaderBuilderExt for C {
@@ -254,6 +328,8 @@ pub trait ContextShaderExt: BaseContext {
     {
         let id = gl::CreateShader(shader_type.gl_enum());
         dbg_gl_sanity_check! {
+            call "glCreateShader";
+            args (shader_type);
             GLError::InvalidEnum => "`shaderType` is not an accepted value",
             _ => "Unknown error"
         }
@@ -290,6 +366,8 @@ pub trait ContextShaderExt: BaseContext {
                              &source_ptr as *const *const GLchar,
                              &source_len as *const GLint);
             dbg_gl_error! {
+                call "glShaderSource";
+                args (shader.id(), source);
                 GLError::InvalidOperation => "`shader` is not a shader object or shader compiler is not supported",
                 GLError::InvalidValue => "`shader` is not a value generated by OpenGL or `count` < 0",
                 _ => "Unknown error"
@@ -328,6 +406,8 @@ pub trait ContextShaderExt: BaseContext {
         let success = unsafe {
             gl::CompileShader(shader.id());
             dbg_gl_error! {
+                call "glCompileShader";
+                args (shader.id());
                 GLError::InvalidOperation => "`shader` is not a shader object or shader compiler is not supported",
                 GLError::InvalidValue => "`shader` is not a value generated by OpenGL",
                 _ => "Unknown error"
@@ -399,6 +479,8 @@ pub trait ContextShaderExt: BaseContext {
                                      ptr::null_mut(),
                                      bytes.as_mut_ptr() as *mut GLchar);
                 dbg_gl_sanity_check! {
+                    call "glGetShaderInfoLog";
+                    args (shader.id(), info_length);
                     GLError::InvalidValue => "`shader` is not a value generated by OpenGL, or `maxLength` < 0",
                     GLError::InvalidOperation => "`shader` is not a shader object",
                     _ => "Unknown error"
@@ -413,6 +495,83 @@ pub trait ContextShaderExt: BaseContext {
             }
         }
     }
+
+    /// Get the information log associated with a shader, parsed into a
+    /// list of [`ShaderDiagnostic`]
+    /// (../shader_diagnostic/struct.ShaderDiagnostic.html) entries (one for
+    /// each non-empty line). This can be used to highlight the line that
+    /// caused a compilation error. Returns an empty list if no diagnostic
+    /// information was generated.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    ///
+    /// # fn main() {
+    /// # let shader: glitter::Shader = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// for diagnostic in gl.get_shader_diagnostics(&shader) {
+    ///     if let Some(line) = diagnostic.line {
+    ///         println!("Line {}: {}", line, diagnostic.message);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # See also
+    /// [`glGetShaderInfoLog`](http://docs.gl/es2/glGetShaderInfoLog) OpenGL docs
+    fn get_shader_diagnostics(&self, shader: &Shader)
+        -> Vec<ShaderDiagnostic>
+    {
+        match self.get_shader_info_log(shader) {
+            Some(log) => ShaderDiagnostic::parse_log(&log),
+            None => vec![]
+        }
+    }
+
+    /// Get the source code that was provided to a shader with
+    /// [`gl.shader_source`](trait.ContextShaderExt.html#method.shader_source),
+    /// or `None` if the shader has no source.
+    ///
+    /// # Note
+    /// If the source contained an invalid UTF-8 sequence, `None` will
+    /// be returned.
+    ///
+    /// # See also
+    /// [`glGetShaderSource`](http://docs.gl/es2/glGetShaderSource) OpenGL docs
+    fn get_shader_source(&self, shader: &Shader) -> Option<String> {
+        unsafe {
+            let mut source_length : GLint = 0;
+            _get_shader_iv(shader,
+                           gl::SHADER_SOURCE_LENGTH,
+                           &mut source_length as *mut GLint);
+
+            if source_length > 0 {
+                let mut bytes =
+                    Vec::<u8>::with_capacity(source_length as usize);
+
+                gl::GetShaderSource(shader.id(),
+                                    source_length,
+                                    ptr::null_mut(),
+                                    bytes.as_mut_ptr() as *mut GLchar);
+                dbg_gl_sanity_check! {
+                    call "glGetShaderSource";
+                    args (shader.id(), source_length);
+                    GLError::InvalidValue => "`shader` is not a value generated by OpenGL, or `bufSize` < 0",
+                    GLError::InvalidOperation => "`shader` is not a shader object",
+                    _ => "Unknown error"
+                }
+
+                bytes.set_len((source_length - 1) as usize);
+
+                String::from_utf8(bytes).ok()
+            }
+            else {
+                None
+            }
+        }
+    }
 }
 
 impl<C: BaseContext> ContextShaderExt for C {
@@ -427,6 +586,29 @@ gl_enum! {
 
         /// A shader that is used for processing per-fragment (per-pixel)
         /// data.
-        pub const FragmentShader as FRAGMENT_SHADER = gl::FRAGMENT_SHADER
+        pub const FragmentShader as FRAGMENT_SHADER = gl::FRAGMENT_SHADER,
+
+        /// A shader that is used for general-purpose computation, outside
+        /// of the rendering pipeline. Compute shaders require OpenGL ES 3.1
+        /// or OpenGL 4.3 (see [`ContextComputeExt`]
+        /// (../context/compute_context/trait.ContextComputeExt.html)).
+        pub const ComputeShader as COMPUTE_SHADER = gl::COMPUTE_SHADER,
+
+        /// A shader that is used for processing whole primitives, which can
+        /// emit new primitives. Geometry shaders require OpenGL 3.2 or
+        /// OpenGL ES 3.2.
+        pub const GeometryShader as GEOMETRY_SHADER = gl::GEOMETRY_SHADER,
+
+        /// A shader that is used to control how much each patch is
+        /// tessellated. Tessellation shaders require OpenGL 4.0 or
+        /// OpenGL ES 3.2.
+        pub const TessControlShader as TESS_CONTROL_SHADER =
+            gl::TESS_CONTROL_SHADER,
+
+        /// A shader that is used to compute the position of each vertex
+        /// generated by tessellation. Tessellation shaders require
+        /// OpenGL 4.0 or OpenGL ES 3.2.
+        pub const TessEvaluationShader as TESS_EVALUATION_SHADER =
+            gl::TESS_EVALUATION_SHADER
     }
 }
@@ -0,0 +1,262 @@
+//! Contains the [`ShaderDiagnostic`](struct.ShaderDiagnostic.html) type,
+//! which is parsed from the info log of a shader that failed to compile.
+
+use std::fmt;
+
+/// The severity of a [`ShaderDiagnostic`](struct.ShaderDiagnostic.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticSeverity {
+    /// An error, which prevented the shader from compiling.
+    Error,
+
+    /// A warning, which did not prevent the shader from compiling.
+    Warning,
+
+    /// Any other message in the info log, such as a note or a summary
+    /// of the number of errors.
+    Info
+}
+
+/// A single message from a shader's info log, such as a compilation error.
+///
+/// Each driver formats its info log differently, so the following formats
+/// are recognized:
+///
+/// - Mesa: `0:12(5): error: message`
+/// - NVIDIA: `0(12) : error C1008: message`
+/// - AMD, Intel, ANGLE, and Apple: `ERROR: 0:12: message`
+///
+/// In each format, the number before the line number is the "source string
+/// number", which is the index of the shader source string, or the file
+/// set by a `#line N FILE` directive (see [`ResolvedSource::file_name`]
+/// (../shader_source/struct.ResolvedSource.html#method.file_name)).
+///
+/// Any other line in the info log results in a diagnostic with no
+/// location and a severity of `Info`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShaderDiagnostic {
+    /// The source string number that the message refers to, if any.
+    pub source: Option<u32>,
+
+    /// The line of the shader source that the message refers to, if any.
+    pub line: Option<u32>,
+
+    /// The column of the shader source that the message refers to, if the
+    /// driver reported one.
+    pub column: Option<u32>,
+
+    /// The severity of the message.
+    pub severity: DiagnosticSeverity,
+
+    /// The message itself, without the location or severity.
+    pub message: String
+}
+
+impl ShaderDiagnostic {
+    /// Parse each non-empty line of a shader info log into a diagnostic.
+    ///
+    /// # Example
+    /// ```
+    /// use glitter::{ShaderDiagnostic, DiagnosticSeverity};
+    ///
+    /// let log = "0:12(5): error: `foo' undeclared\n\
+    ///            0(3) : warning C7050: \"bar\" might be used uninitialized";
+    /// let diagnostics = ShaderDiagnostic::parse_log(log);
+    ///
+    /// assert_eq!(diagnostics[0].source, Some(0));
+    /// assert_eq!(diagnostics[0].line, Some(12));
+    /// assert_eq!(diagnostics[0].column, Some(5));
+    /// assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+    /// assert_eq!(diagnostics[0].message, "`foo' undeclared");
+    ///
+    /// assert_eq!(diagnostics[1].source, Some(0));
+    /// assert_eq!(diagnostics[1].line, Some(3));
+    /// assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Warning);
+    /// ```
+    pub fn parse_log(log: &str) -> Vec<ShaderDiagnostic> {
+        log.lines()
+           .map(|line| line.trim())
+           .filter(|line| !line.is_empty())
+           .map(|line| ShaderDiagnostic::parse_line(line))
+           .collect()
+    }
+
+    /// Parse a single line of a shader info log into a diagnostic.
+    pub fn parse_line(line: &str) -> ShaderDiagnostic {
+        let line = line.trim();
+        _parse_mesa(line)
+            .or_else(|| _parse_nvidia(line))
+            .or_else(|| _parse_prefixed(line))
+            .unwrap_or_else(|| {
+                ShaderDiagnostic {
+                    source: None,
+                    line: None,
+                    column: None,
+                    severity: DiagnosticSeverity::Info,
+                    message: line.to_owned()
+                }
+            })
+    }
+
+    /// Returns `true` if this diagnostic is an error.
+    pub fn is_error(&self) -> bool {
+        self.severity == DiagnosticSeverity::Error
+    }
+}
+
+impl fmt::Display for DiagnosticSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DiagnosticSeverity::Error => write!(f, "error"),
+            DiagnosticSeverity::Warning => write!(f, "warning"),
+            DiagnosticSeverity::Info => write!(f, "info")
+        }
+    }
+}
+
+impl fmt::Display for ShaderDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let (Some(source), Some(_)) = (self.source, self.line) {
+            try!(write!(f, "{}:", source));
+        }
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => {
+                try!(write!(f, "{}:{}: ", line, column));
+            },
+            (Some(line), None) => {
+                try!(write!(f, "{}: ", line));
+            },
+            _ => { }
+        }
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+// Return `None` early if an `Option` is `None`, like `try!` does for
+// `Result`.
+macro_rules! try_opt {
+    ($e:expr) => {
+        match $e {
+            Some(x) => x,
+            None => { return None; }
+        }
+    }
+}
+
+// Split the leading decimal digits off of `s`.
+fn _number(s: &str) -> Option<(u32, &str)> {
+    let end = s.find(|c: char| !c.is_digit(10)).unwrap_or(s.len());
+    if end == 0 {
+        return None;
+    }
+    s[..end].parse().ok().map(|n| (n, &s[end..]))
+}
+
+// Strip the character `c` off of the start of `s`.
+fn _expect(s: &str, c: char) -> Option<&str> {
+    if s.starts_with(c) {
+        Some(&s[c.len_utf8()..])
+    }
+    else {
+        None
+    }
+}
+
+fn _severity(word: &str) -> DiagnosticSeverity {
+    let word = word.trim().to_lowercase();
+    if word.starts_with("error") || word.starts_with("fatal") {
+        DiagnosticSeverity::Error
+    }
+    else if word.starts_with("warning") {
+        DiagnosticSeverity::Warning
+    }
+    else {
+        DiagnosticSeverity::Info
+    }
+}
+
+// Mesa: `0:12(5): error: message`
+fn _parse_mesa(s: &str) -> Option<ShaderDiagnostic> {
+    let (source, s) = try_opt!(_number(s));
+    let s = try_opt!(_expect(s, ':'));
+    let (line, s) = try_opt!(_number(s));
+    let s = try_opt!(_expect(s, '('));
+    let (column, s) = try_opt!(_number(s));
+    let s = try_opt!(_expect(s, ')'));
+    let s = try_opt!(_expect(s, ':'));
+    let colon = try_opt!(s.find(':'));
+
+    Some(ShaderDiagnostic {
+        source: Some(source),
+        line: Some(line),
+        column: Some(column),
+        severity: _severity(&s[..colon]),
+        message: s[colon + 1..].trim().to_owned()
+    })
+}
+
+// NVIDIA: `0(12) : error C1008: message`, with an optional column as in
+// `0(12,5)`.
+fn _parse_nvidia(s: &str) -> Option<ShaderDiagnostic> {
+    let (source, s) = try_opt!(_number(s));
+    let s = try_opt!(_expect(s, '('));
+    let (line, s) = try_opt!(_number(s));
+    let (column, s) = match _expect(s, ',') {
+        Some(s) => {
+            let (column, s) = try_opt!(_number(s));
+            (Some(column), s)
+        },
+        None => (None, s)
+    };
+    let s = try_opt!(_expect(s, ')'));
+    let s = try_opt!(_expect(s.trim_left(), ':'));
+    let colon = try_opt!(s.find(':'));
+
+    Some(ShaderDiagnostic {
+        source: Some(source),
+        line: Some(line),
+        column: column,
+        severity: _severity(&s[..colon]),
+        message: s[colon + 1..].trim().to_owned()
+    })
+}
+
+// AMD, Intel, ANGLE, and Apple: `ERROR: 0:12: message`. Summary lines such
+// as `ERROR: 2 compilation errors.  No code generated.` have no location.
+fn _parse_prefixed(s: &str) -> Option<ShaderDiagnostic> {
+    let colon = try_opt!(s.find(':'));
+    let prefix = &s[..colon];
+    if !prefix.chars().all(|c| c.is_alphabetic()) {
+        return None;
+    }
+
+    let severity = match _severity(prefix) {
+        DiagnosticSeverity::Info => { return None; },
+        severity => severity
+    };
+    let rest = s[colon + 1..].trim();
+
+    let located = _number(rest)
+        .and_then(|(source, s)| _expect(s, ':').map(|s| (source, s)))
+        .and_then(|(source, s)| _number(s).map(|(line, s)| (source, line, s)))
+        .and_then(|(source, line, s)| {
+            _expect(s, ':').map(|s| (source, line, s))
+        });
+
+    Some(match located {
+        Some((source, line, message)) => ShaderDiagnostic {
+            source: Some(source),
+            line: Some(line),
+            column: None,
+            severity: severity,
+            message: message.trim().to_owned()
+        },
+        None => ShaderDiagnostic {
+            source: None,
+            line: None,
+            column: None,
+            severity: severity,
+            message: rest.to_owned()
+        }
+    })
+}
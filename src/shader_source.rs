@@ -0,0 +1,562 @@
+//! Contains the [`ShaderSource`](struct.ShaderSource.html) builder, which
+//! loads GLSL source code, resolves `#include` directives, and injects
+//! `#version` and `#define` directives.
+
+use std::fmt;
+use std::error;
+use std::io::{self, Read};
+use std::fs::File;
+use std::path::Path;
+use std::collections::HashMap;
+
+/// A type that loads the source of files that are included with
+/// `#include "name"`.
+pub trait IncludeResolver {
+    /// Load the file `name`, which was included by the file `from`. Returns
+    /// a unique name for the file (such as its full path), which is used to
+    /// detect recursive includes and for reporting errors, along with the
+    /// file's source.
+    fn resolve(&self, name: &str, from: &str) -> io::Result<(String, String)>;
+}
+
+/// An [`IncludeResolver`](trait.IncludeResolver.html) that loads included
+/// files from the filesystem, relative to the directory of the file that
+/// included them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileResolver;
+
+impl IncludeResolver for FileResolver {
+    fn resolve(&self, name: &str, from: &str) -> io::Result<(String, String)> {
+        let path = match Path::new(from).parent() {
+            Some(dir) => dir.join(name),
+            None => Path::new(name).to_path_buf()
+        };
+        let mut source = String::new();
+        let mut file = try!(File::open(&path));
+        try!(file.read_to_string(&mut source));
+        Ok((path.to_string_lossy().into_owned(), source))
+    }
+}
+
+/// An in-memory resolver, which maps the names of included files to
+/// their sources.
+impl IncludeResolver for HashMap<String, String> {
+    fn resolve(&self, name: &str, _from: &str)
+        -> io::Result<(String, String)>
+    {
+        match self.get(name) {
+            Some(source) => Ok((name.to_owned(), source.clone())),
+            None => {
+                let msg = format!("No source for included file {:?}", name);
+                Err(io::Error::new(io::ErrorKind::NotFound, msg))
+            }
+        }
+    }
+}
+
+/// A version of GLSL or GLSL ES, which can be set with
+/// [`ShaderSource::version`](struct.ShaderSource.html#method.version).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GlslVersion {
+    /// GLSL ES 1.00 (OpenGL ES 2.0).
+    Es100,
+
+    /// GLSL ES 3.00 (OpenGL ES 3.0).
+    Es300,
+
+    /// GLSL ES 3.10 (OpenGL ES 3.1).
+    Es310,
+
+    /// GLSL ES 3.20 (OpenGL ES 3.2).
+    Es320,
+
+    /// GLSL 1.10 (OpenGL 2.0).
+    Glsl110,
+
+    /// GLSL 1.20 (OpenGL 2.1).
+    Glsl120,
+
+    /// GLSL 1.30 (OpenGL 3.0).
+    Glsl130,
+
+    /// GLSL 1.40 (OpenGL 3.1).
+    Glsl140,
+
+    /// GLSL 1.50 (OpenGL 3.2).
+    Glsl150,
+
+    /// GLSL 3.30 (OpenGL 3.3).
+    Glsl330,
+
+    /// GLSL 4.00 (OpenGL 4.0).
+    Glsl400,
+
+    /// GLSL 4.10 (OpenGL 4.1).
+    Glsl410,
+
+    /// GLSL 4.20 (OpenGL 4.2).
+    Glsl420,
+
+    /// GLSL 4.30 (OpenGL 4.3).
+    Glsl430,
+
+    /// GLSL 4.40 (OpenGL 4.4).
+    Glsl440,
+
+    /// GLSL 4.50 (OpenGL 4.5).
+    Glsl450
+}
+
+impl GlslVersion {
+    /// Get the version number, as used in a `#version` directive
+    /// (such as `300` for GLSL ES 3.00).
+    pub fn number(&self) -> u32 {
+        match *self {
+            GlslVersion::Es100 => 100,
+            GlslVersion::Es300 => 300,
+            GlslVersion::Es310 => 310,
+            GlslVersion::Es320 => 320,
+            GlslVersion::Glsl110 => 110,
+            GlslVersion::Glsl120 => 120,
+            GlslVersion::Glsl130 => 130,
+            GlslVersion::Glsl140 => 140,
+            GlslVersion::Glsl150 => 150,
+            GlslVersion::Glsl330 => 330,
+            GlslVersion::Glsl400 => 400,
+            GlslVersion::Glsl410 => 410,
+            GlslVersion::Glsl420 => 420,
+            GlslVersion::Glsl430 => 430,
+            GlslVersion::Glsl440 => 440,
+            GlslVersion::Glsl450 => 450
+        }
+    }
+
+    /// Returns `true` if this is a version of GLSL ES.
+    pub fn is_es(&self) -> bool {
+        match *self {
+            GlslVersion::Es100 | GlslVersion::Es300 |
+            GlslVersion::Es310 | GlslVersion::Es320 => true,
+            _ => false
+        }
+    }
+
+    /// Get the `#version` directive for this version, such as
+    /// `#version 300 es`.
+    pub fn directive(&self) -> String {
+        match (self.number(), self.is_es()) {
+            (100, _) => String::from("#version 100"),
+            (number, true) => format!("#version {} es", number),
+            (number, false) => format!("#version {}", number)
+        }
+    }
+}
+
+/// An error that occurred while resolving the `#include` directives of
+/// a [`ShaderSource`](struct.ShaderSource.html).
+#[derive(Debug)]
+pub enum IncludeError {
+    /// A file could not be loaded. Contains the name of the file, the name
+    /// of the file that included it, and the error.
+    Io(String, String, io::Error),
+
+    /// An `#include` directive was not followed by a quoted file name.
+    /// Contains the name of the file and the line number.
+    Malformed(String, usize),
+
+    /// A file included itself (directly or indirectly). Contains the chain
+    /// of includes, starting and ending with the same file.
+    Recursive(Vec<String>),
+
+    /// A file contained an `#include` directive, but no resolver was set.
+    /// Contains the name of the file and the line number.
+    NoResolver(String, usize)
+}
+
+impl fmt::Display for IncludeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IncludeError::Io(ref name, ref from, ref err) => {
+                write!(f, "Couldn't load {:?} (included from {:?}): {}", name, from, err)
+            },
+            IncludeError::Malformed(ref file, line) => {
+                write!(f, "{}:{}: Expected a file name after #include", file, line)
+            },
+            IncludeError::Recursive(ref chain) => {
+                write!(f, "Recursive #include: {}", chain.join(" -> "))
+            },
+            IncludeError::NoResolver(ref file, line) => {
+                write!(f, "{}:{}: #include used without an include resolver", file, line)
+            }
+        }
+    }
+}
+
+impl error::Error for IncludeError {
+    fn description(&self) -> &str {
+        match *self {
+            IncludeError::Io(..) => "Couldn't load included file",
+            IncludeError::Malformed(..) => "Malformed #include directive",
+            IncludeError::Recursive(..) => "Recursive #include",
+            IncludeError::NoResolver(..) => "#include used without an include resolver"
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            IncludeError::Io(_, _, ref err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+/// The GLSL source produced by [`ShaderSource::resolve`]
+/// (struct.ShaderSource.html#method.resolve), with every `#include`
+/// directive replaced by the included file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedSource {
+    /// The resolved source, which can be passed to [`gl.build_shader`]
+    /// (../shader/trait.ContextShaderBuilderExt.html#tymethod.build_shader).
+    pub source: String,
+
+    /// The names of the files that make up the source. `#line` directives
+    /// in the source refer to each file by its index in this list (GLSL
+    /// doesn't allow file names in `#line` directives), so the "source
+    /// string number" of an error in a shader's info log is an index into
+    /// this list. The first file is the root file.
+    pub files: Vec<String>
+}
+
+impl ResolvedSource {
+    /// Get the name of the file with the given source string number, as
+    /// reported in a shader's info log.
+    pub fn file_name(&self, source_string: usize) -> Option<&str> {
+        self.files.get(source_string).map(|name| name.as_str())
+    }
+}
+
+/// A builder that loads GLSL source code from a file or a string, and
+/// resolves `#include "name"` directives using an [`IncludeResolver`]
+/// (trait.IncludeResolver.html).
+///
+/// Each included file is inserted in place of its `#include` directive,
+/// surrounded by `#line` directives, so that the line numbers in a shader's
+/// info log still refer to the original files. Files can be included more
+/// than once (use `#ifndef` guards to prevent this), but including a file
+/// from itself is an error.
+///
+/// A `#version` directive and `#define` directives can also be injected
+/// with [`version`](#method.version) and [`define`](#method.define), which
+/// makes it possible to compile several permutations of the same source.
+/// They're inserted at the top of the source (replacing the source's own
+/// `#version` directive, if a version is set), followed by a `#line`
+/// directive, so line numbers are unaffected.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use glitter::ShaderSource;
+///
+/// let mut includes = HashMap::new();
+/// includes.insert("common.glsl".to_owned(),
+///                 "vec4 color() { return vec4(1.0); }".to_owned());
+///
+/// let source = "#version 100\n\
+///               #include \"common.glsl\"\n\
+///               void main() { gl_FragColor = color(); }\n";
+/// let resolved = ShaderSource::new("main.frag", source)
+///     .resolver(includes)
+///     .resolve()
+///     .unwrap();
+///
+/// assert_eq!(resolved.source,
+///            "#version 100\n\
+///             #line 0 1\n\
+///             vec4 color() { return vec4(1.0); }\n\
+///             #line 2 0\n\
+///             void main() { gl_FragColor = color(); }\n");
+/// assert_eq!(resolved.file_name(1), Some("common.glsl"));
+/// ```
+pub struct ShaderSource<'a> {
+    name: String,
+    source: String,
+    resolver: Option<Box<IncludeResolver + 'a>>,
+    version: Option<GlslVersion>,
+    defines: Vec<(String, String)>
+}
+
+impl<'a> ShaderSource<'a> {
+    /// Create a new shader source from a string. `name` is used as the name
+    /// of the root file, when reporting errors and when resolving includes.
+    pub fn new(name: &str, source: &str) -> Self {
+        ShaderSource {
+            name: name.to_owned(),
+            source: source.to_owned(),
+            resolver: None,
+            version: None,
+            defines: vec![]
+        }
+    }
+
+    /// Load a shader source from a file. Included files are loaded relative
+    /// to the file's directory using a [`FileResolver`]
+    /// (struct.FileResolver.html), unless a different resolver is set.
+    ///
+    /// # Failures
+    /// An error will be returned if the file could not be read.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut source = String::new();
+        let mut file = try!(File::open(path));
+        try!(file.read_to_string(&mut source));
+        Ok(ShaderSource {
+            name: path.to_string_lossy().into_owned(),
+            source: source,
+            resolver: Some(Box::new(FileResolver)),
+            version: None,
+            defines: vec![]
+        })
+    }
+
+    /// Set the resolver that loads included files.
+    pub fn resolver<R>(mut self, resolver: R) -> Self
+        where R: IncludeResolver + 'a
+    {
+        self.resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// Set the GLSL version of the source. The source's own `#version`
+    /// directive (if it has one) will be replaced.
+    ///
+    /// # Example
+    /// ```
+    /// use glitter::{ShaderSource, GlslVersion};
+    ///
+    /// let source = "#version 100\n\
+    ///               void main() { }\n";
+    /// let resolved = ShaderSource::new("main.vert", source)
+    ///     .version(GlslVersion::Es300)
+    ///     .define("MAX_LIGHTS", "8")
+    ///     .resolve()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(resolved.source,
+    ///            "#version 300 es\n\
+    ///             #define MAX_LIGHTS 8\n\
+    ///             #line 1 0\n\
+    ///             \n\
+    ///             void main() { }\n");
+    /// ```
+    pub fn version(mut self, version: GlslVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Define the preprocessor macro `name` as `value` (which can be empty)
+    /// at the top of the source, after the `#version` directive. Macros are
+    /// defined in the order that they were added.
+    pub fn define(mut self, name: &str, value: &str) -> Self {
+        self.defines.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Resolve all of the `#include` directives in the source, and inject
+    /// the `#version` and `#define` directives (if any).
+    ///
+    /// # Failures
+    /// An error will be returned if an included file could not be loaded,
+    /// if an `#include` directive is malformed, or if a file
+    /// includes itself.
+    pub fn resolve(self) -> Result<ResolvedSource, IncludeError> {
+        let mut resolved = ResolvedSource {
+            source: String::new(),
+            files: vec![self.name.clone()]
+        };
+        let mut stack = vec![self.name.clone()];
+
+        let version_line = _version_line(&self.source);
+        let old_line_numbers = match self.version {
+            Some(version) => {
+                _uses_old_line_numbers(version.number(), version.is_es())
+            },
+            None => _old_line_numbers(&self.source)
+        };
+
+        if self.version.is_none() && self.defines.is_empty() {
+            try!(self._expand(&self.source,
+                              0,
+                              old_line_numbers,
+                              &mut stack,
+                              &mut resolved));
+            return Ok(resolved);
+        }
+
+        // Move the `#version` directive to the header, and leave an empty
+        // line in its place so that the following lines aren't renumbered
+        let mut body = String::new();
+        let mut original_version = None;
+        for (i, line) in self.source.lines().enumerate() {
+            if Some(i) == version_line {
+                original_version = Some(line.trim());
+            }
+            else {
+                body.push_str(line);
+            }
+            body.push('\n');
+        }
+
+        match (self.version, original_version) {
+            (Some(version), _) => {
+                resolved.source.push_str(&version.directive());
+                resolved.source.push('\n');
+            },
+            (None, Some(original_version)) => {
+                resolved.source.push_str(original_version);
+                resolved.source.push('\n');
+            },
+            (None, None) => { }
+        }
+        for &(ref name, ref value) in &self.defines {
+            if value.is_empty() {
+                resolved.source.push_str(&format!("#define {}\n", name));
+            }
+            else {
+                resolved.source.push_str(&format!("#define {} {}\n",
+                                                  name, value));
+            }
+        }
+        _push_line_directive(&mut resolved, 1, 0, old_line_numbers);
+
+        try!(self._expand(&body,
+                          0,
+                          old_line_numbers,
+                          &mut stack,
+                          &mut resolved));
+        Ok(resolved)
+    }
+
+    fn _expand(&self,
+               source: &str,
+               file: usize,
+               old_line_numbers: bool,
+               stack: &mut Vec<String>,
+               resolved: &mut ResolvedSource)
+        -> Result<(), IncludeError>
+    {
+        let current = stack[stack.len() - 1].clone();
+        for (i, line) in source.lines().enumerate() {
+            let number = i + 1;
+            let name = match _include_name(line) {
+                None => {
+                    resolved.source.push_str(line);
+                    resolved.source.push('\n');
+                    continue;
+                },
+                Some(None) => {
+                    return Err(IncludeError::Malformed(current, number));
+                },
+                Some(Some(name)) => name
+            };
+
+            let resolver = match self.resolver {
+                Some(ref resolver) => resolver,
+                None => {
+                    return Err(IncludeError::NoResolver(current, number));
+                }
+            };
+            let (included, included_source) = try! {
+                resolver.resolve(name, &current).map_err(|err| {
+                    IncludeError::Io(name.to_owned(), current.clone(), err)
+                })
+            };
+
+            if stack.contains(&included) {
+                let mut chain = stack.clone();
+                chain.push(included);
+                return Err(IncludeError::Recursive(chain));
+            }
+
+            let included_file = resolved.files.len();
+            resolved.files.push(included.clone());
+            _push_line_directive(resolved, 1, included_file, old_line_numbers);
+
+            stack.push(included);
+            try!(self._expand(&included_source,
+                              included_file,
+                              old_line_numbers,
+                              stack,
+                              resolved));
+            stack.pop();
+
+            _push_line_directive(resolved, number + 1, file, old_line_numbers);
+        }
+        Ok(())
+    }
+}
+
+// Returns `None` if the line isn't an `#include` directive, and `Some(None)`
+// if it is an `#include` directive without a quoted file name
+fn _include_name(line: &str) -> Option<Option<&str>> {
+    let line = line.trim();
+    if !line.starts_with('#') {
+        return None;
+    }
+    let directive = line[1..].trim_left();
+    if !directive.starts_with("include") {
+        return None;
+    }
+
+    let rest = directive["include".len()..].trim();
+    let name = if rest.len() >= 2 && rest.starts_with('"') && rest.ends_with('"') {
+        Some(&rest[1..rest.len() - 1])
+    }
+    else if rest.len() >= 2 && rest.starts_with('<') && rest.ends_with('>') {
+        Some(&rest[1..rest.len() - 1])
+    }
+    else {
+        None
+    };
+    Some(name)
+}
+
+// Get the index of the line containing the `#version` directive, if any
+fn _version_line(source: &str) -> Option<usize> {
+    source.lines().position(|line| line.trim().starts_with("#version"))
+}
+
+// Before GLSL 3.30 (and GLSL ES 3.00), `#line N` sets the number of the
+// *following* line to `N + 1`, rather than `N`.
+fn _uses_old_line_numbers(number: u32, es: bool) -> bool {
+    if es || number == 100 {
+        number < 300
+    }
+    else {
+        number < 330
+    }
+}
+
+fn _old_line_numbers(source: &str) -> bool {
+    let version = _version_line(source)
+        .and_then(|i| source.lines().nth(i))
+        .map(|line| line.trim());
+    match version {
+        Some(version) => {
+            let mut words = version["#version".len()..].split_whitespace();
+            let number = words.next()
+                .and_then(|number| number.parse::<u32>().ok())
+                .unwrap_or(110);
+            let es = words.next() == Some("es");
+            _uses_old_line_numbers(number, es)
+        },
+        None => true
+    }
+}
+
+fn _push_line_directive(resolved: &mut ResolvedSource,
+                        line: usize,
+                        file: usize,
+                        old_line_numbers: bool)
+{
+    let line = if old_line_numbers { line - 1 } else { line };
+    resolved.source.push_str(&format!("#line {} {}\n", line, file));
+}
@@ -0,0 +1,112 @@
+// Writes shader sources (with line numbers) to a temporary file, so that
+// failed (or crashed) shader compiles and program links can be inspected.
+// The dump is written *before* compiling or linking, so that it survives
+// a driver crash, and is removed again if compiling or linking succeeds.
+// The file is always created fresh (never opened if it already exists), with
+// a name that can't be predicted ahead of time, since the temporary
+// directory is usually shared with other users.
+
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+use std::time::{SystemTime, UNIX_EPOCH};
+use types::GLError;
+
+static DUMP_COUNT: AtomicUsize = ATOMIC_USIZE_INIT;
+
+// The number of names to try before giving up
+const MAX_ATTEMPTS: usize = 16;
+
+// An unpredictable suffix for a file name, mixed from the current time, a
+// per-process counter, and the address of a stack value
+fn _random_suffix() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|duration| (duration.as_secs(), duration.subsec_nanos()))
+        .unwrap_or((0, 0));
+    let count = DUMP_COUNT.fetch_add(1, Ordering::SeqCst) as u64;
+    let local = 0u8;
+    let address = &local as *const u8 as usize as u64;
+
+    // A 64-bit FNV-1a hash of each part
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in &[now.0, now.1 as u64, count, address] {
+        for byte in 0..8 {
+            hash ^= (part >> (byte * 8)) & 0xff;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+fn _create_new(kind: &str) -> io::Result<(PathBuf, File)> {
+    let dir = env::temp_dir();
+    let mut last_err = None;
+    for _ in 0..MAX_ATTEMPTS {
+        let name = format!("glitter-{}-{:016x}.glsl", kind, _random_suffix());
+        let path = dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => { return Ok((path, file)); },
+            Err(err) => {
+                if err.kind() != ErrorKind::AlreadyExists {
+                    return Err(err);
+                }
+                last_err = Some(err);
+            }
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| {
+        io::Error::new(ErrorKind::AlreadyExists,
+                       "failed to create a unique source dump file")
+    }))
+}
+
+pub struct SourceDump {
+    path: PathBuf
+}
+
+impl SourceDump {
+    // `sources` is a list of `(label, source)` pairs
+    pub fn create(kind: &str, sources: &[(String, String)])
+        -> io::Result<Self>
+    {
+        let (path, mut file) = try!(_create_new(kind));
+        for &(ref label, ref source) in sources {
+            try!(writeln!(file, "// ---- {} ----", label));
+            for (number, line) in source.lines().enumerate() {
+                try!(writeln!(file, "{:4}: {}", number + 1, line));
+            }
+        }
+
+        Ok(SourceDump { path: path })
+    }
+
+    pub fn remove(self) {
+        let _ = fs::remove_file(&self.path);
+    }
+
+    // Append the error to the dump, and return a new error that includes
+    // the path of the dump.
+    pub fn into_error(self, err: GLError) -> GLError {
+        let msg = format!("{}", err);
+        let appended = OpenOptions::new().append(true).open(&self.path)
+            .and_then(|mut file| {
+                try!(writeln!(file, "// ---- Error ----"));
+                for line in msg.lines() {
+                    try!(writeln!(file, "// {}", line));
+                }
+                Ok(())
+            });
+
+        match appended {
+            Ok(()) => {
+                GLError::Message(format!("{}\n(source written to {})",
+                                         msg,
+                                         self.path.display()))
+            },
+            Err(_) => err
+        }
+    }
+}
@@ -0,0 +1,169 @@
+// Thread-local cache of the objects that glitter has bound, which is used to
+// skip OpenGL calls that wouldn't change any state. A binding that's missing
+// from the cache is unknown, and is always set. The cache is bypassed (and
+// not updated) while bindings are being restored, since the restored
+// bindings aren't recorded.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use gl;
+use gl::types::*;
+use restore_bindings;
+
+struct StateCache {
+    enabled: bool,
+    program: Option<GLuint>,
+    buffers: HashMap<GLenum, GLuint>,
+    active_texture: Option<u32>,
+    textures: HashMap<(u32, GLenum), GLuint>,
+    vertex_array: Option<GLuint>,
+    attrib_arrays: HashSet<GLuint>
+}
+
+impl StateCache {
+    fn new(enabled: bool) -> Self {
+        StateCache {
+            enabled: enabled,
+            program: None,
+            buffers: HashMap::new(),
+            active_texture: None,
+            textures: HashMap::new(),
+            vertex_array: None,
+            attrib_arrays: HashSet::new()
+        }
+    }
+}
+
+thread_local! {
+    static CACHE: RefCell<StateCache> = RefCell::new(StateCache::new(true));
+}
+
+// Returns `true` if the OpenGL call needs to be made, which is always the
+// case when the cache is disabled, or when bindings are being restored.
+fn _update<F>(f: F) -> bool
+    where F: FnOnce(&mut StateCache) -> bool
+{
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.enabled && !restore_bindings::enabled() {
+            f(&mut *cache)
+        }
+        else {
+            true
+        }
+    })
+}
+
+pub fn enabled() -> bool {
+    CACHE.with(|cache| cache.borrow().enabled)
+}
+
+pub fn replace_enabled(enabled: bool) -> bool {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let old_enabled = cache.enabled;
+        *cache = StateCache::new(enabled);
+        old_enabled
+    })
+}
+
+pub fn invalidate() {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let enabled = cache.enabled;
+        *cache = StateCache::new(enabled);
+    });
+}
+
+pub fn use_program(id: GLuint) -> bool {
+    _update(|cache| {
+        let changed = cache.program != Some(id);
+        cache.program = Some(id);
+        changed
+    })
+}
+
+pub fn bind_buffer(target: GLenum, id: GLuint) -> bool {
+    _update(|cache| cache.buffers.insert(target, id) != Some(id))
+}
+
+pub fn active_texture(unit: u32) -> bool {
+    _update(|cache| {
+        let changed = cache.active_texture != Some(unit);
+        cache.active_texture = Some(unit);
+        changed
+    })
+}
+
+pub fn bind_texture(target: GLenum, id: GLuint) -> bool {
+    _update(|cache| {
+        match cache.active_texture {
+            Some(unit) => cache.textures.insert((unit, target), id) != Some(id),
+            None => true
+        }
+    })
+}
+
+// The enabled attrib arrays and the `GL_ELEMENT_ARRAY_BUFFER` binding are
+// part of the bound vertex array object, so they become unknown whenever a
+// different vertex array object is bound.
+pub fn bind_vertex_array(id: GLuint) -> bool {
+    _update(|cache| {
+        if cache.vertex_array == Some(id) {
+            return false;
+        }
+        cache.vertex_array = Some(id);
+        cache.attrib_arrays.clear();
+        cache.buffers.remove(&gl::ELEMENT_ARRAY_BUFFER);
+        true
+    })
+}
+
+pub fn enable_attrib_array(index: GLuint) -> bool {
+    _update(|cache| cache.attrib_arrays.insert(index))
+}
+
+// Deleting an object unbinds it, and its name may be reused by a new
+// object, so any cached bindings of a deleted object are forgotten.
+
+pub fn forget_program(id: GLuint) {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.program == Some(id) {
+            cache.program = None;
+        }
+    });
+}
+
+pub fn forget_buffer(id: GLuint) {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let cache = &mut *cache;
+        cache.buffers = cache.buffers.drain()
+                                     .filter(|&(_, bound)| bound != id)
+                                     .collect();
+    });
+}
+
+// Deleting the bound vertex array object binds vertex array 0 instead, so
+// the state of the vertex array that was bound is forgotten.
+pub fn forget_vertex_array(id: GLuint) {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.vertex_array == Some(id) {
+            cache.vertex_array = None;
+            cache.attrib_arrays.clear();
+            cache.buffers.remove(&gl::ELEMENT_ARRAY_BUFFER);
+        }
+    });
+}
+
+pub fn forget_texture(id: GLuint) {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let cache = &mut *cache;
+        cache.textures = cache.textures.drain()
+                                       .filter(|&(_, bound)| bound != id)
+                                       .collect();
+    });
+}
@@ -0,0 +1,299 @@
+//! Contains the [`StreamBuffer`](struct.StreamBuffer.html) type, which
+//! allocates data that is regenerated every frame from a ring buffer.
+
+use std::mem;
+use std::ptr;
+use std::slice;
+use gl;
+use gl::types::*;
+use context::{BaseContext, ContextBufferExt, FrameHook};
+use buffer::Buffer;
+use frame_ring::FrameRing;
+use types::{GLObject, GLError};
+use vertex_data::{VertexData, VertexBytes};
+use state_cache;
+
+/// A range of a [`StreamBuffer`](struct.StreamBuffer.html) that data was
+/// written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamRange {
+    /// The offset of the data (in bytes) within the stream buffer's
+    /// underlying buffer.
+    pub offset: usize,
+
+    /// The size of the data, in bytes.
+    pub size: usize
+}
+
+impl StreamRange {
+    /// Get the index of the first vertex of the range, for vertices of
+    /// type `T`. Vertices written with [`StreamBuffer::write_vertices`]
+    /// (struct.StreamBuffer.html#method.write_vertices) are aligned to the
+    /// size of `T`, so this can be passed as the first vertex of a draw call
+    /// whose vertex attributes start at the beginning of the buffer.
+    pub fn first<T>(&self) -> u32 {
+        (self.offset / mem::size_of::<T>()) as u32
+    }
+
+    /// Get the number of vertices of type `T` in the range.
+    pub fn count<T>(&self) -> usize {
+        self.size / mem::size_of::<T>()
+    }
+}
+
+/// A buffer for data that is regenerated every frame (such as UI, text, or
+/// particle geometry), which is split into one region for each frame that
+/// can be "in flight" at once. Each frame, data is written to the next
+/// region, so that the CPU never overwrites data that the GPU may still be
+/// reading from a previous frame.
+///
+/// Each region is guarded by a fence, which is placed by [`end_frame`]
+/// (struct.StreamBuffer.html#method.end_frame). [`begin_frame`]
+/// (struct.StreamBuffer.html#method.begin_frame) waits for the fence of the
+/// next region, which only blocks if the CPU is more than `frames` frames
+/// ahead of the GPU. Because of this, data is written with an unsynchronized
+/// mapping, which avoids stalling on the GPU.
+///
+/// A `StreamBuffer` can also be attached to a [`Frame`]
+/// (../context/frame/struct.Frame.html) as a [`FrameHook`]
+/// (../context/frame/trait.FrameHook.html), which calls `begin_frame` and
+/// `end_frame` automatically.
+///
+/// # Note
+/// Mapping buffers and fences require OpenGL ES 3.0, so this type is only
+/// available with the `gles3` feature. The buffer is mapped using the
+/// `GL_COPY_WRITE_BUFFER` target, so it doesn't need to be bound.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::StreamBuffer;
+///
+/// # fn main() {
+/// # let vertices: Vec<[f32; 2]> = vec![];
+/// let mut gl = unsafe { glitter::Context::current_context() };
+/// let mut stream = StreamBuffer::new(&gl, 64 * 1024, 3);
+///
+/// // Each frame:
+/// stream.begin_frame(&gl);
+/// let range = stream.write_vertices(&gl, &vertices).unwrap();
+/// {
+///     let (gl_buffer, gl) = (&mut gl).bind_array_buffer(stream.buffer_mut());
+///     // Set up the vertex attributes, then draw the vertices:
+///     unsafe {
+///         gl.draw_arrays_range(&gl_buffer,
+///                              glitter::TRIANGLES,
+///                              range.first::<[f32; 2]>(),
+///                              range.count::<[f32; 2]>());
+///     }
+/// }
+/// stream.end_frame(&gl);
+/// # }
+/// ```
+pub struct StreamBuffer {
+    buffer: Buffer,
+    ring: FrameRing
+}
+
+impl StreamBuffer {
+    /// Create a new stream buffer with `frames` regions, each of which can
+    /// hold `frame_size` bytes of data.
+    ///
+    /// # Panics
+    /// This function will panic if `frames` is 0.
+    ///
+    /// # See also
+    /// [`glBufferData`](http://docs.gl/es3/glBufferData) OpenGL docs
+    pub fn new<C>(gl: &C, frame_size: usize, frames: usize) -> Self
+        where C: BaseContext
+    {
+        assert!(frames > 0, "A `StreamBuffer` needs at least one frame");
+
+        let mut buffer = gl.gen_buffer();
+        let size = frame_size * frames;
+        _bind_copy_write_buffer(&buffer);
+        unsafe {
+            gl::BufferData(gl::COPY_WRITE_BUFFER,
+                           size as GLsizeiptr,
+                           ptr::null(),
+                           gl::STREAM_DRAW);
+            dbg_gl_error! {
+                call "glBufferData";
+                args (gl::COPY_WRITE_BUFFER, size, gl::STREAM_DRAW);
+                GLError::InvalidValue => "`size` is negative",
+                GLError::OutOfMemory => "Unable to create a large enough buffer",
+                _ => "Unknown error"
+            }
+        }
+        buffer._set_size(size);
+
+        StreamBuffer {
+            buffer: buffer,
+            ring: FrameRing::new(frame_size, frames)
+        }
+    }
+
+    /// Get the underlying buffer, which holds all of the regions.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Get a mutable reference to the underlying buffer, which can be used
+    /// to bind it for drawing.
+    pub fn buffer_mut(&mut self) -> &mut Buffer {
+        &mut self.buffer
+    }
+
+    /// Get the number of regions.
+    pub fn frames(&self) -> usize {
+        self.ring.frames()
+    }
+
+    /// Get the size of each region, in bytes.
+    pub fn frame_size(&self) -> usize {
+        self.ring.frame_size()
+    }
+
+    /// Get the number of bytes that are still free in the current region.
+    pub fn remaining(&self) -> usize {
+        self.ring.remaining()
+    }
+
+    /// Advance to the next region, waiting for the GPU to finish reading
+    /// from it if necessary.
+    ///
+    /// # See also
+    /// [`glClientWaitSync`](http://docs.gl/es3/glClientWaitSync) OpenGL docs
+    pub fn begin_frame<C: BaseContext>(&mut self, gl: &C) {
+        self.ring.begin_frame(gl);
+    }
+
+    /// Place a fence after all of the OpenGL commands that have been issued
+    /// so far, which guards the current region. This should be called after
+    /// the last draw call that reads from the region.
+    ///
+    /// # See also
+    /// [`glFenceSync`](http://docs.gl/es3/glFenceSync) OpenGL docs
+    pub fn end_frame<C: BaseContext>(&mut self, gl: &C) {
+        self.ring.end_frame(gl);
+    }
+
+    /// Write `bytes` to the current region, at an offset (within the
+    /// underlying buffer) that is a multiple of `alignment`. Returns the
+    /// range that was written to, or `None` if there isn't enough room
+    /// left in the current region.
+    ///
+    /// # See also
+    /// [`glMapBufferRange`](http://docs.gl/es3/glMapBufferRange) OpenGL docs
+    pub fn write_bytes<C>(&mut self,
+                          _gl: &C,
+                          bytes: &[u8],
+                          alignment: usize)
+        -> Option<StreamRange>
+        where C: BaseContext
+    {
+        let offset = match self.ring.allocate(bytes.len(), alignment) {
+            Some(offset) => offset,
+            None => { return None; }
+        };
+
+        if !bytes.is_empty() {
+            _write(&self.buffer, offset, bytes);
+        }
+        Some(StreamRange { offset: offset, size: bytes.len() })
+    }
+
+    /// Write `vertices` to the current region, aligned so that the range's
+    /// [`first`](struct.StreamRange.html#method.first) vertex can be used
+    /// for drawing. Returns the range that was written to, or `None` if
+    /// there isn't enough room left in the current region.
+    pub fn write_vertices<C, T>(&mut self, gl: &C, vertices: &[T])
+        -> Option<StreamRange>
+        where C: BaseContext, T: VertexData, [T]: VertexBytes
+    {
+        let bytes: &[u8] = if vertices.is_empty() {
+            &[]
+        }
+        else {
+            vertices.vertex_bytes()
+        };
+        self.write_bytes(gl, bytes, mem::size_of::<T>())
+    }
+}
+
+impl<C: BaseContext> FrameHook<C> for StreamBuffer {
+    fn begin_frame(&mut self, gl: &mut C, _index: u64) {
+        StreamBuffer::begin_frame(self, &*gl);
+    }
+
+    fn end_frame(&mut self, gl: &mut C, _index: u64) {
+        StreamBuffer::end_frame(self, &*gl);
+    }
+}
+
+// Bind a buffer to `GL_COPY_WRITE_BUFFER` through the state cache
+#[doc(hidden)]
+pub fn _bind_copy_write_buffer(buffer: &Buffer) {
+    if !state_cache::bind_buffer(gl::COPY_WRITE_BUFFER, buffer.id()) {
+        return;
+    }
+    unsafe {
+        gl::BindBuffer(gl::COPY_WRITE_BUFFER, buffer.id());
+        dbg_gl_sanity_check! {
+            call "glBindBuffer";
+            args (gl::COPY_WRITE_BUFFER, buffer.id());
+            GLError::InvalidEnum => "`target` is not an allowed value",
+            _ => "Unknown error"
+        }
+    }
+}
+
+// Write `bytes` into `buffer` with an unsynchronized mapping, falling back
+// to `glBufferSubData` if the buffer couldn't be mapped
+fn _write(buffer: &Buffer, offset: usize, bytes: &[u8]) {
+    _bind_copy_write_buffer(buffer);
+
+    let access = gl::MAP_WRITE_BIT |
+                 gl::MAP_INVALIDATE_RANGE_BIT |
+                 gl::MAP_UNSYNCHRONIZED_BIT;
+    unsafe {
+        let ptr = gl::MapBufferRange(gl::COPY_WRITE_BUFFER,
+                                     offset as GLintptr,
+                                     bytes.len() as GLsizeiptr,
+                                     access);
+        dbg_gl_error! {
+            call "glMapBufferRange";
+            args (gl::COPY_WRITE_BUFFER, offset, bytes.len(), access);
+            GLError::InvalidValue => "The range is out of bounds of the buffer",
+            GLError::InvalidOperation => "The buffer is already mapped",
+            _ => "Unknown error"
+        }
+
+        if ptr.is_null() {
+            gl::BufferSubData(gl::COPY_WRITE_BUFFER,
+                              offset as GLintptr,
+                              bytes.len() as GLsizeiptr,
+                              bytes.as_ptr() as *const GLvoid);
+            dbg_gl_error! {
+                call "glBufferSubData";
+                args (gl::COPY_WRITE_BUFFER, offset, bytes.len());
+                GLError::InvalidValue => "`offset` or `size` is out of range",
+                GLError::InvalidOperation => "Object 0 is bound to buffer target",
+                _ => "Unknown error"
+            }
+            return;
+        }
+
+        let mapped = slice::from_raw_parts_mut(ptr as *mut u8, bytes.len());
+        mapped.copy_from_slice(bytes);
+
+        gl::UnmapBuffer(gl::COPY_WRITE_BUFFER);
+        dbg_gl_error! {
+            call "glUnmapBuffer";
+            args (gl::COPY_WRITE_BUFFER);
+            GLError::InvalidOperation => "The buffer is not mapped",
+            _ => "Unknown error"
+        }
+    }
+}
@@ -4,7 +4,48 @@
 use std::marker::PhantomData;
 use gl;
 use gl::types::*;
-use types::GLObject;
+use types::{GLObject, Labeled, DepthFunction};
+use deletion_queue::{self, Deleted, Owner};
+use state_cache;
+use image_data::ImageFormat;
+
+/// Get the number of mipmap levels in a complete mipmap chain for an image
+/// with the given size, including the base level. Returns 0 if either
+/// dimension is 0.
+///
+/// # Example
+/// ```
+/// assert_eq!(glitter::mip_level_count(256, 256), 9);
+/// assert_eq!(glitter::mip_level_count(640, 480), 10);
+/// assert_eq!(glitter::mip_level_count(1, 1), 1);
+/// ```
+pub fn mip_level_count(width: u32, height: u32) -> u32 {
+    let largest = if width > height { width } else { height };
+    if width == 0 || height == 0 {
+        0
+    }
+    else {
+        32 - largest.leading_zeros()
+    }
+}
+
+/// Get the size of the mipmap level `level` of an image with the given
+/// size, as `(width, height)`. Each level is half the size of the previous
+/// level (rounded down), but never smaller than 1.
+///
+/// # Example
+/// ```
+/// assert_eq!(glitter::mip_level_size(640, 480, 0), (640, 480));
+/// assert_eq!(glitter::mip_level_size(640, 480, 3), (80, 60));
+/// assert_eq!(glitter::mip_level_size(640, 480, 9), (1, 1));
+/// ```
+pub fn mip_level_size(width: u32, height: u32, level: u32) -> (u32, u32) {
+    let shrink = |size: u32| {
+        let size = if level >= 32 { 0 } else { size >> level };
+        if size > 0 { size } else { 1 }
+    };
+    (shrink(width), shrink(height))
+}
 
 /// A type of OpenGL texture.
 ///
@@ -42,6 +83,13 @@ use types::GLObject;
 /// with details details about binding a texture in a context.
 pub struct Texture<T: TextureType> {
     gl_id: GLuint,
+    owner: Owner,
+    width: u32,
+    height: u32,
+    depth: u32,
+    format: Option<ImageFormat>,
+    levels: u32,
+    immutable: bool,
     phantom: PhantomData<*mut T>
 }
 
@@ -62,11 +110,148 @@ pub type Texture2d = Texture<Tx2d>;
 /// of textures.
 pub type TextureCubeMap = Texture<TxCubeMap>;
 
+/// An OpenGL texture with 3-dimensional image data, such as a volume or a
+/// color grading lookup table.
+///
+/// See the documentation for [`Texture`](struct.Texture.html) for
+/// more details about textures in glitter, and [`TextureBindingTarget`]
+/// (enum.TextureBindingTarget) for details about the different types
+/// of textures.
+///
+/// # Note
+/// 3D textures require OpenGL ES 3.0, so this type is only available with
+/// the `gles3` feature.
+#[cfg(feature = "gles3")]
+pub type Texture3d = Texture<Tx3d>;
+
+impl<T: TextureType> Texture<T> {
+    /// Get the size of the texture's base level, as `(width, height)`. The
+    /// size is `(0, 0)` if no image data has been set.
+    ///
+    /// # Note
+    /// The texture's size, format, and mipmap levels are tracked by glitter
+    /// when image data is set (such as with [`gl.tex_image_2d`]
+    /// (../context/texture_context/trait.ContextTextureExt.html#method.tex_image_2d)),
+    /// without querying OpenGL. A texture created with
+    /// [`GLObject::from_raw`](../types/trait.GLObject.html#tymethod.from_raw)
+    /// starts with no image data, regardless of its actual contents.
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Get the format of the texture's base level, or `None` if no image
+    /// data has been set (or if the texture holds compressed image data).
+    pub fn format(&self) -> Option<ImageFormat> {
+        self.format
+    }
+
+    /// Get the number of mipmap levels that have image data, including the
+    /// base level. After generating mipmaps, this is the [`mip_level_count`]
+    /// (fn.mip_level_count.html) of the texture's size.
+    pub fn mip_levels(&self) -> u32 {
+        self.levels
+    }
+
+    /// Get the size of the mipmap level `level`, as `(width, height)`, or
+    /// `None` if the level is outside of the texture's complete mipmap chain
+    /// (or if no image data has been set).
+    pub fn mip_size(&self, level: u32) -> Option<(u32, u32)> {
+        if level < mip_level_count(self.width, self.height) {
+            Some(mip_level_size(self.width, self.height, level))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Returns `true` if the texture's storage is immutable, meaning that
+    /// its size, format, and number of mipmap levels can't be changed
+    /// (see [`gl.tex_storage_2d`]
+    /// (../context/texture_context/trait.ContextTextureExt.html#method.tex_storage_2d)).
+    pub fn is_immutable(&self) -> bool {
+        self.immutable
+    }
+
+    // Record that the texture's storage was allocated as immutable storage
+    #[doc(hidden)]
+    pub fn _set_immutable(&mut self) {
+        self.immutable = true;
+    }
+
+    // Record that image data was set for a level of the texture
+    #[doc(hidden)]
+    pub fn _set_level(&mut self,
+                      level: u32,
+                      format: ImageFormat,
+                      width: u32,
+                      height: u32)
+    {
+        if level == 0 {
+            self.width = width;
+            self.height = height;
+            self.format = Some(format);
+            self.levels = if self.levels > 1 { self.levels } else { 1 };
+        }
+        else if level + 1 > self.levels {
+            self.levels = level + 1;
+        }
+    }
+
+    // Record that compressed image data was set for a level of the texture
+    #[doc(hidden)]
+    pub fn _set_compressed_level(&mut self,
+                                 level: u32,
+                                 width: u32,
+                                 height: u32)
+    {
+        if level == 0 {
+            self.width = width;
+            self.height = height;
+            self.format = None;
+            self.levels = if self.levels > 1 { self.levels } else { 1 };
+        }
+        else if level + 1 > self.levels {
+            self.levels = level + 1;
+        }
+    }
+
+    // Record that a complete mipmap chain was generated for the texture
+    #[doc(hidden)]
+    pub fn _set_mipmapped(&mut self) {
+        let height = if self.depth > self.height { self.depth } else { self.height };
+        self.levels = mip_level_count(self.width, height);
+    }
+}
+
+#[cfg(feature = "gles3")]
+impl Texture<Tx3d> {
+    /// Get the size of the 3D texture's base level, as
+    /// `(width, height, depth)`. The size is `(0, 0, 0)` if no image data
+    /// has been set.
+    pub fn dimensions_3d(&self) -> (u32, u32, u32) {
+        (self.width, self.height, self.depth)
+    }
+
+    // Record that image data was set for a level of the 3D texture
+    #[doc(hidden)]
+    pub fn _set_level_3d(&mut self,
+                         level: u32,
+                         format: ImageFormat,
+                         width: u32,
+                         height: u32,
+                         depth: u32)
+    {
+        if level == 0 {
+            self.depth = depth;
+        }
+        self._set_level(level, format, width, height);
+    }
+}
+
 impl<T: TextureType> Drop for Texture<T> {
     fn drop(&mut self) {
-        unsafe {
-            gl::DeleteTextures(1, &self.gl_id as *const GLuint);
-        }
+        state_cache::forget_texture(self.gl_id);
+        deletion_queue::delete(self.owner, Deleted::Texture(self.gl_id));
     }
 }
 
@@ -76,6 +261,13 @@ impl<T: TextureType> GLObject for Texture<T> {
     unsafe fn from_raw(id: Self::Id) -> Self {
         Texture {
             gl_id: id,
+            owner: Owner::current(),
+            width: 0,
+            height: 0,
+            depth: 0,
+            format: None,
+            levels: 0,
+            immutable: false,
             phantom: PhantomData
         }
     }
@@ -85,6 +277,12 @@ impl<T: TextureType> GLObject for Texture<T> {
     }
 }
 
+impl<T: TextureType> Labeled for Texture<T> {
+    fn label_identifier() -> GLenum {
+        gl::TEXTURE
+    }
+}
+
 
 
 /// A trait implemented for types that are used to represent all of the
@@ -186,6 +384,43 @@ impl TextureType for TxCubeMap {
     }
 }
 
+/// The [`TextureType`](trait.TextureType.html) for 3-dimensional textures.
+///
+/// # Note
+/// 3D textures require OpenGL ES 3.0, so this type is only available with
+/// the `gles3` feature.
+#[cfg(feature = "gles3")]
+pub struct Tx3d;
+
+/// The possible image targets for `GL_TEXTURE_3D` (only one variant,
+/// since this *is* the 3D texture).
+///
+/// # Note
+/// 3D textures require OpenGL ES 3.0, so this type is only available with
+/// the `gles3` feature.
+#[cfg(feature = "gles3")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tx3dImageTarget {
+    /// The only possible target for a 3-dimensional texture.
+    Texture3d = gl::TEXTURE_3D as isize
+}
+
+#[cfg(feature = "gles3")]
+impl ImageTargetType for Tx3dImageTarget {
+    fn gl_enum(&self) -> GLenum {
+        *self as GLenum
+    }
+}
+
+#[cfg(feature = "gles3")]
+impl TextureType for Tx3d {
+    type ImageTargetType = Tx3dImageTarget;
+
+    fn target() -> TextureBindingTarget {
+        TextureBindingTarget::Texture3d
+    }
+}
+
 
 
 /// Represents all of the possible types of OpenGL textures.
@@ -197,7 +432,14 @@ pub enum TextureBindingTarget {
     /// A cubemap texture, which is a texture made up of six 2-dimensional
     /// images, each of which represent a face of a cube. This type of texture
     /// is especially useful for skyboxes.
-    TextureCubeMap = gl::TEXTURE_CUBE_MAP as isize
+    TextureCubeMap = gl::TEXTURE_CUBE_MAP as isize,
+
+    /// A 3-dimensional texture, which can be thought of as a stack of 2D
+    /// images. This type of texture is useful for volumes, and for color
+    /// grading lookup tables. This variant is only available with the
+    /// `gles3` feature.
+    #[cfg(feature = "gles3")]
+    Texture3d = gl::TEXTURE_3D as isize
 }
 
 impl TextureBindingTarget {
@@ -265,6 +507,12 @@ pub const TEXTURE_CUBE_MAP : TextureBindingTarget =
 /// for details about how this works.
 pub const TEXTURE_2D : VariantTexture2d = VariantTexture2d;
 
+/// This constant is designed to be used in glitter wherever the constant
+/// `GL_TEXTURE_3D` is used in plain OpenGL code. It is only available with
+/// the `gles3` feature.
+#[cfg(feature = "gles3")]
+pub const TEXTURE_3D : Tx3dImageTarget = Tx3dImageTarget::Texture3d;
+
 
 /// Represents the different forms of texture filtering, which determines
 /// how a texture will be sampled when drawn.
@@ -418,3 +666,124 @@ gl_enum! {
         pub const Repeat as REPEAT = gl::REPEAT
     }
 }
+
+gl_enum! {
+    /// The sources that a texture's color components can be swizzled from
+    /// (see [`gl.set_swizzle`]
+    /// (../context/texture_context/trait.ContextTextureExt.html#method.set_swizzle)).
+    pub gl_enum TextureSwizzle {
+        /// Use the texture's red component.
+        pub const Red as SWIZZLE_RED = gl::RED,
+
+        /// Use the texture's green component.
+        pub const Green as SWIZZLE_GREEN = gl::GREEN,
+
+        /// Use the texture's blue component.
+        pub const Blue as SWIZZLE_BLUE = gl::BLUE,
+
+        /// Use the texture's alpha component.
+        pub const Alpha as SWIZZLE_ALPHA = gl::ALPHA,
+
+        /// Use a constant `0.0`.
+        pub const Zero as SWIZZLE_ZERO = gl::ZERO,
+
+        /// Use a constant `1.0`.
+        pub const One as SWIZZLE_ONE = gl::ONE
+    }
+}
+
+/// The swizzle that returns a texture's components unchanged. This is the
+/// default swizzle for a texture.
+pub const IDENTITY_SWIZZLE: [TextureSwizzle; 4] = [TextureSwizzle::Red,
+                                                   TextureSwizzle::Green,
+                                                   TextureSwizzle::Blue,
+                                                   TextureSwizzle::Alpha];
+
+/// A swizzle that samples a single-channel texture as a luminance texture,
+/// by returning the red component as each color component, with an alpha
+/// component of `1.0`.
+pub const LUMINANCE_SWIZZLE: [TextureSwizzle; 4] = [TextureSwizzle::Red,
+                                                    TextureSwizzle::Red,
+                                                    TextureSwizzle::Red,
+                                                    TextureSwizzle::One];
+
+/// A swizzle that samples a two-channel texture as a luminance/alpha
+/// texture, by returning the red component as each color component, and the
+/// green component as the alpha component.
+pub const LUMINANCE_ALPHA_SWIZZLE: [TextureSwizzle; 4] = [TextureSwizzle::Red,
+                                                          TextureSwizzle::Red,
+                                                          TextureSwizzle::Red,
+                                                          TextureSwizzle::Green];
+
+/// A swizzle that samples a single-channel texture as an alpha texture, by
+/// returning the red component as the alpha component, with color
+/// components of `0.0`.
+pub const ALPHA_SWIZZLE: [TextureSwizzle; 4] = [TextureSwizzle::Zero,
+                                                TextureSwizzle::Zero,
+                                                TextureSwizzle::Zero,
+                                                TextureSwizzle::Red];
+
+/// A complete set of texture sampling parameters, which can be applied to
+/// a texture all at once with [`gl.set_texture_params`]
+/// (../context/texture_context/trait.ContextTextureExt.html#method.set_texture_params).
+/// Each parameter starts with the same default value as OpenGL uses for a
+/// new texture.
+///
+/// # Example
+/// ```
+/// use glitter::TextureParams;
+///
+/// let params = TextureParams {
+///     min_filter: glitter::LINEAR_MIPMAP_LINEAR,
+///     wrap_s: glitter::CLAMP_TO_EDGE,
+///     wrap_t: glitter::CLAMP_TO_EDGE,
+///     ..TextureParams::default()
+/// };
+/// assert!(params != TextureParams::default());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureParams {
+    /// The minifying filter (`NEAREST_MIPMAP_LINEAR` by default).
+    pub min_filter: TextureMipmapFilter,
+
+    /// The magnifying filter (`LINEAR` by default).
+    pub mag_filter: TextureFilter,
+
+    /// The wrap mode for the s-coordinate (`REPEAT` by default).
+    pub wrap_s: TextureWrapMode,
+
+    /// The wrap mode for the t-coordinate (`REPEAT` by default).
+    pub wrap_t: TextureWrapMode,
+
+    /// The wrap mode for the r-coordinate, which is only used by 3D
+    /// textures (`REPEAT` by default). This parameter is ignored without
+    /// the `gles3` feature.
+    pub wrap_r: TextureWrapMode,
+
+    /// The sources of the red, green, blue, and alpha components returned
+    /// when sampling the texture (the texture's own components by
+    /// default). This parameter is ignored without the `gles3` feature.
+    pub swizzle: [TextureSwizzle; 4],
+
+    /// The comparison function used to sample a depth texture as a shadow
+    /// map, or `None` to sample the depth values directly (the default).
+    /// See [`gl.set_compare_func`]
+    /// (../context/texture_context/trait.ContextTextureExt.html#method.set_compare_func)
+    /// for more details. This parameter is ignored without the `gles3`
+    /// feature.
+    pub compare_func: Option<DepthFunction>
+}
+
+impl Default for TextureParams {
+    fn default() -> Self {
+        TextureParams {
+            min_filter: NEAREST_MIPMAP_LINEAR,
+            mag_filter: TextureFilter::Linear,
+            wrap_s: TextureWrapMode::Repeat,
+            wrap_t: TextureWrapMode::Repeat,
+            wrap_r: TextureWrapMode::Repeat,
+            swizzle: IDENTITY_SWIZZLE,
+            compare_func: None
+        }
+    }
+}
@@ -0,0 +1,1035 @@
+//! Contains the [`TextureContainer`](struct.TextureContainer.html) type,
+//! which loads texture data (including all of its mipmap levels and cubemap
+//! faces) from KTX, KTX2, and DDS files.
+
+use std::fmt;
+use std::error;
+use std::borrow::Cow;
+use gl;
+use context::{ContextOf, ContextTextureExt, TextureBinding};
+use texture::{Tx2d, TxCubeMap, Tx2dImageTarget, TxCubeMapImageTarget,
+              TextureType, mip_level_size};
+use image_data::{Image2d, ImageFormat, TexelFormat, TexelType,
+                 CompressedTextureFormat};
+use types::GLError;
+
+const KTX_IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31,
+                                  0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+const KTX2_IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32,
+                                   0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+const DDS_MAGIC: [u8; 4] = [0x44, 0x44, 0x53, 0x20];
+
+// `DDS_PIXELFORMAT` flags
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+
+// `DDS_HEADER` caps
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_VOLUME: u32 = 0x200000;
+
+// `DDS_HEADER_DXT10` misc flags
+const DDS_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+// Cubemap faces, in the order they are stored in KTX and DDS files
+const CUBE_MAP_FACES: [TxCubeMapImageTarget; 6] = [
+    TxCubeMapImageTarget::CubeMapPositiveX,
+    TxCubeMapImageTarget::CubeMapNegativeX,
+    TxCubeMapImageTarget::CubeMapPositiveY,
+    TxCubeMapImageTarget::CubeMapNegativeY,
+    TxCubeMapImageTarget::CubeMapPositiveZ,
+    TxCubeMapImageTarget::CubeMapNegativeZ
+];
+
+/// An error that can occur while reading a [`TextureContainer`]
+/// (struct.TextureContainer.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureContainerError {
+    /// The data doesn't start with a KTX, KTX2, or DDS header.
+    UnknownContainer,
+
+    /// The data ended before the end of the header or texture data.
+    Truncated,
+
+    /// The texture's format can't be represented by glitter as either
+    /// an [`ImageFormat`](../image_data/struct.ImageFormat.html) or a
+    /// [`CompressedTextureFormat`]
+    /// (../image_data/enum.CompressedTextureFormat.html).
+    UnsupportedFormat,
+
+    /// The container uses a feature that glitter doesn't support (such
+    /// as KTX2 supercompression).
+    Unsupported(&'static str),
+
+    /// The container's header is invalid.
+    Invalid(&'static str)
+}
+
+impl fmt::Display for TextureContainerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TextureContainerError::Unsupported(msg) |
+            TextureContainerError::Invalid(msg) => {
+                write!(f, "{}: {}", error::Error::description(self), msg)
+            },
+            _ => write!(f, "{}", error::Error::description(self))
+        }
+    }
+}
+
+impl error::Error for TextureContainerError {
+    fn description(&self) -> &str {
+        match *self {
+            TextureContainerError::UnknownContainer => "The data is not a KTX, KTX2, or DDS texture container",
+            TextureContainerError::Truncated => "The texture container ended unexpectedly",
+            TextureContainerError::UnsupportedFormat => "The texture container's format is not supported",
+            TextureContainerError::Unsupported(_) => "The texture container is not supported",
+            TextureContainerError::Invalid(_) => "The texture container is invalid"
+        }
+    }
+}
+
+impl From<TextureContainerError> for GLError {
+    fn from(e: TextureContainerError) -> GLError {
+        GLError::Message(format!("{}", e))
+    }
+}
+
+/// The format of the images in a [`TextureContainer`]
+/// (struct.TextureContainer.html).
+#[derive(Debug, Clone, Copy)]
+pub enum TextureContainerFormat {
+    /// The images contain block-compressed data.
+    Compressed(CompressedTextureFormat),
+
+    /// The images contain uncompressed texels.
+    Uncompressed(ImageFormat)
+}
+
+impl TextureContainerFormat {
+    /// Get the size of a 2D image with this format, in bytes. The rows
+    /// of uncompressed images are tightly packed.
+    pub fn image_size(&self, width: u32, height: u32) -> usize {
+        match *self {
+            TextureContainerFormat::Compressed(format) => {
+                format.image_size(width, height)
+            },
+            TextureContainerFormat::Uncompressed(format) => {
+                width as usize * height as usize * format.texel_size()
+            }
+        }
+    }
+}
+
+/// A single image from a [`TextureContainer`](struct.TextureContainer.html),
+/// which is one mipmap level of one array layer and cubemap face.
+#[derive(Debug, Clone)]
+pub struct TextureContainerImage {
+    /// The mipmap level of the image.
+    pub level: u32,
+
+    /// The array layer of the image (`0` unless the container holds an
+    /// array texture).
+    pub layer: u32,
+
+    /// The cubemap face of the image, in the order `+X`, `-X`, `+Y`, `-Y`,
+    /// `+Z`, `-Z` (`0` unless the container holds a cubemap).
+    pub face: u32,
+
+    /// The width of the image, in texels.
+    pub width: u32,
+
+    /// The height of the image, in texels.
+    pub height: u32,
+
+    /// The depth of the image, in texels (`1` unless the container holds
+    /// a 3D texture). The slices of a 3D image are stored one after
+    /// another.
+    pub depth: u32,
+
+    /// The image data. The rows of uncompressed images are tightly packed.
+    pub data: Vec<u8>
+}
+
+/// Texture data that was loaded from a KTX, KTX2, or DDS file, which are
+/// the usual containers for pre-compressed textures and pre-generated
+/// mipmaps. A 2D texture or cubemap can be uploaded from a container with
+/// [`gl.upload_texture_container_2d`]
+/// (../context/struct.ContextOf.html#method.upload_texture_container_2d)
+/// or [`gl.upload_texture_container_cube_map`]
+/// (../context/struct.ContextOf.html#method.upload_texture_container_cube_map).
+///
+/// # Note
+/// Texture containers are only available with the `texture_containers`
+/// feature.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use std::fs::File;
+/// use std::io::Read;
+/// use glitter::prelude::*;
+/// use glitter::TextureContainer;
+///
+/// # fn main() {
+/// let mut bytes = vec![];
+/// File::open("skybox.ktx").unwrap().read_to_end(&mut bytes).unwrap();
+/// let container = TextureContainer::from_bytes(&bytes).unwrap();
+///
+/// let mut gl = unsafe { glitter::Context::current_context() };
+/// let mut skybox: glitter::TextureCubeMap = unsafe { gl.gen_texture() };
+/// let (gl_tex_unit, gl) = gl.active_texture_0();
+/// let (mut gl_skybox, _) = gl_tex_unit.bind_texture_cube_map(&mut skybox);
+/// gl.upload_texture_container_cube_map(&mut gl_skybox, &container).unwrap();
+/// if container.levels == 1 {
+///     gl.set_min_filter(&mut gl_skybox, glitter::LINEAR);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextureContainer {
+    /// The format of the container's images.
+    pub format: TextureContainerFormat,
+
+    /// The width of the base mipmap level, in texels.
+    pub width: u32,
+
+    /// The height of the base mipmap level, in texels.
+    pub height: u32,
+
+    /// The depth of the base mipmap level, in texels. This is always `1`,
+    /// since containers that hold a 3D texture are rejected with
+    /// [`TextureContainerError::Unsupported`]
+    /// (enum.TextureContainerError.html#variant.Unsupported).
+    pub depth: u32,
+
+    /// The number of array layers. This is always `1`, since containers
+    /// that hold an array texture are rejected with
+    /// [`TextureContainerError::Unsupported`]
+    /// (enum.TextureContainerError.html#variant.Unsupported).
+    pub layers: u32,
+
+    /// The number of cubemap faces (`6` for a cubemap, or `1` otherwise).
+    pub faces: u32,
+
+    /// The number of mipmap levels, including the base level.
+    pub levels: u32,
+
+    /// The container's images.
+    pub images: Vec<TextureContainerImage>
+}
+
+impl TextureContainer {
+    /// Read a texture container from the contents of a KTX, KTX2, or DDS
+    /// file, which is detected from the start of the data.
+    ///
+    /// # Failures
+    /// An error will be returned if the data isn't a KTX, KTX2, or DDS file,
+    /// or if the file can't be read (see [`TextureContainerError`]
+    /// (enum.TextureContainerError.html)).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TextureContainerError> {
+        if bytes.starts_with(&KTX_IDENTIFIER) {
+            TextureContainer::from_ktx(bytes)
+        }
+        else if bytes.starts_with(&KTX2_IDENTIFIER) {
+            TextureContainer::from_ktx2(bytes)
+        }
+        else if bytes.starts_with(&DDS_MAGIC) {
+            TextureContainer::from_dds(bytes)
+        }
+        else {
+            Err(TextureContainerError::UnknownContainer)
+        }
+    }
+
+    /// Read a texture container from the contents of a KTX (version 1)
+    /// file.
+    ///
+    /// # Example
+    /// ```
+    /// use glitter::{TextureContainer, TextureContainerError};
+    ///
+    /// let result = TextureContainer::from_ktx(b"not a KTX file");
+    /// assert_eq!(result.unwrap_err(), TextureContainerError::UnknownContainer);
+    /// ```
+    pub fn from_ktx(bytes: &[u8]) -> Result<Self, TextureContainerError> {
+        if !bytes.starts_with(&KTX_IDENTIFIER) {
+            return Err(TextureContainerError::UnknownContainer);
+        }
+
+        let big_endian = match try!(_read_u32(bytes, 12, false)) {
+            0x04030201 => false,
+            0x01020304 => true,
+            _ => {
+                return Err(TextureContainerError::Invalid("unknown KTX endianness"));
+            }
+        };
+        let mut header = [0; 12];
+        for (i, field) in header.iter_mut().enumerate() {
+            *field = try!(_read_u32(bytes, 16 + i * 4, big_endian));
+        }
+        let gl_type = header[0];
+        let gl_format = header[2];
+        let gl_internal_format = header[3];
+        let width = header[5];
+        let height = _at_least_1(header[6]);
+        let depth = _at_least_1(header[7]);
+        let layers = _at_least_1(header[8]);
+        let faces = header[9];
+        let levels = _at_least_1(header[10]);
+        let key_value_bytes = header[11] as usize;
+
+        let format = if gl_type == 0 {
+            match CompressedTextureFormat::from_gl(gl_internal_format) {
+                Ok(format) => TextureContainerFormat::Compressed(format),
+                Err(_) => {
+                    return Err(TextureContainerError::UnsupportedFormat);
+                }
+            }
+        }
+        else {
+            let texel_format = match gl_internal_format {
+                gl::SRGB8 | gl::SRGB => Ok(TexelFormat::SRGB),
+                gl::SRGB8_ALPHA8 | gl::SRGB_ALPHA => Ok(TexelFormat::SRGBAlpha),
+                _ => TexelFormat::from_gl(gl_format)
+            };
+            match (texel_format, TexelType::from_gl(gl_type)) {
+                (Ok(texel_format), Ok(texel_type)) => {
+                    TextureContainerFormat::Uncompressed(ImageFormat {
+                        texel_type: texel_type,
+                        texel_format: texel_format
+                    })
+                },
+                _ => {
+                    return Err(TextureContainerError::UnsupportedFormat);
+                }
+            }
+        };
+        try!(_check_shape(width, depth, layers, faces, levels));
+
+        let mut images = vec![];
+        let mut offset = try!(_checked_add(64, key_value_bytes));
+        for level in 0..levels {
+            // Skip `imageSize`, since the size of each image is computed
+            // from the format instead
+            try!(_read_u32(bytes, offset, big_endian));
+            offset += 4;
+
+            let (level_width, level_height) = mip_level_size(width,
+                                                             height,
+                                                             level);
+            let level_depth = _mip_depth(depth, level);
+            for layer in 0..layers {
+                for face in 0..faces {
+                    // KTX pads the rows of uncompressed images to 4 bytes
+                    let (data, size) = match format {
+                        TextureContainerFormat::Compressed(_) => {
+                            let size = try!(_image_size(format,
+                                                        level_width,
+                                                        level_height,
+                                                        level_depth));
+                            (try!(_slice(bytes, offset, size)).to_vec(), size)
+                        },
+                        TextureContainerFormat::Uncompressed(format) => {
+                            let row = try!(_checked_mul(level_width as usize,
+                                                        format.texel_size()));
+                            let rows = try!(_checked_mul(level_height as usize,
+                                                         level_depth as usize));
+                            let padded_row = try!(_checked_add(row, 3)) / 4 * 4;
+                            let size = try!(_checked_mul(padded_row, rows));
+                            let padded = try!(_slice(bytes, offset, size));
+                            (_unpad_rows(padded, row, rows), size)
+                        }
+                    };
+                    images.push(TextureContainerImage {
+                        level: level,
+                        layer: layer,
+                        face: face,
+                        width: level_width,
+                        height: level_height,
+                        depth: level_depth,
+                        data: data
+                    });
+                    offset += _pad_4(size);
+                }
+            }
+            offset = _pad_4(offset);
+        }
+
+        Ok(TextureContainer {
+            format: format,
+            width: width,
+            height: height,
+            depth: depth,
+            layers: layers,
+            faces: faces,
+            levels: levels,
+            images: images
+        })
+    }
+
+    /// Read a texture container from the contents of a KTX2 file. Only
+    /// textures with a known `vkFormat` and without supercompression
+    /// are supported.
+    pub fn from_ktx2(bytes: &[u8]) -> Result<Self, TextureContainerError> {
+        if !bytes.starts_with(&KTX2_IDENTIFIER) {
+            return Err(TextureContainerError::UnknownContainer);
+        }
+
+        let mut header = [0; 9];
+        for (i, field) in header.iter_mut().enumerate() {
+            *field = try!(_read_u32(bytes, 12 + i * 4, false));
+        }
+        let vk_format = header[0];
+        let width = header[2];
+        let height = _at_least_1(header[3]);
+        let depth = _at_least_1(header[4]);
+        let layers = _at_least_1(header[5]);
+        let faces = header[6];
+        let levels = _at_least_1(header[7]);
+        let supercompression_scheme = header[8];
+
+        if supercompression_scheme != 0 {
+            let msg = "supercompressed KTX2 textures are not supported";
+            return Err(TextureContainerError::Unsupported(msg));
+        }
+        let format = try!(_ktx2_format(vk_format));
+        try!(_check_shape(width, depth, layers, faces, levels));
+
+        let mut images = vec![];
+        for level in 0..levels {
+            let index = 80 + level as usize * 24;
+            let mut offset = try!(_read_u64(bytes, index)) as usize;
+
+            let (level_width, level_height) = mip_level_size(width,
+                                                             height,
+                                                             level);
+            let level_depth = _mip_depth(depth, level);
+            let size = try!(_image_size(format,
+                                        level_width,
+                                        level_height,
+                                        level_depth));
+            for layer in 0..layers {
+                for face in 0..faces {
+                    let data = try!(_slice(bytes, offset, size));
+                    images.push(TextureContainerImage {
+                        level: level,
+                        layer: layer,
+                        face: face,
+                        width: level_width,
+                        height: level_height,
+                        depth: level_depth,
+                        data: data.to_vec()
+                    });
+                    offset += size;
+                }
+            }
+        }
+
+        Ok(TextureContainer {
+            format: format,
+            width: width,
+            height: height,
+            depth: depth,
+            layers: layers,
+            faces: faces,
+            levels: levels,
+            images: images
+        })
+    }
+
+    /// Read a texture container from the contents of a DDS file. DXT1,
+    /// DXT3, DXT5, and 8-bit RGB and RGBA textures are supported (including
+    /// with a `DX10` header).
+    pub fn from_dds(bytes: &[u8]) -> Result<Self, TextureContainerError> {
+        if !bytes.starts_with(&DDS_MAGIC) {
+            return Err(TextureContainerError::UnknownContainer);
+        }
+        if try!(_read_u32(bytes, 4, false)) != 124 {
+            return Err(TextureContainerError::Invalid("invalid DDS header size"));
+        }
+
+        let height = _at_least_1(try!(_read_u32(bytes, 12, false)));
+        let width = try!(_read_u32(bytes, 16, false));
+        let depth = try!(_read_u32(bytes, 24, false));
+        let levels = _at_least_1(try!(_read_u32(bytes, 28, false)));
+        let pf_flags = try!(_read_u32(bytes, 80, false));
+        let four_cc = try!(_read_u32(bytes, 84, false));
+        let rgb_bits = try!(_read_u32(bytes, 88, false));
+        let masks = (try!(_read_u32(bytes, 92, false)),
+                     try!(_read_u32(bytes, 96, false)),
+                     try!(_read_u32(bytes, 100, false)),
+                     try!(_read_u32(bytes, 104, false)));
+        let caps2 = try!(_read_u32(bytes, 112, false));
+
+        let depth = if caps2 & DDSCAPS2_VOLUME != 0 { _at_least_1(depth) }
+                    else { 1 };
+        let mut faces = if caps2 & DDSCAPS2_CUBEMAP != 0 { 6 } else { 1 };
+        let mut layers = 1;
+        let mut offset = 128;
+
+        let format = if pf_flags & DDPF_FOURCC != 0 {
+            if four_cc == _four_cc(b"DXT1") {
+                TextureContainerFormat::Compressed(CompressedTextureFormat::RGBAS3tcDxt1)
+            }
+            else if four_cc == _four_cc(b"DXT3") {
+                TextureContainerFormat::Compressed(CompressedTextureFormat::RGBAS3tcDxt3)
+            }
+            else if four_cc == _four_cc(b"DXT5") {
+                TextureContainerFormat::Compressed(CompressedTextureFormat::RGBAS3tcDxt5)
+            }
+            else if four_cc == _four_cc(b"DX10") {
+                let dxgi_format = try!(_read_u32(bytes, 128, false));
+                let misc_flags = try!(_read_u32(bytes, 136, false));
+                layers = _at_least_1(try!(_read_u32(bytes, 140, false)));
+                if misc_flags & DDS_RESOURCE_MISC_TEXTURECUBE != 0 {
+                    faces = 6;
+                }
+                offset = 148;
+                try!(_dxgi_format(dxgi_format))
+            }
+            else {
+                return Err(TextureContainerError::UnsupportedFormat);
+            }
+        }
+        else if pf_flags & DDPF_RGB != 0 {
+            match (rgb_bits, masks) {
+                (32, (0xFF, 0xFF00, 0xFF0000, 0xFF000000)) => {
+                    TextureContainerFormat::Uncompressed(ImageFormat::rgba8())
+                },
+                (24, (0xFF, 0xFF00, 0xFF0000, _)) => {
+                    TextureContainerFormat::Uncompressed(ImageFormat {
+                        texel_type: TexelType::UnsignedByte,
+                        texel_format: TexelFormat::RGB
+                    })
+                },
+                _ => {
+                    return Err(TextureContainerError::UnsupportedFormat);
+                }
+            }
+        }
+        else {
+            return Err(TextureContainerError::UnsupportedFormat);
+        };
+        try!(_check_shape(width, depth, layers, faces, levels));
+
+        // Unlike KTX, DDS stores the full mipmap chain of each face together
+        let mut images = vec![];
+        for layer in 0..layers {
+            for face in 0..faces {
+                for level in 0..levels {
+                    let (level_width, level_height) = mip_level_size(width,
+                                                                     height,
+                                                                     level);
+                    let level_depth = _mip_depth(depth, level);
+                    let size = try!(_image_size(format,
+                                                level_width,
+                                                level_height,
+                                                level_depth));
+                    let data = try!(_slice(bytes, offset, size));
+                    images.push(TextureContainerImage {
+                        level: level,
+                        layer: layer,
+                        face: face,
+                        width: level_width,
+                        height: level_height,
+                        depth: level_depth,
+                        data: data.to_vec()
+                    });
+                    offset += size;
+                }
+            }
+        }
+
+        Ok(TextureContainer {
+            format: format,
+            width: width,
+            height: height,
+            depth: depth,
+            layers: layers,
+            faces: faces,
+            levels: levels,
+            images: images
+        })
+    }
+
+    /// Returns `true` if the container holds a cubemap.
+    pub fn is_cube_map(&self) -> bool {
+        self.faces == 6
+    }
+
+    /// Returns `true` if the container holds an array texture.
+    pub fn is_array(&self) -> bool {
+        self.layers > 1
+    }
+
+    /// Returns `true` if the container holds a 3D texture.
+    pub fn is_3d(&self) -> bool {
+        self.depth > 1
+    }
+
+    /// Get the image for a mipmap level, array layer, and cubemap face,
+    /// or `None` if the container has no such image.
+    pub fn image(&self, level: u32, layer: u32, face: u32)
+        -> Option<&TextureContainerImage>
+    {
+        self.images.iter().find(|image| {
+            image.level == level && image.layer == layer && image.face == face
+        })
+    }
+}
+
+// An uncompressed container image, with its rows padded to 4 bytes (the
+// default `GL_UNPACK_ALIGNMENT`)
+struct ContainerImage2d<'a> {
+    format: ImageFormat,
+    width: u32,
+    height: u32,
+    data: Cow<'a, [u8]>
+}
+
+impl<'a> Image2d for ContainerImage2d<'a> {
+    fn width(&self) -> usize {
+        self.width as usize
+    }
+
+    fn height(&self) -> usize {
+        self.height as usize
+    }
+
+    fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    fn texel_bytes(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
+    /// Upload every mipmap level of a [`TextureContainer`]
+    /// (../texture_container/struct.TextureContainer.html) to a 2D texture.
+    /// Compressed images are uploaded with [`gl.compressed_tex_image_2d`]
+    /// (struct.ContextOf.html#method.compressed_tex_image_2d), and
+    /// uncompressed images are uploaded with [`gl.tex_image_2d`]
+    /// (trait.ContextTextureExt.html#method.tex_image_2d).
+    ///
+    /// If the container only has a base level, the texture's minifying
+    /// filter should be set to a filter that doesn't use mipmaps (or
+    /// mipmaps should be generated), so that the texture is complete.
+    ///
+    /// # Failures
+    /// An error will be returned if the container doesn't hold a 2D
+    /// texture (such as a cubemap or an array texture), or if the
+    /// container's compressed format isn't supported by the context.
+    ///
+    /// # Note
+    /// This method is only available with the `texture_containers` feature.
+    pub fn upload_texture_container_2d<X>(&self,
+                                          gl_texture: &mut X,
+                                          container: &TextureContainer)
+        -> Result<(), GLError>
+        where X: TextureBinding<TextureType = Tx2d>
+    {
+        if container.is_cube_map() || container.is_array() || container.is_3d() {
+            let msg = "Error uploading texture container: the container does not hold a 2D texture";
+            return Err(GLError::Message(msg.to_owned()));
+        }
+
+        for image in &container.images {
+            try!(self._upload_container_image(gl_texture,
+                                              Tx2dImageTarget::Texture2d,
+                                              container.format,
+                                              image));
+        }
+        Ok(())
+    }
+
+    /// Upload every mipmap level of each face of a [`TextureContainer`]
+    /// (../texture_container/struct.TextureContainer.html) to a cubemap
+    /// texture. See [`gl.upload_texture_container_2d`]
+    /// (struct.ContextOf.html#method.upload_texture_container_2d) for
+    /// more details.
+    ///
+    /// # Failures
+    /// An error will be returned if the container doesn't hold a cubemap,
+    /// or if the container's compressed format isn't supported by the
+    /// context.
+    ///
+    /// # Note
+    /// This method is only available with the `texture_containers` feature.
+    pub fn upload_texture_container_cube_map<X>(&self,
+                                                gl_texture: &mut X,
+                                                container: &TextureContainer)
+        -> Result<(), GLError>
+        where X: TextureBinding<TextureType = TxCubeMap>
+    {
+        if !container.is_cube_map() || container.is_array() || container.is_3d() {
+            let msg = "Error uploading texture container: the container does not hold a cubemap";
+            return Err(GLError::Message(msg.to_owned()));
+        }
+
+        for image in &container.images {
+            let target = CUBE_MAP_FACES[image.face as usize];
+            try!(self._upload_container_image(gl_texture,
+                                              target,
+                                              container.format,
+                                              image));
+        }
+        Ok(())
+    }
+
+    fn _upload_container_image<X>(&self,
+                                  gl_texture: &mut X,
+                                  target: <X::TextureType as TextureType>::ImageTargetType,
+                                  format: TextureContainerFormat,
+                                  image: &TextureContainerImage)
+        -> Result<(), GLError>
+        where X: TextureBinding
+    {
+        match format {
+            TextureContainerFormat::Compressed(format) => {
+                try!(self.compressed_tex_image_2d(gl_texture,
+                                                  target,
+                                                  image.level,
+                                                  format,
+                                                  image.width,
+                                                  image.height,
+                                                  &image.data));
+            },
+            TextureContainerFormat::Uncompressed(format) => {
+                let row = image.width as usize * format.texel_size();
+                let data = if row % 4 == 0 {
+                    Cow::Borrowed(&image.data[..])
+                }
+                else {
+                    Cow::Owned(_pad_rows(&image.data, row, image.height as usize))
+                };
+                let image_2d = ContainerImage2d {
+                    format: format,
+                    width: image.width,
+                    height: image.height,
+                    data: data
+                };
+                self.tex_image_2d(gl_texture, target, image.level, &image_2d);
+            }
+        }
+        Ok(())
+    }
+}
+
+
+
+fn _read_u32(bytes: &[u8], offset: usize, big_endian: bool)
+    -> Result<u32, TextureContainerError>
+{
+    let b = try!(_slice(bytes, offset, 4));
+    let (b0, b1, b2, b3) = (b[0] as u32, b[1] as u32, b[2] as u32, b[3] as u32);
+    if big_endian {
+        Ok(b0 << 24 | b1 << 16 | b2 << 8 | b3)
+    }
+    else {
+        Ok(b0 | b1 << 8 | b2 << 16 | b3 << 24)
+    }
+}
+
+fn _read_u64(bytes: &[u8], offset: usize)
+    -> Result<u64, TextureContainerError>
+{
+    let low = try!(_read_u32(bytes, offset, false)) as u64;
+    let high = try!(_read_u32(bytes, offset + 4, false)) as u64;
+    Ok(low | high << 32)
+}
+
+fn _slice(bytes: &[u8], offset: usize, len: usize)
+    -> Result<&[u8], TextureContainerError>
+{
+    match offset.checked_add(len) {
+        Some(end) if end <= bytes.len() => Ok(&bytes[offset..end]),
+        _ => Err(TextureContainerError::Truncated)
+    }
+}
+
+fn _four_cc(code: &[u8; 4]) -> u32 {
+    (code[0] as u32) |
+    (code[1] as u32) << 8 |
+    (code[2] as u32) << 16 |
+    (code[3] as u32) << 24
+}
+
+fn _at_least_1(value: u32) -> u32 {
+    if value > 0 { value } else { 1 }
+}
+
+fn _pad_4(size: usize) -> usize {
+    (size + 3) / 4 * 4
+}
+
+fn _mip_depth(depth: u32, level: u32) -> u32 {
+    let depth = if level >= 32 { 0 } else { depth >> level };
+    _at_least_1(depth)
+}
+
+fn _check_shape(width: u32, depth: u32, layers: u32, faces: u32, levels: u32)
+    -> Result<(), TextureContainerError>
+{
+    if width == 0 {
+        Err(TextureContainerError::Invalid("the texture has a width of 0"))
+    }
+    else if faces != 1 && faces != 6 {
+        Err(TextureContainerError::Invalid("the texture must have 1 or 6 faces"))
+    }
+    else if levels > 32 {
+        Err(TextureContainerError::Invalid("the texture has more than 32 mipmap levels"))
+    }
+    else if depth > 1 {
+        Err(TextureContainerError::Unsupported("3D textures are not supported"))
+    }
+    else if layers > 1 {
+        Err(TextureContainerError::Unsupported("array textures are not supported"))
+    }
+    else {
+        Ok(())
+    }
+}
+
+fn _checked_add(a: usize, b: usize) -> Result<usize, TextureContainerError> {
+    a.checked_add(b)
+        .ok_or(TextureContainerError::Invalid("the texture is too large"))
+}
+
+fn _checked_mul(a: usize, b: usize) -> Result<usize, TextureContainerError> {
+    a.checked_mul(b)
+        .ok_or(TextureContainerError::Invalid("the texture is too large"))
+}
+
+// Get the size of an image (with tightly packed rows), returning an error
+// if the size would overflow
+fn _image_size(format: TextureContainerFormat,
+               width: u32,
+               height: u32,
+               depth: u32)
+    -> Result<usize, TextureContainerError>
+{
+    let (columns, rows, unit_size) = match format {
+        TextureContainerFormat::Compressed(format) => {
+            let (block_width, block_height) = format.block_dimensions();
+            (_div_ceil(width, block_width),
+             _div_ceil(height, block_height),
+             format.block_size())
+        },
+        TextureContainerFormat::Uncompressed(format) => {
+            (width, height, format.texel_size())
+        }
+    };
+    let size = try!(_checked_mul(columns as usize, rows as usize));
+    let size = try!(_checked_mul(size, unit_size));
+    _checked_mul(size, depth as usize)
+}
+
+fn _div_ceil(value: u32, divisor: u32) -> u32 {
+    value / divisor + (value % divisor != 0) as u32
+}
+
+// Remove the padding from the end of each row
+fn _unpad_rows(padded: &[u8], row: usize, rows: usize) -> Vec<u8> {
+    let padded_row = _pad_4(row);
+    if padded_row == row {
+        return padded.to_vec();
+    }
+
+    let mut data = Vec::with_capacity(row * rows);
+    for i in 0..rows {
+        let start = i * padded_row;
+        data.extend_from_slice(&padded[start..start + row]);
+    }
+    data
+}
+
+// Pad the end of each row to 4 bytes
+fn _pad_rows(data: &[u8], row: usize, rows: usize) -> Vec<u8> {
+    let padding = _pad_4(row) - row;
+    let mut padded = Vec::with_capacity(_pad_4(row) * rows);
+    for i in 0..rows {
+        let start = i * row;
+        padded.extend_from_slice(&data[start..start + row]);
+        padded.extend_from_slice(&[0, 0, 0][..padding]);
+    }
+    padded
+}
+
+fn _ktx2_format(vk_format: u32)
+    -> Result<TextureContainerFormat, TextureContainerError>
+{
+    let srgb8 = ImageFormat {
+        texel_type: TexelType::UnsignedByte,
+        texel_format: TexelFormat::SRGB
+    };
+    let srgb8_alpha8 = ImageFormat {
+        texel_type: TexelType::UnsignedByte,
+        texel_format: TexelFormat::SRGBAlpha
+    };
+    let rgb8 = ImageFormat {
+        texel_type: TexelType::UnsignedByte,
+        texel_format: TexelFormat::RGB
+    };
+
+    let compressed = match vk_format {
+        23 => { return Ok(TextureContainerFormat::Uncompressed(rgb8)); },
+        29 => { return Ok(TextureContainerFormat::Uncompressed(srgb8)); },
+        37 => {
+            let format = ImageFormat::rgba8();
+            return Ok(TextureContainerFormat::Uncompressed(format));
+        },
+        43 => {
+            return Ok(TextureContainerFormat::Uncompressed(srgb8_alpha8));
+        },
+        131 => CompressedTextureFormat::RGBS3tcDxt1,
+        133 => CompressedTextureFormat::RGBAS3tcDxt1,
+        135 => CompressedTextureFormat::RGBAS3tcDxt3,
+        137 => CompressedTextureFormat::RGBAS3tcDxt5,
+        147 => CompressedTextureFormat::RGB8Etc2,
+        148 => CompressedTextureFormat::SRGB8Etc2,
+        149 => CompressedTextureFormat::RGB8PunchthroughAlpha1Etc2,
+        151 => CompressedTextureFormat::RGBA8Etc2Eac,
+        152 => CompressedTextureFormat::SRGB8Alpha8Etc2Eac,
+        157 => CompressedTextureFormat::RGBAAstc4x4,
+        161 => CompressedTextureFormat::RGBAAstc5x5,
+        165 => CompressedTextureFormat::RGBAAstc6x6,
+        171 => CompressedTextureFormat::RGBAAstc8x8,
+        179 => CompressedTextureFormat::RGBAAstc10x10,
+        183 => CompressedTextureFormat::RGBAAstc12x12,
+        _ => { return Err(TextureContainerError::UnsupportedFormat); }
+    };
+    Ok(TextureContainerFormat::Compressed(compressed))
+}
+
+fn _dxgi_format(dxgi_format: u32)
+    -> Result<TextureContainerFormat, TextureContainerError>
+{
+    match dxgi_format {
+        28 => Ok(TextureContainerFormat::Uncompressed(ImageFormat::rgba8())),
+        29 => {
+            Ok(TextureContainerFormat::Uncompressed(ImageFormat {
+                texel_type: TexelType::UnsignedByte,
+                texel_format: TexelFormat::SRGBAlpha
+            }))
+        },
+        71 => {
+            let format = CompressedTextureFormat::RGBAS3tcDxt1;
+            Ok(TextureContainerFormat::Compressed(format))
+        },
+        74 => {
+            let format = CompressedTextureFormat::RGBAS3tcDxt3;
+            Ok(TextureContainerFormat::Compressed(format))
+        },
+        77 => {
+            let format = CompressedTextureFormat::RGBAS3tcDxt5;
+            Ok(TextureContainerFormat::Compressed(format))
+        },
+        _ => Err(TextureContainerError::UnsupportedFormat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gl;
+    use super::{TextureContainer, TextureContainerError, KTX_IDENTIFIER,
+                KTX2_IDENTIFIER, DDS_MAGIC};
+
+    fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+        bytes.extend_from_slice(&[value as u8,
+                                  (value >> 8) as u8,
+                                  (value >> 16) as u8,
+                                  (value >> 24) as u8]);
+    }
+
+    // A little-endian KTX file with one RGBA8 image of `width` by `height`
+    // texels, and `data_len` bytes of image data
+    fn ktx(width: u32, height: u32, depth: u32, data_len: usize) -> Vec<u8> {
+        let mut bytes = KTX_IDENTIFIER.to_vec();
+        push_u32(&mut bytes, 0x04030201);
+        for &field in &[gl::UNSIGNED_BYTE, 1, gl::RGBA, gl::RGBA8, gl::RGBA,
+                        width, height, depth, 0, 1, 1, 0] {
+            push_u32(&mut bytes, field);
+        }
+        push_u32(&mut bytes, data_len as u32);
+        bytes.extend(vec![0; data_len]);
+        bytes
+    }
+
+    // A KTX2 file with one RGBA8 image of `width` by `height` texels, and
+    // `data_len` bytes of image data
+    fn ktx2(width: u32, height: u32, data_len: usize) -> Vec<u8> {
+        let mut bytes = KTX2_IDENTIFIER.to_vec();
+        for &field in &[37, 1, width, height, 0, 0, 1, 1, 0] {
+            push_u32(&mut bytes, field);
+        }
+        bytes.extend(vec![0; 80 - bytes.len()]);
+        for &field in &[104, 0, data_len as u32, 0, data_len as u32, 0] {
+            push_u32(&mut bytes, field);
+        }
+        bytes.extend(vec![0; data_len]);
+        bytes
+    }
+
+    // A DDS file with one 32-bit RGBA image of `width` by `height` texels,
+    // and `data_len` bytes of image data
+    fn dds(width: u32, height: u32, data_len: usize) -> Vec<u8> {
+        let mut bytes = DDS_MAGIC.to_vec();
+        for &field in &[124, 0, height, width, 0, 0, 1] {
+            push_u32(&mut bytes, field);
+        }
+        bytes.extend(vec![0; 76 - bytes.len()]);
+        for &field in &[32, 0x40, 0, 32, 0xFF, 0xFF00, 0xFF0000, 0xFF000000] {
+            push_u32(&mut bytes, field);
+        }
+        bytes.extend(vec![0; 128 - bytes.len()]);
+        bytes.extend(vec![0; data_len]);
+        bytes
+    }
+
+    #[test]
+    fn reads_valid_containers() {
+        for bytes in &[ktx(2, 2, 0, 16), ktx2(2, 2, 16), dds(2, 2, 16)] {
+            let container = TextureContainer::from_bytes(bytes).unwrap();
+            assert_eq!((container.width, container.height), (2, 2));
+            assert_eq!(container.images.len(), 1);
+            assert_eq!(container.images[0].data.len(), 16);
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_headers() {
+        for bytes in &[ktx(2, 2, 0, 16), ktx2(2, 2, 16), dds(2, 2, 16)] {
+            for len in &[12, 20, 60] {
+                let result = TextureContainer::from_bytes(&bytes[..*len]);
+                assert_eq!(result.unwrap_err(), TextureContainerError::Truncated);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        for bytes in &[ktx(2, 2, 0, 15), ktx2(2, 2, 15), dds(2, 2, 15)] {
+            let result = TextureContainer::from_bytes(bytes);
+            assert_eq!(result.unwrap_err(), TextureContainerError::Truncated);
+        }
+    }
+
+    #[test]
+    fn rejects_oversized_headers() {
+        let max = !0;
+        for bytes in &[ktx(max, max, 0, 0), ktx2(max, max, 0), dds(max, max, 0)] {
+            let result = TextureContainer::from_bytes(bytes);
+            assert_eq!(result.unwrap_err(),
+                       TextureContainerError::Invalid("the texture is too large"));
+        }
+    }
+
+    #[test]
+    fn rejects_3d_textures() {
+        let result = TextureContainer::from_bytes(&ktx(2, 2, 2, 32));
+        assert_eq!(result.unwrap_err(),
+                   TextureContainerError::Unsupported("3D textures are not supported"));
+    }
+}
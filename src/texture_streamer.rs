@@ -0,0 +1,229 @@
+//! Contains the [`TextureStreamer`](struct.TextureStreamer.html) type, which
+//! uploads a new image to a texture every frame through pixel buffers.
+
+use std::ptr;
+use std::time::Duration;
+use gl;
+use gl::types::*;
+use context::{AContext, ContextBufferExt, TextureBinding};
+use buffer::Buffer;
+use fence::{Fence, FenceStatus, ContextFenceExt};
+use texture::Tx2d;
+use image_data::ImageFormat;
+use types::{GLObject, GLError};
+use state_cache;
+
+/// Streams frames of texel data (such as the frames of a video) to a 2D
+/// texture, without stalling the CPU while the GPU copies each frame.
+///
+/// The streamer owns two pixel unpack buffers. Each frame is written to
+/// the next buffer, and the texture is updated from that buffer, so that
+/// the copy into the texture happens asynchronously. Each buffer is
+/// guarded by a fence, so writing a frame only blocks if the GPU hasn't
+/// finished copying the frame that was written to the same buffer two
+/// frames earlier.
+///
+/// # Note
+/// Pixel buffers and fences require OpenGL ES 3.0 or OpenGL 3.2, so this
+/// type is only available with the `gles3` feature.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::{ImageFormat, ContextTextureStreamerExt};
+///
+/// # fn main() {
+/// # let decoded_frame = vec![0; 1280 * 720 * 4];
+/// let mut gl = unsafe { glitter::Context::current_context() };
+/// let mut video = gl.build_texture_2d()
+///                   .empty(ImageFormat::rgba8(), 1280, 720)
+///                   .min_filter(glitter::LINEAR)
+///                   .unwrap();
+/// let mut streamer = gl.new_texture_streamer(ImageFormat::rgba8(),
+///                                            1280,
+///                                            720);
+///
+/// // Each frame:
+/// let (gl_tex_unit, gl) = gl.active_texture_0();
+/// let (mut gl_video, _) = gl_tex_unit.bind_texture_2d(&mut video);
+/// gl.push_frame(&mut streamer, &mut gl_video, &decoded_frame);
+/// # }
+/// ```
+pub struct TextureStreamer {
+    buffers: Vec<Buffer>,
+    fences: Vec<Option<Fence>>,
+    current: usize,
+    format: ImageFormat,
+    width: u32,
+    height: u32
+}
+
+impl TextureStreamer {
+    /// Get the format of each frame.
+    pub fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    /// Get the width of each frame, in texels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the height of each frame, in texels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get the size of each frame, in bytes. Each row of a frame must be
+    /// padded to a multiple of 4 bytes (the default `GL_UNPACK_ALIGNMENT`).
+    pub fn frame_size(&self) -> usize {
+        let row = self.width as usize * self.format.texel_size();
+        let padded_row = (row + 3) / 4 * 4;
+        padded_row * self.height as usize
+    }
+}
+
+fn _bind_pixel_unpack_buffer(id: GLuint) {
+    if !state_cache::bind_buffer(gl::PIXEL_UNPACK_BUFFER, id) {
+        return;
+    }
+    unsafe {
+        gl::BindBuffer(gl::PIXEL_UNPACK_BUFFER, id);
+        dbg_gl_sanity_check! {
+            call "glBindBuffer";
+            args (gl::PIXEL_UNPACK_BUFFER, id);
+            GLError::InvalidEnum => "`target` is not an allowed value",
+            _ => "Unknown error"
+        }
+    }
+}
+
+/// An extension trait that adds [`TextureStreamer`]
+/// (struct.TextureStreamer.html) methods to OpenGL contexts.
+///
+/// # Note
+/// These methods bind the `GL_PIXEL_UNPACK_BUFFER` target, which isn't
+/// tracked by the context's bindings. The target is reset to 0 afterwards,
+/// so that other texture uploads read from client memory as usual.
+pub trait ContextTextureStreamerExt: AContext {
+    /// Create a new texture streamer for frames of `width` by `height`
+    /// texels, with the given format.
+    ///
+    /// # See also
+    /// [`glBufferData`](http://docs.gl/es3/glBufferData) OpenGL docs
+    fn new_texture_streamer(&self,
+                            format: ImageFormat,
+                            width: u32,
+                            height: u32)
+        -> TextureStreamer
+    {
+        let streamer = TextureStreamer {
+            buffers: vec![self.gen_buffer(), self.gen_buffer()],
+            fences: vec![None, None],
+            current: 1,
+            format: format,
+            width: width,
+            height: height
+        };
+
+        let size = streamer.frame_size();
+        for buffer in &streamer.buffers {
+            _bind_pixel_unpack_buffer(buffer.id());
+            unsafe {
+                gl::BufferData(gl::PIXEL_UNPACK_BUFFER,
+                               size as GLsizeiptr,
+                               ptr::null(),
+                               gl::STREAM_DRAW);
+                dbg_gl_error! {
+                    call "glBufferData";
+                    args (gl::PIXEL_UNPACK_BUFFER, size, gl::STREAM_DRAW);
+                    GLError::InvalidValue => "`size` is negative",
+                    GLError::OutOfMemory => "Unable to create a large enough buffer",
+                    _ => "Unknown error"
+                }
+            }
+        }
+        _bind_pixel_unpack_buffer(0);
+
+        streamer
+    }
+
+    /// Write the next frame to the streamer, and update `gl_texture` from
+    /// it. If the GPU is still copying the last frame that was written to
+    /// the same pixel buffer, this function blocks until it is done.
+    ///
+    /// # Panics
+    /// This function will panic if the length of `data` doesn't match the
+    /// streamer's [`frame_size`]
+    /// (struct.TextureStreamer.html#method.frame_size).
+    ///
+    /// # See also
+    /// [`glBufferSubData`](http://docs.gl/es3/glBufferSubData) and
+    /// [`glTexSubImage2D`](http://docs.gl/es3/glTexSubImage2D) OpenGL docs
+    fn push_frame<T>(&self,
+                     streamer: &mut TextureStreamer,
+                     gl_texture: &mut T,
+                     data: &[u8])
+        where T: TextureBinding<TextureType = Tx2d>
+    {
+        assert!(data.len() == streamer.frame_size(),
+                "Frame size does not match the texture streamer's size");
+
+        streamer.current = (streamer.current + 1) % streamer.buffers.len();
+
+        let current = streamer.current;
+        if let Some(fence) = streamer.fences[current].take() {
+            let timeout = Duration::from_secs(1);
+            loop {
+                match self.wait_client(&fence, timeout) {
+                    FenceStatus::TimeoutExpired => { },
+                    _ => { break; }
+                }
+            }
+        }
+
+        let target = gl_texture.target().gl_enum();
+        let format = streamer.format;
+        let (width, height) = (streamer.width, streamer.height);
+        _bind_pixel_unpack_buffer(streamer.buffers[current].id());
+        unsafe {
+            gl::BufferSubData(gl::PIXEL_UNPACK_BUFFER,
+                              0,
+                              data.len() as GLsizeiptr,
+                              data.as_ptr() as *const GLvoid);
+            dbg_gl_error! {
+                call "glBufferSubData";
+                args (gl::PIXEL_UNPACK_BUFFER, 0, data.len());
+                GLError::InvalidValue => "`offset` or `size` is out of range",
+                GLError::InvalidOperation => "Object 0 is bound to buffer target",
+                _ => "Unknown error"
+            }
+
+            // With a pixel unpack buffer bound, the data "pointer" is an
+            // offset into the buffer
+            gl::TexSubImage2D(target,
+                              0,
+                              0,
+                              0,
+                              width as GLsizei,
+                              height as GLsizei,
+                              format.texel_format.gl_enum(),
+                              format.texel_type.gl_enum(),
+                              ptr::null());
+            dbg_gl_error! {
+                call "glTexSubImage2D";
+                args (target, 0, 0, 0, width, height, format.texel_format, format.texel_type);
+                GLError::InvalidValue => "The frame is larger than the texture",
+                GLError::InvalidOperation => "The frame's format does not match the texture's format",
+                _ => "Unknown error"
+            }
+        }
+        streamer.fences[current] = Some(self.fence());
+        _bind_pixel_unpack_buffer(0);
+    }
+}
+
+impl<C: AContext> ContextTextureStreamerExt for C {
+
+}
@@ -4,6 +4,7 @@ use std::mem;
 use std::fmt;
 use std::error;
 use gl;
+use image_data::CompressedTextureFormat;
 
 /// A color, with floating-point RGBA components.
 pub struct Color {
@@ -28,7 +29,9 @@ impl Color {
 }
 
 /// An OpenGL viewport, with an origin and size, with integer components.
-#[derive(Clone, Copy)]
+/// A `Viewport` is also used as the rectangle for the scissor test (see
+/// [`gl.scissor`](context/trait.ContextExt.html#method.scissor)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Viewport {
     /// The X coordinate of the viewport's origin
     pub x: u32,
@@ -54,6 +57,128 @@ impl Viewport {
             height: height
         }
     }
+
+    /// Create a viewport within `parent`, where `x`, `y`, `width`, and
+    /// `height` are fractions of the parent's size (so `0.0` is the parent's
+    /// left or bottom edge, and `1.0` is the parent's right or top edge).
+    /// Edges are rounded to the nearest pixel, so viewports that share an
+    /// edge never overlap or leave a gap.
+    ///
+    /// # Example
+    /// ```
+    /// use glitter::Viewport;
+    ///
+    /// let window = Viewport::new(0, 0, 1280, 720);
+    /// // The left third of the window
+    /// let sidebar = Viewport::from_normalized(window, 0.0, 0.0, 1.0 / 3.0, 1.0);
+    /// assert_eq!(sidebar, Viewport::new(0, 0, 427, 720));
+    /// ```
+    pub fn from_normalized(parent: Viewport,
+                           x: f32,
+                           y: f32,
+                           width: f32,
+                           height: f32)
+        -> Self
+    {
+        Viewport::from_normalized_with(parent,
+                                       x,
+                                       y,
+                                       width,
+                                       height,
+                                       PixelRounding::Nearest)
+    }
+
+    /// Create a viewport within `parent`, where `x`, `y`, `width`, and
+    /// `height` are fractions of the parent's size, using `rounding` to
+    /// round the edges of the viewport to whole pixels. Edges that fall
+    /// outside of the parent are clamped to the parent.
+    ///
+    /// # Example
+    /// ```
+    /// use glitter::{Viewport, PixelRounding};
+    ///
+    /// let parent = Viewport::new(10, 10, 100, 100);
+    /// let inner = Viewport::from_normalized_with(parent,
+    ///                                            0.255, 0.255, 0.5, 0.5,
+    ///                                            PixelRounding::Inward);
+    /// assert_eq!(inner, Viewport::new(36, 36, 49, 49));
+    ///
+    /// let outer = Viewport::from_normalized_with(parent,
+    ///                                            0.255, 0.255, 0.5, 0.5,
+    ///                                            PixelRounding::Outward);
+    /// assert_eq!(outer, Viewport::new(35, 35, 51, 51));
+    /// ```
+    pub fn from_normalized_with(parent: Viewport,
+                                x: f32,
+                                y: f32,
+                                width: f32,
+                                height: f32,
+                                rounding: PixelRounding)
+        -> Self
+    {
+        let (left, right) = _round_edges(parent.x,
+                                         parent.width,
+                                         x,
+                                         x + width,
+                                         rounding);
+        let (bottom, top) = _round_edges(parent.y,
+                                         parent.height,
+                                         y,
+                                         y + height,
+                                         rounding);
+
+        Viewport {
+            x: left,
+            y: bottom,
+            width: right - left,
+            height: top - bottom
+        }
+    }
+}
+
+/// Determines how the edges of a [`Viewport`](struct.Viewport.html) created
+/// from normalized coordinates are rounded to whole pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PixelRounding {
+    /// Round each edge to the nearest pixel. Viewports that share an edge
+    /// will neither overlap nor leave a gap between them.
+    Nearest,
+
+    /// Round each edge towards the center of the viewport, so the viewport
+    /// only contains pixels that are entirely within the normalized area.
+    Inward,
+
+    /// Round each edge away from the center of the viewport, so the
+    /// viewport contains every pixel that is partially within the
+    /// normalized area.
+    Outward
+}
+
+// Map the normalized range `[start, end]` onto the pixel range that starts
+// at `origin` and is `size` pixels long, returning the rounded, clamped
+// `(start, end)` pixel coordinates.
+fn _round_edges(origin: u32,
+                size: u32,
+                start: f32,
+                end: f32,
+                rounding: PixelRounding)
+    -> (u32, u32)
+{
+    let (start, end) = if start <= end { (start, end) } else { (end, start) };
+    let start = start * size as f32;
+    let end = end * size as f32;
+
+    let (start, end) = match rounding {
+        PixelRounding::Nearest => (start.round(), end.round()),
+        PixelRounding::Inward => (start.ceil(), end.floor()),
+        PixelRounding::Outward => (start.floor(), end.ceil())
+    };
+
+    let clamp = |value: f32| value.max(0.0).min(size as f32) as u32;
+    let start = clamp(start);
+    let end = clamp(end).max(start);
+
+    (origin + start, origin + end)
 }
 
 /// An OpenGL object.
@@ -94,6 +219,71 @@ pub trait GLObject {
     }
 }
 
+/// An OpenGL object that can be given a human-readable label, which will
+/// show up in debugging tools (such as RenderDoc) and in debug messages.
+///
+/// # See also
+/// [`gl.label`](context/debug_context/trait.ContextDebugExt.html#method.label):
+/// Set an object's label.
+pub trait Labeled: GLObject<Id = gl::types::GLuint> {
+    /// The OpenGL identifier for the type of object (such as `GL_BUFFER`).
+    fn label_identifier() -> gl::types::GLenum;
+}
+
+/// A rectangle of a framebuffer that is copied with [`gl.blit_framebuffer`]
+/// (../context/framebuffer_context/trait.ContextFramebufferExt.html#method.blit_framebuffer),
+/// given by the coordinates of two opposite corners. The rectangle is
+/// mirrored if the corners are swapped on an axis (such as if `x1` is less
+/// than `x0`).
+///
+/// # Example
+/// ```
+/// use glitter::{BlitRect, Viewport};
+///
+/// let rect = BlitRect::from(Viewport::new(0, 0, 640, 480));
+/// assert_eq!(rect, BlitRect { x0: 0, y0: 0, x1: 640, y1: 480 });
+///
+/// let mirrored = rect.flip_x();
+/// assert_eq!(mirrored, BlitRect { x0: 640, y0: 0, x1: 0, y1: 480 });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlitRect {
+    /// The X coordinate of the first corner.
+    pub x0: i32,
+
+    /// The Y coordinate of the first corner.
+    pub y0: i32,
+
+    /// The X coordinate of the second corner (exclusive).
+    pub x1: i32,
+
+    /// The Y coordinate of the second corner (exclusive).
+    pub y1: i32
+}
+
+impl BlitRect {
+    /// Return the rectangle mirrored horizontally.
+    pub fn flip_x(&self) -> Self {
+        BlitRect { x0: self.x1, y0: self.y0, x1: self.x0, y1: self.y1 }
+    }
+
+    /// Return the rectangle mirrored vertically.
+    pub fn flip_y(&self) -> Self {
+        BlitRect { x0: self.x0, y0: self.y1, x1: self.x1, y1: self.y0 }
+    }
+}
+
+impl From<Viewport> for BlitRect {
+    fn from(viewport: Viewport) -> Self {
+        BlitRect {
+            x0: viewport.x as i32,
+            y0: viewport.y as i32,
+            x1: (viewport.x + viewport.width) as i32,
+            y1: (viewport.y + viewport.height) as i32
+        }
+    }
+}
+
 bitflags! {
     /// The possible buffers that the active framebuffer may contain.
     pub flags BufferBits: ::gl::types::GLbitfield {
@@ -190,13 +380,68 @@ pub enum GLError {
     /// enough memory to satisfy a request.
     OutOfMemory,
 
+    /// Indicates that a stack push operation (such as pushing a debug
+    /// group) would overflow the stack.
+    StackOverflow,
+
+    /// Indicates that a stack pop operation (such as popping a debug
+    /// group) was attempted on an empty stack.
+    StackUnderflow,
+
+    /// Indicates that the OpenGL context was lost (such as after a
+    /// graphics card reset), and must be recreated.
+    ContextLost,
+
     /// Indicates a framebuffer-related error.
     FramebufferError(GLFramebufferError),
 
     /// Indicates an error with a message attached (such as
     /// a message from an info log, or an error message
     /// originating from glitter).
-    Message(String)
+    Message(String),
+
+    /// Indicates an error that was generated by a specific OpenGL call.
+    Call(GLCallError)
+}
+
+impl GLError {
+    /// Convert a raw OpenGL error code (as returned by `glGetError`) into
+    /// a `GLError`. Returns `None` for `GL_NO_ERROR`, or if the error
+    /// code is not recognized.
+    pub fn from_gl_enum(gl_enum: gl::types::GLenum) -> Option<Self> {
+        match gl_enum {
+            gl::INVALID_ENUM => Some(GLError::InvalidEnum),
+            gl::INVALID_VALUE => Some(GLError::InvalidValue),
+            gl::INVALID_OPERATION => Some(GLError::InvalidOperation),
+            gl::INVALID_FRAMEBUFFER_OPERATION => {
+                Some(GLError::InvalidFramebufferOperation)
+            },
+            gl::OUT_OF_MEMORY => Some(GLError::OutOfMemory),
+            gl::STACK_OVERFLOW => Some(GLError::StackOverflow),
+            gl::STACK_UNDERFLOW => Some(GLError::StackUnderflow),
+            gl::CONTEXT_LOST => Some(GLError::ContextLost),
+            _ => None
+        }
+    }
+
+    /// Return the raw OpenGL error code for this error, or `None` if
+    /// the error did not originate from `glGetError`.
+    pub fn gl_enum(&self) -> Option<gl::types::GLenum> {
+        match *self {
+            GLError::InvalidEnum => Some(gl::INVALID_ENUM),
+            GLError::InvalidValue => Some(gl::INVALID_VALUE),
+            GLError::InvalidOperation => Some(gl::INVALID_OPERATION),
+            GLError::InvalidFramebufferOperation => {
+                Some(gl::INVALID_FRAMEBUFFER_OPERATION)
+            },
+            GLError::OutOfMemory => Some(gl::OUT_OF_MEMORY),
+            GLError::StackOverflow => Some(gl::STACK_OVERFLOW),
+            GLError::StackUnderflow => Some(gl::STACK_UNDERFLOW),
+            GLError::ContextLost => Some(gl::CONTEXT_LOST),
+            GLError::Call(ref e) => Some(e.gl_enum),
+            GLError::FramebufferError(_) | GLError::Message(_) => None
+        }
+    }
 }
 
 impl fmt::Display for GLError {
@@ -217,11 +462,23 @@ impl fmt::Display for GLError {
             GLError::OutOfMemory => {
                 write!(f, "Out of memory")
             },
+            GLError::StackOverflow => {
+                write!(f, "Stack overflow")
+            },
+            GLError::StackUnderflow => {
+                write!(f, "Stack underflow")
+            },
+            GLError::ContextLost => {
+                write!(f, "Context lost")
+            },
             GLError::FramebufferError(ref e) => {
                 write!(f, "{:?}", e)
             },
             GLError::Message(ref s) => {
                 write!(f, "{}", s)
+            },
+            GLError::Call(ref e) => {
+                write!(f, "{}", e)
             }
         }
     }
@@ -235,21 +492,73 @@ impl error::Error for GLError {
             GLError::InvalidOperation => "The specified OpenGL operation is not allowed in the current state.",
             GLError::InvalidFramebufferOperation => "OpenGL command tried to read or write to an incomplete framebuffer.",
             GLError::OutOfMemory => "There is not enough memory left to execute the specified OpenGL command.",
+            GLError::StackOverflow => "The specified OpenGL command would cause a stack overflow.",
+            GLError::StackUnderflow => "The specified OpenGL command would cause a stack underflow.",
+            GLError::ContextLost => "The OpenGL context was lost.",
             GLError::FramebufferError(ref e) => {
                 error::Error::description(e)
             },
-            GLError::Message(ref s) => &s
+            GLError::Message(ref s) => &s,
+            GLError::Call(ref e) => {
+                error::Error::description(e)
+            }
         }
     }
 
     fn cause(&self) -> Option<&error::Error> {
         match *self {
             GLError::FramebufferError(ref e) => { Some(e) },
+            GLError::Call(ref e) => { Some(e) },
             _ => { None }
         }
     }
 }
 
+/// An error that was generated by a specific OpenGL call.
+#[derive(Debug)]
+pub struct GLCallError {
+    /// The name of the OpenGL function that generated the error
+    /// (such as `"glBufferData"`).
+    pub call: &'static str,
+
+    /// The raw OpenGL error code, as returned by `glGetError`.
+    pub gl_enum: gl::types::GLenum,
+
+    /// A message describing the likely cause of the error, if any.
+    pub message: Option<String>
+}
+
+impl GLCallError {
+    /// Return the basic `GLError` that corresponds to this error's
+    /// error code, or `None` if the error code is not recognized.
+    pub fn error(&self) -> Option<GLError> {
+        GLError::from_gl_enum(self.gl_enum)
+    }
+}
+
+impl fmt::Display for GLCallError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let error = match self.error() {
+            Some(e) => format!("{}", e),
+            None => String::from("Unknown error")
+        };
+        try!(write!(f, "{} (0x{:04X}) in {}", error, self.gl_enum, self.call));
+        if let Some(ref message) = self.message {
+            try!(write!(f, ": {}", message));
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for GLCallError {
+    fn description(&self) -> &str {
+        match self.message {
+            Some(ref message) => &message,
+            None => "An OpenGL call generated an error."
+        }
+    }
+}
+
 /// The possible framebuffer-incomplete errors.
 #[derive(Debug)]
 pub enum GLFramebufferError {
@@ -304,6 +613,299 @@ impl From<GLFramebufferError> for GLError {
     }
 }
 
+/// An error that indicates that an OpenGL function is not supported by
+/// the current context, because the context's version is too old (and the
+/// function isn't provided by an extension).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedFunction {
+    /// The name of the OpenGL function that is not supported
+    /// (such as `"glDrawElementsBaseVertex"`).
+    pub call: &'static str,
+
+    /// The OpenGL versions that support the function
+    /// (such as `"OpenGL 3.2 or OpenGL ES 3.2"`).
+    pub requires: &'static str
+}
+
+impl fmt::Display for UnsupportedFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is not supported (requires {})", self.call, self.requires)
+    }
+}
+
+impl error::Error for UnsupportedFunction {
+    fn description(&self) -> &str {
+        "An OpenGL function is not supported by the current context"
+    }
+}
+
+impl From<UnsupportedFunction> for GLError {
+    fn from(e: UnsupportedFunction) -> GLError {
+        GLError::Message(format!("{}", e))
+    }
+}
+
+/// An error returned by [`gl.set_line_width`]
+/// (../context/struct.ContextOf.html#method.set_line_width) when the line
+/// width is outside of the range supported by the context.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineWidthOutOfRange {
+    /// The line width that was requested.
+    pub width: f32,
+
+    /// The smallest and largest supported line widths, as `[min, max]`
+    /// (from `GL_ALIASED_LINE_WIDTH_RANGE`).
+    pub range: [f32; 2]
+}
+
+impl fmt::Display for LineWidthOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "Line width {} is outside of the supported range [{}, {}]",
+               self.width, self.range[0], self.range[1])
+    }
+}
+
+impl error::Error for LineWidthOutOfRange {
+    fn description(&self) -> &str {
+        "Line width is outside of the supported range"
+    }
+}
+
+impl From<LineWidthOutOfRange> for GLError {
+    fn from(e: LineWidthOutOfRange) -> GLError {
+        GLError::Message(format!("{}", e))
+    }
+}
+
+/// An error returned when uploading compressed texture data (such as with
+/// [`gl.compressed_tex_image_2d`]
+/// (../context/struct.ContextOf.html#method.compressed_tex_image_2d)) with
+/// a format that isn't supported by the current context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedCompressedFormat {
+    /// The compressed texture format that is not supported.
+    pub format: CompressedTextureFormat
+}
+
+impl fmt::Display for UnsupportedCompressedFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "Compressed texture format {:?} is not supported",
+               self.format)
+    }
+}
+
+impl error::Error for UnsupportedCompressedFormat {
+    fn description(&self) -> &str {
+        "A compressed texture format is not supported by the current context"
+    }
+}
+
+impl From<UnsupportedCompressedFormat> for GLError {
+    fn from(e: UnsupportedCompressedFormat) -> GLError {
+        GLError::Message(format!("{}", e))
+    }
+}
+
+
+
+/// Determines how glitter handles OpenGL errors that are generated by
+/// the OpenGL calls it makes. The policy is tracked per-thread, and can
+/// be changed with [`ContextOf::set_error_policy`]
+/// (context/struct.ContextOf.html#method.set_error_policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorPolicy {
+    /// Check for errors after OpenGL calls, and panic if an error was
+    /// generated. Like the `debug_assert!` macro, errors are only checked
+    /// when the `debug_assertions` configuration option is set. This
+    /// is the default policy.
+    Panic,
+
+    /// Never check for errors after OpenGL calls.
+    Ignore,
+
+    /// Check for errors after OpenGL calls (even in release builds), and
+    /// record the first error that was generated, so that it can be returned
+    /// as a `Result`. See [`ContextOf::checked`]
+    /// (context/struct.ContextOf.html#method.checked).
+    Result
+}
+
+
+
+gl_enum! {
+    /// The comparison functions that can be used for the depth test,
+    /// which compare an incoming fragment's depth value against the
+    /// value stored in the depth buffer.
+    pub gl_enum DepthFunction {
+        /// The depth test never passes.
+        pub const Never as NEVER = gl::NEVER,
+
+        /// Passes if the incoming depth value is less than the stored value.
+        pub const Less as LESS = gl::LESS,
+
+        /// Passes if the incoming depth value is equal to the stored value.
+        pub const Equal as EQUAL = gl::EQUAL,
+
+        /// Passes if the incoming depth value is less than or equal to
+        /// the stored value.
+        pub const LessOrEqual as LEQUAL = gl::LEQUAL,
+
+        /// Passes if the incoming depth value is greater than the
+        /// stored value.
+        pub const Greater as GREATER = gl::GREATER,
+
+        /// Passes if the incoming depth value is not equal to the
+        /// stored value.
+        pub const NotEqual as NOTEQUAL = gl::NOTEQUAL,
+
+        /// Passes if the incoming depth value is greater than or equal to
+        /// the stored value.
+        pub const GreaterOrEqual as GEQUAL = gl::GEQUAL,
+
+        /// The depth test always passes.
+        pub const Always as ALWAYS = gl::ALWAYS
+    }
+}
+
+gl_enum! {
+    /// The faces of a polygon that can be culled while face culling is
+    /// enabled.
+    pub gl_enum CullFace {
+        /// Cull front-facing polygons.
+        pub const Front as FRONT = gl::FRONT,
+
+        /// Cull back-facing polygons.
+        pub const Back as BACK = gl::BACK,
+
+        /// Cull both front-facing and back-facing polygons, so that only
+        /// points and lines are drawn.
+        pub const FrontAndBack as FRONT_AND_BACK = gl::FRONT_AND_BACK
+    }
+}
+
+gl_enum! {
+    /// The winding order of a polygon's vertices (in window coordinates),
+    /// which determines whether the polygon is front-facing.
+    pub gl_enum Winding {
+        /// Polygons with clockwise winding are front-facing.
+        pub const Clockwise as CW = gl::CW,
+
+        /// Polygons with counter-clockwise winding are front-facing. This
+        /// is the default winding.
+        pub const CounterClockwise as CCW = gl::CCW
+    }
+}
+
+gl_enum! {
+    /// The reset status of an OpenGL context, as returned by
+    /// [`gl.reset_status`]
+    /// (../context/struct.ContextOf.html#method.reset_status).
+    pub gl_enum ResetStatus {
+        /// The context has not been reset.
+        pub const NoReset as NO_RESET = gl::NO_ERROR,
+
+        /// The context was reset by a problem caused by this context.
+        pub const GuiltyContextReset as GUILTY_CONTEXT_RESET =
+            gl::GUILTY_CONTEXT_RESET,
+
+        /// The context was reset by a problem caused by another context.
+        pub const InnocentContextReset as INNOCENT_CONTEXT_RESET =
+            gl::INNOCENT_CONTEXT_RESET,
+
+        /// The context was reset, but the cause is unknown.
+        pub const UnknownContextReset as UNKNOWN_CONTEXT_RESET =
+            gl::UNKNOWN_CONTEXT_RESET
+    }
+}
+
+/// The state that controls how polygons are rasterized, which can be
+/// applied all at once with [`gl.set_rasterizer_state`]
+/// (../context/trait.ContextExt.html#method.set_rasterizer_state).
+///
+/// The default state matches the initial OpenGL state, where face culling
+/// is disabled and counter-clockwise polygons are front-facing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RasterizerState {
+    /// The faces to cull, or `None` to disable face culling.
+    pub cull_face: Option<CullFace>,
+
+    /// The winding of front-facing polygons.
+    pub front_face: Winding
+}
+
+impl Default for RasterizerState {
+    fn default() -> Self {
+        RasterizerState {
+            cull_face: None,
+            front_face: Winding::CounterClockwise
+        }
+    }
+}
+
+/// The depth offset that is added to each fragment of a filled polygon
+/// while polygon offset is enabled, which is used to avoid z-fighting with
+/// decals, or to bias depth values when drawing shadow maps. See
+/// [`gl.set_polygon_offset`]
+/// (../context/trait.ContextExt.html#method.set_polygon_offset).
+///
+/// The offset for a fragment is `factor * dz + units * r`, where `dz` is
+/// the polygon's depth slope and `r` is the smallest resolvable difference
+/// in depth values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolygonOffset {
+    /// The amount to scale the polygon's depth slope by.
+    pub factor: f32,
+
+    /// The amount to scale the smallest resolvable depth difference by.
+    pub units: f32
+}
+
+/// The coverage value that is combined with the coverage of each fragment
+/// while multisampling, set with [`gl.set_sample_coverage`]
+/// (../context/trait.ContextExt.html#method.set_sample_coverage).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleCoverage {
+    /// The coverage value, from `0.0` to `1.0`.
+    pub value: f32,
+
+    /// Whether the coverage mask should be inverted, which can be used to
+    /// blend two objects with complementary masks.
+    pub invert: bool
+}
+
+/// The state that controls how fragments are written to a multisampled
+/// framebuffer, which can be applied all at once with
+/// [`gl.set_multisample_state`]
+/// (../context/trait.ContextExt.html#method.set_multisample_state).
+///
+/// The default state matches the initial OpenGL state, where sample
+/// coverage and alpha-to-coverage are disabled, and dithering is enabled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultisampleState {
+    /// The sample coverage value, or `None` to disable sample coverage.
+    pub sample_coverage: Option<SampleCoverage>,
+
+    /// Whether each fragment's alpha value is used to compute its coverage,
+    /// which gives smooth edges to alpha-tested geometry (such as foliage).
+    pub alpha_to_coverage: bool,
+
+    /// Whether colors are dithered before being written to the color
+    /// buffer, which reduces banding with low-precision color buffers.
+    pub dither: bool
+}
+
+impl Default for MultisampleState {
+    fn default() -> Self {
+        MultisampleState {
+            sample_coverage: None,
+            alpha_to_coverage: false,
+            dither: true
+        }
+    }
+}
+
 
 
 gl_enum! {
@@ -362,6 +964,26 @@ gl_enum! {
         pub const Fixed as FIXED = gl::FIXED,
 
         /// A 32-bit, IEEE floating-point number.
-        pub const Float as FLOAT = gl::FLOAT
+        pub const Float as FLOAT = gl::FLOAT,
+
+        /// A signed 32-bit integer. 32-bit integer vertex attributes require
+        /// OpenGL ES 3.0.
+        pub const Int as INT = gl::INT,
+
+        /// An unsigned 32-bit integer. 32-bit integer vertex attributes
+        /// require OpenGL ES 3.0.
+        pub const UnsignedInt as UNSIGNED_INT = gl::UNSIGNED_INT
+    }
+}
+
+impl DataType {
+    /// Get the size of a single value of this data type, in bytes.
+    pub fn size(&self) -> usize {
+        match *self {
+            DataType::Byte | DataType::UnsignedByte => 1,
+            DataType::Short | DataType::UnsignedShort => 2,
+            DataType::Fixed | DataType::Float => 4,
+            DataType::Int | DataType::UnsignedInt => 4
+        }
     }
 }
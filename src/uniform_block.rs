@@ -0,0 +1,313 @@
+//! Contains types that describe the memory layout of uniform blocks, and
+//! that pack uniform values into buffers with that layout.
+
+use std::fmt;
+use std::error;
+use gl::types::*;
+use uniform_data::{UniformData, UniformDatum, UniformDatumType, MatrixOrder};
+use matrix_order;
+
+/// A uniform within a uniform block, along with where the uniform is stored
+/// within a buffer that backs the block.
+///
+/// # See also
+/// [`gl.get_uniform_block_layout`](../context/program_context/trait.ContextProgramExt.html#method.get_uniform_block_layout):
+/// Get the layout of a program's uniform block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UniformBlockMember {
+    /// The name of the uniform, as reported by OpenGL. For arrays, OpenGL
+    /// appends `[0]` to the name of the uniform, and uniforms within
+    /// structs are named like `lights[0].color`.
+    pub name: String,
+
+    /// The raw OpenGL type of the uniform (such as `GL_FLOAT_VEC3`).
+    pub gl_type: GLenum,
+
+    /// The number of elements of the uniform. This is `1` for uniforms
+    /// that are not arrays.
+    pub size: usize,
+
+    /// The offset (in bytes) of the uniform from the start of the block.
+    pub offset: usize,
+
+    /// The distance (in bytes) between the elements of an array, or `0`
+    /// for uniforms that are not arrays.
+    pub array_stride: usize,
+
+    /// The distance (in bytes) between the columns of a matrix (or the
+    /// rows, for row-major matrices), or `0` for uniforms that are
+    /// not matrices.
+    pub matrix_stride: usize,
+
+    /// `true` if the uniform is a matrix that is stored in row-major order.
+    pub row_major: bool
+}
+
+/// A description of the memory layout of a uniform block, as chosen by
+/// OpenGL when a program is linked. The layout can be used to write
+/// uniform values into a buffer that backs the block, regardless of which
+/// `layout` qualifier the block was declared with.
+///
+/// # See also
+/// [`gl.get_uniform_block_layout`](../context/program_context/trait.ContextProgramExt.html#method.get_uniform_block_layout):
+/// Get the layout of a program's uniform block.
+///
+/// [`Std140Writer`](struct.Std140Writer.html): Pack values into the
+/// `std140` layout without querying the program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UniformBlockLayout {
+    /// The minimum size (in bytes) of a buffer that backs the block.
+    pub data_size: usize,
+
+    /// The active uniforms within the block.
+    pub members: Vec<UniformBlockMember>
+}
+
+impl UniformBlockLayout {
+    /// Find a member of the block by name. The `[0]` suffix that OpenGL
+    /// appends to arrays can be omitted.
+    pub fn member(&self, name: &str) -> Option<&UniformBlockMember> {
+        self.members.iter().find(|member| {
+            member.name == name || member.name.trim_right_matches("[0]") == name
+        })
+    }
+
+    /// Create a zeroed buffer that is large enough to back the block.
+    pub fn new_data(&self) -> Vec<u8> {
+        vec![0; self.data_size]
+    }
+
+    /// Write a uniform value (or an array of values) into `data`, at the
+    /// position of the block member `name`. Matrices are written in the
+    /// order of the member, transposing them if necessary.
+    ///
+    /// # Failures
+    /// An error will be returned if the block has no member named `name`.
+    ///
+    /// # Panics
+    /// This function will panic if `value` has more elements than the
+    /// member, or if `data` is too small to hold the member.
+    pub fn write<'a, T>(&self, data: &mut [u8], name: &'a str, value: &T)
+        -> Result<(), UnknownUniformBlockMember<'a>>
+        where T: UniformData + ?Sized
+    {
+        let member = match self.member(name) {
+            Some(member) => member,
+            None => { return Err(UnknownUniformBlockMember { name: name }); }
+        };
+
+        let elements = value.uniform_elements();
+        assert!(elements <= member.size,
+                "Too many elements for uniform block member {:?}",
+                member.name);
+
+        let bytes = value.uniform_bytes();
+        let element_size = if elements > 0 { bytes.len() / elements } else { 0 };
+        let order = T::matrix_order().unwrap_or_else(matrix_order::order);
+        for i in 0..elements {
+            let element = &bytes[i * element_size..(i + 1) * element_size];
+            _write_element(data,
+                           member.offset + i * member.array_stride,
+                           T::uniform_datum_type(),
+                           element,
+                           order,
+                           member.matrix_stride,
+                           member.row_major);
+        }
+        Ok(())
+    }
+}
+
+/// An error that represents a uniform block member that could not be found.
+#[derive(Debug)]
+pub struct UnknownUniformBlockMember<'a> {
+    name: &'a str
+}
+
+impl<'a> fmt::Display for UnknownUniformBlockMember<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Unknown uniform block member: {:?}", self.name)
+    }
+}
+
+impl<'a> error::Error for UnknownUniformBlockMember<'a> {
+    fn description(&self) -> &str {
+        "The desired uniform block member was not found"
+    }
+}
+
+// Get the number of columns and rows of a datum type (vectors have a
+// single column)
+fn _shape(ty: UniformDatumType) -> (usize, usize) {
+    match ty {
+        UniformDatumType::Vec1(_) => (1, 1),
+        UniformDatumType::Vec2(_) => (1, 2),
+        UniformDatumType::Vec3(_) => (1, 3),
+        UniformDatumType::Vec4(_) => (1, 4),
+        UniformDatumType::Matrix2x2 => (2, 2),
+        UniformDatumType::Matrix3x3 => (3, 3),
+        UniformDatumType::Matrix4x4 => (4, 4)
+    }
+}
+
+// Write a single datum (`src`, which is stored in `src_order` if it is a
+// matrix) at `offset` within `dst`. Every component is 4 bytes.
+fn _write_element(dst: &mut [u8],
+                  offset: usize,
+                  ty: UniformDatumType,
+                  src: &[u8],
+                  src_order: MatrixOrder,
+                  matrix_stride: usize,
+                  row_major: bool)
+{
+    let (columns, rows) = _shape(ty);
+    for c in 0..columns {
+        for r in 0..rows {
+            let src_index = match src_order {
+                MatrixOrder::ColumnMajor => c * rows + r,
+                MatrixOrder::RowMajor => r * columns + c
+            };
+            let dst_offset = if row_major {
+                offset + r * matrix_stride + c * 4
+            }
+            else {
+                offset + c * matrix_stride + r * 4
+            };
+            dst[dst_offset..dst_offset + 4]
+                .copy_from_slice(&src[src_index * 4..src_index * 4 + 4]);
+        }
+    }
+}
+
+fn _round_up(value: usize, alignment: usize) -> usize {
+    (value + alignment - 1) / alignment * alignment
+}
+
+/// Packs uniform values into a buffer with the `std140` layout, in the
+/// order that they are declared in a uniform block. Each value is aligned
+/// according to the `std140` rules, so that a block declared with
+/// `layout(std140)` can read the values without querying its layout.
+///
+/// Arrays are written with [`push_array`]
+/// (struct.Std140Writer.html#method.push_array), and structs are written
+/// by pushing their fields between [`begin_struct`]
+/// (struct.Std140Writer.html#method.begin_struct) and [`end_struct`]
+/// (struct.Std140Writer.html#method.end_struct). Matrices are always
+/// written in column-major order (the default in GLSL).
+///
+/// # Example
+/// ```
+/// use glitter::Std140Writer;
+///
+/// // layout(std140) uniform Light {
+/// //     vec3 position;
+/// //     float intensity;
+/// //     vec2 attenuation;
+/// //     float weights[2];
+/// // };
+/// let mut writer = Std140Writer::new();
+/// assert_eq!(writer.push(&[1.0f32, 2.0, 3.0]), 0);
+/// assert_eq!(writer.push(&0.5f32), 12);
+/// assert_eq!(writer.push(&[0.1f32, 0.2]), 16);
+/// assert_eq!(writer.push_array(&[0.25f32, 0.75]), 32);
+///
+/// let bytes = writer.into_bytes();
+/// assert_eq!(bytes.len(), 64);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Std140Writer {
+    bytes: Vec<u8>
+}
+
+impl Std140Writer {
+    /// Create a new, empty writer.
+    pub fn new() -> Self {
+        Std140Writer {
+            bytes: vec![]
+        }
+    }
+
+    /// Get the offset (in bytes) where the next value would be written,
+    /// before it is aligned.
+    pub fn offset(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn _align(&mut self, alignment: usize) -> usize {
+        let offset = _round_up(self.bytes.len(), alignment);
+        self.bytes.resize(offset, 0);
+        offset
+    }
+
+    /// Write a single value, returning the offset that it was written at.
+    pub fn push<T: UniformDatum>(&mut self, value: &T) -> usize {
+        let ty = <T as UniformDatum>::uniform_datum_type();
+        let (columns, rows) = _shape(ty);
+        let (alignment, size) = match (columns, rows) {
+            (1, 1) => (4, 4),
+            (1, 2) => (8, 8),
+            (1, 3) => (16, 12),
+            (1, _) => (16, 16),
+            (columns, _) => (16, columns * 16)
+        };
+
+        let offset = self._align(alignment);
+        self.bytes.resize(offset + size, 0);
+        let order = <T as UniformDatum>::matrix_order()
+            .unwrap_or_else(matrix_order::order);
+        _write_element(&mut self.bytes,
+                       offset,
+                       ty,
+                       UniformData::uniform_bytes(value),
+                       order,
+                       16,
+                       false);
+        offset
+    }
+
+    /// Write an array of values, returning the offset of the first element.
+    /// Every element of an array is aligned to 16 bytes.
+    pub fn push_array<T: UniformDatum>(&mut self, values: &[T]) -> usize {
+        let ty = <T as UniformDatum>::uniform_datum_type();
+        let (columns, _) = _shape(ty);
+        let stride = if columns > 1 { columns * 16 } else { 16 };
+
+        let offset = self._align(16);
+        self.bytes.resize(offset + stride * values.len(), 0);
+        let order = <T as UniformDatum>::matrix_order()
+            .unwrap_or_else(matrix_order::order);
+        for (i, value) in values.iter().enumerate() {
+            _write_element(&mut self.bytes,
+                           offset + i * stride,
+                           ty,
+                           UniformData::uniform_bytes(value),
+                           order,
+                           16,
+                           false);
+        }
+        offset
+    }
+
+    /// Start writing the fields of a struct, returning the offset of the
+    /// struct. Structs are aligned to 16 bytes.
+    pub fn begin_struct(&mut self) -> usize {
+        self._align(16)
+    }
+
+    /// Finish writing the fields of a struct, padding the struct to a
+    /// multiple of 16 bytes.
+    pub fn end_struct(&mut self) {
+        self._align(16);
+    }
+
+    /// Get the values that have been written so far.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Return the written values, padded to a multiple of 16 bytes.
+    pub fn into_bytes(mut self) -> Vec<u8> {
+        self._align(16);
+        self.bytes
+    }
+}
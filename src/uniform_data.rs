@@ -4,9 +4,12 @@
 
 use std::slice;
 use std::mem;
+use context::{ContextProgramExt, ProgramBinding};
+use program::UniformLocations;
 
 /// The basic value types that are composed in the [`UniformDatumTypes`]
 /// (enum.UniformDatumType.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UniformPrimitiveType {
     /// A 32-bit floating point value.
     Float,
@@ -16,6 +19,7 @@ pub enum UniformPrimitiveType {
 }
 
 /// The basic types that can be used as uniform values in a program object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UniformDatumType {
     /// A single scalar value, containing one primitive (essentially a vector
     /// of one component).
@@ -40,6 +44,26 @@ pub enum UniformDatumType {
     Matrix4x4
 }
 
+/// The order that the elements of a matrix are stored in memory.
+///
+/// GLSL expects matrices in column-major order. When a matrix is uploaded
+/// in row-major order, glitter transposes it before uploading it. The
+/// order used for plain arrays (such as `[[f32; 4]; 4]`) can be changed
+/// with [`ContextOf::set_matrix_order`]
+/// (../context/struct.ContextOf.html#method.set_matrix_order), while
+/// math library types (such as `cgmath::Matrix4`) always use their own
+/// order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatrixOrder {
+    /// Each inner array is a column of the matrix, so `m[c][r]` is the
+    /// element in column `c` and row `r`. This is the default order.
+    ColumnMajor,
+
+    /// Each inner array is a row of the matrix, so `m[r][c]` is the
+    /// element in row `r` and column `c`.
+    RowMajor
+}
+
 /// A type that can be set to a uniform value in a program object, using
 /// the [`gl.set_uniform`]
 /// (../context/program_context/trait.ContextProgramExt.html#method.set_uniform)
@@ -55,10 +79,46 @@ pub trait UniformData {
 
     /// Return the number of uniform data elements that `self` contains.
     fn uniform_elements(&self) -> usize;
+
+    /// Return the order that matrix elements are stored in, or `None` if
+    /// the current [`MatrixOrder`](enum.MatrixOrder.html) should be used.
+    /// This is ignored for non-matrix types.
+    fn matrix_order() -> Option<MatrixOrder> {
+        None
+    }
 }
 
 
 
+/// A type whose fields are set as the uniforms of a program, such as a
+/// struct of material parameters. This trait is usually implemented with
+/// the [`impl_uniforms!`](../macro.impl_uniforms!.html) macro, which uses
+/// the name of each field as the name of its uniform.
+///
+/// # See also
+/// [`gl.get_uniform_locations`]
+/// (../context/program_context/trait.ContextProgramExt.html#method.get_uniform_locations):
+/// Look up the locations of a `Uniforms` type's uniforms within a program.
+///
+/// [`gl.set_uniforms`]
+/// (../context/program_context/trait.ContextProgramExt.html#method.set_uniforms):
+/// Set every uniform of a `Uniforms` value.
+pub trait Uniforms {
+    /// Call `f` with the name of each uniform, in the same order that
+    /// [`apply`](#tymethod.apply) sets them.
+    fn visit_uniforms<F>(f: F) where F: FnMut(&'static str);
+
+    /// Set each uniform of the program in use to the value of the
+    /// corresponding field of `self`, using the locations from
+    /// `locations`. Uniforms that are not active in the program
+    /// are skipped.
+    fn apply<C>(&self,
+                gl: &C,
+                gl_program: &ProgramBinding,
+                locations: &UniformLocations)
+        where C: ContextProgramExt;
+}
+
 /// A single uniform value, which corresponds to a single
 /// primitive GLSL uniform type.
 ///
@@ -76,6 +136,14 @@ pub unsafe trait UniformDatum {
     /// specified by the returned [`UniformDatumType`]
     /// (enum.UniformDatumType.html).
     fn uniform_datum_type() -> UniformDatumType;
+
+    /// Return the order that matrix elements are stored in, or `None` if
+    /// the current [`MatrixOrder`](enum.MatrixOrder.html) should be used.
+    /// Types that always store matrices in a fixed order (such as math
+    /// library types) should override this method.
+    fn matrix_order() -> Option<MatrixOrder> {
+        None
+    }
 }
 
 
@@ -125,19 +193,19 @@ unsafe impl<T: UniformPrimitive> UniformDatum for [T; 1] {
 
 unsafe impl<T: UniformPrimitive> UniformDatum for [T; 2] {
     fn uniform_datum_type() -> UniformDatumType {
-        UniformDatumType::Vec1(T::uniform_primitive_type())
+        UniformDatumType::Vec2(T::uniform_primitive_type())
     }
 }
 
 unsafe impl<T: UniformPrimitive> UniformDatum for [T; 3] {
     fn uniform_datum_type() -> UniformDatumType {
-        UniformDatumType::Vec1(T::uniform_primitive_type())
+        UniformDatumType::Vec3(T::uniform_primitive_type())
     }
 }
 
 unsafe impl<T> UniformDatum for [T; 4] where T: UniformPrimitive {
     fn uniform_datum_type() -> UniformDatumType {
-        UniformDatumType::Vec1(T::uniform_primitive_type())
+        UniformDatumType::Vec4(T::uniform_primitive_type())
     }
 }
 
@@ -173,6 +241,10 @@ impl<T: UniformDatum> UniformData for T {
     fn uniform_elements(&self) -> usize {
         1
     }
+
+    fn matrix_order() -> Option<MatrixOrder> {
+        T::matrix_order()
+    }
 }
 
 impl<T: UniformDatum> UniformData for [T] {
@@ -183,11 +255,74 @@ impl<T: UniformDatum> UniformData for [T] {
     fn uniform_bytes(&self) -> &[u8] {
         let size = mem::size_of::<T>() * self.len();
         unsafe {
-            slice::from_raw_parts(mem::transmute(&self[0]), size)
+            slice::from_raw_parts(self.as_ptr() as *const u8, size)
         }
     }
 
     fn uniform_elements(&self) -> usize {
         self.len()
     }
+
+    fn matrix_order() -> Option<MatrixOrder> {
+        T::matrix_order()
+    }
+}
+
+/// Implement the [`Uniforms`](uniform_data/trait.Uniforms.html) trait for a
+/// struct. Each listed field must be a `Copy` type that implements
+/// [`UniformData`](uniform_data/trait.UniformData.html), and is set to the
+/// uniform with the same name as the field.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+///
+/// # fn main() {
+/// struct Material {
+///     diffuse: [f32; 4],
+///     shininess: f32
+/// }
+///
+/// // Implement `Uniforms`, setting the "diffuse" and "shininess" uniforms
+/// impl_uniforms!(Material, diffuse, shininess);
+///
+/// # let mut program: glitter::Program = unsafe { ::std::mem::uninitialized() };
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let locations = gl.get_uniform_locations::<Material>(&program);
+///
+/// let material = Material { diffuse: [1.0, 0.0, 0.0, 1.0], shininess: 8.0 };
+/// let (gl_program, gl) = gl.use_program(&mut program);
+/// gl.set_uniforms(&gl_program, &material, &locations);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! impl_uniforms {
+    ($name:ty, $($field_name:ident),*) => {
+        impl $crate::Uniforms for $name {
+            fn visit_uniforms<F>(mut f: F)
+                where F: FnMut(&'static str)
+            {
+                $(
+                    f(stringify!($field_name));
+                )*
+            }
+
+            fn apply<C>(&self,
+                        gl: &C,
+                        gl_program: &$crate::ProgramBinding,
+                        locations: &$crate::UniformLocations)
+                where C: $crate::ContextProgramExt
+            {
+                let mut _index = 0;
+                $(
+                    if let Some(uniform) = locations.get(_index) {
+                        gl.set_uniform(gl_program, uniform, self.$field_name);
+                    }
+                    _index += 1;
+                )*
+            }
+        }
+    };
 }
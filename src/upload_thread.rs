@@ -0,0 +1,482 @@
+//! Contains the [`UploadThread`](struct.UploadThread.html) type, which
+//! uploads buffer and texture data from a worker thread with a shared
+//! OpenGL context.
+
+use std::mem;
+use std::thread;
+use std::marker::PhantomData;
+use std::sync::mpsc::{self, Sender, Receiver, TryRecvError};
+use gl;
+use gl::types::*;
+use context::{Context, ContextOf, ContextHandle, ContextBufferExt,
+              ArrayBufferContext, ContextTextureBuilderExt};
+use buffer::{Buffer, BufferDataUsage};
+use fence::Fence;
+use texture::Texture2d;
+use image_data::Image2d;
+use types::{GLObject, GLError};
+
+// How long `PendingUpload::wait` waits for the upload's fence at a time, in
+// nanoseconds.
+const WAIT_TIMEOUT: GLuint64 = 1_000_000_000;
+
+// A fence created on the upload thread. Sync objects are shared between
+// contexts that share objects, so the fence can be waited on by the
+// main context.
+struct UploadFence(GLsync);
+
+unsafe impl Send for UploadFence {}
+
+type UploadResult = Result<(GLuint, UploadFence), GLError>;
+
+enum UploadJob {
+    Buffer {
+        bytes: Vec<u8>,
+        usage: BufferDataUsage,
+        result: Sender<UploadResult>
+    },
+    Texture2d {
+        image: Box<Image2d + Send>,
+        generate_mipmap: bool,
+        result: Sender<UploadResult>
+    },
+    Run(Box<FnMut(&mut Context) + Send>)
+}
+
+unsafe fn _fence() -> UploadFence {
+    let fence = gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0);
+    dbg_gl_sanity_check! {
+        call "glFenceSync";
+        args (gl::SYNC_GPU_COMMANDS_COMPLETE, 0);
+        GLError::InvalidEnum => "`condition` is not an allowed value",
+        GLError::InvalidValue => "`flags` is not 0",
+        _ => "Unknown error"
+    }
+
+    // Make sure the fence (and the upload before it) reaches the GPU, so
+    // that another context waiting on the fence doesn't wait forever
+    gl::Flush();
+    UploadFence(fence)
+}
+
+fn _run_job(gl: &mut Context, job: UploadJob) {
+    match job {
+        UploadJob::Buffer { bytes, usage, result } => {
+            let mut buffer = gl.gen_buffer();
+            {
+                let (mut gl_buffer, gl) = gl.bind_array_buffer(&mut buffer);
+                gl.buffer_bytes(&mut gl_buffer, &bytes, usage);
+            }
+            let fence = unsafe { _fence() };
+            let _ = result.send(Ok((buffer.into_raw(), fence)));
+        },
+        UploadJob::Texture2d { image, generate_mipmap, result } => {
+            let texture = {
+                let builder = gl.build_texture_2d().image_2d(&*image);
+                if generate_mipmap {
+                    builder.generate_mipmap().try_unwrap()
+                }
+                else {
+                    builder.try_unwrap()
+                }
+            };
+            let uploaded = texture.map(|texture| {
+                let fence = unsafe { _fence() };
+                (texture.into_raw(), fence)
+            });
+            let _ = result.send(uploaded);
+        },
+        UploadJob::Run(mut f) => {
+            (*f)(gl);
+        }
+    }
+}
+
+/// A worker thread with its own OpenGL context, which is used to upload
+/// buffer and texture data without stalling the main thread's context.
+///
+/// Each upload returns a [`PendingUpload`](struct.PendingUpload.html)
+/// handle. After the worker thread has uploaded the data, it places a fence,
+/// and the handle can be turned into a usable object on the main context
+/// once the fence has been signaled.
+///
+/// The worker thread exits after the `UploadThread` is dropped, once all of
+/// the uploads that were already submitted have finished.
+///
+/// # Note
+/// Fences require OpenGL ES 3.0 or OpenGL 3.2, so this type is only
+/// available with the `gles3` feature.
+///
+/// # Example
+/// ```no_run
+/// #[macro_use] extern crate glitter;
+/// use glitter::prelude::*;
+/// use glitter::UploadThread;
+///
+/// # fn make_shared_context_current() { }
+/// # fn main() {
+/// # let pixels = glitter::Pixels::new(256, 256);
+/// let gl = unsafe { glitter::Context::current_context() };
+/// let uploads = unsafe {
+///     UploadThread::spawn(|| make_shared_context_current())
+/// };
+///
+/// let mut texture = uploads.upload_texture_2d(pixels, true);
+/// loop {
+///     match texture.try_take(&gl).unwrap() {
+///         Some(texture) => {
+///             // The texture can now be bound on the main context
+///             break;
+///         },
+///         None => {
+///             // Keep rendering with a placeholder texture
+///         }
+///     }
+/// }
+/// # }
+/// ```
+pub struct UploadThread {
+    jobs: Option<Sender<UploadJob>>,
+    thread: Option<thread::JoinHandle<()>>,
+    owner: Option<ContextHandle>
+}
+
+impl UploadThread {
+    /// Spawn a new upload thread. `make_current` is called on the new
+    /// thread, and must create an OpenGL context that shares objects with
+    /// the main context, and make it current on the new thread. The value
+    /// returned by `make_current` (such as the windowing library's context
+    /// object) is kept alive until the thread exits.
+    ///
+    /// # Safety
+    /// The context made current by `make_current` must share objects with
+    /// the context that the uploaded objects will be used with, and the
+    /// OpenGL function pointers must already have been loaded with
+    /// [`ContextOf::load_with`]
+    /// (../context/struct.ContextOf.html#method.load_with). The same rules
+    /// apply to the new thread's context as the
+    /// [`ContextOf::current_context()` method]
+    /// (../context/struct.ContextOf.html#method.current_context).
+    pub unsafe fn spawn<F, G>(make_current: F) -> Self
+        where F: FnOnce() -> G + Send + 'static
+    {
+        let (jobs, job_receiver) = mpsc::channel::<UploadJob>();
+        let thread = thread::spawn(move || {
+            let _shared_context = make_current();
+            let mut gl = Context::current_context();
+            for job in job_receiver {
+                _run_job(&mut gl, job);
+            }
+        });
+
+        UploadThread {
+            jobs: Some(jobs),
+            thread: Some(thread),
+            owner: None
+        }
+    }
+
+    /// Spawn a new upload thread, whose uploads can only be taken on the
+    /// render context `gl` (or a context derived from it). `make_current`
+    /// is called on the new thread, and must create an OpenGL context that
+    /// shares objects with `gl`, and make it current on the new thread.
+    ///
+    /// Since uploaded objects can only be taken on `gl`, they can only be
+    /// bound on the render context, even though they were created by the
+    /// upload thread's context.
+    ///
+    /// # Safety
+    /// The same rules apply to this function as [`UploadThread::spawn`]
+    /// (struct.UploadThread.html#method.spawn).
+    pub unsafe fn spawn_shared<B, F, P, R, T, M, G>(gl: &ContextOf<B, F, P, R, T>,
+                                                    make_current: M)
+        -> Self
+        where M: FnOnce() -> G + Send + 'static
+    {
+        let mut uploads = UploadThread::spawn(make_current);
+        uploads.owner = Some(gl.handle());
+        uploads
+    }
+
+    fn submit(&self, job: UploadJob) {
+        if let Some(ref jobs) = self.jobs {
+            // If the thread has exited, the job's result sender is dropped,
+            // which is reported by the `PendingUpload`
+            let _ = jobs.send(job);
+        }
+    }
+
+    /// Upload `bytes` to a new buffer, using the given usage hint.
+    pub fn upload_buffer(&self, bytes: Vec<u8>, usage: BufferDataUsage)
+        -> PendingUpload<Buffer>
+    {
+        let (result, receiver) = mpsc::channel();
+        self.submit(UploadJob::Buffer {
+            bytes: bytes,
+            usage: usage,
+            result: result
+        });
+        PendingUpload::new(receiver, self.owner.clone())
+    }
+
+    /// Upload `image` to a new 2D texture, optionally generating mipmaps
+    /// for the texture.
+    ///
+    /// # Failures
+    /// The returned `PendingUpload` will return an error if the texture
+    /// could not be built (see [`Texture2dBuilder::try_unwrap`]
+    /// (../context/texture_context/struct.Texture2dBuilder.html#method.try_unwrap)).
+    pub fn upload_texture_2d<I>(&self, image: I, generate_mipmap: bool)
+        -> PendingUpload<Texture2d>
+        where I: Image2d + Send + 'static
+    {
+        let (result, receiver) = mpsc::channel();
+        self.submit(UploadJob::Texture2d {
+            image: Box::new(image),
+            generate_mipmap: generate_mipmap,
+            result: result
+        });
+        PendingUpload::new(receiver, self.owner.clone())
+    }
+
+    /// Run `f` on the upload thread, with the upload thread's context, to
+    /// create an object (such as a buffer, texture, or renderbuffer) and
+    /// upload its data.
+    ///
+    /// # Failures
+    /// The returned `PendingUpload` will return the error returned by `f`,
+    /// if any.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::{UploadThread, Texture2d};
+    ///
+    /// # fn make_shared_context_current() { }
+    /// # fn main() {
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let uploads = unsafe {
+    ///     UploadThread::spawn_shared(&gl, || make_shared_context_current())
+    /// };
+    ///
+    /// let pending = uploads.run(|gl| {
+    ///     gl.build_texture_2d()
+    ///       .empty(glitter::ImageFormat::rgba8(), 1024, 1024)
+    ///       .try_unwrap()
+    /// });
+    /// let texture: Texture2d = pending.wait(&gl).unwrap();
+    /// # }
+    /// ```
+    pub fn run<O, F>(&self, f: F) -> PendingUpload<O>
+        where O: GLObject<Id = GLuint>,
+              F: FnOnce(&mut Context) -> Result<O, GLError> + Send + 'static
+    {
+        let (result, receiver) = mpsc::channel::<UploadResult>();
+        let mut f = Some(f);
+        self.submit(UploadJob::Run(Box::new(move |gl: &mut Context| {
+            if let Some(f) = f.take() {
+                let uploaded = f(gl).map(|object| {
+                    let fence = unsafe { _fence() };
+                    (object.into_raw(), fence)
+                });
+                let _ = result.send(uploaded);
+            }
+        })));
+        PendingUpload::new(receiver, self.owner.clone())
+    }
+}
+
+impl Drop for UploadThread {
+    fn drop(&mut self) {
+        // Closing the job channel makes the thread exit after its
+        // remaining jobs are done
+        self.jobs = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+enum PendingState {
+    Waiting(Receiver<UploadResult>),
+    Fenced(GLuint, GLsync),
+    Done
+}
+
+/// An object that is being uploaded by an [`UploadThread`]
+/// (struct.UploadThread.html). Once the upload has finished, and its fence
+/// has been signaled, the object can be taken with [`try_take`]
+/// (struct.PendingUpload.html#method.try_take) or [`wait`]
+/// (struct.PendingUpload.html#method.wait).
+///
+/// # Note
+/// If a `PendingUpload` is dropped before the worker thread has finished
+/// the upload, or after the context it was uploaded for has died, the
+/// uploaded object will not be deleted.
+pub struct PendingUpload<O: GLObject<Id = GLuint>> {
+    state: PendingState,
+    owner: Option<ContextHandle>,
+    _phantom: PhantomData<*mut O>
+}
+
+impl<O: GLObject<Id = GLuint>> PendingUpload<O> {
+    fn new(receiver: Receiver<UploadResult>, owner: Option<ContextHandle>)
+        -> Self
+    {
+        PendingUpload {
+            state: PendingState::Waiting(receiver),
+            owner: owner,
+            _phantom: PhantomData
+        }
+    }
+
+    fn _check_owner<B, F, P, R, T>(&self, gl: &ContextOf<B, F, P, R, T>)
+        -> Result<(), GLError>
+    {
+        match self.owner {
+            Some(ref owner) if !owner.is_handle_of(gl) => {
+                let msg = "The uploaded object can only be taken on the context that the upload thread was spawned for";
+                Err(GLError::Message(msg.to_owned()))
+            },
+            _ => Ok(())
+        }
+    }
+
+    fn _receive(&mut self, block: bool) -> Result<(), GLError> {
+        let received = match self.state {
+            PendingState::Waiting(ref receiver) => {
+                if block {
+                    receiver.recv().map_err(|_| TryRecvError::Disconnected)
+                }
+                else {
+                    receiver.try_recv()
+                }
+            },
+            _ => { return Ok(()); }
+        };
+
+        match received {
+            Ok(Ok((id, UploadFence(fence)))) => {
+                self.state = PendingState::Fenced(id, fence);
+                Ok(())
+            },
+            Ok(Err(err)) => {
+                self.state = PendingState::Done;
+                Err(err)
+            },
+            Err(TryRecvError::Empty) => Ok(()),
+            Err(TryRecvError::Disconnected) => {
+                self.state = PendingState::Done;
+                let msg = "The upload thread exited before finishing the upload";
+                Err(GLError::Message(msg.to_owned()))
+            }
+        }
+    }
+
+    // Wait up to `timeout` nanoseconds for the upload's fence, and take the
+    // object if it has been signaled. The fence is kept if the wait fails,
+    // so that it's deleted along with the object when this is dropped.
+    fn _take(&mut self, timeout: GLuint64) -> Result<Option<O>, GLError> {
+        let (id, fence) = match self.state {
+            PendingState::Fenced(id, fence) => (id, fence),
+            _ => { return Ok(None); }
+        };
+
+        let status = unsafe {
+            gl::ClientWaitSync(fence, gl::SYNC_FLUSH_COMMANDS_BIT, timeout)
+        };
+        dbg_gl_error! {
+            call "glClientWaitSync";
+            args (fence, gl::SYNC_FLUSH_COMMANDS_BIT, timeout);
+            GLError::InvalidValue => "`sync` is not a sync object, or `flags` is invalid",
+            _ => "Unknown error"
+        }
+
+        match status {
+            gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => {
+                self.state = PendingState::Done;
+                unsafe {
+                    gl::DeleteSync(fence);
+                    Ok(Some(O::from_raw(id)))
+                }
+            },
+            gl::TIMEOUT_EXPIRED => Ok(None),
+            _ => {
+                let msg = "Waiting for the upload's fence failed";
+                Err(GLError::Message(msg.to_owned()))
+            }
+        }
+    }
+
+    /// Take the uploaded object, if the upload has finished. Returns
+    /// `Ok(None)` if the upload is still in progress, or if the object
+    /// was already taken.
+    ///
+    /// # Failures
+    /// An error will be returned if the upload failed, if the upload
+    /// thread exited before finishing the upload, if checking the upload's
+    /// fence failed, or if the upload thread was spawned with [`UploadThread::spawn_shared`]
+    /// (struct.UploadThread.html#method.spawn_shared) for a different
+    /// context than `gl`.
+    ///
+    /// # See also
+    /// [`glClientWaitSync`](http://docs.gl/es3/glClientWaitSync) OpenGL docs
+    pub fn try_take<B, F, P, R, T>(&mut self, gl: &ContextOf<B, F, P, R, T>)
+        -> Result<Option<O>, GLError>
+    {
+        try!(self._check_owner(gl));
+        try!(self._receive(false));
+        self._take(0)
+    }
+
+    /// Block until the upload has finished, and return the uploaded object.
+    ///
+    /// # Failures
+    /// An error will be returned if the upload failed, if the upload
+    /// thread exited before finishing the upload, if the object was
+    /// already taken, if waiting for the upload's fence failed (such as
+    /// if the context was lost), or if the upload thread was spawned for a
+    /// different context than `gl`.
+    ///
+    /// # See also
+    /// [`glClientWaitSync`](http://docs.gl/es3/glClientWaitSync) OpenGL docs
+    pub fn wait<B, F, P, R, T>(mut self, gl: &ContextOf<B, F, P, R, T>)
+        -> Result<O, GLError>
+    {
+        try!(self._check_owner(gl));
+        try!(self._receive(true));
+        loop {
+            if let PendingState::Done = self.state {
+                let msg = "The uploaded object was already taken";
+                return Err(GLError::Message(msg.to_owned()));
+            }
+            if let Some(object) = try!(self._take(WAIT_TIMEOUT)) {
+                return Ok(object);
+            }
+        }
+    }
+}
+
+impl<O: GLObject<Id = GLuint>> Drop for PendingUpload<O> {
+    fn drop(&mut self) {
+        // The object and its fence are leaked if the context they were
+        // uploaded for has died, since it may not be current anymore
+        if let Some(ref owner) = self.owner {
+            if !owner.is_alive() {
+                return;
+            }
+        }
+
+        // Otherwise, they're queued for deletion like any other object
+        let _ = self._receive(false);
+        if let PendingState::Fenced(id, fence) =
+            mem::replace(&mut self.state, PendingState::Done)
+        {
+            unsafe {
+                drop(Fence::from_sync(fence));
+                drop(O::from_raw(id));
+            }
+        }
+    }
+}
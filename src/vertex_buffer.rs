@@ -1,16 +1,39 @@
 //! Contains a higher-level abstraction for creating vertex and index
 //! buffer.
 
+use std::mem;
+#[cfg(feature = "gles3")] use std::cmp;
 use std::marker::PhantomData;
 use std::collections::{HashMap, HashSet};
 use context::{ContextOf, AContext, ContextBufferExt,
               ArrayBufferBinding, ArrayBufferContext,
-              ElementArrayBufferBinding, ElementArrayBufferContext};
+              ElementArrayBufferBinding, ElementArrayBufferContext,
+              BufferBinding, RawVertexSource, IndexSource};
 use program::ProgramAttrib;
-use vertex_data::{VertexData, VertexBytes, VertexAttribute};
-use index_data::{IndexData, IndexDatum};
+use vertex_data::{VertexData, VertexBytes, VertexAttribute, AttribKind};
+use index_data::{IndexData, IndexDatum, IndexDatumType, index_range};
 use buffer::Buffer;
 use types::DrawingMode;
+use index_validation;
+
+// In debug builds, panic if `count` elements of `stride` bytes each are
+// beyond the end of the data that was sent to a buffer, such as if the
+// buffer was written to directly with fewer elements.
+fn _debug_check_size(elements: &str,
+                     count: usize,
+                     stride: usize,
+                     size: Option<usize>)
+{
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    if let Some(size) = size {
+        assert!(count * stride <= size,
+                "{} {} are out of bounds of the buffer, which only contains {} bytes",
+                count, elements, size);
+    }
+}
 
 /// An error generated when trying add an attribute to an [`AttribBinder`]
 /// (struct.AttribBinder.html) using the [`AttribBinder::add`]
@@ -40,17 +63,39 @@ pub struct AttribError {
 /// operating on vertex attributes. Consider using the direct lower-level
 /// glitter API's if heap allocations become a performance bottleneck.
 pub struct AttribBinder {
-    attribs: HashMap<String, ProgramAttrib>
+    attribs: HashMap<String, ProgramAttrib>,
+    divisor: u32
 }
 
 impl AttribBinder {
     /// Create a new, empty `AttribBinder`.
     pub fn new() -> Self {
         AttribBinder {
-            attribs: HashMap::new()
+            attribs: HashMap::new(),
+            divisor: 0
         }
     }
 
+    /// Get the divisor of the vertex attributes. A divisor of 0 means that
+    /// the vertex attributes advance once per vertex, and any other divisor
+    /// `n` means that they advance once every `n` instances.
+    pub fn divisor(&self) -> u32 {
+        self.divisor
+    }
+
+    /// Set the divisor of the vertex attributes, which is used when they
+    /// are bound with [`bind`](#method.bind). Use a divisor of 1 for a
+    /// buffer of per-instance data, such as a transform matrix for
+    /// each instance.
+    ///
+    /// # Note
+    /// Instanced vertex attributes require OpenGL ES 3.0, so this method is
+    /// only available with the `gles3` feature.
+    #[cfg(feature = "gles3")]
+    pub fn set_divisor(&mut self, divisor: u32) {
+        self.divisor = divisor;
+    }
+
     /// Add an attribute to the `AttribBinder`.
     ///
     /// # Failures
@@ -128,15 +173,27 @@ impl AttribBinder {
     {
         // TODO: Use a plain `for` loop? Do we actually want the `V` parameter?
         //       Do we actually *only* want the `V` parameter?
-        self.for_each::<V, _>(|_, program_attrib| {
-            gl.enable_vertex_attrib_array(program_attrib);
+        self.for_each::<V, _>(|vertex_attrib, program_attrib| {
+            for column in 0..vertex_attrib.ty.columns {
+                gl.enable_vertex_attrib_array(ProgramAttrib {
+                    gl_index: program_attrib.gl_index + column as u32
+                });
+            }
         })
     }
 
     /// Set up each vertex attribute with the appropriate attribute options
     /// (using [`glVertexAttribPointer`]
-    /// (http://docs.gl/es2/glVertexAttribPointer)). The `VertexData` type
-    /// parameter is used to get the attribute options for each attribute.
+    /// (http://docs.gl/es2/glVertexAttribPointer), or
+    /// [`glVertexAttribIPointer`](http://docs.gl/es3/glVertexAttribIPointer)
+    /// for [integer attributes](../vertex_data/enum.AttribKind.html)). The
+    /// `VertexData` type parameter is used to get the attribute options for
+    /// each attribute.
+    ///
+    /// Matrix vertex attributes are set up as one attribute for each column,
+    /// at consecutive locations. With the `gles3` feature, the
+    /// [divisor](#method.divisor) of each attribute is also set (using
+    /// [`glVertexAttribDivisor`](http://docs.gl/es3/glVertexAttribDivisor)).
     ///
     /// # Failures
     /// An error will be returned if the set of vertex attributes contained
@@ -151,16 +208,38 @@ impl AttribBinder {
     pub fn bind<V, C>(&self, gl: &C) -> Result<(), AttribError>
         where V: VertexData, C: AContext
     {
+        let divisor = self.divisor;
         self.for_each::<V, _>(|vertex_attrib, program_attrib| {
-            unsafe {
-                // TODO: Refactor!
-                // (Make vertex_attrib_pointer take vertex_attrib)
-                gl.vertex_attrib_pointer(program_attrib,
-                                         vertex_attrib.ty.components,
-                                         vertex_attrib.ty.data,
-                                         vertex_attrib.ty.normalize,
-                                         vertex_attrib.stride,
-                                         vertex_attrib.offset);
+            let ty = &vertex_attrib.ty;
+            for column in 0..ty.columns {
+                let attrib = ProgramAttrib {
+                    gl_index: program_attrib.gl_index + column as u32
+                };
+                let offset = vertex_attrib.offset +
+                             ty.column_size() * column as usize;
+                unsafe {
+                    // TODO: Refactor!
+                    // (Make vertex_attrib_pointer take vertex_attrib)
+                    match ty.kind {
+                        AttribKind::Float => {
+                            gl.vertex_attrib_pointer(attrib,
+                                                     ty.components,
+                                                     ty.data,
+                                                     ty.normalize,
+                                                     vertex_attrib.stride,
+                                                     offset);
+                        },
+                        #[cfg(feature = "gles3")]
+                        AttribKind::Integer => {
+                            gl.vertex_attrib_i_pointer(attrib,
+                                                       ty.components,
+                                                       ty.data,
+                                                       vertex_attrib.stride,
+                                                       offset);
+                        }
+                    }
+                }
+                _attrib_divisor(gl, attrib, divisor);
             }
         })
     }
@@ -168,6 +247,21 @@ impl AttribBinder {
 
 
 
+#[cfg(feature = "gles3")]
+fn _attrib_divisor<C: AContext>(gl: &C, attrib: ProgramAttrib, divisor: u32) {
+    gl.vertex_attrib_divisor(attrib, divisor);
+}
+
+#[cfg(not(feature = "gles3"))]
+fn _attrib_divisor<C: AContext>(_gl: &C,
+                                 _attrib: ProgramAttrib,
+                                 _divisor: u32)
+{
+
+}
+
+
+
 /// An error generated when binding vertex attributes.
 #[derive(Debug)]
 pub enum VertexBindError {
@@ -217,10 +311,33 @@ impl<V: VertexData> VertexBuffer<V> {
 /// been bound to a context.
 pub struct VertexBufferBinding<'a, T: VertexData + 'a> {
     gl_buffer: ArrayBufferBinding<'a>,
+    attrib_binder: &'a AttribBinder,
     count: &'a mut usize,
     _phantom: PhantomData<*const VertexBuffer<T>>
 }
 
+impl<'a, T: VertexData> VertexBufferBinding<'a, T> {
+    /// Get the number of vertices that the buffer has room for, which is 0
+    /// if the size of the buffer is unknown.
+    pub fn capacity(&self) -> usize {
+        self.gl_buffer.buffer().size().unwrap_or(0) / mem::size_of::<T>()
+    }
+}
+
+unsafe impl<'a, T: VertexData> RawVertexSource for VertexBufferBinding<'a, T> {
+    fn array_buffer(&self) -> &ArrayBufferBinding {
+        &self.gl_buffer
+    }
+
+    fn vertex_count(&self) -> usize {
+        _debug_check_size("vertices",
+                          *self.count,
+                          mem::size_of::<T>(),
+                          self.gl_buffer.buffer().size());
+        *self.count
+    }
+}
+
 /// An extension trait that adds vertex buffer and index buffer-related methods
 /// to OpenGL contexts.
 pub trait ContextVertexBufferExt: AContext {
@@ -249,6 +366,116 @@ pub trait ContextVertexBufferExt: AContext {
                           usage);
     }
 
+    /// Make room for at least `additional` more vertices after the vertices
+    /// that have been buffered. If the buffer is too small, it is
+    /// reallocated (with [`gl.resize_buffer`]
+    /// (../context/buffer_context/trait.ContextBufferExt.html#method.resize_buffer))
+    /// to at least double its capacity, so repeatedly adding vertices
+    /// doesn't reallocate it every time.
+    ///
+    /// # Failures
+    /// The vertex attributes are set up again after the buffer is
+    /// reallocated, so an error will be returned if the attributes of the
+    /// `VertexData` type don't match the buffer's `AttribBinder` (see
+    /// [`AttribBinder::bind`](struct.AttribBinder.html#method.bind)).
+    ///
+    /// # Note
+    /// Reallocating a buffer requires OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    ///
+    /// If the size of the buffer is unknown (see [`VertexBufferBinding::capacity`]
+    /// (struct.VertexBufferBinding.html#method.capacity)), it is treated as
+    /// empty, so any vertices that were buffered are not kept.
+    #[cfg(feature = "gles3")]
+    fn reserve_vertices<T>(&self,
+                           gl_vbo: &mut VertexBufferBinding<T>,
+                           additional: usize)
+        -> Result<(), AttribError>
+        where T: VertexData, Self: Sized
+    {
+        let capacity = gl_vbo.capacity();
+        let needed = *gl_vbo.count + additional;
+        if needed <= capacity {
+            return Ok(());
+        }
+
+        let new_capacity = cmp::max(needed, capacity * 2);
+        self.resize_buffer(&mut gl_vbo.gl_buffer,
+                           new_capacity * mem::size_of::<T>(),
+                           super::BufferDataUsage::DynamicDraw);
+
+        // The vertex attributes still point to the old buffer object, so
+        // they need to be set up again
+        gl_vbo.attrib_binder.bind::<T, _>(self)
+    }
+
+    /// Add vertices after the vertices that have been buffered, growing
+    /// the buffer if there isn't enough room (see
+    /// [`gl.reserve_vertices`](trait.ContextVertexBufferExt.html#method.reserve_vertices)).
+    ///
+    /// # Failures
+    /// An error will be returned if the buffer had to grow, and the
+    /// attributes of the `VertexData` type don't match the buffer's
+    /// `AttribBinder`. No vertices are added in that case.
+    ///
+    /// # Note
+    /// Reallocating a buffer requires OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// #[macro_use] extern crate glitter;
+    /// use glitter::prelude::*;
+    /// use glitter::RawVertexSource;
+    ///
+    /// # fn main() {
+    /// # let mut particles: glitter::VertexBuffer<[f32; 2]> = unsafe { ::std::mem::uninitialized() };
+    /// let gl = unsafe { glitter::Context::current_context() };
+    /// let (mut gl_particles, gl) = gl.bind_vertex_buffer(&mut particles);
+    ///
+    /// gl.extend_vertices(&mut gl_particles, &[[0.0, 0.0], [1.0, 0.0]]).unwrap();
+    /// gl.push_vertex(&mut gl_particles, [0.5, 1.0]).unwrap();
+    /// assert_eq!(gl_particles.vertex_count(), 3);
+    /// # }
+    /// ```
+    #[cfg(feature = "gles3")]
+    fn extend_vertices<T>(&self,
+                          gl_vbo: &mut VertexBufferBinding<T>,
+                          vertices: &[T])
+        -> Result<(), AttribError>
+        where T: VertexData, [T]: VertexBytes, Self: Sized
+    {
+        if vertices.is_empty() {
+            return Ok(());
+        }
+
+        try!(self.reserve_vertices(gl_vbo, vertices.len()));
+        let offset = *gl_vbo.count * mem::size_of::<T>();
+        self.buffer_sub_bytes(&mut gl_vbo.gl_buffer,
+                              offset,
+                              vertices.vertex_bytes());
+        *gl_vbo.count += vertices.len();
+        Ok(())
+    }
+
+    /// Add a single vertex after the vertices that have been buffered,
+    /// growing the buffer if there isn't enough room.
+    ///
+    /// # Failures
+    /// The same errors are returned as [`gl.extend_vertices`]
+    /// (trait.ContextVertexBufferExt.html#method.extend_vertices).
+    ///
+    /// # Note
+    /// Reallocating a buffer requires OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    #[cfg(feature = "gles3")]
+    fn push_vertex<T>(&self, gl_vbo: &mut VertexBufferBinding<T>, vertex: T)
+        -> Result<(), AttribError>
+        where T: VertexData, [T]: VertexBytes, Self: Sized
+    {
+        self.extend_vertices(gl_vbo, &[vertex])
+    }
+
     /// Send data to an index buffer. Note that this will replace the buffer's
     /// current contents, if any.
     fn buffer_indices<T>(&self,
@@ -258,6 +485,7 @@ pub trait ContextVertexBufferExt: AContext {
         where T: IndexDatum, [T]: IndexData
     {
         *gl_ibo.count = indices.len();
+        *gl_ibo.range = index_range(indices);
         self.buffer_bytes(&mut gl_ibo.gl_buffer, indices.index_bytes(), usage);
     }
 
@@ -279,7 +507,7 @@ pub trait ContextVertexBufferExt: AContext {
                                 length: usize)
         where V: VertexData
     {
-        debug_assert!((start as usize) + length <= *gl_vbo.count);
+        debug_assert!((start as usize) + length <= gl_vbo.vertex_count());
 
         unsafe {
             self.draw_arrays_range(&gl_vbo.gl_buffer, mode, start, length);
@@ -301,7 +529,7 @@ pub trait ContextVertexBufferExt: AContext {
             self.draw_arrays_range(&gl_vbo.gl_buffer,
                                    mode,
                                    0,
-                                   *gl_vbo.count);
+                                   gl_vbo.vertex_count());
         }
     }
 
@@ -320,6 +548,9 @@ pub trait ContextVertexBufferExt: AContext {
         where V: VertexData, I: IndexDatum
     {
         debug_assert!(length <= *gl_ibo.count);
+        index_validation::check("`draw_n_elements_buffered_vbo`",
+                                gl_ibo.index_range().map(|(_, max)| max),
+                                gl_vbo.vertex_count());
 
         unsafe {
             self.draw_n_elements_buffered(&gl_vbo.gl_buffer,
@@ -343,6 +574,10 @@ pub trait ContextVertexBufferExt: AContext {
                                         mode: DrawingMode)
         where V: VertexData, I: IndexDatum
     {
+        index_validation::check("`draw_elements_buffered_vbo`",
+                                gl_ibo.index_range().map(|(_, max)| max),
+                                gl_vbo.vertex_count());
+
         unsafe {
             self.draw_n_elements_buffered(&gl_vbo.gl_buffer,
                                           &gl_ibo.gl_buffer,
@@ -352,6 +587,88 @@ pub trait ContextVertexBufferExt: AContext {
         }
     }
 
+    /// Draw primitives using a range of the provided index buffer as
+    /// the indices into the provided vertex buffer.
+    ///
+    /// - `gl_vbo`: The binding of the buffer that contains the vertex data.
+    /// - `gl_ibo`: The binding of the buffer that contains the index data.
+    /// - `mode`: The type of primitives to draw.
+    /// - `first`: The first index to read.
+    /// - `length`: The number of indices to read.
+    ///
+    /// # Panics
+    /// This function will panic if the `first` and `length` are out
+    /// of bounds of the currently-buffered indices.
+    fn draw_elements_range_buffered_vbo<V, I>(&self,
+                                              gl_vbo: &VertexBufferBinding<V>,
+                                              gl_ibo: &IndexBufferBinding<I>,
+                                              mode: DrawingMode,
+                                              first: usize,
+                                              length: usize)
+        where V: VertexData, I: IndexDatum
+    {
+        assert!(first + length <= *gl_ibo.count);
+        index_validation::check("`draw_elements_range_buffered_vbo`",
+                                gl_ibo.index_range().map(|(_, max)| max),
+                                gl_vbo.vertex_count());
+
+        unsafe {
+            self.draw_n_elements_buffered_range(&gl_vbo.gl_buffer,
+                                                &gl_ibo.gl_buffer,
+                                                mode,
+                                                first,
+                                                length,
+                                                I::index_datum_type());
+        }
+    }
+
+    /// Draw primitives using the provided index buffer as the indices into
+    /// the provided vertex buffer, passing the range of indices that was
+    /// tracked when the indices were buffered to `glDrawRangeElements`. All
+    /// buffered indices will be used.
+    ///
+    /// - `gl_vbo`: The binding of the buffer that contains the vertex data.
+    /// - `gl_ibo`: The binding of the buffer that contains the index data.
+    /// - `mode`: The type of primitives to draw.
+    ///
+    /// # Note
+    /// `glDrawRangeElements` requires OpenGL ES 3.0, so this method is only
+    /// available with the `gles3` feature.
+    ///
+    /// # Panics
+    /// If debug assertions are enabled, this function will panic if an
+    /// index refers to a vertex beyond the currently-buffered vertices.
+    ///
+    /// # See also
+    /// [`glDrawRangeElements`](http://docs.gl/es3/glDrawRangeElements)
+    /// OpenGL docs
+    #[cfg(feature = "gles3")]
+    fn draw_range_elements_buffered_vbo<V, I>(&self,
+                                              gl_vbo: &VertexBufferBinding<V>,
+                                              gl_ibo: &IndexBufferBinding<I>,
+                                              mode: DrawingMode)
+        where V: VertexData, I: IndexDatum
+    {
+        let (start, end) = match *gl_ibo.range {
+            Some(range) => range,
+            None => { return; }
+        };
+        debug_assert!((end as usize) < gl_vbo.vertex_count(),
+                      "Index {} is out of bounds of {} vertices",
+                      end, gl_vbo.vertex_count());
+
+        unsafe {
+            self.draw_range_elements_buffered(&gl_vbo.gl_buffer,
+                                              &gl_ibo.gl_buffer,
+                                              mode,
+                                              start,
+                                              end,
+                                              0,
+                                              *gl_ibo.count,
+                                              I::index_datum_type());
+        }
+    }
+
     /// Draw primitives specified by the provided index array,
     /// treated as indices into the provided vertex buffer.
     ///
@@ -366,6 +683,13 @@ pub trait ContextVertexBufferExt: AContext {
                                  indices: &[I])
         where V: VertexData, I: IndexDatum, [I]: IndexData
     {
+        if index_validation::enabled() {
+            let indices = &indices[..count];
+            index_validation::check("`draw_n_elements_vbo`",
+                                    index_range(indices).map(|(_, max)| max),
+                                    gl_vbo.vertex_count());
+        }
+
         unsafe {
             self.draw_n_elements(&gl_vbo.gl_buffer, mode, count, indices);
         }
@@ -383,6 +707,12 @@ pub trait ContextVertexBufferExt: AContext {
                                indices: &[I])
         where V: VertexData, I: IndexDatum, [I]: IndexData
     {
+        if index_validation::enabled() {
+            index_validation::check("`draw_elements_vbo`",
+                                    index_range(indices).map(|(_, max)| max),
+                                    gl_vbo.vertex_count());
+        }
+
         unsafe {
             self.draw_elements(&gl_vbo.gl_buffer, mode, indices);
         }
@@ -409,13 +739,13 @@ pub trait VertexBufferContext: ArrayBufferContext + Sized {
         where V: VertexData
     {
         // TODO: Cleanup error handling
-        let (gl_array_buffer, rest) = match vbo.attrib_binder {
+        let (gl_array_buffer, binder, rest) = match vbo.attrib_binder {
             Some(ref binder) => {
                 let buf = &mut vbo.buffer;
                 let (gl_buffer, mut rest) = self.bind_array_buffer(buf);
                 binder.enable::<V, _>(&mut rest).unwrap();
                 binder.bind::<V, _>(&rest).unwrap();
-                (gl_buffer, rest)
+                (gl_buffer, binder, rest)
             },
             None => {
                 panic!("No attribute bindings provided for vertex buffer");
@@ -425,12 +755,26 @@ pub trait VertexBufferContext: ArrayBufferContext + Sized {
         (
             VertexBufferBinding {
                 gl_buffer: gl_array_buffer,
+                attrib_binder: binder,
                 count: &mut vbo.count,
                 _phantom: PhantomData
             },
             rest
         )
     }
+
+    /// Bind a vertex buffer to this context for the duration of `f`, which is
+    /// called with the binding and the remaining context. This is a scoped
+    /// alternative to [`bind_vertex_buffer`](#method.bind_vertex_buffer), which
+    /// can be easier to use when nesting short-lived bindings.
+    fn with_vertex_buffer<'a, V, U, C>(self, vbo: &'a mut VertexBuffer<V>, f: C)
+        -> U
+        where V: VertexData,
+              C: FnOnce(VertexBufferBinding<'a, V>, Self::Rest) -> U
+    {
+        let (gl_vbo, gl) = self.bind_vertex_buffer(vbo);
+        f(gl_vbo, gl)
+    }
 }
 
 impl<C: ArrayBufferContext> VertexBufferContext for C {
@@ -457,11 +801,25 @@ pub trait IndexBufferContext: ElementArrayBufferContext + Sized {
             IndexBufferBinding {
                 gl_buffer: gl_be,
                 count: &mut ibo.count,
+                range: &mut ibo.range,
                 _phantom: PhantomData
             },
             rest
         )
     }
+
+    /// Bind an index buffer to this context for the duration of `f`, which is
+    /// called with the binding and the remaining context. This is a scoped
+    /// alternative to [`bind_index_buffer`](#method.bind_index_buffer), which
+    /// can be easier to use when nesting short-lived bindings.
+    fn with_index_buffer<'a, I, U, C>(self, ibo: &'a mut IndexBuffer<I>, f: C)
+        -> U
+        where I: IndexDatum,
+              C: FnOnce(IndexBufferBinding<'a, I>, Self::Rest) -> U
+    {
+        let (gl_ibo, gl) = self.bind_index_buffer(ibo);
+        f(gl_ibo, gl)
+    }
 }
 
 impl<C: ElementArrayBufferContext> IndexBufferContext for C {
@@ -472,10 +830,11 @@ impl<C: ElementArrayBufferContext> IndexBufferContext for C {
 
 /// A buffer that contains index data. In addition to storing a buffer, an
 /// `IndexBuffer` store a count of the amount of `IndexData` that has been
-/// buffered.
+/// buffered, along with the smallest and largest buffered index.
 pub struct IndexBuffer<T: IndexDatum> {
     buffer: Buffer,
     count: usize,
+    range: Option<(u32, u32)>,
     phantom: PhantomData<*const T>
 }
 
@@ -489,6 +848,15 @@ impl<T: IndexDatum> IndexBuffer<T> {
     pub fn buffer_mut(&mut self) -> &mut Buffer {
         &mut self.buffer
     }
+
+    /// Get the smallest and largest index that was buffered with
+    /// [`gl.buffer_indices`]
+    /// (trait.ContextVertexBufferExt.html#method.buffer_indices), or `None`
+    /// if no indices have been buffered. The range isn't updated when the
+    /// underlying buffer is written to directly.
+    pub fn index_range(&self) -> Option<(u32, u32)> {
+        self.range
+    }
 }
 
 /// Represents an [`IndexBuffer`](struct.IndexBuffer.html) that has been
@@ -496,15 +864,39 @@ impl<T: IndexDatum> IndexBuffer<T> {
 pub struct IndexBufferBinding<'a, T: IndexDatum + 'a> {
     gl_buffer: ElementArrayBufferBinding<'a>,
     count: &'a mut usize,
+    range: &'a mut Option<(u32, u32)>,
     _phantom: PhantomData<*const IndexBuffer<T>>
 }
 
+unsafe impl<'a, T: IndexDatum> IndexSource for IndexBufferBinding<'a, T> {
+    fn element_array_buffer(&self) -> &ElementArrayBufferBinding {
+        &self.gl_buffer
+    }
+
+    fn index_type(&self) -> IndexDatumType {
+        T::index_datum_type()
+    }
+
+    fn index_count(&self) -> usize {
+        _debug_check_size("indices",
+                          *self.count,
+                          mem::size_of::<T>(),
+                          self.gl_buffer.buffer().size());
+        *self.count
+    }
+
+    fn index_range(&self) -> Option<(u32, u32)> {
+        *self.range
+    }
+}
+
 impl<B, F, P, R, T> ContextOf<B, F, P, R, T> {
     /// Create a new, empty index buffer.
     pub fn new_index_buffer<I: IndexDatum>(&self) -> IndexBuffer<I> {
         IndexBuffer {
             buffer: self.gen_buffer(),
             count: 0,
+            range: None,
             phantom: PhantomData
         }
     }
@@ -0,0 +1,294 @@
+//! Contains helpers for compressing vertex data, such as encoding normals
+//! and tangent frames into compact, normalized integer vertex attributes.
+//!
+//! Each encoder has a matching decoder, both on the CPU side (for testing
+//! and tools), and as GLSL source that can be included in a shader (such as
+//! [`OCTAHEDRAL_NORMAL_GLSL`](constant.OCTAHEDRAL_NORMAL_GLSL.html)).
+
+use vertex_data::{VertexDatum, VertexAttributeType, AttribKind};
+use types::DataType;
+
+/// A pair of signed, normalized 16-bit values. When used as a vertex
+/// attribute, each component is mapped from `[-32767, 32767]` to
+/// `[-1.0, 1.0]`, and is read as a `vec2` in a shader.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snorm16x2(pub [i16; 2]);
+
+/// Four signed, normalized 16-bit values. When used as a vertex
+/// attribute, each component is mapped from `[-32767, 32767]` to
+/// `[-1.0, 1.0]`, and is read as a `vec4` in a shader.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snorm16x4(pub [i16; 4]);
+
+unsafe impl VertexDatum for Snorm16x2 {
+    fn attrib_type() -> VertexAttributeType {
+        VertexAttributeType {
+            data: DataType::Short,
+            components: 2,
+            columns: 1,
+            normalize: true,
+            kind: AttribKind::Float
+        }
+    }
+}
+
+unsafe impl VertexDatum for Snorm16x4 {
+    fn attrib_type() -> VertexAttributeType {
+        VertexAttributeType {
+            data: DataType::Short,
+            components: 4,
+            columns: 1,
+            normalize: true,
+            kind: AttribKind::Float
+        }
+    }
+}
+
+fn _to_snorm16(x: f32) -> i16 {
+    (x.max(-1.0).min(1.0) * 32767.0).round() as i16
+}
+
+fn _from_snorm16(x: i16) -> f32 {
+    (x as f32 / 32767.0).max(-1.0)
+}
+
+fn _sign_not_zero(x: f32) -> f32 {
+    if x >= 0.0 { 1.0 } else { -1.0 }
+}
+
+fn _dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn _cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1],
+     a[2] * b[0] - a[0] * b[2],
+     a[0] * b[1] - a[1] * b[0]]
+}
+
+fn _normalize(v: [f32; 3]) -> [f32; 3] {
+    let length = _dot(v, v).sqrt();
+    if length > 0.0 {
+        [v[0] / length, v[1] / length, v[2] / length]
+    }
+    else {
+        v
+    }
+}
+
+
+
+/// Encode a unit normal vector using an octahedral mapping, which
+/// packs it into two signed, normalized 16-bit components (4 bytes,
+/// instead of 12 bytes for 3 floats). The normal does not need to be
+/// normalized beforehand.
+///
+/// The encoded normal can be decoded in a shader using the
+/// `decode_octahedral_normal` function from [`OCTAHEDRAL_NORMAL_GLSL`]
+/// (constant.OCTAHEDRAL_NORMAL_GLSL.html).
+///
+/// # Example
+/// ```
+/// use glitter::{encode_octahedral_normal, decode_octahedral_normal};
+///
+/// let normal = [0.0, 0.6, -0.8];
+/// let decoded = decode_octahedral_normal(encode_octahedral_normal(normal));
+/// for (a, b) in normal.iter().zip(decoded.iter()) {
+///     assert!((a - b).abs() < 0.001);
+/// }
+/// ```
+pub fn encode_octahedral_normal(normal: [f32; 3]) -> Snorm16x2 {
+    let l1 = normal[0].abs() + normal[1].abs() + normal[2].abs();
+    if l1 == 0.0 {
+        return Snorm16x2([0, 0]);
+    }
+
+    let x = normal[0] / l1;
+    let y = normal[1] / l1;
+    let (x, y) = if normal[2] < 0.0 {
+        // Fold the lower hemisphere over the diagonals
+        ((1.0 - y.abs()) * _sign_not_zero(x),
+         (1.0 - x.abs()) * _sign_not_zero(y))
+    }
+    else {
+        (x, y)
+    };
+
+    Snorm16x2([_to_snorm16(x), _to_snorm16(y)])
+}
+
+/// Decode a normal that was encoded with [`encode_octahedral_normal`]
+/// (fn.encode_octahedral_normal.html). The returned normal is normalized.
+pub fn decode_octahedral_normal(encoded: Snorm16x2) -> [f32; 3] {
+    let x = _from_snorm16(encoded.0[0]);
+    let y = _from_snorm16(encoded.0[1]);
+    let z = 1.0 - x.abs() - y.abs();
+    let (x, y) = if z < 0.0 {
+        ((1.0 - y.abs()) * _sign_not_zero(x),
+         (1.0 - x.abs()) * _sign_not_zero(y))
+    }
+    else {
+        (x, y)
+    };
+
+    _normalize([x, y, z])
+}
+
+/// Encode a tangent frame as a quaternion, packed into four signed,
+/// normalized 16-bit components (8 bytes, instead of 28 bytes for a normal
+/// and a tangent with a handedness).
+///
+/// - `normal`: The normal vector.
+/// - `tangent`: The tangent vector, where the `w` component is the
+///   handedness of the bitangent (`1.0` or `-1.0`), using the same
+///   convention as glTF. The bitangent is `cross(normal, tangent.xyz) * w`.
+///
+/// The tangent is orthogonalized against the normal before encoding, and
+/// the handedness is stored in the sign of the quaternion's `w` component.
+///
+/// The encoded tangent frame can be decoded in a shader using the
+/// `decode_tangent_frame` function from [`TANGENT_FRAME_GLSL`]
+/// (constant.TANGENT_FRAME_GLSL.html).
+///
+/// # Example
+/// ```
+/// use glitter::{encode_tangent_frame, decode_tangent_frame};
+///
+/// let normal = [0.0, 0.0, 1.0];
+/// let tangent = [1.0, 0.0, 0.0, -1.0];
+/// let (n, t) = decode_tangent_frame(encode_tangent_frame(normal, tangent));
+/// assert!((n[2] - 1.0).abs() < 0.001);
+/// assert!((t[0] - 1.0).abs() < 0.001);
+/// assert_eq!(t[3], -1.0);
+/// ```
+pub fn encode_tangent_frame(normal: [f32; 3], tangent: [f32; 4])
+    -> Snorm16x4
+{
+    let n = _normalize(normal);
+    let t = [tangent[0], tangent[1], tangent[2]];
+    let n_dot_t = _dot(n, t);
+    let t = _normalize([t[0] - n[0] * n_dot_t,
+                        t[1] - n[1] * n_dot_t,
+                        t[2] - n[2] * n_dot_t]);
+    let b = _cross(n, t);
+
+    // The rotation matrix with the columns `t`, `b`, and `n`
+    let (m00, m01, m02) = (t[0], b[0], n[0]);
+    let (m10, m11, m12) = (t[1], b[1], n[1]);
+    let (m20, m21, m22) = (t[2], b[2], n[2]);
+
+    let trace = m00 + m11 + m22;
+    let mut q = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [(m21 - m12) / s, (m02 - m20) / s, (m10 - m01) / s, 0.25 * s]
+    }
+    else if m00 > m11 && m00 > m22 {
+        let s = (1.0 + m00 - m11 - m22).sqrt() * 2.0;
+        [0.25 * s, (m01 + m10) / s, (m02 + m20) / s, (m21 - m12) / s]
+    }
+    else if m11 > m22 {
+        let s = (1.0 + m11 - m00 - m22).sqrt() * 2.0;
+        [(m01 + m10) / s, 0.25 * s, (m12 + m21) / s, (m02 - m20) / s]
+    }
+    else {
+        let s = (1.0 + m22 - m00 - m11).sqrt() * 2.0;
+        [(m02 + m20) / s, (m12 + m21) / s, 0.25 * s, (m10 - m01) / s]
+    };
+
+    // `q` and `-q` represent the same rotation, so make `w` positive,
+    // and keep it from quantizing to 0 (so that its sign can store
+    // the handedness)
+    if q[3] < 0.0 {
+        q = [-q[0], -q[1], -q[2], -q[3]];
+    }
+    let bias = 1.0 / 32767.0;
+    if q[3] < bias {
+        let scale = (1.0 - bias * bias).sqrt();
+        q = [q[0] * scale, q[1] * scale, q[2] * scale, bias];
+    }
+    if tangent[3] < 0.0 {
+        q = [-q[0], -q[1], -q[2], -q[3]];
+    }
+
+    Snorm16x4([_to_snorm16(q[0]),
+               _to_snorm16(q[1]),
+               _to_snorm16(q[2]),
+               _to_snorm16(q[3])])
+}
+
+/// Decode a tangent frame that was encoded with [`encode_tangent_frame`]
+/// (fn.encode_tangent_frame.html), returning the normal, and the tangent
+/// with its handedness as the `w` component.
+pub fn decode_tangent_frame(encoded: Snorm16x4) -> ([f32; 3], [f32; 4]) {
+    let q = [_from_snorm16(encoded.0[0]),
+             _from_snorm16(encoded.0[1]),
+             _from_snorm16(encoded.0[2]),
+             _from_snorm16(encoded.0[3])];
+    let length = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt();
+    let (x, y, z, w) = (q[0] / length, q[1] / length, q[2] / length, q[3] / length);
+
+    let normal = [2.0 * (x * z + w * y),
+                  2.0 * (y * z - w * x),
+                  1.0 - 2.0 * (x * x + y * y)];
+    let tangent = [1.0 - 2.0 * (y * y + z * z),
+                   2.0 * (x * y + w * z),
+                   2.0 * (x * z - w * y),
+                   _sign_not_zero(w)];
+
+    (normal, tangent)
+}
+
+
+
+/// GLSL source for decoding normals that were encoded with
+/// [`encode_octahedral_normal`](fn.encode_octahedral_normal.html). The
+/// source defines the function `vec3 decode_octahedral_normal(vec2 e)`, and
+/// is compatible with GLSL ES 1.00.
+///
+/// # Example
+/// ```
+/// let vertex_source = format!(r#"
+///     {}
+///
+///     attribute vec2 normal;
+///
+///     void main() {{
+///         vec3 n = decode_octahedral_normal(normal);
+///         // ...
+///     }}
+/// "#, glitter::OCTAHEDRAL_NORMAL_GLSL);
+/// ```
+pub const OCTAHEDRAL_NORMAL_GLSL: &'static str = r#"
+vec3 decode_octahedral_normal(vec2 e) {
+    vec3 n = vec3(e.xy, 1.0 - abs(e.x) - abs(e.y));
+    if (n.z < 0.0) {
+        vec2 signs = vec2(n.x >= 0.0 ? 1.0 : -1.0, n.y >= 0.0 ? 1.0 : -1.0);
+        n.xy = (1.0 - abs(n.yx)) * signs;
+    }
+    return normalize(n);
+}
+"#;
+
+/// GLSL source for decoding tangent frames that were encoded with
+/// [`encode_tangent_frame`](fn.encode_tangent_frame.html). The source
+/// defines the function
+/// `void decode_tangent_frame(vec4 q, out vec3 normal, out vec3 tangent, out vec3 bitangent)`,
+/// and is compatible with GLSL ES 1.00.
+pub const TANGENT_FRAME_GLSL: &'static str = r#"
+void decode_tangent_frame(vec4 q,
+                          out vec3 normal,
+                          out vec3 tangent,
+                          out vec3 bitangent)
+{
+    q = normalize(q);
+    normal = vec3(2.0 * (q.x * q.z + q.w * q.y),
+                  2.0 * (q.y * q.z - q.w * q.x),
+                  1.0 - 2.0 * (q.x * q.x + q.y * q.y));
+    tangent = vec3(1.0 - 2.0 * (q.y * q.y + q.z * q.z),
+                   2.0 * (q.x * q.y + q.w * q.z),
+                   2.0 * (q.x * q.z - q.w * q.y));
+    bitangent = cross(normal, tangent) * (q.w >= 0.0 ? 1.0 : -1.0);
+}
+"#;
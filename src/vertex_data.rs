@@ -36,11 +36,7 @@ use types::DataType;
 ///         // The vertex attribute type for an `[f32; 3]`, or `vec3`
 ///         let vec3 = <[f32; 3] as VertexDatum>::attrib_type();
 ///
-///         // let vec3 = VertexAttributeType {
-///         //     data: glitter::FLOAT,
-///         //     components: 3,
-///         //     normalize: false
-///         // };
+///         // let vec3 = VertexAttributeType::new(glitter::FLOAT, 3, false);
 ///
 ///         let stride = mem::size_of::<MyVertex>();
 ///         let position_offset = 0;
@@ -154,16 +150,112 @@ pub struct VertexAttributeType {
     /// The type of data that makes up this vertex attribute.
     pub data: DataType,
 
-    /// The number of `data` components that make up this vertex attribute.
+    /// The number of `data` components that make up this vertex attribute
+    /// (or each column of a matrix vertex attribute).
     pub components: i8,
 
+    /// The number of columns that make up this vertex attribute, which is
+    /// 1 for everything except matrices. A matrix vertex attribute (such as
+    /// a `mat4`) uses one attribute location for each column, starting at
+    /// the location of the attribute, and the columns are stored one after
+    /// another in the vertex data.
+    pub columns: i8,
+
     /// If the `data` type is fixed-point data, indicates if the data
     /// should be normalized when being accessed. `true` indicates
     /// that the vertex attribute **should** be normalized when being
     /// accessed.
-    pub normalize: bool
+    pub normalize: bool,
+
+    /// Whether the vertex attribute is read as floating-point values or as
+    /// integer values in a shader.
+    pub kind: AttribKind
 }
 
+impl VertexAttributeType {
+    /// Create a vertex attribute type with a single column, which is read
+    /// as floating-point values in a shader. Other attribute types can be
+    /// created by updating the `columns` or `kind` fields.
+    ///
+    /// # Example
+    /// ```
+    /// use glitter::VertexAttributeType;
+    ///
+    /// // The attribute type of a `mat4`
+    /// let mat4 = VertexAttributeType {
+    ///     columns: 4,
+    ///     ..VertexAttributeType::new(glitter::FLOAT, 4, false)
+    /// };
+    /// assert_eq!(mat4.column_size(), 16);
+    /// ```
+    pub fn new(data: DataType, components: i8, normalize: bool) -> Self {
+        VertexAttributeType {
+            data: data,
+            components: components,
+            columns: 1,
+            normalize: normalize,
+            kind: AttribKind::Float
+        }
+    }
+
+    /// Get the size of a single column of this vertex attribute, in bytes.
+    pub fn column_size(&self) -> usize {
+        self.data.size() * (self.components as usize)
+    }
+}
+
+/// The kind of values that a vertex attribute is read as in a shader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttribKind {
+    /// The vertex attribute is read as floating-point values (such as a
+    /// `float` or a `vec4`), and is set up with [`glVertexAttribPointer`]
+    /// (http://docs.gl/es2/glVertexAttribPointer). Integer data is converted
+    /// to floating-point values (and normalized, if `normalize` is `true`).
+    Float,
+
+    /// The vertex attribute is read as integer values (such as an `int`, an
+    /// `ivec4`, or a `uvec4`), and is set up with [`glVertexAttribIPointer`]
+    /// (http://docs.gl/es3/glVertexAttribIPointer). The `data` type must be
+    /// an integer type, and `normalize` is ignored.
+    ///
+    /// # Note
+    /// Integer vertex attributes require OpenGL ES 3.0, so this variant is
+    /// only available with the `gles3` feature.
+    #[cfg(feature = "gles3")]
+    Integer
+}
+
+/// A wrapper for a [`VertexDatum`](trait.VertexDatum.html) with integer
+/// components, which is read as integer values in a shader (such as bone
+/// indices declared as a `uvec4`), rather than being converted to
+/// floating-point values.
+///
+/// # Note
+/// Integer vertex attributes require OpenGL ES 3.0, so this type is only
+/// available with the `gles3` feature.
+///
+/// # Example
+/// ```
+/// #[macro_use] extern crate glitter;
+/// use glitter::IntegerAttrib;
+///
+/// # fn main() {
+/// #[derive(Clone, Copy)]
+/// #[repr(C)]
+/// struct SkinnedVertex {
+///     position: [f32; 3],
+///     bone_indices: IntegerAttrib<[u8; 4]>, // in uvec4 bone_indices;
+///     bone_weights: [f32; 4]
+/// }
+///
+/// impl_vertex_data!(SkinnedVertex, position, bone_indices, bone_weights);
+/// # }
+/// ```
+#[cfg(feature = "gles3")]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegerAttrib<T: VertexDatum>(pub T);
+
 
 
 unsafe impl VertexPrimitive for i8 {
@@ -182,6 +274,14 @@ unsafe impl VertexPrimitive for u16 {
     fn data_type() -> DataType { DataType::UnsignedShort }
 }
 
+unsafe impl VertexPrimitive for i32 {
+    fn data_type() -> DataType { DataType::Int }
+}
+
+unsafe impl VertexPrimitive for u32 {
+    fn data_type() -> DataType { DataType::UnsignedInt }
+}
+
 unsafe impl VertexPrimitive for f32 {
     fn data_type() -> DataType { DataType::Float }
 }
@@ -191,7 +291,9 @@ unsafe impl<T: VertexPrimitive> VertexDatum for T {
         VertexAttributeType {
             data: T::data_type(),
             components: 1,
-            normalize: false
+            columns: 1,
+            normalize: false,
+            kind: AttribKind::Float
         }
     }
 }
@@ -201,7 +303,9 @@ unsafe impl<T: VertexPrimitive> VertexDatum for [T; 1] {
         VertexAttributeType {
             data: T::data_type(),
             components: 1,
-            normalize: false
+            columns: 1,
+            normalize: false,
+            kind: AttribKind::Float
         }
     }
 }
@@ -211,7 +315,9 @@ unsafe impl<T: VertexPrimitive> VertexDatum for [T; 2] {
         VertexAttributeType {
             data: T::data_type(),
             components: 2,
-            normalize: false
+            columns: 1,
+            normalize: false,
+            kind: AttribKind::Float
         }
     }
 }
@@ -221,7 +327,9 @@ unsafe impl<T: VertexPrimitive> VertexDatum for [T; 3] {
         VertexAttributeType {
             data: T::data_type(),
             components: 3,
-            normalize: false
+            columns: 1,
+            normalize: false,
+            kind: AttribKind::Float
         }
     }
 }
@@ -231,12 +339,72 @@ unsafe impl<T: VertexPrimitive> VertexDatum for [T; 4] {
         VertexAttributeType {
             data: T::data_type(),
             components: 4,
-            normalize: false
+            columns: 1,
+            normalize: false,
+            kind: AttribKind::Float
+        }
+    }
+}
+
+
+
+unsafe impl<T: VertexPrimitive> VertexDatum for [[T; 2]; 2] {
+    fn attrib_type() -> VertexAttributeType {
+        VertexAttributeType {
+            data: T::data_type(),
+            components: 2,
+            columns: 2,
+            normalize: false,
+            kind: AttribKind::Float
+        }
+    }
+}
+
+unsafe impl<T: VertexPrimitive> VertexDatum for [[T; 3]; 3] {
+    fn attrib_type() -> VertexAttributeType {
+        VertexAttributeType {
+            data: T::data_type(),
+            components: 3,
+            columns: 3,
+            normalize: false,
+            kind: AttribKind::Float
+        }
+    }
+}
+
+unsafe impl<T: VertexPrimitive> VertexDatum for [[T; 4]; 4] {
+    fn attrib_type() -> VertexAttributeType {
+        VertexAttributeType {
+            data: T::data_type(),
+            components: 4,
+            columns: 4,
+            normalize: false,
+            kind: AttribKind::Float
         }
     }
 }
 
+unsafe impl VertexDatum for [f32; 16] {
+    fn attrib_type() -> VertexAttributeType {
+        VertexAttributeType {
+            data: DataType::Float,
+            components: 4,
+            columns: 4,
+            normalize: false,
+            kind: AttribKind::Float
+        }
+    }
+}
 
+#[cfg(feature = "gles3")]
+unsafe impl<T: VertexDatum> VertexDatum for IntegerAttrib<T> {
+    fn attrib_type() -> VertexAttributeType {
+        VertexAttributeType {
+            kind: AttribKind::Integer,
+            ..T::attrib_type()
+        }
+    }
+}
 
 /// Indicates that a type can be coerced to a `u8` slice that can
 /// then be treated as a stream of vertex data.